@@ -55,6 +55,7 @@ async fn main() -> anyhow::Result<()> {
             source: AppBundleSource::Bundle(bundle),
             membrane_proofs: Default::default(),
             network_seed: None,
+            use_chc: false,
         };
 
         let r = AdminRequest::InstallApp(Box::new(payload));
@@ -13,6 +13,7 @@ use anyhow::bail;
 use anyhow::ensure;
 use holochain_conductor_api::AdminRequest;
 use holochain_conductor_api::AdminResponse;
+use holochain_conductor_api::AllowedOrigins;
 use holochain_conductor_api::AppStatusFilter;
 use holochain_conductor_api::InterfaceDriver;
 use holochain_conductor_api::{AdminInterfaceConfig, AppInfo};
@@ -58,7 +59,9 @@ pub struct Call {
 #[derive(Debug, Parser, Clone)]
 pub enum AdminRequestCli {
     AddAdminWs(AddAdminWs),
+    RemoveAdminWs(RemoveAdminWs),
     AddAppWs(AddAppWs),
+    DetachAppWs(DetachAppWs),
     RegisterDna(RegisterDna),
     InstallApp(InstallApp),
     /// Calls AdminRequest::UninstallApp.
@@ -80,6 +83,7 @@ pub enum AdminRequestCli {
     /// _Unimplemented_.
     AddAgents,
     ListAgents(ListAgents),
+    IssueAppToken(IssueAppToken),
 }
 
 /// Calls AdminRequest::AddAdminInterfaces
@@ -91,6 +95,19 @@ pub struct AddAdminWs {
     pub port: Option<u16>,
 }
 
+/// Calls AdminRequest::RemoveAdminInterface
+/// and closes an admin interface.
+#[derive(Debug, Parser, Clone)]
+pub struct RemoveAdminWs {
+    /// The port the interface is listening on on `127.0.0.1`.
+    #[arg(required_unless_present = "socket")]
+    pub port: Option<u16>,
+
+    /// The path of the Unix domain socket the interface is listening on.
+    #[arg(long, conflicts_with = "port")]
+    pub socket: Option<PathBuf>,
+}
+
 /// Calls AdminRequest::AttachAppInterface
 /// and adds another app interface.
 #[derive(Debug, Parser, Clone)]
@@ -98,6 +115,43 @@ pub struct AddAppWs {
     /// Optional port number.
     /// Defaults to assigned by OS.
     pub port: Option<u16>,
+
+    /// Only allow clients to connect from these web origins,
+    /// e.g. `http://localhost:8888`.
+    /// Defaults to allowing any origin.
+    #[arg(long, value_delimiter = ',')]
+    pub allowed_origins: Vec<String>,
+
+    /// Only allow requests about this app on the interface.
+    #[arg(long)]
+    pub app_id: Option<String>,
+}
+
+/// Calls AdminRequest::DetachAppInterface
+/// and closes an app interface.
+#[derive(Debug, Parser, Clone)]
+pub struct DetachAppWs {
+    /// The port the interface is listening on on `127.0.0.1`.
+    #[arg(required_unless_present = "socket")]
+    pub port: Option<u16>,
+
+    /// The path of the Unix domain socket the interface is listening on.
+    #[arg(long, conflicts_with = "port")]
+    pub socket: Option<PathBuf>,
+}
+
+/// Calls AdminRequest::IssueAppAuthenticationToken
+/// and prints a token for connecting to an app interface once.
+#[derive(Debug, Parser, Clone)]
+pub struct IssueAppToken {
+    /// How many seconds the token may be used for.
+    #[arg(long)]
+    pub expiry_seconds: Option<u64>,
+
+    /// Only connect to app interfaces for this app.
+    /// Defaults to interfaces open to every app.
+    #[arg(long)]
+    pub app_id: Option<String>,
 }
 
 /// Calls AdminRequest::RegisterDna
@@ -266,10 +320,24 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             let port = add_admin_interface(cmd, args).await?;
             msg!("Added admin port {}", port);
         }
+        AdminRequestCli::RemoveAdminWs(args) => {
+            let driver = listening_driver(args.port, args.socket.clone());
+            remove_admin_interface(cmd, args).await?;
+            msg!("Removed admin interface {:?}", driver);
+        }
         AdminRequestCli::AddAppWs(args) => {
             let port = attach_app_interface(cmd, args).await?;
             msg!("Added app port {}", port);
         }
+        AdminRequestCli::DetachAppWs(args) => {
+            let driver = listening_driver(args.port, args.socket.clone());
+            detach_app_interface(cmd, args).await?;
+            msg!("Detached app interface {:?}", driver);
+        }
+        AdminRequestCli::IssueAppToken(args) => {
+            let token = issue_app_token(cmd, args).await?;
+            msg!("App authentication token: {}", token);
+        }
         AdminRequestCli::ListAppWs => {
             let ports = list_app_ws(cmd).await?;
             msg!("Attached app interfaces {:?}", ports);
@@ -381,7 +449,7 @@ pub async fn add_admin_interface(cmd: &mut CmdRunner, args: AddAdminWs) -> anyho
     let resp = cmd
         .command(AdminRequest::AddAdminInterfaces(vec![
             AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port },
+                driver: InterfaceDriver::websocket(port),
             },
         ]))
         .await?;
@@ -394,6 +462,32 @@ pub async fn add_admin_interface(cmd: &mut CmdRunner, args: AddAdminWs) -> anyho
     Ok(port)
 }
 
+/// The driver of an interface listening on a loopback port or a socket.
+fn listening_driver(port: Option<u16>, socket: Option<PathBuf>) -> InterfaceDriver {
+    match socket {
+        Some(path) => InterfaceDriver::UnixSocket { path, mode: None },
+        None => InterfaceDriver::websocket(port.unwrap_or_default()),
+    }
+}
+
+/// Calls [`AdminRequest::RemoveAdminInterface`] and closes an admin interface.
+pub async fn remove_admin_interface(
+    cmd: &mut CmdRunner,
+    args: RemoveAdminWs,
+) -> anyhow::Result<()> {
+    let resp = cmd
+        .command(AdminRequest::RemoveAdminInterface {
+            driver: listening_driver(args.port, args.socket),
+        })
+        .await?;
+    ensure!(
+        matches!(resp, AdminResponse::AdminInterfaceRemoved),
+        "Failed to remove admin interface, got: {:?}",
+        resp
+    );
+    Ok(())
+}
+
 /// Calls [`AdminRequest::RegisterDna`] and registers DNA.
 pub async fn register_dna(cmd: &mut CmdRunner, args: RegisterDna) -> anyhow::Result<DnaHash> {
     let RegisterDna {
@@ -451,6 +545,7 @@ pub async fn install_app_bundle(cmd: &mut CmdRunner, args: InstallApp) -> anyhow
         source: AppBundleSource::Path(path),
         membrane_proofs: Default::default(),
         network_seed,
+        use_chc: false,
     };
 
     let r = AdminRequest::InstallApp(Box::new(payload));
@@ -483,7 +578,7 @@ pub async fn uninstall_app(cmd: &mut CmdRunner, args: UninstallApp) -> anyhow::R
 }
 
 /// Calls [`AdminRequest::ListAppInterfaces`].
-pub async fn list_app_ws(cmd: &mut CmdRunner) -> anyhow::Result<Vec<u16>> {
+pub async fn list_app_ws(cmd: &mut CmdRunner) -> anyhow::Result<Vec<InterfaceDriver>> {
     let resp = cmd.command(AdminRequest::ListAppInterfaces).await?;
     Ok(expect_match!(resp => AdminResponse::AppInterfacesListed, "Failed to list app interfaces"))
 }
@@ -547,11 +642,20 @@ pub async fn disable_app(cmd: &mut CmdRunner, args: DisableApp) -> anyhow::Resul
 /// Calls [`AdminRequest::AttachAppInterface`] and adds another app interface.
 pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow::Result<u16> {
     let resp = cmd
-        .command(AdminRequest::AttachAppInterface { port: args.port })
+        .command(AdminRequest::AttachAppInterface {
+            port: args.port,
+            driver: None,
+            allowed_origins: if args.allowed_origins.is_empty() {
+                AllowedOrigins::Any
+            } else {
+                AllowedOrigins::Origins(args.allowed_origins.iter().cloned().collect())
+            },
+            installed_app_id: args.app_id.clone(),
+        })
         .await?;
     tracing::debug!(?resp);
     match resp {
-        AdminResponse::AppInterfaceAttached { port } => Ok(port),
+        AdminResponse::AppInterfaceAttached { port, .. } => Ok(port),
         _ => Err(anyhow!(
             "Failed to attach app interface {:?}, got: {:?}",
             args.port,
@@ -560,6 +664,38 @@ pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow
     }
 }
 
+/// Calls [`AdminRequest::DetachAppInterface`] and closes an app interface.
+pub async fn detach_app_interface(cmd: &mut CmdRunner, args: DetachAppWs) -> anyhow::Result<()> {
+    let resp = cmd
+        .command(AdminRequest::DetachAppInterface {
+            driver: listening_driver(args.port, args.socket),
+        })
+        .await?;
+    ensure!(
+        matches!(resp, AdminResponse::AppInterfaceDetached),
+        "Failed to detach app interface, got: {:?}",
+        resp
+    );
+    Ok(())
+}
+
+/// Calls [`AdminRequest::IssueAppAuthenticationToken`].
+pub async fn issue_app_token(cmd: &mut CmdRunner, args: IssueAppToken) -> anyhow::Result<String> {
+    let resp = cmd
+        .command(AdminRequest::IssueAppAuthenticationToken {
+            expiry_seconds: args.expiry_seconds,
+            installed_app_id: args.app_id,
+        })
+        .await?;
+    match resp {
+        AdminResponse::AppAuthenticationTokenIssued { token, .. } => Ok(token),
+        _ => Err(anyhow!(
+            "Failed to issue app authentication token, got: {:?}",
+            resp
+        )),
+    }
+}
+
 /// Calls [`AdminRequest::DumpState`] and dumps the current cell's state.
 // TODO: Add pretty print.
 // TODO: Default to dumping all cell state.
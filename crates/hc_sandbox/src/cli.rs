@@ -104,7 +104,8 @@ pub struct Run {
     /// This allows your UI to talk to the conductor.
     /// For example, `hc -p=0,9000,0` will create three app interfaces.
     /// Important: Interfaces are persistent. If you add an interface
-    /// it will be there next time you run the conductor.
+    /// it will be there next time you run the conductor, unless you
+    /// remove it with `hc sandbox call detach-app-ws`.
     #[arg(short, long, value_delimiter = ',')]
     ports: Vec<u16>,
 
@@ -56,6 +56,8 @@ pub async fn run(
             &mut cmd,
             AddAppWs {
                 port: Some(app_port),
+                allowed_origins: Vec::new(),
+                app_id: None,
             },
         )
         .await?;
@@ -106,7 +106,7 @@ pub fn create_cap_claim(cap_claim_entry: CapClaimEntry) -> ExternResult<ActionHa
 /// extern at one time, or may apply in addition to a stricter grant. In this case, revoking a
 /// stricter grant, or failing to revoke all Unrestricted grants will leave the function open.
 //
-// @todo administration functions to query active grants
+// Use [`capability_grants`] to list the currently active grants.
 ///
 /// There is an apparent "chicken or the egg" situation where [`CapGrant`] are required for remote
 /// agents to call externs, so how does an agent request a grant in the first place?
@@ -198,3 +198,39 @@ pub fn update_cap_grant(
         chain_top_ordering: ChainTopOrdering::default(),
     })
 }
+
+/// List the capability grants committed to the local source chain.
+///
+/// Every [`CapGrantInfo`] carries the [`ActionHash`] of the grant so that it can be passed to
+/// [`update_cap_grant`] or [`delete_cap_grant`], and the timestamps at which it was created and,
+/// if applicable, revoked by an update or delete.
+///
+/// Revoked grants are only listed when [`CapGrantQuery::include_revoked`] is set.
+/// Grants can also be filtered by their exact tag.
+///
+/// ```ignore
+/// let live_grants = capability_grants(CapGrantQuery::default())?;
+/// ```
+pub fn capability_grants(cap_grant_query: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>> {
+    HDK.with(|h| h.borrow().capability_grants(cap_grant_query))
+}
+
+/// List the capability claims committed to the local source chain.
+///
+/// Deleted claims are never listed.
+/// Claims can be filtered by their exact tag and by the agent that issued the grant.
+///
+/// There is no guarantee that any listed claim is still honoured by its grantor, see
+/// [`create_cap_claim`].
+pub fn capability_claims(cap_claim_query: CapClaimQuery) -> ExternResult<Vec<CapClaim>> {
+    HDK.with(|h| h.borrow().capability_claims(cap_claim_query))
+}
+
+/// Get the capability grant that authorized the current zome call.
+///
+/// Returns `None` when the call was made by the chain author, who needs no grant.
+/// This is the grant to revoke with [`delete_cap_grant`] to stop the caller from making further calls.
+/// The caller itself is the provenance in [`call_info`].
+pub fn capability_info() -> ExternResult<Option<CapGrantInfo>> {
+    HDK.with(|h| h.borrow().capability_info(()))
+}
@@ -36,6 +36,10 @@ pub trait HdkT: HdiT {
     fn delete(&self, delete_input: DeleteInput) -> ExternResult<ActionHash>;
     fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>>;
     fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
+    // Capability
+    fn capability_claims(&self, cap_claim_query: CapClaimQuery) -> ExternResult<Vec<CapClaim>>;
+    fn capability_grants(&self, cap_grant_query: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>>;
+    fn capability_info(&self, capability_info_input: ()) -> ExternResult<Option<CapGrantInfo>>;
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -109,6 +113,11 @@ mockall::mock! {
         fn delete(&self, delete_input: DeleteInput) -> ExternResult<ActionHash>;
         fn get(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Record>>>;
         fn get_details(&self, get_input: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>>;
+        // Capability
+        fn capability_claims(&self, cap_claim_query: CapClaimQuery) -> ExternResult<Vec<CapClaim>>;
+        fn capability_grants(&self, cap_grant_query: CapGrantQuery)
+            -> ExternResult<Vec<CapGrantInfo>>;
+        fn capability_info(&self, capability_info_input: ()) -> ExternResult<Option<CapGrantInfo>>;
         // CounterSigning
         fn accept_countersigning_preflight_request(
             &self,
@@ -303,6 +312,16 @@ impl HdkT for ErrHdk {
     fn get_details(&self, _: Vec<GetInput>) -> ExternResult<Vec<Option<Details>>> {
         Self::err()
     }
+    // Capability
+    fn capability_claims(&self, _: CapClaimQuery) -> ExternResult<Vec<CapClaim>> {
+        Self::err()
+    }
+    fn capability_grants(&self, _: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>> {
+        Self::err()
+    }
+    fn capability_info(&self, _: ()) -> ExternResult<Option<CapGrantInfo>> {
+        Self::err()
+    }
     // CounterSigning
     fn accept_countersigning_preflight_request(
         &self,
@@ -513,6 +532,15 @@ impl HdkT for HostHdk {
             preflight_request,
         )
     }
+    fn capability_claims(&self, cap_claim_query: CapClaimQuery) -> ExternResult<Vec<CapClaim>> {
+        host_call::<CapClaimQuery, Vec<CapClaim>>(__hc__capability_claims_1, cap_claim_query)
+    }
+    fn capability_grants(&self, cap_grant_query: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>> {
+        host_call::<CapGrantQuery, Vec<CapGrantInfo>>(__hc__capability_grants_1, cap_grant_query)
+    }
+    fn capability_info(&self, _: ()) -> ExternResult<Option<CapGrantInfo>> {
+        host_call::<(), Option<CapGrantInfo>>(__hc__capability_info_1, ())
+    }
     fn agent_info(&self, _: ()) -> ExternResult<AgentInfo> {
         host_call::<(), AgentInfo>(__hc__agent_info_1, ())
     }
@@ -174,6 +174,12 @@
 //!   - Only the originating zome is called.
 //!   - Failure overrides retry.
 //!   - See [`validate`](crate::hdi::prelude::validate) for more details.
+//! - `fn weigh(input: WeighInput) -> ExternResult<RateWeight>`:
+//!   - Assigns each create, update, delete and create link action to a rate limiting bucket, with a weight.
+//!   - Only the integrity zome which owns the entry or link type is called.
+//!   - Must be deterministic, as validators re-weigh every action and reject any whose weight differs.
+//!   - The DNA declares a capacity and period for each bucket. Actions which overflow their bucket are rejected.
+//!   - If not implemented, actions are given the default weight, which is zero units in bucket 255.
 //!
 //! # HDK has layers 🧅
 //!
@@ -136,7 +136,13 @@ pub fn schedule(scheduled_fn: &str) -> ExternResult<()> {
     HDK.with(|h| h.borrow().schedule(String::from(scheduled_fn)))
 }
 
-/// @todo Not implemented
+/// Pause the current zome call for a while, e.g. to back off between polls
+/// of a remote cell while waiting for it to see some data.
+///
+/// Sleeping is non-deterministic, so it is only possible in zome calls and
+/// callbacks which are allowed non-determinism, never in validation.
+/// The host refuses to sleep for more than a minute at a time, or past the
+/// expiry of the zome call.
 pub fn sleep(wake_after: std::time::Duration) -> ExternResult<()> {
     HDK.with(|h| h.borrow().sleep(wake_after))
 }
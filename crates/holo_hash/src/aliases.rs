@@ -37,6 +37,9 @@ pub type WasmHash = HoloHash<hash_type::Wasm>;
 /// The hash of some external data that can't or doesn't exist on the DHT.
 pub type ExternalHash = HoloHash<hash_type::External>;
 
+/// The hash of an app bundle
+pub type AppHash = HoloHash<hash_type::App>;
+
 // COMPOSITE HASH TYPES
 
 /// The hash of anything referrable in the DHT.
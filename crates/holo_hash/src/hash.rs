@@ -304,6 +304,10 @@ mod tests {
             "ExternalHash",
             ExternalHash::from_raw_36(vec![0xdb; HOLO_HASH_UNTYPED_LEN]),
         );
+        assert_type(
+            "AppHash",
+            AppHash::from_raw_36(vec![0xdb; HOLO_HASH_UNTYPED_LEN]),
+        );
     }
 
     #[test]
@@ -79,6 +79,9 @@ pub type WasmHashB64 = HoloHashB64<hash_type::Wasm>;
 /// Base64-ready version of ExternalHash
 pub type ExternalHashB64 = HoloHashB64<hash_type::External>;
 
+/// Base64-ready version of AppHash
+pub type AppHashB64 = HoloHashB64<hash_type::App>;
+
 /// Base64-ready version of AnyDhtHash
 pub type AnyDhtHashB64 = HoloHashB64<hash_type::AnyDht>;
 
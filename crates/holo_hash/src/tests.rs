@@ -82,6 +82,13 @@ fn holo_hash_parse() {
         "ExternalHash(uhC8kWCsAgoKkkfwyJAglj30xX_GLLV-3BXuFy436a2SqpcEwyBzm)",
         &format!("{:?}", h),
     );
+
+    let h = AppHash::try_from("uhCskWCsAgoKkkfwyJAglj30xX_GLLV-3BXuFy436a2SqpcEwyBzm").unwrap();
+    assert_eq!(expected_loc, h.get_loc());
+    assert_eq!(
+        "AppHash(uhCskWCsAgoKkkfwyJAglj30xX_GLLV-3BXuFy436a2SqpcEwyBzm)",
+        &format!("{:?}", h),
+    );
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -6,6 +6,8 @@ use holochain::conductor::Conductor;
 use holochain::conductor::ConductorHandle;
 use holochain_conductor_api::conductor::ConductorConfigError;
 use holochain_conductor_api::config::conductor::KeystoreConfig;
+use holochain_metrics::HolochainMetricsConfig;
+use holochain_sqlite::schema::SchemaKind;
 use holochain_trace::Output;
 use holochain_util::tokio_helper;
 #[cfg(unix)]
@@ -27,6 +29,8 @@ struct Opt {
     - Log: Output logs to stdout with spans (human readable)
     - Compact: Same as Log but with less information
     - Json: Output logs as structured json (machine readable)
+    - OpenTel: Same as Log, and export spans as OpenTelemetry traces with OTLP
+      over gRPC to OTEL_EXPORTER_OTLP_ENDPOINT (requires the otlp feature)
     ",
         default_value = "Log"
     )]
@@ -60,6 +64,34 @@ struct Opt {
         help = "Display version information such as git revision and HDK version"
     )]
     build_info: bool,
+
+    #[structopt(
+        long,
+        help = "Display the schema version of each database, and the latest version of each
+    schema which this conductor understands, then exit"
+    )]
+    database_versions: bool,
+
+    #[structopt(
+        long,
+        value_name = "SCHEMA=VERSION",
+        number_of_values = 1,
+        parse(try_from_str = parse_schema_version),
+        help = "Migrate the databases with a schema to a version of it, so that an older
+    version of Holochain can use them, then exit. Can be given once for each of the
+    schemas: cell, conductor, wasm, p2p_agent_store, p2p_metrics"
+    )]
+    migrate_databases: Vec<(SchemaKind, u16)>,
+}
+
+fn parse_schema_version(s: &str) -> Result<(SchemaKind, u16), String> {
+    let (schema, version) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected SCHEMA=VERSION, got {:?}", s))?;
+    let version = version
+        .parse()
+        .map_err(|e| format!("invalid version {:?}: {}", version, e))?;
+    Ok((schema.parse()?, version))
 }
 
 fn main() {
@@ -89,9 +121,12 @@ async fn async_main() {
     holochain_trace::init_fmt(opt.structured.clone()).expect("Failed to start contextual logging");
     debug!("holochain_trace initialized");
 
-    holochain_metrics::HolochainMetricsConfig::new(config.environment_path.as_ref())
-        .init()
-        .await;
+    if opt.database_versions || !opt.migrate_databases.is_empty() {
+        manage_databases(&opt, config).await;
+        return;
+    }
+
+    metrics_config(&config).init().await;
 
     kitsune_p2p_types::metrics::init_sys_info_poll();
 
@@ -141,9 +176,32 @@ fn get_conductor_config(opt: &Opt) -> ConductorConfig {
     config
 }
 
-async fn conductor_handle_from_config(opt: &Opt, config: ConductorConfig) -> ConductorHandle {
-    // read the passphrase to prepare for usage
-    let passphrase = match &config.keystore {
+fn metrics_config(config: &ConductorConfig) -> HolochainMetricsConfig {
+    let from_env = HolochainMetricsConfig::new(config.environment_path.as_ref());
+
+    if let Some(bind) = config.prometheus_metrics_bind {
+        if !matches!(from_env, HolochainMetricsConfig::Disabled) {
+            println!(
+                "Metrics are configured by both prometheus_metrics_bind in the conductor config and environment variables. Please only configure them in one place."
+            );
+            std::process::exit(ERROR_CODE);
+        }
+
+        #[cfg(feature = "metrics_prometheus")]
+        return HolochainMetricsConfig::Prometheus { bind };
+
+        #[cfg(not(feature = "metrics_prometheus"))]
+        warn!(
+            %bind,
+            "prometheus_metrics_bind is set, but this conductor was built without the metrics_prometheus feature"
+        );
+    }
+
+    from_env
+}
+
+fn read_passphrase(opt: &Opt, config: &ConductorConfig) -> Option<sodoken::BufRead> {
+    match &config.keystore {
         KeystoreConfig::DangerTestKeystore => None,
         KeystoreConfig::LairServer { .. } | KeystoreConfig::LairServerInProc { .. } => {
             if opt.piped {
@@ -152,7 +210,43 @@ async fn conductor_handle_from_config(opt: &Opt, config: ConductorConfig) -> Con
 
             Some(holochain_util::pw::pw_get().unwrap())
         }
+    }
+}
+
+/// Display or migrate the schema versions of the databases, instead of
+/// running the conductor.
+async fn manage_databases(opt: &Opt, config: ConductorConfig) {
+    let passphrase = read_passphrase(opt, &config);
+    let builder = Conductor::builder().config(config).passphrase(passphrase);
+    let result = if opt.migrate_databases.is_empty() {
+        builder.database_versions().await
+    } else {
+        builder
+            .migrate_databases(opt.migrate_databases.clone())
+            .await
+    };
+    let versions = match result {
+        Ok(versions) => versions,
+        Err(e) => {
+            println!("Couldn't read or migrate the databases: {}", e);
+            std::process::exit(ERROR_CODE);
+        }
     };
+    for schema in SchemaKind::ALL {
+        println!(
+            "{} schema (latest version {}):",
+            schema,
+            schema.schema().version()
+        );
+        for version in versions.iter().filter(|v| v.schema == schema) {
+            println!("    {}: {}", version.path.display(), version.version);
+        }
+    }
+}
+
+async fn conductor_handle_from_config(opt: &Opt, config: ConductorConfig) -> ConductorHandle {
+    // read the passphrase to prepare for usage
+    let passphrase = read_passphrase(opt, &config);
 
     // Check if database is present
     // In interactive mode give the user a chance to create it, otherwise create it automatically
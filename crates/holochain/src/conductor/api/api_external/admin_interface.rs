@@ -5,6 +5,7 @@ use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::SerializationError;
 use crate::conductor::conductor::CellStatus;
+use crate::conductor::conductor::DpkiServiceExt;
 use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
@@ -74,6 +75,10 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AdminInterfacesAdded)
             }
+            RemoveAdminInterface { driver } => {
+                self.conductor_handle.remove_admin_interface(&driver)?;
+                Ok(AdminResponse::AdminInterfaceRemoved)
+            }
             RegisterDna(payload) => {
                 trace!(register_dna_payload = ?payload);
                 let RegisterDnaPayload { modifiers, source } = *payload;
@@ -153,6 +158,10 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     &dna_definitions,
                 )))
             }
+            StoreAppBundle { source } => {
+                let app_hash = self.conductor_handle.store_app_bundle(source).await?;
+                Ok(AdminResponse::AppBundleStored(app_hash))
+            }
             UninstallApp { installed_app_id } => {
                 self.conductor_handle
                     .clone()
@@ -171,6 +180,11 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .clone()
                     .new_sign_keypair_random()
                     .await?;
+                if let Some(dpki) = self.conductor_handle.dpki() {
+                    dpki.register_key(agent_pub_key.clone())
+                        .await
+                        .map_err(ConductorError::from)?;
+                }
                 Ok(AdminResponse::AgentPubKeyGenerated(agent_pub_key))
             }
             ListCellIds => {
@@ -220,14 +234,27 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppDisabled)
             }
-            AttachAppInterface { port } => {
-                let port = port.unwrap_or(0);
-                let port = self
+            AttachAppInterface {
+                port,
+                driver,
+                allowed_origins,
+                installed_app_id,
+            } => {
+                let driver =
+                    driver.unwrap_or_else(|| InterfaceDriver::websocket(port.unwrap_or(0)));
+                let driver = self
                     .conductor_handle
                     .clone()
-                    .add_app_interface(either::Either::Left(port))
+                    .add_app_interface_with_driver(driver, allowed_origins, installed_app_id)
                     .await?;
-                Ok(AdminResponse::AppInterfaceAttached { port })
+                Ok(AdminResponse::AppInterfaceAttached {
+                    port: driver.port().unwrap_or(0),
+                    driver,
+                })
+            }
+            DetachAppInterface { driver } => {
+                self.conductor_handle.detach_app_interface(driver).await?;
+                Ok(AdminResponse::AppInterfaceDetached)
             }
             ListAppInterfaces => {
                 let interfaces = self.conductor_handle.list_app_interfaces().await?;
@@ -281,6 +308,26 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::ZomeCallCapabilityGranted)
             }
+            ListCapabilityGrants {
+                cell_id,
+                include_revoked,
+            } => {
+                let grants = self
+                    .conductor_handle
+                    .list_capability_grants(&cell_id, include_revoked)
+                    .await?;
+                Ok(AdminResponse::CapabilityGrantsInfo(grants))
+            }
+            RevokeZomeCallCapability {
+                cell_id,
+                action_hash,
+            } => {
+                self.conductor_handle
+                    .clone()
+                    .revoke_zome_call_capability(cell_id, action_hash)
+                    .await?;
+                Ok(AdminResponse::ZomeCallCapabilityRevoked)
+            }
             DeleteCloneCell(payload) => {
                 self.conductor_handle
                     .clone()
@@ -291,10 +338,79 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
             StorageInfo => Ok(AdminResponse::StorageInfo(
                 self.conductor_handle.storage_info().await?,
             )),
+            MigrateAgent(payload) => {
+                let cell_id = self
+                    .conductor_handle
+                    .clone()
+                    .migrate_agent(*payload)
+                    .await?;
+                Ok(AdminResponse::AgentMigrated(cell_id))
+            }
+            AttachChc { cell_id } => {
+                self.conductor_handle.clone().attach_chc(cell_id).await?;
+                Ok(AdminResponse::ChcAttached)
+            }
+            DetachChc { cell_id } => {
+                self.conductor_handle.clone().detach_chc(cell_id).await?;
+                Ok(AdminResponse::ChcDetached)
+            }
+            Block { target, interval } => {
+                self.conductor_handle
+                    .block(holochain_zome_types::block::Block::new(
+                        target,
+                        valid_interval(interval)?,
+                    ))
+                    .await?;
+                Ok(AdminResponse::Blocked)
+            }
+            Unblock { target, interval } => {
+                self.conductor_handle
+                    .unblock(holochain_zome_types::block::Block::new(
+                        target,
+                        valid_interval(interval)?,
+                    ))
+                    .await?;
+                Ok(AdminResponse::Unblocked)
+            }
+            ListBlocks => {
+                let blocks = self
+                    .conductor_handle
+                    .list_blocks()
+                    .await?
+                    .into_iter()
+                    .map(|block| BlockInfo {
+                        target: block.target().clone(),
+                        interval: block.interval().clone(),
+                    })
+                    .collect();
+                Ok(AdminResponse::BlocksListed(blocks))
+            }
+            RotateDatabaseKey => {
+                self.conductor_handle.rotate_database_key().await?;
+                Ok(AdminResponse::DatabaseKeyRotated)
+            }
+            IssueAppAuthenticationToken {
+                expiry_seconds,
+                installed_app_id,
+            } => {
+                let (token, expires_at) = self
+                    .conductor_handle
+                    .issue_app_authentication_token(expiry_seconds, installed_app_id)?;
+                Ok(AdminResponse::AppAuthenticationTokenIssued { token, expires_at })
+            }
         }
     }
 }
 
+/// Blocks arrive over the wire, so their intervals haven't been through
+/// the constructor that checks start is not after end.
+fn valid_interval(
+    interval: InclusiveTimestampInterval,
+) -> ConductorApiResult<InclusiveTimestampInterval> {
+    InclusiveTimestampInterval::try_new(interval.start(), interval.end())
+        .map_err(|e| ConductorApiError::other(e.to_string()))
+}
+
 #[async_trait::async_trait]
 impl InterfaceApi for RealAdminInterfaceApi {
     type ApiRequest = AdminRequest;
@@ -457,6 +573,77 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn block_list_unblock() -> Result<()> {
+        holochain_trace::test_run().ok();
+        let env_dir = test_db_dir();
+        let handle = Conductor::builder().test(env_dir.path(), &[]).await?;
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+
+        let ip: std::net::IpAddr = "203.0.113.7".parse().unwrap();
+        let target = holochain_zome_types::block::BlockTarget::Ip(
+            ip,
+            kitsune_p2p_block::IpBlockReason::Abuse.into(),
+        );
+        let interval = InclusiveTimestampInterval::try_new(
+            Timestamp::from_micros(0),
+            Timestamp::from_micros(100),
+        )?;
+
+        assert_matches!(
+            admin_api
+                .handle_admin_request(AdminRequest::Block {
+                    target: target.clone(),
+                    interval: interval.clone(),
+                })
+                .await,
+            AdminResponse::Blocked
+        );
+        assert!(
+            handle
+                .is_blocked(BlockTargetId::Ip(ip), Timestamp::from_micros(50))
+                .await?
+        );
+
+        let listed = admin_api
+            .handle_admin_request(AdminRequest::ListBlocks)
+            .await;
+        assert_matches!(
+            listed,
+            AdminResponse::BlocksListed(blocks)
+                if blocks.len() == 1 && blocks[0].interval.end() == interval.end()
+        );
+
+        // Only lift the block for part of its span.
+        assert_matches!(
+            admin_api
+                .handle_admin_request(AdminRequest::Unblock {
+                    target,
+                    interval: InclusiveTimestampInterval::try_new(
+                        Timestamp::from_micros(0),
+                        Timestamp::from_micros(60),
+                    )?,
+                })
+                .await,
+            AdminResponse::Unblocked
+        );
+        assert!(
+            !handle
+                .is_blocked(BlockTargetId::Ip(ip), Timestamp::from_micros(50))
+                .await?
+        );
+        assert!(
+            handle
+                .is_blocked(BlockTargetId::Ip(ip), Timestamp::from_micros(80))
+                .await?
+        );
+
+        tokio::time::timeout(std::time::Duration::from_secs(1), handle.shutdown())
+            .await
+            .ok();
+        Ok(())
+    }
+
     // @todo fix test by using new InstallApp call
     // #[tokio::test(flavor = "multi_thread")]
     // async fn install_list_dna_app() {
@@ -1,4 +1,5 @@
 use super::InterfaceApi;
+use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::ExternalApiWireError;
 use crate::conductor::api::error::SerializationError;
@@ -9,6 +10,9 @@ use crate::conductor::ConductorHandle;
 use holochain_serialized_bytes::prelude::*;
 
 use holochain_types::prelude::*;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::sync::Arc;
 
 pub use holochain_conductor_api::*;
 
@@ -23,6 +27,17 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 
     // -- provided -- //
 
+    /// Whether a signal may be sent to the clients of this interface
+    async fn allows_signal(&self, _signal: &Signal) -> bool {
+        true
+    }
+
+    /// Whether a cell belongs to an app, so that a signal subscription for
+    /// the app only lets through signals from its cells
+    async fn app_has_cell(&self, _installed_app_id: &InstalledAppId, _cell_id: &CellId) -> bool {
+        true
+    }
+
     /// Deal with error cases produced by `handle_app_request_inner`
     async fn handle_app_request(&self, request: AppRequest) -> AppResponse {
         tracing::debug!("app request: {:?}", request);
@@ -41,12 +56,91 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 #[derive(Clone)]
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
+    /// The app which requests are restricted to, if any
+    app: Option<InstalledAppId>,
+    /// The cells apps were last seen to have, so that they needn't be
+    /// looked up for every request and signal
+    known_cells: Arc<parking_lot::RwLock<HashMap<InstalledAppId, KnownCells>>>,
+}
+
+/// The cells an app was seen to have, and the version of the conductor
+/// state they were seen in.
+struct KnownCells {
+    cells: HashSet<CellId>,
+    state_version: u64,
 }
 
 impl RealAppInterfaceApi {
     /// Create a new instance from a shared Conductor reference
     pub fn new(conductor_handle: ConductorHandle) -> Self {
-        Self { conductor_handle }
+        Self {
+            conductor_handle,
+            app: None,
+            known_cells: Default::default(),
+        }
+    }
+
+    /// Create a new instance which only accepts requests about one app,
+    /// and only sends signals from its cells.
+    pub fn restricted_to_app(
+        conductor_handle: ConductorHandle,
+        installed_app_id: InstalledAppId,
+    ) -> Self {
+        Self {
+            conductor_handle,
+            app: Some(installed_app_id),
+            known_cells: Default::default(),
+        }
+    }
+
+    /// Check that a request about an app may be made on this interface.
+    fn check_app(&self, installed_app_id: &InstalledAppId) -> ConductorApiResult<()> {
+        match &self.app {
+            Some(app) if app != installed_app_id => {
+                Err(ConductorApiError::AppInterfaceRestricted(app.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check that a request about a cell may be made on this interface.
+    async fn check_cell(&self, cell_id: &CellId) -> ConductorApiResult<()> {
+        match &self.app {
+            Some(app) if !self.lookup_app_cell(app, cell_id).await? => {
+                Err(ConductorApiError::AppInterfaceRestricted(app.clone()))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether a cell is one of an app's enabled cells. The app's cells are
+    /// only looked up again when the conductor state has changed since they
+    /// were last seen, as a clone may have been created, disabled or deleted.
+    async fn lookup_app_cell(
+        &self,
+        installed_app_id: &InstalledAppId,
+        cell_id: &CellId,
+    ) -> ConductorApiResult<bool> {
+        let state_version = self.conductor_handle.state_version();
+        if let Some(known) = self.known_cells.read().get(installed_app_id) {
+            if known.state_version == state_version {
+                return Ok(known.cells.contains(cell_id));
+            }
+        }
+        let state = self.conductor_handle.get_state().await?;
+        let cells: HashSet<CellId> = match state.installed_apps().get(installed_app_id) {
+            Some(installed_app) => installed_app.all_enabled_cells().cloned().collect(),
+            None => HashSet::new(),
+        };
+        let has_cell = cells.contains(cell_id);
+        self.known_cells.write().insert(
+            installed_app_id.clone(),
+            KnownCells {
+                cells,
+                state_version,
+            },
+        );
+        Ok(has_cell)
     }
 }
 
@@ -58,12 +152,16 @@ impl AppInterfaceApi for RealAppInterfaceApi {
         request: AppRequest,
     ) -> ConductorApiResult<AppResponse> {
         match request {
-            AppRequest::AppInfo { installed_app_id } => Ok(AppResponse::AppInfo(
-                self.conductor_handle
-                    .get_app_info(&installed_app_id)
-                    .await?,
-            )),
+            AppRequest::AppInfo { installed_app_id } => {
+                self.check_app(&installed_app_id)?;
+                Ok(AppResponse::AppInfo(
+                    self.conductor_handle
+                        .get_app_info(&installed_app_id)
+                        .await?,
+                ))
+            }
             AppRequest::CallZome(call) => {
+                self.check_cell(&call.cell_id).await?;
                 match self.conductor_handle.call_zome(*call.clone()).await? {
                     Ok(ZomeCallResponse::Ok(output)) => Ok(AppResponse::ZomeCalled(Box::new(output))),
                     Ok(ZomeCallResponse::Unauthorized(zome_call_authorization, _, zome_name, fn_name, _)) => Ok(AppResponse::Error(
@@ -86,6 +184,7 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                 }
             }
             AppRequest::CreateCloneCell(payload) => {
+                self.check_app(&payload.app_id)?;
                 let clone_cell = self
                     .conductor_handle
                     .clone()
@@ -94,6 +193,7 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                 Ok(AppResponse::CloneCellCreated(clone_cell))
             }
             AppRequest::DisableCloneCell(payload) => {
+                self.check_app(&payload.app_id)?;
                 self.conductor_handle
                     .clone()
                     .disable_clone_cell(&payload)
@@ -101,6 +201,7 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                 Ok(AppResponse::CloneCellDisabled)
             }
             AppRequest::EnableCloneCell(payload) => {
+                self.check_app(&payload.app_id)?;
                 let enabled_cell = self
                     .conductor_handle
                     .clone()
@@ -109,14 +210,41 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                 Ok(AppResponse::CloneCellEnabled(enabled_cell))
             }
             AppRequest::NetworkInfo(payload) => {
+                for dna_hash in &payload.dnas {
+                    let cell_id = CellId::new(dna_hash.clone(), payload.agent_pub_key.clone());
+                    self.check_cell(&cell_id).await?;
+                }
                 let info = self.conductor_handle.network_info(&payload).await?;
                 Ok(AppResponse::NetworkInfo(info))
             }
             AppRequest::ListWasmHostFunctions => Ok(AppResponse::ListWasmHostFunctions(
                 self.conductor_handle.list_wasm_host_functions().await?,
             )),
+            AppRequest::SubscribeSignals(subscription) => {
+                // The subscription itself is applied by the interface
+                // connection which received this request.
+                self.check_app(&subscription.installed_app_id)?;
+                self.conductor_handle
+                    .get_state()
+                    .await?
+                    .get_app(&subscription.installed_app_id)?;
+                Ok(AppResponse::SignalsSubscribed)
+            }
+        }
+    }
+
+    async fn allows_signal(&self, signal: &Signal) -> bool {
+        match (&self.app, signal) {
+            (Some(app), Signal::App { cell_id, .. }) => self.app_has_cell(app, cell_id).await,
+            _ => true,
         }
     }
+
+    async fn app_has_cell(&self, installed_app_id: &InstalledAppId, cell_id: &CellId) -> bool {
+        self.lookup_app_cell(installed_app_id, cell_id)
+            .await
+            .unwrap_or(false)
+    }
 }
 
 #[async_trait::async_trait]
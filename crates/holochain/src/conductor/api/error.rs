@@ -111,6 +111,10 @@ pub enum ConductorApiError {
     #[error(transparent)]
     RibosomeError(#[from] crate::core::ribosome::error::RibosomeError),
 
+    /// The app interface only accepts requests about another app.
+    #[error("This app interface only accepts requests about the app {0}")]
+    AppInterfaceRestricted(InstalledAppId),
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
@@ -19,6 +19,7 @@ use crate::core::ribosome::ZomeCallInvocation;
 use crate::core::workflow::call_zome_workflow;
 use crate::core::workflow::countersigning_workflow::countersigning_success;
 use crate::core::workflow::countersigning_workflow::incoming_countersigning;
+use crate::core::workflow::error::WorkflowError;
 use crate::core::workflow::genesis_workflow::genesis_workflow;
 use crate::core::workflow::initialize_zomes_workflow;
 use crate::core::workflow::CallZomeWorkflowArgs;
@@ -389,8 +390,17 @@ impl Cell {
                 payload,
                 nonce,
                 expires_at,
+                trace_context,
                 ..
             } => {
+                let span = info_span!("call_remote");
+                // Continue the trace of the remote caller.
+                #[cfg(feature = "otlp")]
+                if let Some(trace_context) = trace_context {
+                    holochain_trace::otlp::set_parent(&span, &trace_context);
+                }
+                #[cfg(not(feature = "otlp"))]
+                let _ = trace_context;
                 async {
                     let res = self
                         .handle_call_remote(
@@ -401,7 +411,7 @@ impl Cell {
                         .map_err(holochain_p2p::HolochainP2pError::other);
                     respond.respond(Ok(async move { res }.boxed().into()));
                 }
-                .instrument(debug_span!("call_remote"))
+                .instrument(span)
                 .await;
             }
 
@@ -656,14 +666,17 @@ impl Cell {
             .map_err(Into::into)
     }
 
-    #[instrument(skip(self, _dht_hash, _options))]
+    #[instrument(skip(self, options))]
     /// a remote node is asking us for metadata
     async fn handle_get_meta(
         &self,
-        _dht_hash: holo_hash::AnyDhtHash,
-        _options: holochain_p2p::event::GetMetaOptions,
+        dht_hash: holo_hash::AnyDhtHash,
+        options: holochain_p2p::event::GetMetaOptions,
     ) -> CellResult<MetadataSet> {
-        unimplemented!()
+        let db = self.space.dht_db.clone();
+        authority::handle_get_meta(db.into(), dht_hash, options)
+            .await
+            .map_err(Into::into)
     }
 
     #[instrument(skip(self, options))]
@@ -841,7 +854,7 @@ impl Cell {
     }
 
     /// Function called by the Conductor
-    // #[instrument(skip(self, call, workspace_lock))]
+    #[instrument(skip_all, fields(zome = %call.zome_name, function = %call.fn_name))]
     pub async fn call_zome(
         &self,
         call: ZomeCall,
@@ -924,7 +937,7 @@ impl Cell {
         // get the dna
         let ribosome = self.get_ribosome()?;
 
-        let dna_def = ribosome.dna_def().clone();
+        let dna_def = Arc::new(ribosome.dna_def().clone().into_content());
 
         // Create the workspace
         let workspace = SourceChainWorkspace::init_as_root(
@@ -934,7 +947,7 @@ impl Cell {
             self.cache().clone(),
             keystore.clone(),
             id.agent_pubkey().clone(),
-            Arc::new(dna_def.into_content()),
+            dna_def.clone(),
         )
         .await?;
 
@@ -953,10 +966,45 @@ impl Cell {
             signal_tx,
             cell_id: self.id.clone(),
         };
-        let init_result =
-            initialize_zomes_workflow(workspace, self.holochain_p2p_cell.clone(), keystore, args)
-                .await
-                .map_err(Box::new)?;
+        let init_result = match initialize_zomes_workflow(
+            workspace,
+            self.holochain_p2p_cell.clone(),
+            keystore.clone(),
+            args,
+        )
+        .await
+        {
+            Err(err @ WorkflowError::SourceChainError(SourceChainError::ChcHeadMoved(..))) => {
+                use holochain_p2p::HolochainP2pDnaT;
+                let chc = match self.holochain_p2p_cell.chc() {
+                    Some(chc) => chc,
+                    None => return Err(CellError::WorkflowError(Box::new(err))),
+                };
+                self.conductor_handle
+                    .clone()
+                    .repair_source_chain_from_chc(self.id.clone(), chc)
+                    .await
+                    .map_err(Box::new)?;
+                // Another device may have initialized the chain already, in
+                // which case there's nothing left to do once it's repaired.
+                // Init isn't run again, so it's up to the caller to retry.
+                let workspace = SourceChainWorkspace::new(
+                    self.authored_db().clone(),
+                    self.dht_db().clone(),
+                    self.space.dht_query_cache.clone(),
+                    self.cache().clone(),
+                    keystore,
+                    id.agent_pubkey().clone(),
+                    dna_def,
+                )
+                .await?;
+                if workspace.source_chain().zomes_initialized().await? {
+                    return Ok(());
+                }
+                return Err(CellError::WorkflowError(Box::new(err)));
+            }
+            result => result.map_err(Box::new)?,
+        };
         trace!(?init_result);
         match init_result {
             InitResult::Pass => {}
@@ -1011,9 +1059,8 @@ impl Cell {
         &self.space.cache_db
     }
 
-    #[cfg(any(test, feature = "test_utils"))]
     /// Get the triggers for the cell
-    /// Useful for testing when you want to
+    /// Useful when you want to
     /// Cause workflows to trigger
     pub(crate) fn triggers(&self) -> &QueueTriggers {
         &self.queue_triggers
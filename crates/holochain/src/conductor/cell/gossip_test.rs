@@ -37,6 +37,44 @@ async fn gossip_test() {
     assert_eq!(hashes.0.len(), 1);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn gossip_resumes_after_partition_heals() {
+    holochain_trace::test_run().ok();
+    // Rounds across the partition fail, so don't wait long to retry them
+    let config = SweetConductorConfig::standard().no_publish().tune(|tp| {
+        tp.gossip_round_timeout_ms = 1000;
+        tp.gossip_peer_on_error_next_gossip_delay_ms = 1000;
+    });
+    let mut conductors = SweetConductorBatch::from_config(2, config).await;
+
+    let (dna_file, _, _) =
+        SweetDnaFile::unique_from_inline_zomes(("zome", simple_create_read_zome())).await;
+
+    let apps = conductors.setup_app("app", &[dna_file]).await.unwrap();
+    let ((cell_1,), (cell_2,)) = apps.into_tuples();
+    conductors.exchange_peer_info().await;
+    conductors.partition(&[&[0], &[1]]).await;
+
+    let hash: ActionHash = conductors[0].call(&cell_1.zome("zome"), "create", ()).await;
+
+    // Wait until both sides have tried to reach each other across the partition
+    crate::wait_for_10s!(
+        conductors.iter().all(|c| c.network_faults().dropped() > 0),
+        |x: &bool| *x,
+        |x: bool| assert!(x)
+    );
+    let record: Option<Record> = conductors[1]
+        .call(&cell_2.zome("zome"), "read", hash.clone())
+        .await;
+    assert!(record.is_none());
+
+    conductors.heal();
+    consistency_60s([&cell_1, &cell_2]).await;
+
+    let record: Option<Record> = conductors[1].call(&cell_2.zome("zome"), "read", hash).await;
+    assert!(record.is_some());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn signature_smoke_test() {
     holochain_trace::test_run().ok();
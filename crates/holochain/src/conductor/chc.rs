@@ -21,22 +21,17 @@ const CHC_LOCAL_MAGIC_URL: &str = "local:";
 /// Build the appropriate CHC implementation.
 ///
 /// In particular, if the url is the magic string "local:", then a [`ChcLocal`]
-/// implementation will be used. Otherwise, if the url is set, and the CellId
-/// is "CHC-enabled", then a [`ChcRemote`] will be produced.
+/// implementation will be used. Otherwise, if the url is set, a [`ChcRemote`]
+/// will be produced. Whether a cell uses the CHC at all is up to the apps it
+/// belongs to.
 pub fn build_chc(url: Option<&Url>, keystore: MetaLairClient, cell_id: &CellId) -> Option<ChcImpl> {
-    // TODO: check if the agent key is Holo-hosted, otherwise return none
-    let is_holo_agent = true;
-    if is_holo_agent {
-        url.map(|url| {
-            if url.as_str() == CHC_LOCAL_MAGIC_URL {
-                chc_local(keystore, cell_id.clone())
-            } else {
-                chc_remote(url.clone(), keystore, cell_id)
-            }
-        })
-    } else {
-        None
-    }
+    url.map(|url| {
+        if url.as_str() == CHC_LOCAL_MAGIC_URL {
+            chc_local(keystore, cell_id.clone())
+        } else {
+            chc_remote(url.clone(), keystore, cell_id)
+        }
+    })
 }
 
 fn chc_local(keystore: MetaLairClient, cell_id: CellId) -> ChcImpl {
@@ -91,10 +91,13 @@ impl ChainHeadCoordinatorExt for ChcLocal {
 #[cfg(test)]
 mod tests {
     use holochain_conductor_api::conductor::ConductorConfig;
+    use holochain_conductor_api::AdminSignal;
     use isotest::Iso;
 
     use crate::{
+        conductor::api::error::{ConductorApiError, ConductorApiResult},
         conductor::chc::{ChcRemote, CHC_LOCAL_MAGIC_URL, CHC_LOCAL_MAP},
+        conductor::error::ConductorError,
         sweettest::*,
         test_utils::valid_arbitrary_chain,
     };
@@ -327,31 +330,70 @@ mod tests {
             )
             .await;
 
-        // This should fail and require triggering a CHC sync
-        let hash1: Result<ActionHash, _> = conductors[1]
-            .call_fallible(
-                &c1.zome(SweetInlineZomes::COORDINATOR),
-                "create_string",
-                AppString::new("one"),
-            )
-            .await;
+        let mut signals0 = conductors[0].raw_handle().subscribe_admin_signals();
+        let mut signals1 = conductors[1].raw_handle().subscribe_admin_signals();
+        let mut signals2 = conductors[2].raw_handle().subscribe_admin_signals();
 
-        dbg!(&hash1);
-
-        regex::Regex::new(
-            r#".*ChcHeadMoved\("SourceChain::flush", InvalidChain\(4, ActionHash\([a-zA-Z0-9-_]+\).*"#
-        ).unwrap().captures(&format!("{:?}", hash1)).unwrap();
+        // The chain is behind the CHC, so init is rejected, but the chain was
+        // already initialized by the other conductor, so once the missing
+        // records are pulled from the CHC the call goes ahead
+        for (conductor, cell, string) in
+            [(&conductors[1], &c1, "one"), (&conductors[2], &c2, "two")]
+        {
+            let _: ActionHash = conductor
+                .call(
+                    &cell.zome(SweetInlineZomes::COORDINATOR),
+                    "create_string",
+                    AppString::new(string),
+                )
+                .await;
+        }
 
-        // This should trigger a CHC sync
-        let hash2: Result<ActionHash, _> = conductors[2]
-            .call_fallible(
-                &c2.zome(SweetInlineZomes::COORDINATOR),
-                "create_string",
-                AppString::new("two"),
+        // A commit with relaxed ordering is rebased onto the repaired chain
+        // and flushed again, so it succeeds on the first attempt
+        let _: ActionHash = conductors[0]
+            .call(
+                &c0.zome(SweetInlineZomes::COORDINATOR),
+                "create_string_relaxed",
+                AppString::new("three"),
             )
             .await;
 
-        assert_eq!(format!("{:?}", hash1), format!("{:?}", hash2));
+        // A commit with strict ordering is rejected after the repair, and
+        // succeeds when the call is retried
+        let zome = c1.zome(SweetInlineZomes::COORDINATOR);
+        let result: ConductorApiResult<ActionHash> = conductors[1]
+            .call_fallible(&zome, "create_string", AppString::new("four"))
+            .await;
+        assert!(result.is_err());
+        let _: ActionHash = conductors[1]
+            .call(&zome, "create_string", AppString::new("four"))
+            .await;
+
+        // - Each repair is reported with the records which were grafted
+        let grafted = |signal| match signal {
+            AdminSignal::ChcRepaired {
+                cell_id: repaired,
+                grafted,
+            } => {
+                assert_eq!(&repaired, cell_id);
+                grafted.len()
+            }
+        };
+        // The init and "zero" records
+        assert_eq!(grafted(signals1.recv().await.unwrap()), 2);
+        // Those and the "one" record
+        assert_eq!(grafted(signals2.recv().await.unwrap()), 3);
+        // The "one" and "two" records
+        assert_eq!(grafted(signals0.recv().await.unwrap()), 2);
+        // The "two" and "three" records
+        assert_eq!(grafted(signals1.recv().await.unwrap()), 2);
+
+        conductors[0]
+            .raw_handle()
+            .chc_sync(cell_id.clone(), None)
+            .await
+            .unwrap();
 
         conductors[1]
             .raw_handle()
@@ -378,7 +420,7 @@ mod tests {
             .await
             .unwrap();
 
-        assert_eq!(dump0.source_chain_dump.records.len(), 5);
+        assert_eq!(dump0.source_chain_dump.records.len(), 9);
         assert_eq!(
             dump0.source_chain_dump.records,
             dump1.source_chain_dump.records
@@ -390,22 +432,277 @@ mod tests {
     }
 
     #[tokio::test(flavor = "multi_thread")]
-    #[ignore = "this test requires a remote service, so it should only be run manually"]
+    async fn attach_and_detach_chc() {
+        use holochain::test_utils::inline_zomes::{simple_crud_zome, AppString};
+
+        let mut config = ConductorConfig::default();
+        config.chc_url = Some(url2::Url2::parse(CHC_LOCAL_MAGIC_URL));
+        let mut conductor = SweetConductor::from_config(config).await;
+
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+        let (agent, _) = SweetAgents::alice_and_bob();
+
+        let (cell,) = conductor
+            .setup_app_for_agent("app", agent.clone(), [&dna_file])
+            .await
+            .unwrap()
+            .into_tuple();
+        let cell_id = cell.cell_id().clone();
+        let chc = CHC_LOCAL_MAP.lock().get(&cell_id).unwrap().clone();
+        let local_chain = || async {
+            conductor
+                .dump_full_cell_state(&cell_id, None)
+                .await
+                .unwrap()
+                .source_chain_dump
+                .records
+                .into_iter()
+                .map(|r| r.action_address)
+                .collect::<Vec<_>>()
+        };
+        let chc_chain = || async {
+            chc.clone()
+                .get_record_data(None)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|r| r.action_address().clone())
+                .collect::<Vec<_>>()
+        };
+        let uses_chc = || async {
+            conductor
+                .raw_handle()
+                .get_state()
+                .await
+                .unwrap()
+                .cell_uses_chc(&cell_id)
+        };
+        assert!(uses_chc().await);
+
+        // - A detached cell commits without the CHC
+        conductor
+            .raw_handle()
+            .detach_chc(cell_id.clone())
+            .await
+            .unwrap();
+        assert!(!uses_chc().await);
+        let _: ActionHash = conductor
+            .call(
+                &cell.zome(SweetInlineZomes::COORDINATOR),
+                "create_string",
+                AppString::new("detached"),
+            )
+            .await;
+        assert_eq!(chc_chain().await.len(), 3);
+        assert!(local_chain().await.len() > 3);
+
+        // - Attaching sends the CHC the records it is missing
+        conductor
+            .raw_handle()
+            .attach_chc(cell_id.clone())
+            .await
+            .unwrap();
+        assert!(uses_chc().await);
+        assert_eq!(chc_chain().await, local_chain().await);
+
+        // - The chain is unlocked again once attached, and commits reach the CHC
+        let _: ActionHash = conductor
+            .call(
+                &cell.zome(SweetInlineZomes::COORDINATOR),
+                "create_string",
+                AppString::new("attached"),
+            )
+            .await;
+        assert_eq!(chc_chain().await, local_chain().await);
+
+        // - A cell can't be detached while the CHC is ahead of it
+        let top = local_chain().await;
+        let izc = InitZomesComplete {
+            author: agent.clone(),
+            timestamp: Timestamp::now(),
+            action_seq: top.len() as u32,
+            prev_action: top.last().unwrap().clone(),
+        };
+        let new_action = ActionHashed::from_content_sync(Action::InitZomesComplete(izc));
+        let new_action = SignedActionHashed::sign(&conductor.keystore(), new_action)
+            .await
+            .unwrap();
+        chc.clone()
+            .add_records(vec![Record::new(new_action, None)])
+            .await
+            .unwrap();
+        assert!(matches!(
+            conductor.raw_handle().detach_chc(cell_id.clone()).await,
+            Err(ConductorApiError::ConductorError(
+                ConductorError::ChcOutOfSync(_)
+            ))
+        ));
+        assert!(uses_chc().await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn attach_and_detach_chc_over_the_admin_api() {
+        use crate::conductor::api::{AdminInterfaceApi, RealAdminInterfaceApi};
+        use holochain::test_utils::inline_zomes::{simple_crud_zome, AppString};
+        use holochain_conductor_api::{AdminRequest, AdminResponse, ExternalApiWireError};
+
+        let internal_error = |err: ConductorError| ConductorApiError::from(err).to_string();
+        let is_error = |response: AdminResponse, expected: String| matches!(response, AdminResponse::Error(ExternalApiWireError::InternalError(e)) if e == expected);
+
+        let mut config = ConductorConfig::default();
+        config.chc_url = Some(url2::Url2::parse(CHC_LOCAL_MAGIC_URL));
+        let mut conductor = SweetConductor::from_config(config).await;
+        let admin_api = RealAdminInterfaceApi::new(conductor.raw_handle());
+
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+        let (agent, _) = SweetAgents::alice_and_bob();
+        let (cell,) = conductor
+            .setup_app_for_agent("app", agent.clone(), [&dna_file])
+            .await
+            .unwrap()
+            .into_tuple();
+        let cell_id = cell.cell_id().clone();
+        let chc = CHC_LOCAL_MAP.lock().get(&cell_id).unwrap().clone();
+
+        // - Only cells of installed apps can be attached
+        let unknown_cell_id = CellId::new(dna_file.dna_hash().clone(), fixt!(AgentPubKey));
+        let response = admin_api
+            .handle_admin_request(AdminRequest::AttachChc {
+                cell_id: unknown_cell_id.clone(),
+            })
+            .await;
+        assert!(is_error(
+            response,
+            internal_error(ConductorError::CellMissing(unknown_cell_id))
+        ));
+
+        let response = admin_api
+            .handle_admin_request(AdminRequest::DetachChc {
+                cell_id: cell_id.clone(),
+            })
+            .await;
+        assert!(matches!(response, AdminResponse::ChcDetached));
+
+        // - A cell can't be attached if its chain has diverged from the CHC's
+        let _: ActionHash = conductor
+            .call(
+                &cell.zome(SweetInlineZomes::COORDINATOR),
+                "create_string",
+                AppString::new("detached"),
+            )
+            .await;
+        let chc_records = chc.clone().get_record_data(None).await.unwrap();
+        let izc = InitZomesComplete {
+            author: agent.clone(),
+            timestamp: Timestamp::now(),
+            action_seq: chc_records.len() as u32,
+            prev_action: chc_records.last().unwrap().action_address().clone(),
+        };
+        let new_action = ActionHashed::from_content_sync(Action::InitZomesComplete(izc));
+        let new_action = SignedActionHashed::sign(&conductor.keystore(), new_action)
+            .await
+            .unwrap();
+        chc.clone()
+            .add_records(vec![Record::new(new_action, None)])
+            .await
+            .unwrap();
+        let response = admin_api
+            .handle_admin_request(AdminRequest::AttachChc {
+                cell_id: cell_id.clone(),
+            })
+            .await;
+        assert!(is_error(
+            response,
+            internal_error(ConductorError::ChcOutOfSync(cell_id.clone()))
+        ));
+        assert!(!conductor
+            .raw_handle()
+            .get_state()
+            .await
+            .unwrap()
+            .cell_uses_chc(&cell_id));
+
+        // - Nothing can be attached without a CHC
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let admin_api = RealAdminInterfaceApi::new(conductor.raw_handle());
+        let (cell,) = conductor
+            .setup_app("app", [&dna_file])
+            .await
+            .unwrap()
+            .into_tuple();
+        let response = admin_api
+            .handle_admin_request(AdminRequest::AttachChc {
+                cell_id: cell.cell_id().clone(),
+            })
+            .await;
+        assert!(is_error(
+            response,
+            internal_error(ConductorError::ChcNotConfigured)
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn apps_installed_before_chc_cells_are_migrated_on_startup() {
+        use holochain::test_utils::inline_zomes::simple_crud_zome;
+
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+
+        for has_chc in [true, false] {
+            let mut config = ConductorConfig::default();
+            if has_chc {
+                config.chc_url = Some(url2::Url2::parse(CHC_LOCAL_MAGIC_URL));
+            }
+            let mut conductor = SweetConductor::from_config(config).await;
+            let (cell,) = conductor
+                .setup_app("app", [&dna_file])
+                .await
+                .unwrap()
+                .into_tuple();
+            let cell_id = cell.cell_id().clone();
+
+            // Store the app as it was before the CHC could be chosen per cell
+            conductor
+                .raw_handle()
+                .update_state(|mut state| {
+                    let app = state.get_app_mut(&"app".to_string())?;
+                    let mut legacy = serde_json::to_value(&**app).unwrap();
+                    legacy.as_object_mut().unwrap().remove("chc_cells");
+                    **app = serde_json::from_value(legacy).unwrap();
+                    Ok(state)
+                })
+                .await
+                .unwrap();
+
+            conductor.shutdown().await;
+            conductor.startup().await;
+
+            // - Its cells use the CHC only if the conductor has one
+            let state = conductor.raw_handle().get_state().await.unwrap();
+            assert_eq!(state.cell_uses_chc(&cell_id), has_chc);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
     async fn test_add_records_remote() {
+        let (driver, addr, shutdown) = holochain_chc_server::run(([127, 0, 0, 1], 0), None)
+            .await
+            .unwrap();
+        tokio::spawn(driver);
+
         let keystore = test_keystore();
         let agent = fake_agent_pubkey_1();
+
+        let mut g = random_generator();
+
+        let chain = valid_arbitrary_chain(&mut g, keystore.clone(), agent.clone(), 20).await;
+
         let cell_id = CellId::new(fixt!(DnaHash), agent.clone());
         let chc = Arc::new(ChcRemote::new(
-            url::Url::parse("http://127.0.0.1:40845/v1/").unwrap(),
-            // url::Url::parse("https://chc.dev.holotest.net/v1/").unwrap(),
+            url::Url::parse(&format!("http://{}/v1/", addr)).unwrap(),
             keystore.clone(),
             &cell_id,
         ));
 
-        let mut g = random_generator();
-
-        let chain = valid_arbitrary_chain(&mut g, keystore, agent, 20).await;
-
         let t0 = &chain[0..3];
         let t1 = &chain[3..6];
         let t2 = &chain[6..9];
@@ -456,5 +753,17 @@ mod tests {
             chc.clone().get_record_data(Some(hash(13))).await.unwrap(),
             &[]
         );
+
+        // - Records signed by another agent are refused
+        let other_cell_id = CellId::new(cell_id.dna_hash().clone(), fake_agent_pubkey_2());
+        let imposter = Arc::new(ChcRemote::new(
+            url::Url::parse(&format!("http://{}/v1/", addr)).unwrap(),
+            keystore.clone(),
+            &other_cell_id,
+        ));
+        assert!(imposter.clone().add_records(t0.to_vec()).await.is_err());
+        assert_eq!(imposter.head().await.unwrap(), None);
+
+        shutdown();
     }
 }
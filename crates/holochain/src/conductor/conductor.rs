@@ -37,11 +37,13 @@ use super::config::InterfaceDriver;
 use super::entry_def_store::get_entry_defs;
 use super::error::ConductorError;
 use super::interface::error::InterfaceResult;
+use super::interface::websocket::check_app_handshake;
 use super::interface::websocket::spawn_admin_interface_tasks;
 use super::interface::websocket::spawn_app_interface_task;
 use super::interface::websocket::spawn_websocket_listener;
 use super::interface::websocket::SIGNAL_BUFFER_SIZE;
 use super::interface::AppInterfaceRuntime;
+use super::interface::InterfaceCloser;
 use super::interface::SignalBroadcaster;
 use super::manager::TaskManagerResult;
 use super::p2p_agent_store;
@@ -78,7 +80,11 @@ use futures::future::FutureExt;
 use futures::future::TryFutureExt;
 use futures::stream::StreamExt;
 use holo_hash::DnaHash;
+use holochain_conductor_api::conductor::DpkiConfig;
 use holochain_conductor_api::conductor::KeystoreConfig;
+use holochain_conductor_api::AdminSignal;
+use holochain_conductor_api::AllowedOrigins;
+use holochain_conductor_api::AppAuthenticationToken;
 use holochain_conductor_api::AppInfo;
 use holochain_conductor_api::AppStatusFilter;
 use holochain_conductor_api::ClonedCell;
@@ -86,6 +92,7 @@ use holochain_conductor_api::FullIntegrationStateDump;
 use holochain_conductor_api::FullStateDump;
 use holochain_conductor_api::IntegrationStateDump;
 use holochain_conductor_api::JsonDump;
+use holochain_conductor_api::DEFAULT_APP_AUTHENTICATION_TOKEN_EXPIRY_SECONDS;
 use holochain_keystore::lair_keystore::spawn_lair_keystore;
 use holochain_keystore::lair_keystore::spawn_lair_keystore_in_proc;
 use holochain_keystore::test_keystore::spawn_test_keystore;
@@ -103,14 +110,17 @@ use holochain_state::prelude::from_blob;
 use holochain_state::prelude::StateMutationResult;
 use holochain_state::prelude::StateQueryResult;
 use holochain_state::prelude::*;
+use holochain_state::query::map_sql_warrant_op;
 use holochain_state::source_chain;
 use holochain_types::prelude::{wasm, *};
+use holochain_websocket::HandshakeCheck;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 use kitsune_p2p::KitsuneP2pError;
 use kitsune_p2p_types::config::JOIN_NETWORK_TIMEOUT;
 use rusqlite::Transaction;
 use std::borrow::Borrow;
 use std::collections::{HashMap, HashSet};
+use std::net::Ipv4Addr;
 use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use tokio::sync::mpsc::error::SendError;
@@ -134,6 +144,9 @@ mod conductor_services;
 
 pub use conductor_services::*;
 
+#[cfg(feature = "sqlite-encrypted")]
+mod database_key;
+
 pub use accessor_impls::*;
 pub use app_impls::*;
 pub use app_status_impls::*;
@@ -149,6 +162,8 @@ pub use state_impls::*;
 
 mod graft_records_onto_source_chain;
 
+mod migrate_agent;
+
 /// A list of Cells which failed to start, and why
 pub type CellStartupErrors = Vec<(CellId, CellError)>;
 
@@ -219,9 +234,21 @@ pub struct Conductor {
     /// the dynamically allocated port later.
     admin_websocket_ports: RwShare<Vec<u16>>,
 
+    /// The admin interfaces this conductor has open, by the driver they
+    /// were bound with.
+    admin_interfaces: RwShare<Vec<(InterfaceDriver, InterfaceCloser)>>,
+
+    /// Broadcasts [`AdminSignal`]s to every admin interface connection.
+    admin_signal_tx: tokio::sync::broadcast::Sender<AdminSignal>,
+
     /// Collection app interface data, keyed by id
     app_interfaces: RwShare<HashMap<AppInterfaceId, AppInterfaceRuntime>>,
 
+    /// The unused tokens for connecting to app interfaces, with their expiry
+    /// and the app whose interfaces they are for, if any
+    app_authentication_tokens:
+        RwShare<HashMap<AppAuthenticationToken, (Timestamp, Option<InstalledAppId>)>>,
+
     /// The interface to the task manager
     task_manager: TaskManagerClient,
 
@@ -244,6 +271,10 @@ pub struct Conductor {
     scheduler: Arc<parking_lot::Mutex<Option<tokio::task::JoinHandle<()>>>>,
 
     pub(crate) services: RwShare<Option<ConductorServices>>,
+
+    /// Faults injected on the messages this conductor sends, for testing
+    #[cfg(any(test, feature = "test_utils"))]
+    network_faults: kitsune_p2p::fault::NetworkFaults,
 }
 
 impl Conductor {
@@ -281,16 +312,21 @@ mod startup_shutdown_impls {
                 config,
                 shutting_down: Arc::new(AtomicBool::new(false)),
                 app_interfaces: RwShare::new(HashMap::new()),
+                app_authentication_tokens: RwShare::new(HashMap::new()),
                 task_manager: TaskManagerClient::new(outcome_sender),
                 // Must be initialized later, since it requires an Arc<Conductor>
                 outcomes_task: RwShare::new(None),
                 admin_websocket_ports: RwShare::new(Vec::new()),
+                admin_interfaces: RwShare::new(Vec::new()),
+                admin_signal_tx: tokio::sync::broadcast::channel(SIGNAL_BUFFER_SIZE).0,
                 scheduler: Arc::new(parking_lot::Mutex::new(None)),
                 ribosome_store,
                 keystore,
                 holochain_p2p,
                 post_commit,
                 services: RwShare::new(None),
+                #[cfg(any(test, feature = "test_utils"))]
+                network_faults: Default::default(),
             }
         }
 
@@ -336,6 +372,7 @@ mod startup_shutdown_impls {
             admin_configs: Vec<AdminInterfaceConfig>,
         ) -> ConductorResult<CellStartupErrors> {
             self.load_dnas().await?;
+            self.migrate_chc_cells().await?;
 
             // Start the task manager
             self.outcomes_task.share_mut(|lock| {
@@ -353,23 +390,97 @@ mod startup_shutdown_impls {
                 dpki.expect_key_mutation()
                     .returning(|_, _| box_fut_plain(Ok(())));
 
-                let app_store = MockAppStoreService::new();
+                let app_store_dir: std::path::PathBuf = self.config.environment_path.clone().into();
 
                 *services = Some(ConductorServices {
                     dpki: Arc::new(dpki),
-                    app_store: Arc::new(app_store),
+                    app_store: AppStoreBuiltin::new(app_store_dir.join("app_store")),
                 });
             });
 
             self.clone().add_admin_interfaces(admin_configs).await?;
             self.clone().startup_app_interfaces().await?;
 
+            // DPKI has to be running before any other cell starts, so that
+            // their genesis and validation are checked against it.
+            if let Some(dpki_config) = self.config.dpki.clone() {
+                self.clone().initialize_dpki(dpki_config).await?;
+            }
+
             // We don't care what fx are returned here, since all cells need to
             // be spun up
             let _ = self.start_paused_apps().await?;
 
             self.process_app_status_fx(AppStatusFx::SpinUp, None).await
         }
+
+        /// Install the DPKI DNA if it isn't installed yet, start its cell, and
+        /// switch the DPKI service over to using it. No other app's cells are
+        /// started.
+        ///
+        /// Until this is done, every key is treated as valid, which is also
+        /// what allows the DPKI cell itself to pass genesis.
+        async fn initialize_dpki(self: Arc<Self>, config: DpkiConfig) -> ConductorResult<()> {
+            let DpkiConfig {
+                instance_id,
+                dna_path,
+                network_seed,
+                ..
+            } = config;
+
+            let installed = self.get_state().await?.get_app(&instance_id).is_ok();
+            if !installed {
+                let manifest = AppManifestCurrent {
+                    name: instance_id.clone(),
+                    description: None,
+                    roles: vec![AppRoleManifest {
+                        name: DPKI_ROLE_NAME.into(),
+                        dna: AppRoleDnaManifest {
+                            location: Some(DnaLocation::Path(dna_path)),
+                            modifiers: DnaModifiersOpt::none(),
+                            installed_hash: None,
+                            clone_limit: 0,
+                        },
+                        provisioning: Some(CellProvisioning::Create { deferred: false }),
+                    }],
+                };
+                let bundle =
+                    AppBundle::new(manifest.into(), vec![], std::path::PathBuf::from(".")).await?;
+                let agent_key = self.keystore().new_sign_keypair_random().await?;
+                self.clone()
+                    .install_app_bundle(InstallAppPayload {
+                        agent_key,
+                        source: AppBundleSource::Bundle(bundle),
+                        installed_app_id: Some(instance_id.clone()),
+                        network_seed,
+                        use_chc: false,
+                        membrane_proofs: HashMap::new(),
+                    })
+                    .await?;
+            }
+
+            let (app, _) = self
+                .transition_app_status(instance_id.clone(), AppStatusTransition::Enable)
+                .await?;
+            let cell_id = app.role(&DPKI_ROLE_NAME.to_string())?.cell_id().clone();
+            if let Some((_, err)) = self
+                .clone()
+                .create_and_add_initialized_cells_for_running_apps(Some(&instance_id))
+                .await?
+                .into_iter()
+                .next()
+            {
+                return Err(err.into());
+            }
+
+            self.services.share_mut(|services| {
+                if let Some(services) = services {
+                    services.dpki = DeepkeyBuiltin::new(self.clone(), cell_id);
+                }
+            });
+            tracing::info!(app_id = %instance_id, "DPKI service is running");
+            Ok(())
+        }
     }
 }
 
@@ -386,44 +497,87 @@ mod interface_impls {
         ) -> ConductorResult<()> {
             let admin_api = RealAdminInterfaceApi::new(self.clone());
             let tm = self.task_manager();
+            let signal_tx = self.admin_signal_tx.clone();
 
             // Closure to process each admin config item
             let spawn_from_config = |AdminInterfaceConfig { driver, .. }| {
                 let admin_api = admin_api.clone();
                 let tm = tm.clone();
+                let signal_tx = signal_tx.clone();
                 async move {
-                    match driver {
-                        InterfaceDriver::Websocket { port } => {
-                            let (listener_handle, listener) =
-                                spawn_websocket_listener(port).await?;
-                            let port = listener_handle.local_addr().port().unwrap_or(port);
-                            spawn_admin_interface_tasks(
-                                tm.clone(),
-                                listener_handle,
-                                listener,
-                                admin_api.clone(),
-                                port,
-                            );
-
-                            InterfaceResult::Ok(port)
+                    let (listener_handle, listener, driver) =
+                        spawn_websocket_listener(&driver, None).await?;
+                    let closer = spawn_admin_interface_tasks(
+                        tm.clone(),
+                        listener_handle,
+                        listener,
+                        admin_api.clone(),
+                        signal_tx,
+                    );
+
+                    // Only the ports which can be reached at `ws://127.0.0.1`
+                    // are recorded, as that is where their users connect.
+                    let port = match &driver {
+                        InterfaceDriver::Websocket {
+                            port,
+                            bind_address,
+                            tls: None,
+                        } if bind_address.map_or(true, |addr| {
+                            addr == Ipv4Addr::LOCALHOST || addr == Ipv4Addr::UNSPECIFIED
+                        }) =>
+                        {
+                            Some(*port)
                         }
-                    }
+                        _ => None,
+                    };
+                    InterfaceResult::Ok((driver, closer, port))
                 }
             };
 
             // spawn interface tasks, collect their JoinHandles,
             // panic on errors.
-            let ports: Result<Vec<_>, _> =
+            let interfaces: Result<Vec<_>, _> =
                 future::join_all(configs.into_iter().map(spawn_from_config))
                     .await
                     .into_iter()
                     .collect();
             // Exit if the admin interfaces fail to be created
-            let ports = ports.map_err(Box::new)?;
+            let interfaces = interfaces.map_err(Box::new)?;
+
+            for (driver, closer, port) in interfaces {
+                if let Some(port) = port {
+                    self.add_admin_port(port);
+                }
+                self.admin_interfaces
+                    .share_mut(|admin_interfaces| admin_interfaces.push((driver, closer)));
+            }
+            Ok(())
+        }
 
-            for p in ports {
-                self.add_admin_port(p);
+        /// Close the admin interface listening with a driver, and every
+        /// connection to it. The last admin interface can't be removed,
+        /// so that the conductor can still be administered.
+        pub fn remove_admin_interface(&self, driver: &InterfaceDriver) -> ConductorResult<()> {
+            let (driver, closer) = self.admin_interfaces.share_mut(|admin_interfaces| {
+                let index = admin_interfaces
+                    .iter()
+                    .position(|(d, _)| d.listens_at(driver))
+                    .ok_or_else(|| ConductorError::AdminInterfaceNotFound(driver.clone()))?;
+                if admin_interfaces.len() == 1 {
+                    return Err(ConductorError::other(
+                        "the last admin interface can't be removed",
+                    ));
+                }
+                Ok(admin_interfaces.remove(index))
+            })?;
+            if let Some(port) = driver.port() {
+                self.admin_websocket_ports
+                    .share_mut(|ports| ports.retain(|p| *p != port));
             }
+            // The interface may have closed already if the conductor is
+            // shutting down.
+            let _ = closer.send(());
+            tracing::debug!(?driver, "Admin interface removed");
             Ok(())
         }
 
@@ -435,24 +589,88 @@ mod interface_impls {
             self: Arc<Self>,
             port: either::Either<u16, AppInterfaceId>,
         ) -> ConductorResult<u16> {
-            let interface_id = match port {
-                either::Either::Left(port) => AppInterfaceId::new(port),
-                either::Either::Right(id) => id,
+            let (interface_id, config) = match port {
+                either::Either::Left(port) => (
+                    AppInterfaceId::new(port),
+                    AppInterfaceConfig::websocket(port),
+                ),
+                either::Either::Right(id) => {
+                    // Restart the interface as it was configured, but with
+                    // the port it was asked for rather than the one it got.
+                    let config = match self.get_state().await?.interface_by_id(&id) {
+                        Some(mut config) => {
+                            if let InterfaceDriver::Websocket { port, .. } = &mut config.driver {
+                                *port = id.port();
+                            }
+                            config
+                        }
+                        None => AppInterfaceConfig::websocket(id.port()),
+                    };
+                    (id, config)
+                }
+            };
+            let driver = self.spawn_app_interface(interface_id, config).await?;
+            Ok(driver.port().unwrap_or(0))
+        }
+
+        /// Spawn a new app interface task which listens as described by the
+        /// driver, like [`Conductor::add_app_interface`]. Clients may only
+        /// connect from the allowed origins, and if an app is given, only
+        /// make requests about that app.
+        /// Returns the driver with the port which was bound.
+        pub async fn add_app_interface_with_driver(
+            self: Arc<Self>,
+            driver: InterfaceDriver,
+            allowed_origins: AllowedOrigins,
+            installed_app_id: Option<InstalledAppId>,
+        ) -> ConductorResult<InterfaceDriver> {
+            let interface_id = AppInterfaceId::new(driver.port().unwrap_or(0));
+            let config = AppInterfaceConfig {
+                allowed_origins,
+                installed_app_id,
+                ..AppInterfaceConfig::new(driver)
+            };
+            self.spawn_app_interface(interface_id, config).await
+        }
+
+        async fn spawn_app_interface(
+            self: Arc<Self>,
+            interface_id: AppInterfaceId,
+            mut config: AppInterfaceConfig,
+        ) -> ConductorResult<InterfaceDriver> {
+            tracing::debug!("Attaching interface {:?}", config);
+            let app_api = match &config.installed_app_id {
+                Some(app_id) => {
+                    RealAppInterfaceApi::restricted_to_app(self.clone(), app_id.clone())
+                }
+                None => RealAppInterfaceApi::new(self.clone()),
             };
-            let port = interface_id.port();
-            tracing::debug!("Attaching interface {}", port);
-            let app_api = RealAppInterfaceApi::new(self.clone());
             // This receiver is thrown away because we can produce infinite new
             // receivers from the Sender
             let (signal_tx, _r) = tokio::sync::broadcast::channel(SIGNAL_BUFFER_SIZE);
 
+            let allowed_origins = config.allowed_origins.clone();
+            let installed_app_id = config.installed_app_id.clone();
+            let conductor = self.clone();
+            let handshake_check = HandshakeCheck::new(move |request| {
+                check_app_handshake(request, &allowed_origins, |token| {
+                    conductor.authenticate_app_token(token, installed_app_id.as_ref())
+                })
+            });
+
             let tm = self.task_manager();
 
             // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
-            let port = spawn_app_interface_task(tm.clone(), port, app_api, signal_tx.clone())
-                .await
-                .map_err(Box::new)?;
-            let interface = AppInterfaceRuntime::Websocket { signal_tx };
+            let (driver, closer) = spawn_app_interface_task(
+                tm.clone(),
+                &config.driver,
+                handshake_check,
+                app_api,
+                signal_tx.clone(),
+            )
+            .await
+            .map_err(Box::new)?;
+            let interface = AppInterfaceRuntime::Websocket { signal_tx, closer };
 
             self.app_interfaces.share_mut(|app_interfaces| {
                 if app_interfaces.contains_key(&interface_id) {
@@ -464,14 +682,85 @@ mod interface_impls {
                 app_interfaces.insert(interface_id.clone(), interface);
                 Ok(())
             })?;
-            let config = AppInterfaceConfig::websocket(port);
+            config.driver = driver.clone();
             self.update_state(|mut state| {
                 state.app_interfaces.insert(interface_id, config);
                 Ok(state)
             })
             .await?;
-            tracing::debug!("App interface added at port: {}", port);
-            Ok(port)
+            tracing::debug!("App interface added: {:?}", driver);
+            Ok(driver)
+        }
+
+        /// Close the app interface listening with a driver, and every
+        /// connection to it, and remove it from the conductor state so that
+        /// it isn't opened again on restart.
+        pub async fn detach_app_interface(&self, driver: InterfaceDriver) -> ConductorResult<()> {
+            let (_, interface_id) = self
+                .update_state_prime(move |mut state| {
+                    let interface_id = state
+                        .app_interfaces
+                        .iter()
+                        .find(|(_, config)| config.driver.listens_at(&driver))
+                        .map(|(id, _)| id.clone())
+                        .ok_or(ConductorError::AppInterfaceNotFound(driver))?;
+                    state.app_interfaces.remove(&interface_id);
+                    Ok((state, interface_id))
+                })
+                .await?;
+            let interface = self
+                .app_interfaces
+                .share_mut(|app_interfaces| app_interfaces.remove(&interface_id));
+            if let Some(AppInterfaceRuntime::Websocket { closer, .. }) = interface {
+                let _ = closer.send(());
+            }
+            tracing::debug!(?interface_id, "App interface detached");
+            Ok(())
+        }
+
+        /// Issue a token which a client can use once to connect to an app
+        /// interface before it expires. A token for an app is only accepted
+        /// by interfaces for that app, and any other only by interfaces open
+        /// to every app.
+        pub fn issue_app_authentication_token(
+            &self,
+            expiry_seconds: Option<u64>,
+            installed_app_id: Option<InstalledAppId>,
+        ) -> ConductorResult<(AppAuthenticationToken, Timestamp)> {
+            let expiry = std::time::Duration::from_secs(
+                expiry_seconds.unwrap_or(DEFAULT_APP_AUTHENTICATION_TOKEN_EXPIRY_SECONDS),
+            );
+            let expires_at = (Timestamp::now() + expiry).map_err(ConductorError::other)?;
+            let token: AppAuthenticationToken = rand::random::<[u8; 32]>()
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect();
+            let now = Timestamp::now();
+            self.app_authentication_tokens.share_mut(|tokens| {
+                tokens.retain(|_, (expires_at, _)| *expires_at > now);
+                tokens.insert(token.clone(), (expires_at, installed_app_id));
+            });
+            Ok((token, expires_at))
+        }
+
+        /// Use up a token for connecting to an app interface for an app,
+        /// or open to every app, returning whether it was valid there.
+        /// A token for another interface is left for that one.
+        pub(crate) fn authenticate_app_token(
+            &self,
+            token: &str,
+            installed_app_id: Option<&InstalledAppId>,
+        ) -> bool {
+            let now = Timestamp::now();
+            self.app_authentication_tokens.share_mut(|tokens| {
+                tokens.retain(|_, (expires_at, _)| *expires_at > now);
+                match tokens.get(token) {
+                    Some((_, app)) if app.as_ref() == installed_app_id => {
+                        tokens.remove(token).is_some()
+                    }
+                    _ => false,
+                }
+            })
         }
 
         /// Returns a port which is guaranteed to have a websocket listener with an Admin interface
@@ -480,14 +769,14 @@ mod interface_impls {
             self.admin_websocket_ports.share_ref(|p| p.first().copied())
         }
 
-        /// Give a list of networking ports taken up as running app interface tasks
-        pub async fn list_app_interfaces(&self) -> ConductorResult<Vec<u16>> {
+        /// Give a list of the drivers of the running app interface tasks
+        pub async fn list_app_interfaces(&self) -> ConductorResult<Vec<InterfaceDriver>> {
             Ok(self
                 .get_state()
                 .await?
                 .app_interfaces
                 .values()
-                .map(|config| config.driver.port())
+                .map(|config| config.driver.clone())
                 .collect())
         }
 
@@ -652,6 +941,18 @@ mod dna_impls {
             &self.holochain_p2p
         }
 
+        /// The DPKI service, once the conductor's services are initialized.
+        pub fn dpki(&self) -> Option<Arc<dyn DpkiService>> {
+            self.services
+                .share_ref(|services| services.as_ref().map(|s| s.dpki.clone()))
+        }
+
+        /// The app store service, once the conductor's services are initialized.
+        pub fn app_store(&self) -> Option<Arc<dyn AppStoreService>> {
+            self.services
+                .share_ref(|services| services.as_ref().map(|s| s.app_store.clone()))
+        }
+
         /// Remove cells from the cell map in the Conductor
         pub(crate) async fn remove_cells(&self, cell_ids: &[CellId]) {
             let to_cleanup: Vec<_> = self.running_cells.share_mut(|cells| {
@@ -828,6 +1129,11 @@ mod network_impls {
             self.spaces.unblock(input).await
         }
 
+        /// All the blocks, including those which have ended.
+        pub async fn list_blocks(&self) -> DatabaseResult<Vec<Block>> {
+            self.spaces.list_blocks().await
+        }
+
         /// Check if some target is blocked.
         pub async fn is_blocked(
             &self,
@@ -1323,6 +1629,7 @@ mod app_impls {
             self: Arc<Self>,
             installed_app_id: InstalledAppId,
             cell_data: Vec<(InstalledCell, Option<MembraneProof>)>,
+            use_chc: bool,
         ) -> ConductorResult<()> {
             crate::conductor::conductor::genesis_cells(
                 self.clone(),
@@ -1330,11 +1637,17 @@ mod app_impls {
                     .iter()
                     .map(|(c, p)| (c.as_id().clone(), p.clone()))
                     .collect(),
+                use_chc,
             )
             .await?;
 
             let cell_data = cell_data.into_iter().map(|(c, _)| c);
-            let app = InstalledAppCommon::new_legacy(installed_app_id, cell_data)?;
+            let mut app = InstalledAppCommon::new_legacy(installed_app_id, cell_data)?;
+            if use_chc {
+                for cell_id in app.all_cells().cloned().collect::<Vec<_>>() {
+                    app.attach_chc(&cell_id)?;
+                }
+            }
 
             // Update the db
             let _ = self.add_disabled_app_to_db(app).await?;
@@ -1342,7 +1655,19 @@ mod app_impls {
             Ok(())
         }
 
+        /// Put an app bundle into the app store, so that it can later be
+        /// installed by hash.
+        pub async fn store_app_bundle(&self, source: AppBundleSource) -> ConductorResult<AppHash> {
+            let bundle = source.resolve().await?;
+            let app_store = self
+                .app_store()
+                .ok_or_else(|| ConductorError::other("The app store is not initialized"))?;
+            Ok(app_store.put_app_bundle(bundle).await?)
+        }
+
         /// Install DNAs and set up Cells as specified by an AppBundle
+        ///
+        /// An [`AppBundleSource::Hash`] is looked up in the app store.
         pub async fn install_app_bundle(
             self: Arc<Self>,
             payload: InstallAppPayload,
@@ -1353,10 +1678,25 @@ mod app_impls {
                 installed_app_id,
                 membrane_proofs,
                 network_seed,
+                use_chc,
             } = payload;
 
+            let mut app_store = None;
             let bundle = {
-                let original_bundle = source.resolve().await?;
+                let original_bundle = match source {
+                    AppBundleSource::Hash(app_hash) => {
+                        let store = self
+                            .app_store()
+                            .ok_or(AppBundleError::AppStoreRequired(app_hash.clone()))?;
+                        let bundle = store
+                            .get_app_bundle(app_hash.clone())
+                            .await?
+                            .ok_or(ConductorError::AppBundleNotInStore(app_hash))?;
+                        app_store = Some(store);
+                        bundle
+                    }
+                    source => source.resolve().await?,
+                };
                 if let Some(network_seed) = network_seed {
                     let mut manifest = original_bundle.manifest().to_owned();
                     manifest.set_network_seed(network_seed);
@@ -1371,17 +1711,51 @@ mod app_impls {
             let installed_app_id =
                 installed_app_id.unwrap_or_else(|| manifest.app_name().to_owned());
 
-            let local_dnas = self
+            let mut local_dnas = self
                 .ribosome_store()
                 .share_ref(|store| bundle.get_all_dnas_from_store(store));
+
+            // An app installed from the app store uses the DNAs which the store
+            // has indexed, for the roles which pin their DNA hash.
+            if let Some(app_store) = app_store {
+                for hash in manifest
+                    .app_roles()
+                    .into_iter()
+                    .filter_map(|role| role.dna.installed_hash)
+                    .map(DnaHash::from)
+                {
+                    if local_dnas.contains_key(&hash) {
+                        continue;
+                    }
+                    if let Some(dna_bundle) = app_store.get_dna_bundle(hash.clone()).await? {
+                        let (dna_file, _) =
+                            dna_bundle.into_dna_file(DnaModifiersOpt::none()).await?;
+                        local_dnas.insert(hash, dna_file);
+                    }
+                }
+            }
+
+            let state = self.get_state().await?;
+
+            // Only provisioned cells may be shared with other apps, never clones
+            let existing_cells: HashSet<CellId> = state
+                .installed_apps()
+                .values()
+                .flat_map(|app| app.provisioned_cells().map(second).cloned())
+                .collect();
+
             let ops = bundle
-                .resolve_cells(&local_dnas, agent_key.clone(), membrane_proofs)
+                .resolve_cells(
+                    &local_dnas,
+                    agent_key.clone(),
+                    membrane_proofs,
+                    &existing_cells,
+                )
                 .await?;
 
             let cells_to_create = ops.cells_to_create();
 
             // check if cells_to_create contains a cell identical to an existing one
-            let state = self.get_state().await?;
             let all_cells: HashSet<_> = state
                 .installed_apps()
                 .values()
@@ -1400,10 +1774,48 @@ mod app_impls {
                 self.clone().register_dna(dna).await?;
             }
 
-            crate::conductor::conductor::genesis_cells(self.clone(), cells_to_create).await?;
+            let created_cell_ids: Vec<CellId> = cells_to_create
+                .iter()
+                .map(|(cell_id, _)| cell_id.clone())
+                .collect();
+
+            if let Err(err) =
+                crate::conductor::conductor::genesis_cells(self.clone(), cells_to_create, use_chc)
+                    .await
+            {
+                // Genesis only ran for the cells this app was creating, so only
+                // their data is removed. Cells shared with other apps are never
+                // touched, and neither are DNAs which another app still uses.
+                let dnas_in_use: HashSet<DnaHash> = self
+                    .get_state()
+                    .await?
+                    .installed_apps()
+                    .values()
+                    .flat_map(|app| app.all_cells().map(|cell_id| cell_id.dna_hash().clone()))
+                    .collect();
+                let dnas_to_cleanup: HashSet<&DnaHash> = created_cell_ids
+                    .iter()
+                    .map(|cell_id| cell_id.dna_hash())
+                    .filter(|dna_hash| !dnas_in_use.contains(*dna_hash))
+                    .collect();
+                for dna_hash in dnas_to_cleanup {
+                    if let Err(e) = self.delete_dna_databases(dna_hash).await {
+                        tracing::error!(?e, %dna_hash, "Failed to clean up after failed genesis");
+                    }
+                }
+                return Err(err);
+            }
 
             let roles = ops.role_assignments;
-            let app = InstalledAppCommon::new(installed_app_id, agent_key, roles, manifest)?;
+            let mut app = InstalledAppCommon::new(installed_app_id, agent_key, roles, manifest)?;
+
+            // Existing cells keep their own CHC setting, which can be changed
+            // with `attach_chc` once their chain has been checked against the CHC.
+            if use_chc {
+                for cell_id in &created_cell_ids {
+                    app.attach_chc(cell_id)?;
+                }
+            }
 
             // Update the db
             let stopped_app = self.add_disabled_app_to_db(app).await?;
@@ -1477,7 +1889,9 @@ mod app_impls {
                 .collect())
         }
 
-        /// Find the ID of the first active installed App which uses this Cell
+        /// Find the Cell with the given role in the first active installed App
+        /// which uses this Cell and has that role.
+        /// A Cell may be shared by several Apps, so all of them are considered.
         pub async fn find_cell_with_role_alongside_cell(
             &self,
             cell_id: &CellId,
@@ -1487,8 +1901,8 @@ mod app_impls {
                 .get_state()
                 .await?
                 .running_apps()
-                .find(|(_, running_app)| running_app.all_cells().any(|i| i == cell_id))
-                .and_then(|(_, running_app)| {
+                .filter(|(_, running_app)| running_app.all_cells().any(|i| i == cell_id))
+                .find_map(|(_, running_app)| {
                     running_app
                         .into_common()
                         .role(role_name)
@@ -1645,8 +2059,9 @@ mod clone_cell_impls {
                 .await?;
 
             // run genesis on cloned cell
+            let use_chc = self.get_state().await?.cell_uses_chc(&clone_cell.cell_id);
             let cells = vec![(clone_cell.cell_id.clone(), membrane_proof)];
-            crate::conductor::conductor::genesis_cells(self.clone(), cells).await?;
+            crate::conductor::conductor::genesis_cells(self.clone(), cells, use_chc).await?;
             self.create_and_add_initialized_cells_for_running_apps(Some(&app_id))
                 .await?;
             Ok(clone_cell)
@@ -2075,6 +2490,12 @@ mod state_impls {
             self.spaces.get_state().await
         }
 
+        /// The number of times the state has been updated, which changes
+        /// whenever apps or their cells do.
+        pub(crate) fn state_version(&self) -> u64 {
+            self.spaces.state_version()
+        }
+
         /// Update the internal state with a pure function mapping old state to new
         pub(crate) async fn update_state<F: Send>(&self, f: F) -> ConductorResult<ConductorState>
         where
@@ -2205,6 +2626,72 @@ mod misc_impls {
             Ok(())
         }
 
+        /// List the zome call capability grants on a cell's source chain
+        pub async fn list_capability_grants(
+            &self,
+            cell_id: &CellId,
+            include_revoked: bool,
+        ) -> ConductorApiResult<Vec<CapGrantInfo>> {
+            let source_chain = SourceChain::new(
+                self.get_or_create_authored_db(cell_id.dna_hash())?,
+                self.get_or_create_dht_db(cell_id.dna_hash())?,
+                self.get_or_create_space(cell_id.dna_hash())?
+                    .dht_query_cache,
+                self.keystore.clone(),
+                cell_id.agent_pubkey().clone(),
+            )
+            .await?;
+
+            Ok(source_chain
+                .cap_grant_info(CapGrantQuery {
+                    tag: None,
+                    include_revoked,
+                })
+                .await?)
+        }
+
+        /// Revoke a zome call capability grant by deleting it from the cell's
+        /// source chain. The grant stops authorizing zome calls as soon as
+        /// this returns.
+        pub async fn revoke_zome_call_capability(
+            &self,
+            cell_id: CellId,
+            action_hash: ActionHash,
+        ) -> ConductorApiResult<()> {
+            let source_chain = SourceChain::new(
+                self.get_or_create_authored_db(cell_id.dna_hash())?,
+                self.get_or_create_dht_db(cell_id.dna_hash())?,
+                self.get_or_create_space(cell_id.dna_hash())?
+                    .dht_query_cache,
+                self.keystore.clone(),
+                cell_id.agent_pubkey().clone(),
+            )
+            .await?;
+
+            let cap_grant_info = source_chain
+                .cap_grant_info(CapGrantQuery::default())
+                .await?
+                .into_iter()
+                .find(|info| info.action_hash == action_hash)
+                .ok_or_else(|| ConductorError::CapGrantNotFound(cell_id.clone(), action_hash))?;
+
+            let action_builder = builder::Delete {
+                deletes_entry_address: EntryHash::with_data_sync(&Entry::CapGrant(
+                    cap_grant_info.cap_grant,
+                )),
+                deletes_address: cap_grant_info.action_hash,
+            };
+
+            source_chain
+                .put_weightless(action_builder, None, ChainTopOrdering::default())
+                .await?;
+
+            let cell = self.cell_by_id(&cell_id, false).await?;
+            source_chain.flush(cell.holochain_p2p_dna()).await?;
+
+            Ok(())
+        }
+
         /// Create a JSON dump of the cell's state
         pub async fn dump_cell_state(&self, cell_id: &CellId) -> ConductorApiResult<String> {
             let cell = self.cell_by_id(cell_id, false).await?;
@@ -2313,6 +2800,42 @@ mod misc_impls {
             .await
         }
 
+        /// Migrate the agent of an app's cell to a new DNA.
+        ///
+        /// The old chain is closed and the new chain opened, each pointing at
+        /// the other, and the role is then based on the new cell.
+        ///
+        /// # Returns
+        ///
+        /// The id of the new cell.
+        pub async fn migrate_agent(
+            self: Arc<Self>,
+            payload: MigrateAgentPayload,
+        ) -> ConductorResult<CellId> {
+            migrate_agent::migrate_agent(self, payload).await
+        }
+
+        /// Re-encrypt the conductor's databases with a newly generated key.
+        ///
+        /// Databases which are in use are re-keyed in place once the reads
+        /// and writes in progress on them have finished.
+        pub async fn rotate_database_key(&self) -> ConductorResult<()> {
+            #[cfg(feature = "sqlite-encrypted")]
+            {
+                database_key::rotate_database_key(
+                    &self.keystore,
+                    self.config.environment_path.as_ref(),
+                )
+                .await
+            }
+            #[cfg(not(feature = "sqlite-encrypted"))]
+            {
+                Err(ConductorError::other(
+                    "the databases are not encrypted, because this conductor was built without the sqlite-encrypted feature",
+                ))
+            }
+        }
+
         /// Update coordinator zomes on an existing dna.
         pub async fn update_coordinators(
             &self,
@@ -2374,6 +2897,17 @@ mod accessor_impls {
             SignalBroadcaster::new(senders)
         }
 
+        /// Subscribe to the [`AdminSignal`]s which are sent to admin interfaces
+        pub fn subscribe_admin_signals(&self) -> tokio::sync::broadcast::Receiver<AdminSignal> {
+            self.admin_signal_tx.subscribe()
+        }
+
+        /// Send a signal to every admin interface connection
+        pub(crate) fn send_admin_signal(&self, signal: AdminSignal) {
+            // It's fine if nobody is listening
+            let _ = self.admin_signal_tx.send(signal);
+        }
+
         /// Instantiate a Ribosome for use with a DNA
         pub(crate) fn get_ribosome(&self, dna_hash: &DnaHash) -> ConductorResult<RealRibosome> {
             self.ribosome_store
@@ -2547,41 +3081,47 @@ impl Conductor {
 
         // For any unrepresented DNAs, clean up those DNA-specific databases
         for dna_hash in dnas_to_cleanup {
-            futures::future::join_all(
-                [
-                    self.spaces
-                        .authored_db(dna_hash)
-                        .unwrap()
-                        .write_async(|txn| {
-                            DatabaseResult::Ok(txn.execute("DELETE FROM Action", ())?)
-                        })
-                        .boxed(),
-                    self.spaces
-                        .dht_db(dna_hash)
-                        .unwrap()
-                        .write_async(|txn| {
-                            DatabaseResult::Ok(txn.execute("DELETE FROM Action", ())?)
-                        })
-                        .boxed(),
-                    self.spaces
-                        .cache(dna_hash)
-                        .unwrap()
-                        .write_async(|txn| {
-                            DatabaseResult::Ok(txn.execute("DELETE FROM Action", ())?)
-                        })
-                        .boxed(),
-                    // TODO: also delete stale Wasms
-                ]
-                .into_iter(),
-            )
-            .await
-            .into_iter()
-            .collect::<Result<Vec<usize>, _>>()?;
+            self.delete_dna_databases(dna_hash).await?;
         }
 
         Ok(())
     }
 
+    /// Delete all actions and ops held for a DNA, in its authored, DHT and
+    /// cache databases. Only call this for DNAs which no installed app uses.
+    async fn delete_dna_databases(&self, dna_hash: &DnaHash) -> ConductorResult<()> {
+        // Deleting an action deletes its ops, but warrant ops aren't stored
+        // with an action, so they are deleted as well.
+        let delete_ops_and_actions = |txn: &mut Transaction| {
+            txn.execute("DELETE FROM DhtOp", ())?;
+            DatabaseResult::Ok(txn.execute("DELETE FROM Action", ())?)
+        };
+
+        futures::future::join_all(
+            [
+                self.spaces
+                    .authored_db(dna_hash)?
+                    .write_async(delete_ops_and_actions)
+                    .boxed(),
+                self.spaces
+                    .dht_db(dna_hash)?
+                    .write_async(delete_ops_and_actions)
+                    .boxed(),
+                self.spaces
+                    .cache(dna_hash)?
+                    .write_async(delete_ops_and_actions)
+                    .boxed(),
+                // TODO: also delete stale Wasms
+            ]
+            .into_iter(),
+        )
+        .await
+        .into_iter()
+        .collect::<Result<Vec<usize>, _>>()?;
+
+        Ok(())
+    }
+
     /// Attempt to create all necessary Cells which have not already been created
     /// and added to the conductor, namely the cells which are referenced by
     /// Running apps. If there are no cells to create, this function does nothing.
@@ -2627,7 +3167,11 @@ impl Conductor {
 
         let tasks = app_cells.difference(&on_cells).map(|cell_id| {
             let handle = self.clone();
-            let chc = handle.chc(self.keystore().clone(), cell_id);
+            let chc = if state.cell_uses_chc(cell_id) {
+                handle.chc(self.keystore().clone(), cell_id)
+            } else {
+                None
+            };
             async move {
                 let holochain_p2p_cell =
                     handle.holochain_p2p.to_dna(cell_id.dna_hash().clone(), chc);
@@ -2877,6 +3421,22 @@ mod test_utils_impls {
             let cell = self.cell_by_id(cell_id, false).await?;
             Ok(cell.triggers().clone())
         }
+
+        /// The faults injected on the messages this conductor sends.
+        /// These are cleared when the conductor restarts.
+        pub fn network_faults(&self) -> kitsune_p2p::fault::NetworkFaults {
+            self.network_faults.clone()
+        }
+
+        /// Share the faults injected by the network host, so that they can
+        /// be configured through the conductor.
+        pub(crate) fn with_network_faults(
+            mut self,
+            network_faults: kitsune_p2p::fault::NetworkFaults,
+        ) -> Self {
+            self.network_faults = network_faults;
+            self
+        }
     }
 }
 
@@ -2888,6 +3448,7 @@ mod test_utils_impls {
 pub(crate) async fn genesis_cells(
     conductor: ConductorHandle,
     cell_ids_with_proofs: Vec<(CellId, Option<MembraneProof>)>,
+    use_chc: bool,
 ) -> ConductorResult<()> {
     let cells_tasks = cell_ids_with_proofs.into_iter().map(|(cell_id, proof)| {
         let space = conductor
@@ -2899,7 +3460,11 @@ pub(crate) async fn genesis_cells(
             let dht_db = space.dht_db;
             let dht_db_cache = space.dht_query_cache;
             let conductor = conductor.clone();
-            let chc = conductor.chc(conductor.keystore().clone(), &cell_id);
+            let chc = if use_chc {
+                conductor.chc(conductor.keystore().clone(), &cell_id)
+            } else {
+                None
+            };
             let cell_id_inner = cell_id.clone();
             let ribosome = conductor
                 .get_ribosome(cell_id.dna_hash())
@@ -3034,6 +3599,9 @@ fn query_dht_ops_from_statement(
 
     let r: Vec<DhtOp> = stmt
         .query_and_then([], |row| {
+            if let Some(op) = map_sql_warrant_op(row)? {
+                return Ok(op);
+            }
             let action = from_blob::<SignedAction>(row.get("action_blob")?)?;
             let op_type: DhtOpType = row.get("dht_type")?;
             let entry = match action.0.entry_type().map(|et| et.visibility()) {
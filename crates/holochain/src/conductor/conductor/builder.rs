@@ -3,6 +3,8 @@ use crate::conductor::kitsune_host_impl::KitsuneHostImpl;
 use crate::conductor::manager::OutcomeReceiver;
 use crate::conductor::ribosome_store::RibosomeStore;
 use crate::conductor::ConductorHandle;
+use holochain_sqlite::db::DatabaseVersion;
+use holochain_sqlite::schema::SchemaKind;
 
 /// A configurable Builder for Conductor and sometimes ConductorHandle
 #[derive(Default)]
@@ -48,49 +50,97 @@ impl ConductorBuilder {
         self
     }
 
+    /// Spawn the keystore described by the config.
+    async fn spawn_keystore(&self) -> ConductorResult<MetaLairClient> {
+        pub(crate) fn warn_no_encryption() {
+            #[cfg(not(feature = "sqlite-encrypted"))]
+            {
+                const MSG: &str = "WARNING: running without local db encryption";
+                eprintln!("{}", MSG);
+                println!("{}", MSG);
+                tracing::warn!("{}", MSG);
+            }
+        }
+        let get_passphrase = || -> ConductorResult<sodoken::BufRead> {
+            match self.passphrase.clone() {
+                None => {
+                    Err(one_err::OneErr::new("passphrase required for lair keystore api").into())
+                }
+                Some(p) => Ok(p),
+            }
+        };
+        match &self.config.keystore {
+            KeystoreConfig::DangerTestKeystore => Ok(spawn_test_keystore().await?),
+            KeystoreConfig::LairServer { connection_url } => {
+                warn_no_encryption();
+                let passphrase = get_passphrase()?;
+                Ok(spawn_lair_keystore(connection_url.clone(), passphrase).await?)
+            }
+            KeystoreConfig::LairServerInProc { lair_root } => {
+                warn_no_encryption();
+                let mut keystore_config_path = lair_root.clone().unwrap_or_else(|| {
+                    let mut p: std::path::PathBuf = self.config.environment_path.clone().into();
+                    p.push("keystore");
+                    p
+                });
+                keystore_config_path.push("lair-keystore-config.yaml");
+                let passphrase = get_passphrase()?;
+                Ok(spawn_lair_keystore_in_proc(keystore_config_path, passphrase).await?)
+            }
+        }
+    }
+
+    /// Get the conductor's databases ready to be opened, without starting the
+    /// conductor, returning the directory they are in.
+    async fn prepare_databases(&self) -> ConductorResult<std::path::PathBuf> {
+        let root: std::path::PathBuf = self.config.environment_path.clone().into();
+        #[cfg(feature = "sqlite-encrypted")]
+        {
+            let keystore = match self.keystore.clone() {
+                Some(keystore) => keystore,
+                None => self.spawn_keystore().await?,
+            };
+            super::database_key::load_database_key(&keystore, &root).await?;
+        }
+        Ok(root)
+    }
+
+    /// Report the schema version of each of the conductor's databases,
+    /// without starting the conductor.
+    pub async fn database_versions(self) -> ConductorResult<Vec<DatabaseVersion>> {
+        let root = self.prepare_databases().await?;
+        Ok(
+            tokio::task::spawn_blocking(move || holochain_sqlite::db::database_versions(&root))
+                .await??,
+        )
+    }
+
+    /// Migrate the conductor's databases which have each schema to a version
+    /// of it, without starting the conductor, returning the schema version of
+    /// every database afterwards.
+    ///
+    /// Databases are migrated forward whenever the conductor starts, so this
+    /// is for migrating them back down, so that an older version of Holochain
+    /// can use them. The databases must not be in use by a running conductor.
+    pub async fn migrate_databases(
+        self,
+        versions: Vec<(SchemaKind, u16)>,
+    ) -> ConductorResult<Vec<DatabaseVersion>> {
+        let root = self.prepare_databases().await?;
+        Ok(tokio::task::spawn_blocking(move || {
+            holochain_sqlite::db::migrate_databases(&root, &versions)?;
+            holochain_sqlite::db::database_versions(&root)
+        })
+        .await??)
+    }
+
     /// Initialize a "production" Conductor
     pub async fn build(self) -> ConductorResult<ConductorHandle> {
         tracing::info!(?self.config);
 
-        let keystore = if let Some(keystore) = self.keystore {
-            keystore
-        } else {
-            pub(crate) fn warn_no_encryption() {
-                #[cfg(not(feature = "sqlite-encrypted"))]
-                {
-                    const MSG: &str = "WARNING: running without local db encryption";
-                    eprintln!("{}", MSG);
-                    println!("{}", MSG);
-                    tracing::warn!("{}", MSG);
-                }
-            }
-            let get_passphrase = || -> ConductorResult<sodoken::BufRead> {
-                match self.passphrase {
-                    None => Err(
-                        one_err::OneErr::new("passphrase required for lair keystore api").into(),
-                    ),
-                    Some(p) => Ok(p),
-                }
-            };
-            match &self.config.keystore {
-                KeystoreConfig::DangerTestKeystore => spawn_test_keystore().await?,
-                KeystoreConfig::LairServer { connection_url } => {
-                    warn_no_encryption();
-                    let passphrase = get_passphrase()?;
-                    spawn_lair_keystore(connection_url.clone(), passphrase).await?
-                }
-                KeystoreConfig::LairServerInProc { lair_root } => {
-                    warn_no_encryption();
-                    let mut keystore_config_path = lair_root.clone().unwrap_or_else(|| {
-                        let mut p: std::path::PathBuf = self.config.environment_path.clone().into();
-                        p.push("keystore");
-                        p
-                    });
-                    keystore_config_path.push("lair-keystore-config.yaml");
-                    let passphrase = get_passphrase()?;
-                    spawn_lair_keystore_in_proc(keystore_config_path, passphrase).await?
-                }
-            }
+        let keystore = match self.keystore.clone() {
+            Some(keystore) => keystore,
+            None => self.spawn_keystore().await?,
         };
 
         let Self {
@@ -101,6 +151,9 @@ impl ConductorBuilder {
 
         let ribosome_store = RwShare::new(ribosome_store);
 
+        #[cfg(feature = "sqlite-encrypted")]
+        super::database_key::load_database_key(&keystore, config.environment_path.as_ref()).await?;
+
         let spaces = Spaces::new(&config)?;
         let tag = spaces.get_state().await?.tag().clone();
 
@@ -130,6 +183,8 @@ impl ConductorBuilder {
             Some(tag_ed),
             Some(keystore.lair_client()),
         );
+        #[cfg(any(test, feature = "test_utils"))]
+        let network_faults = host.network_faults();
 
         let (holochain_p2p, p2p_evt) =
             match holochain_p2p::spawn_holochain_p2p(network_config, tls_config, host).await {
@@ -155,6 +210,9 @@ impl ConductorBuilder {
             outcome_tx,
         );
 
+        #[cfg(any(test, feature = "test_utils"))]
+        let conductor = conductor.with_network_faults(network_faults);
+
         let shutting_down = conductor.shutting_down.clone();
 
         #[cfg(any(test, feature = "test_utils"))]
@@ -301,6 +359,9 @@ impl ConductorBuilder {
             .unwrap_or_else(holochain_types::prelude::test_keystore);
         self.config.environment_path = env_path.to_path_buf().into();
 
+        #[cfg(feature = "sqlite-encrypted")]
+        super::database_key::load_database_key(&keystore, env_path).await?;
+
         let spaces = Spaces::new(&self.config)?;
         let tag = spaces.get_state().await?.tag().clone();
 
@@ -323,6 +384,7 @@ impl ConductorBuilder {
             Some(tag_ed),
             Some(keystore.lair_client()),
         );
+        let network_faults = host.network_faults();
 
         let (holochain_p2p, p2p_evt) =
                 holochain_p2p::spawn_holochain_p2p(network_config, holochain_p2p::kitsune_p2p::dependencies::kitsune_p2p_types::tls::TlsConfig::new_ephemeral().await.unwrap(), host)
@@ -342,6 +404,7 @@ impl ConductorBuilder {
             post_commit_sender,
             outcome_tx,
         );
+        let conductor = conductor.with_network_faults(network_faults);
 
         let conductor = Self::update_fake_state(self.state, conductor).await?;
 
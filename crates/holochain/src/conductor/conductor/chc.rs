@@ -2,6 +2,13 @@ use holochain_p2p::ChcImpl;
 
 use super::*;
 
+/// The id of the chain lock held while a cell is attached to the CHC.
+const ATTACH_CHC_LOCK: &[u8] = b"attach_chc";
+
+/// How long the chain stays locked if attaching it to the CHC never finishes,
+/// for instance because the conductor stopped in the meantime.
+const ATTACH_CHC_LOCK_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(60);
+
 impl Conductor {
     #[allow(unused_variables)]
     pub(crate) fn chc(&self, keystore: MetaLairClient, cell_id: &CellId) -> Option<ChcImpl> {
@@ -14,6 +21,170 @@ impl Conductor {
         }
     }
 
+    /// Whether a Chain Head Coordinator is configured for the conductor.
+    pub(crate) fn has_chc(&self) -> bool {
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "chc")] {
+                self.config.chc_url.is_some()
+            } else {
+                false
+            }
+        }
+    }
+
+    /// Apps installed before the CHC could be chosen per cell used it for all
+    /// of their cells, so they are migrated to keep doing so if the conductor
+    /// has a CHC.
+    pub(crate) async fn migrate_chc_cells(&self) -> ConductorResult<()> {
+        let has_chc = self.has_chc();
+        self.update_state(move |mut state| {
+            let app_ids: Vec<InstalledAppId> = state.installed_apps().keys().cloned().collect();
+            for app_id in app_ids {
+                state.get_app_mut(&app_id)?.migrate_chc_cells(has_chc);
+            }
+            Ok(state)
+        })
+        .await?;
+        Ok(())
+    }
+
+    /// Start coordinating an existing cell's source chain with the conductor's
+    /// Chain Head Coordinator.
+    ///
+    /// The CHC must not have any records which the cell's chain doesn't have.
+    /// Any records which the CHC is missing are added to it, and the cell is
+    /// restarted so that all of its commits are coordinated from now on.
+    ///
+    /// The chain is locked while this happens, so commits made in the
+    /// meantime fail with [`SourceChainError::ChainLocked`] instead of being
+    /// written without ever reaching the CHC.
+    pub async fn attach_chc(self: Arc<Self>, cell_id: CellId) -> ConductorApiResult<()> {
+        self.check_cell_installed(&cell_id).await?;
+        let chc = self
+            .chc(self.keystore().clone(), &cell_id)
+            .ok_or(ConductorError::ChcNotConfigured)?;
+
+        let db = self.get_or_create_authored_db(cell_id.dna_hash())?;
+        let author = cell_id.agent_pubkey().clone();
+        let expires_at =
+            (Timestamp::now() + ATTACH_CHC_LOCK_TIMEOUT).map_err(ConductorError::other)?;
+        db.write_async({
+            let author = author.clone();
+            move |txn| {
+                if chain_lock::is_chain_locked(txn, ATTACH_CHC_LOCK, &author)? {
+                    return Err(SourceChainError::ChainLocked);
+                }
+                lock_chain(txn, ATTACH_CHC_LOCK, &author, &expires_at)?;
+                SourceChainResult::Ok(())
+            }
+        })
+        .await?;
+
+        let attached = async {
+            let missing = self.chc_sync_check(&cell_id, chc.clone()).await?;
+            if !missing.is_empty() {
+                chc.add_records(missing).await?;
+            }
+            self.clone().set_cell_uses_chc(cell_id, true).await
+        }
+        .await;
+
+        db.write_async(move |txn| remove_chain_lock(txn, ATTACH_CHC_LOCK, &author))
+            .await?;
+        attached
+    }
+
+    /// Stop coordinating a cell's source chain with the conductor's Chain
+    /// Head Coordinator.
+    ///
+    /// The CHC must not have any records which the cell's chain doesn't
+    /// have, so that nothing is lost by no longer syncing with it.
+    pub async fn detach_chc(self: Arc<Self>, cell_id: CellId) -> ConductorApiResult<()> {
+        self.check_cell_installed(&cell_id).await?;
+        if let Some(chc) = self.chc(self.keystore().clone(), &cell_id) {
+            self.chc_sync_check(&cell_id, chc).await?;
+        }
+        self.set_cell_uses_chc(cell_id, false).await
+    }
+
+    async fn check_cell_installed(&self, cell_id: &CellId) -> ConductorResult<()> {
+        let installed = self
+            .get_state()
+            .await?
+            .installed_apps()
+            .values()
+            .any(|app| app.all_cells().any(|c| c == cell_id));
+        if !installed {
+            return Err(ConductorError::CellMissing(cell_id.clone()));
+        }
+        Ok(())
+    }
+
+    /// Check that the CHC's chain for the cell is the start of the cell's own
+    /// chain, returning the records which the CHC doesn't have yet.
+    async fn chc_sync_check(
+        &self,
+        cell_id: &CellId,
+        chc: ChcImpl,
+    ) -> ConductorApiResult<Vec<Record>> {
+        let local = self
+            .get_or_create_space(cell_id.dna_hash())?
+            .source_chain(self.keystore().clone(), cell_id.agent_pubkey().clone())
+            .await?
+            .query(ChainQueryFilter::new().include_entries(true))
+            .await?;
+        let remote = chc.get_record_data(None).await?;
+        let in_sync = remote.len() <= local.len()
+            && remote
+                .iter()
+                .zip(local.iter())
+                .all(|(r, l)| r.action_address() == l.action_address());
+        if !in_sync {
+            return Err(ConductorError::ChcOutOfSync(cell_id.clone()).into());
+        }
+        Ok(local.into_iter().skip(remote.len()).collect())
+    }
+
+    async fn set_cell_uses_chc(
+        self: Arc<Self>,
+        cell_id: CellId,
+        uses_chc: bool,
+    ) -> ConductorApiResult<()> {
+        self.update_state_prime({
+            let cell_id = cell_id.clone();
+            move |mut state| {
+                let app_ids: Vec<InstalledAppId> = state
+                    .installed_apps()
+                    .iter()
+                    .filter(|(_, app)| app.all_cells().any(|c| *c == cell_id))
+                    .map(|(id, _)| id.clone())
+                    .collect();
+                if app_ids.is_empty() {
+                    return Err(ConductorError::CellMissing(cell_id));
+                }
+                for app_id in app_ids {
+                    let app = state.get_app_mut(&app_id)?;
+                    if uses_chc {
+                        app.attach_chc(&cell_id)?;
+                    } else {
+                        app.detach_chc(&cell_id)?;
+                    }
+                }
+                Ok((state, ()))
+            }
+        })
+        .await?;
+
+        // The cell's network holds on to the CHC it was created with,
+        // so a running cell is recreated to pick up the change.
+        if self.running_cells.share_ref(|c| c.contains_key(&cell_id)) {
+            self.remove_cells(&[cell_id]).await;
+            self.create_and_add_initialized_cells_for_running_apps(None)
+                .await?;
+        }
+        Ok(())
+    }
+
     #[cfg(any(test))]
     #[allow(dead_code)]
     pub(crate) async fn chc_sync(
@@ -22,22 +193,50 @@ impl Conductor {
         enable_app: Option<InstalledAppId>,
     ) -> ConductorApiResult<()> {
         if let Some(chc) = self.chc(self.keystore().clone(), &cell_id) {
-            let db = self.get_authored_db(cell_id.dna_hash())?;
-            let author = cell_id.agent_pubkey().clone();
-            let top_hash = db
-                .read_async(move |txn| {
-                    SourceChainResult::Ok(chain_head_db(&txn, Arc::new(author))?.map(|h| h.action))
-                })
-                .await?;
-            let records = chc.get_record_data(top_hash).await?;
-
-            self.clone()
-                .graft_records_onto_source_chain(cell_id, true, records)
-                .await?;
+            self.clone().graft_records_from_chc(cell_id, chc).await?;
             if let Some(app_id) = enable_app {
                 self.enable_app(app_id).await?;
             }
         }
         Ok(())
     }
+
+    /// Validate and graft onto the cell's source chain the records which
+    /// the CHC has beyond the chain's head, returning their hashes.
+    pub(crate) async fn graft_records_from_chc(
+        self: Arc<Self>,
+        cell_id: CellId,
+        chc: ChcImpl,
+    ) -> ConductorApiResult<Vec<ActionHash>> {
+        let db = self.get_or_create_authored_db(cell_id.dna_hash())?;
+        let author = cell_id.agent_pubkey().clone();
+        let top_hash = db
+            .read_async(move |txn| {
+                SourceChainResult::Ok(chain_head_db(&txn, Arc::new(author))?.map(|h| h.action))
+            })
+            .await?;
+        let records = chc.get_record_data(top_hash).await?;
+        let grafted = records.iter().map(|r| r.action_address().clone()).collect();
+
+        self.graft_records_onto_source_chain(cell_id, true, records)
+            .await?;
+        Ok(grafted)
+    }
+
+    /// Repair a cell's source chain which a commit has found to be behind
+    /// its CHC, by grafting the missing records onto it, and report the
+    /// repair to admin interface clients.
+    pub(crate) async fn repair_source_chain_from_chc(
+        self: Arc<Self>,
+        cell_id: CellId,
+        chc: ChcImpl,
+    ) -> ConductorApiResult<()> {
+        tracing::info!(?cell_id, "Source chain is behind the CHC, repairing");
+        let grafted = self
+            .clone()
+            .graft_records_from_chc(cell_id.clone(), chc)
+            .await?;
+        self.send_admin_signal(AdminSignal::ChcRepaired { cell_id, grafted });
+        Ok(())
+    }
 }
@@ -4,7 +4,7 @@
 //! implementation details we don't know or care about. We want well-defined interfaces for these
 //! services such that a third party could write their own.
 
-use std::{collections::HashSet, sync::Arc};
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
 
 mod dpki_service;
 pub use dpki_service::*;
@@ -26,10 +26,14 @@ pub struct ConductorServices {
 
 impl ConductorServices {
     /// Construct services from the default built-in implementations
-    pub fn builtin(conductor: ConductorHandle, cell_ids: ConductorServiceCells) -> Self {
+    pub fn builtin(
+        conductor: ConductorHandle,
+        cell_ids: ConductorServiceCells,
+        app_store_dir: PathBuf,
+    ) -> Self {
         Self {
-            dpki: DeepkeyBuiltin::new(conductor.clone(), cell_ids.dpki),
-            app_store: AppStoreBuiltin::new(conductor, cell_ids.app_store),
+            dpki: DeepkeyBuiltin::new(conductor, cell_ids.dpki),
+            app_store: AppStoreBuiltin::new(app_store_dir),
         }
     }
 
@@ -47,6 +51,4 @@ impl ConductorServices {
 pub struct ConductorServiceCells {
     /// The CellId to use for DPKI
     pub dpki: CellId,
-    /// The CellId to use for the AppStore
-    pub app_store: CellId,
 }
@@ -1,8 +1,9 @@
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use holochain_types::prelude::*;
-
-use crate::conductor::ConductorHandle;
+use holochain_util::ffs;
+use mr_bundle::error::MrBundleError;
 
 /// Interface for the AppStore service
 #[async_trait::async_trait]
@@ -15,48 +16,149 @@ pub trait AppStoreService: Send + Sync {
     /// Fetch an app bundle from the store
     async fn get_app_bundle(&self, app_hash: AppHash) -> AppStoreServiceResult<Option<AppBundle>>;
 
+    /// Put an app bundle into the store, along with each of the DNA bundles
+    /// it contains, returning the hash by which the app can be fetched.
+    async fn put_app_bundle(&self, bundle: AppBundle) -> AppStoreServiceResult<AppHash>;
+
     /// The CellIds in use by this service, which need to be protected
     fn cell_ids<'a>(&'a self) -> std::collections::HashSet<&'a CellId>;
 }
 
 /// The errors which can be produced by the AppStoreService
 #[derive(thiserror::Error, Debug)]
-pub enum AppStoreServiceError {}
+pub enum AppStoreServiceError {
+    /// An app bundle could not be hashed or decoded
+    #[error(transparent)]
+    AppBundleError(#[from] AppBundleError),
+
+    /// A DNA bundle could not be hashed or decoded
+    #[error(transparent)]
+    DnaError(#[from] DnaError),
+
+    /// A bundle could not be encoded
+    #[error(transparent)]
+    MrBundleError(#[from] MrBundleError),
+
+    /// The store could not be read from or written to
+    #[error(transparent)]
+    IoError(#[from] ffs::IoError),
+
+    /// The stored file doesn't match the hash it is stored under,
+    /// most likely because it was modified on disk.
+    #[error("The stored bundle at {0} does not match its hash")]
+    Corrupted(PathBuf),
+}
 /// Alias
 pub type AppStoreServiceResult<T> = Result<T, AppStoreServiceError>;
 
-/// This doesn't exist yet. We need to define it.
-pub enum AppHash {}
-
-/// The built-in implementation of the app store service, which runs a DNA
+/// The built-in implementation of the app store service.
+///
+/// Bundles are kept in a local content-addressed directory:
+/// `apps/<AppHash>.happ` and `dnas/<DnaHash>.dna`. A DNA bundle is indexed
+/// by the hash of its DNA without any modifiers applied, which is the hash
+/// the bundle itself defines.
+///
+/// A later version will keep the store in a DNA, so that bundles can be
+/// fetched from peers.
 pub struct AppStoreBuiltin {
-    _conductor: ConductorHandle,
-    _cell_id: CellId,
+    dir: PathBuf,
 }
 
 impl AppStoreBuiltin {
     /// Constructor
-    pub fn new(conductor: ConductorHandle, cell_id: CellId) -> Arc<Self> {
-        Arc::new(Self {
-            _conductor: conductor,
-            _cell_id: cell_id,
-        })
+    pub fn new(dir: PathBuf) -> Arc<Self> {
+        Arc::new(Self { dir })
+    }
+
+    fn app_path(&self, app_hash: &AppHash) -> PathBuf {
+        self.dir
+            .join("apps")
+            .join(format!("{}.happ", AppHashB64::from(app_hash.clone())))
+    }
+
+    fn dna_path(&self, dna_hash: &DnaHash) -> PathBuf {
+        self.dir
+            .join("dnas")
+            .join(format!("{}.dna", DnaHashB64::from(dna_hash.clone())))
+    }
+
+    /// Write to a temporary file first so that a partially written bundle
+    /// is never visible under its hash.
+    async fn write_atomic(path: &Path, bytes: &[u8]) -> AppStoreServiceResult<()> {
+        if let Some(parent) = path.parent() {
+            ffs::create_dir_all(parent).await?;
+        }
+        // Each write has its own temporary file, so that concurrent puts of
+        // the same bundle can't interleave their writes.
+        let tmp = path.with_extension(format!("{}.tmp", nanoid::nanoid!()));
+        ffs::write(&tmp, bytes).await?;
+        tokio::fs::rename(&tmp, path)
+            .await
+            .map_err(|e| ffs::IoError::new(e, path.to_owned()))?;
+        Ok(())
+    }
+
+    async fn read(path: &Path) -> AppStoreServiceResult<Option<Vec<u8>>> {
+        match tokio::fs::read(path).await {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(ffs::IoError::new(e, path.to_owned()).into()),
+        }
+    }
+
+    async fn put_dna_bundle(&self, bytes: &[u8]) -> AppStoreServiceResult<DnaHash> {
+        let dna_hash = DnaBundle::decode(bytes)?.original_dna_hash().await?;
+        Self::write_atomic(&self.dna_path(&dna_hash), bytes).await?;
+        Ok(dna_hash)
     }
 }
 
 #[async_trait::async_trait]
 #[allow(clippy::needless_lifetimes)]
 impl AppStoreService for AppStoreBuiltin {
-    async fn get_dna_bundle(&self, _dna_hash: DnaHash) -> AppStoreServiceResult<Option<DnaBundle>> {
-        todo!("placeholder")
+    async fn get_dna_bundle(&self, dna_hash: DnaHash) -> AppStoreServiceResult<Option<DnaBundle>> {
+        let path = self.dna_path(&dna_hash);
+        let bytes = match Self::read(&path).await? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+        let bundle = DnaBundle::decode(&bytes)?;
+        if bundle.original_dna_hash().await? != dna_hash {
+            return Err(AppStoreServiceError::Corrupted(path));
+        }
+        Ok(Some(bundle))
     }
 
-    async fn get_app_bundle(&self, _app_hash: AppHash) -> AppStoreServiceResult<Option<AppBundle>> {
-        todo!("placeholder")
+    async fn get_app_bundle(&self, app_hash: AppHash) -> AppStoreServiceResult<Option<AppBundle>> {
+        let path = self.app_path(&app_hash);
+        let bytes = match Self::read(&path).await? {
+            Some(bytes) => bytes,
+            None => return Ok(None),
+        };
+        let bundle = AppBundle::decode(&bytes)?;
+        if bundle.app_hash()? != app_hash {
+            return Err(AppStoreServiceError::Corrupted(path));
+        }
+        Ok(Some(bundle))
+    }
+
+    async fn put_app_bundle(&self, bundle: AppBundle) -> AppStoreServiceResult<AppHash> {
+        let app_hash = bundle.app_hash()?;
+        // Only the resources which roles refer to are DNA bundles.
+        let resources = bundle.bundled_resources();
+        for role in bundle.manifest().app_roles() {
+            if let Some(mr_bundle::Location::Bundled(path)) = &role.dna.location {
+                if let Some(bytes) = resources.get(path) {
+                    self.put_dna_bundle(bytes).await?;
+                }
+            }
+        }
+        Self::write_atomic(&self.app_path(&app_hash), &bundle.encode()?).await?;
+        Ok(app_hash)
     }
 
     fn cell_ids<'a>(&'a self) -> std::collections::HashSet<&'a CellId> {
-        [&self._cell_id].into_iter().collect()
+        std::collections::HashSet::new()
     }
 }
 
@@ -68,3 +170,67 @@ pub fn mock_app_store() -> MockAppStoreService {
         .return_const(std::collections::HashSet::new());
     app_store
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_types::test_utils::fake_dna_zomes;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn app_store_roundtrip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let store = AppStoreBuiltin::new(tmp.path().to_owned());
+
+        let dna = fake_dna_zomes("app_store_roundtrip", vec![]);
+        let dna_hash = dna.dna_hash().clone();
+        let path = PathBuf::from(format!("{}", dna_hash));
+        let roles = vec![AppRoleManifest {
+            name: "role".into(),
+            dna: AppRoleDnaManifest {
+                location: Some(DnaLocation::Bundled(path.clone())),
+                modifiers: DnaModifiersOpt::none(),
+                installed_hash: None,
+                clone_limit: 0,
+            },
+            provisioning: None,
+        }];
+        let manifest = AppManifestCurrentBuilder::default()
+            .name("app".into())
+            .description(None)
+            .roles(roles)
+            .build()
+            .unwrap();
+        let resources = vec![(path, DnaBundle::from_dna_file(dna).await.unwrap())];
+        let bundle = AppBundle::new(manifest.into(), resources, PathBuf::from("."))
+            .await
+            .unwrap();
+        let app_hash = bundle.app_hash().unwrap();
+
+        assert!(store
+            .get_app_bundle(app_hash.clone())
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(store.put_app_bundle(bundle).await.unwrap(), app_hash);
+
+        let fetched = store
+            .get_app_bundle(app_hash.clone())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(fetched.app_hash().unwrap(), app_hash);
+        assert!(store.get_dna_bundle(dna_hash).await.unwrap().is_some());
+
+        // Tampering with the stored file is detected
+        let happ = store.app_path(&app_hash);
+        let mut tampered = fetched.into_inner();
+        let mut manifest = tampered.manifest().clone();
+        manifest.set_network_seed("tampered".into());
+        tampered = tampered.update_manifest(manifest).unwrap();
+        std::fs::write(&happ, tampered.encode().unwrap()).unwrap();
+        matches::assert_matches!(
+            store.get_app_bundle(app_hash).await,
+            Err(AppStoreServiceError::Corrupted(_))
+        );
+    }
+}
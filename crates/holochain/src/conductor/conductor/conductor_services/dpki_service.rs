@@ -1,10 +1,19 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
 use holo_hash::AgentPubKey;
-use holochain_zome_types::{CellId, Timestamp};
+use holochain_zome_types::{
+    CellId, KeyMutation, KeyState, KeyStateQuery, Timestamp, DPKI_KEY_MUTATION_FN_NAME,
+    DPKI_KEY_STATE_FN_NAME, DPKI_ZOME_NAME,
+};
 
 use crate::conductor::ConductorHandle;
 
+/// The role name of the DPKI DNA in the app which the conductor installs for it
+pub const DPKI_ROLE_NAME: &str = "dpki";
+
 /// Interface for the DPKI service
 #[async_trait::async_trait]
 #[mockall::automock]
@@ -63,21 +72,81 @@ pub trait DpkiServiceExt: DpkiService {
     }
 }
 
-/// The built-in implementation of the DPKI service contract, which runs a DNA
+impl<T: DpkiService + ?Sized> DpkiServiceExt for T {}
+
+/// How long an answer from the DPKI DNA that a key has not been revoked is
+/// trusted for, before asking again in case it has been revoked since.
+const KEY_STATE_CACHE_TTL: Duration = Duration::from_secs(60);
+
+/// The state of a key as-at some time, as last fetched from the DPKI DNA.
+struct CachedKeyState {
+    state: KeyState,
+    as_at: Timestamp,
+    fetched_at: Instant,
+}
+
+impl CachedKeyState {
+    /// Whether this answer can be used for the validity of the key at the
+    /// given time. A revocation is final, so it answers for any time.
+    fn covers(&self, timestamp: Timestamp) -> bool {
+        matches!(self.state, KeyState::Invalid(_))
+            || (timestamp <= self.as_at && self.fetched_at.elapsed() < KEY_STATE_CACHE_TTL)
+    }
+
+    /// Whether the key was valid at the given time, which must be covered.
+    /// Keys which DPKI doesn't know of are valid, since their registration
+    /// may not have reached us yet, and keys which didn't come from DPKI
+    /// are never registered with it.
+    fn is_valid_at(&self, timestamp: Timestamp) -> bool {
+        match self.state {
+            KeyState::Valid(registered_at) => timestamp >= registered_at,
+            KeyState::Invalid(revoked_at) => timestamp < revoked_at,
+            KeyState::NotFound => true,
+        }
+    }
+}
+
+/// The built-in implementation of the DPKI service contract, which runs a DNA.
+///
+/// The DNA must expose the zome functions named in [`holochain_zome_types::dpki`].
+/// The state of each key is cached, so that validating many actions by the
+/// same author only calls into the DNA once.
 pub struct DeepkeyBuiltin {
     conductor: ConductorHandle,
     cell_id: CellId,
+    key_states: parking_lot::Mutex<HashMap<AgentPubKey, CachedKeyState>>,
 }
 
 impl DeepkeyBuiltin {
     /// Constructor
     pub fn new(conductor: ConductorHandle, cell_id: CellId) -> Arc<Self> {
-        Arc::new(Self { conductor, cell_id })
+        Arc::new(Self {
+            conductor,
+            cell_id,
+            key_states: Default::default(),
+        })
+    }
+
+    async fn call<I, O>(&self, fn_name: &str, payload: I) -> DpkiServiceResult<O>
+    where
+        I: serde::Serialize + std::fmt::Debug,
+        O: serde::de::DeserializeOwned + std::fmt::Debug,
+    {
+        let provenance = self.cell_id.agent_pubkey().clone();
+        self.conductor
+            .easy_call_zome(
+                &provenance,
+                None,
+                self.cell_id.clone(),
+                DPKI_ZOME_NAME,
+                fn_name,
+                payload,
+            )
+            .await
+            .map_err(|e| DpkiServiceError::ZomeCallFailed(e.to_string()))
     }
 }
 
-#[allow(unreachable_code)]
-#[allow(unused_variables)]
 #[allow(clippy::needless_lifetimes)]
 #[async_trait::async_trait]
 impl DpkiService for DeepkeyBuiltin {
@@ -86,26 +155,32 @@ impl DpkiService for DeepkeyBuiltin {
         key: AgentPubKey,
         timestamp: Timestamp,
     ) -> DpkiServiceResult<bool> {
-        let keystore = self.conductor.keystore();
-        let cell_id = self.cell_id.clone();
-        let zome_name: String = "TODO: depends on dna implementation".to_string();
-        let fn_name: String = "TODO: depends on dna implementation".to_string();
-        let payload = "TODO: depends on dna implementation".to_string();
-        let cap_secret = None;
-        let provenance = cell_id.agent_pubkey().clone();
-        let is_valid: bool = self
-            .conductor
-            .easy_call_zome(
-                &provenance,
-                cap_secret,
-                cell_id,
-                zome_name,
-                fn_name,
-                payload,
+        if let Some(cached) = self.key_states.lock().get(&key) {
+            if cached.covers(timestamp) {
+                return Ok(cached.is_valid_at(timestamp));
+            }
+        }
+
+        // Ask for the state of the key as-at now rather than as-at the given
+        // time, so that the answer covers every earlier time too.
+        let as_at = std::cmp::max(timestamp, Timestamp::now());
+        let state: KeyState = self
+            .call(
+                DPKI_KEY_STATE_FN_NAME,
+                KeyStateQuery {
+                    key: key.clone(),
+                    timestamp: as_at,
+                },
             )
-            .await
-            .map_err(|e| DpkiServiceError::ZomeCallFailed(e.to_string()))?;
-        Ok(is_valid)
+            .await?;
+        let cached = CachedKeyState {
+            state,
+            as_at,
+            fetched_at: Instant::now(),
+        };
+        let valid = cached.is_valid_at(timestamp);
+        self.key_states.lock().insert(key, cached);
+        Ok(valid)
     }
 
     async fn key_mutation(
@@ -113,7 +188,22 @@ impl DpkiService for DeepkeyBuiltin {
         old_key: Option<AgentPubKey>,
         new_key: Option<AgentPubKey>,
     ) -> DpkiServiceResult<()> {
-        todo!()
+        if old_key.is_none() && new_key.is_none() {
+            return Ok(());
+        }
+        self.call(
+            DPKI_KEY_MUTATION_FN_NAME,
+            KeyMutation {
+                old_key: old_key.clone(),
+                new_key: new_key.clone(),
+            },
+        )
+        .await?;
+        let mut key_states = self.key_states.lock();
+        for key in old_key.iter().chain(new_key.iter()) {
+            key_states.remove(key);
+        }
+        Ok(())
     }
 
     fn cell_ids<'a>(&'a self) -> std::collections::HashSet<&'a CellId> {
@@ -131,3 +221,64 @@ pub fn mock_dpki() -> MockDpkiService {
         .return_const(std::collections::HashSet::new());
     dpki
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cached(state: KeyState, as_at: Timestamp) -> CachedKeyState {
+        CachedKeyState {
+            state,
+            as_at,
+            fetched_at: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn cached_key_state_covers_times_up_to_when_it_was_fetched() {
+        let now = Timestamp::now();
+        let before = (now - Duration::from_secs(10)).unwrap();
+        let after = (now + Duration::from_secs(10)).unwrap();
+
+        let valid = cached(KeyState::Valid(before), now);
+        assert!(valid.covers(before));
+        assert!(valid.covers(now));
+        assert!(!valid.covers(after));
+        assert!(valid.is_valid_at(before));
+        assert!(valid.is_valid_at(now));
+
+        let not_found = cached(KeyState::NotFound, now);
+        assert!(not_found.covers(before));
+        assert!(!not_found.covers(after));
+        assert!(not_found.is_valid_at(before));
+
+        let mut stale = cached(KeyState::Valid(before), now);
+        stale.fetched_at = Instant::now() - KEY_STATE_CACHE_TTL;
+        assert!(!stale.covers(before));
+    }
+
+    #[test]
+    fn key_is_not_valid_before_it_was_registered() {
+        let now = Timestamp::now();
+        let before = (now - Duration::from_secs(10)).unwrap();
+
+        let valid = cached(KeyState::Valid(now), now);
+        assert!(valid.covers(before));
+        assert!(!valid.is_valid_at(before));
+        assert!(valid.is_valid_at(now));
+    }
+
+    #[test]
+    fn cached_revocation_covers_any_time() {
+        let now = Timestamp::now();
+        let before = (now - Duration::from_secs(10)).unwrap();
+        let after = (now + Duration::from_secs(10)).unwrap();
+
+        let mut revoked = cached(KeyState::Invalid(now), before);
+        revoked.fetched_at = Instant::now() - KEY_STATE_CACHE_TTL;
+        assert!(revoked.covers(after));
+        assert!(revoked.is_valid_at(before));
+        assert!(!revoked.is_valid_at(now));
+        assert!(!revoked.is_valid_at(after));
+    }
+}
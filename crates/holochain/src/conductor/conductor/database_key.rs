@@ -0,0 +1,166 @@
+//! The key which the conductor's databases are encrypted with.
+//!
+//! The key is random. It is kept in the environment directory, encrypted with
+//! a shared secret which is held in Lair, so the databases can only be read
+//! by someone who can also unlock the keystore.
+
+use super::*;
+use holochain_sqlite::db::DbKey;
+use std::path::Path;
+
+/// The file in the environment directory which holds the database key.
+const DATABASE_KEY_FILE: &str = "database_key.yaml";
+
+/// Rotations rewrite the key file, so only one may run at a time.
+static ROTATION: once_cell::sync::Lazy<tokio::sync::Mutex<()>> =
+    once_cell::sync::Lazy::new(|| tokio::sync::Mutex::new(()));
+
+/// A database key, encrypted with a shared secret held in Lair.
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
+struct WrappedKey {
+    /// The tag of the shared secret which the key is encrypted with.
+    tag: String,
+    nonce: [u8; 24],
+    cipher: Vec<u8>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct DatabaseKeyFile {
+    current: WrappedKey,
+    /// The key which is being rotated away from. It is only present while
+    /// a rotation is in progress, or if a rotation was interrupted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    previous: Option<WrappedKey>,
+}
+
+/// Load the key for the databases under `root`, creating it the first time,
+/// and encrypt any databases which aren't yet encrypted with it.
+///
+/// This must be run before any of the databases are opened.
+pub(crate) async fn load_database_key(
+    keystore: &MetaLairClient,
+    root: &Path,
+) -> ConductorResult<()> {
+    std::fs::create_dir_all(root)?;
+    let path = root.join(DATABASE_KEY_FILE);
+    let existing = read_key_file(&path)?;
+    let (key, previous) = match &existing {
+        Some(file) => {
+            let key = unwrap_key(keystore, &file.current).await?;
+            let mut previous = Vec::new();
+            if let Some(wrapped) = &file.previous {
+                previous.push(unwrap_key(keystore, wrapped).await?);
+            }
+            (key, previous)
+        }
+        None => {
+            let key = DbKey::generate();
+            let file = DatabaseKeyFile {
+                current: wrap_key(keystore, &key).await?,
+                previous: None,
+            };
+            write_key_file(&path, &file)?;
+            (key, Vec::new())
+        }
+    };
+
+    {
+        let root = root.to_path_buf();
+        let key = key.clone();
+        tokio::task::spawn_blocking(move || {
+            holochain_sqlite::db::encrypt_databases(&root, &key, &previous)
+        })
+        .await??;
+    }
+    if let Some(mut file) = existing.filter(|file| file.previous.is_some()) {
+        // An interrupted rotation has now been finished.
+        file.previous = None;
+        write_key_file(&path, &file)?;
+    }
+
+    holochain_sqlite::db::set_database_key(root, key);
+    Ok(())
+}
+
+/// Re-encrypt the databases under `root` with a new key.
+pub(crate) async fn rotate_database_key(
+    keystore: &MetaLairClient,
+    root: &Path,
+) -> ConductorResult<()> {
+    let _rotation = ROTATION.lock().await;
+    let path = root.join(DATABASE_KEY_FILE);
+    let file = read_key_file(&path)?
+        .ok_or_else(|| ConductorError::other(format!("there is no database key at {:?}", path)))?;
+
+    let key = DbKey::generate();
+    let current = wrap_key(keystore, &key).await?;
+    // The old key is kept until every database has been re-keyed, so that
+    // they can still be opened if the rotation is interrupted.
+    write_key_file(
+        &path,
+        &DatabaseKeyFile {
+            current: current.clone(),
+            previous: Some(file.current),
+        },
+    )?;
+    holochain_sqlite::db::rotate_database_key(root, key).await?;
+    write_key_file(
+        &path,
+        &DatabaseKeyFile {
+            current,
+            previous: None,
+        },
+    )?;
+    tracing::info!(?root, "Rotated the database key");
+    Ok(())
+}
+
+/// Encrypt a key with a new shared secret.
+async fn wrap_key(keystore: &MetaLairClient, key: &DbKey) -> ConductorResult<WrappedKey> {
+    let tag = format!(
+        "database-key-{}",
+        rand::random::<[u8; 16]>()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    );
+    keystore.new_shared_secret(tag.as_str().into()).await?;
+    let (nonce, cipher) = keystore
+        .shared_secret_encrypt(tag.as_str().into(), key.as_bytes().to_vec().into())
+        .await?;
+    Ok(WrappedKey {
+        tag,
+        nonce,
+        cipher: cipher.to_vec(),
+    })
+}
+
+async fn unwrap_key(keystore: &MetaLairClient, wrapped: &WrappedKey) -> ConductorResult<DbKey> {
+    let bytes = keystore
+        .shared_secret_decrypt(
+            wrapped.tag.as_str().into(),
+            wrapped.nonce,
+            wrapped.cipher.clone().into(),
+        )
+        .await?;
+    let bytes = <[u8; 32]>::try_from(&bytes[..])
+        .map_err(|_| ConductorError::other("the database key is not 32 bytes long"))?;
+    Ok(DbKey::from_bytes(bytes))
+}
+
+fn read_key_file(path: &Path) -> ConductorResult<Option<DatabaseKeyFile>> {
+    match std::fs::read(path) {
+        Ok(bytes) => Ok(Some(serde_yaml::from_slice(&bytes)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Write the key file through a temporary file, so that it is never left
+/// half written.
+fn write_key_file(path: &Path, file: &DatabaseKeyFile) -> ConductorResult<()> {
+    let tmp = path.with_extension("yaml.tmp");
+    std::fs::write(&tmp, serde_yaml::to_string(file)?)?;
+    std::fs::rename(tmp, path)?;
+    Ok(())
+}
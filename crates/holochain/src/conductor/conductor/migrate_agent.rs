@@ -0,0 +1,135 @@
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
+use crate::core::workflow::migrate_agent_workflow::migrate_agent_workflow;
+use crate::core::workflow::migrate_agent_workflow::MigrateAgentSide;
+
+use super::*;
+
+pub(crate) async fn migrate_agent(
+    handle: ConductorHandle,
+    payload: MigrateAgentPayload,
+) -> ConductorResult<CellId> {
+    let MigrateAgentPayload {
+        app_id,
+        role_name,
+        new_dna_hash,
+        membrane_proof,
+    } = payload;
+
+    let state = handle.get_state().await?;
+    let old_cell_id = state
+        .get_app(&app_id)?
+        .role(&role_name)?
+        .provisioned_cell()
+        .cloned()
+        .ok_or_else(|| AppError::RoleNotProvisioned(role_name.clone()))?;
+    let new_cell_id = CellId::new(new_dna_hash.clone(), old_cell_id.agent_pubkey().clone());
+    if state
+        .installed_apps()
+        .values()
+        .flat_map(|app| app.all_cells())
+        .any(|cell_id| *cell_id == new_cell_id)
+    {
+        return Err(AppError::DuplicateCellId(new_cell_id).into());
+    }
+    // The new chain is coordinated with the CHC if the old one was.
+    let use_chc = state.cell_uses_chc(&old_cell_id);
+
+    // The old cell must be running, so that it can publish the close of its chain.
+    let old_cell = handle.cell_by_id(&old_cell_id, false).await?;
+
+    // Genesis is a no-op if the new chain already exists, which allows a
+    // migration to be retried after one of the callbacks failed.
+    genesis_cells(
+        handle.clone(),
+        vec![(new_cell_id.clone(), membrane_proof)],
+        use_chc,
+    )
+    .await?;
+
+    // The new cell has to be running for its chain to be written to, so the
+    // role is moved over to it first, and moved back if the migration fails.
+    set_role_dna(&handle, &app_id, &role_name, new_dna_hash).await?;
+
+    let result = async {
+        let startup_errors = handle
+            .clone()
+            .create_and_add_initialized_cells_for_running_apps(Some(&app_id))
+            .await?;
+        if let Some((_, err)) = startup_errors
+            .into_iter()
+            .find(|(cell_id, _)| *cell_id == new_cell_id)
+        {
+            return Err(err.into());
+        }
+        let close = migrate_agent_side(&handle, &old_cell_id, use_chc).await?;
+        let open = migrate_agent_side(&handle, &new_cell_id, use_chc).await?;
+        match migrate_agent_workflow(close, open, handle.clone()).await? {
+            MigrateAgentResult::Pass => Ok(()),
+            MigrateAgentResult::Fail(zome_name, reason) => {
+                Err(ConductorError::MigrateAgentFailed(zome_name, reason))
+            }
+        }
+    }
+    .await;
+    if let Err(err) = result {
+        set_role_dna(&handle, &app_id, &role_name, old_cell_id.dna_hash().clone()).await?;
+        handle.remove_cells(&[new_cell_id]).await;
+        return Err(err);
+    }
+
+    // The old cell is no longer part of the app, and will be dropped the next
+    // time dangling cells are cleaned up. Until then it stays running, so that
+    // the close of its chain reaches the old network.
+    old_cell
+        .triggers()
+        .publish_dht_ops
+        .trigger(&"migrate_agent");
+
+    Ok(new_cell_id)
+}
+
+async fn set_role_dna(
+    handle: &ConductorHandle,
+    app_id: &InstalledAppId,
+    role_name: &RoleName,
+    dna_hash: DnaHash,
+) -> ConductorResult<()> {
+    handle
+        .update_state_prime({
+            let app_id = app_id.clone();
+            let role_name = role_name.clone();
+            move |mut state| {
+                state
+                    .get_app_mut(&app_id)?
+                    .migrate_base_cell(&role_name, dna_hash)?;
+                Ok((state, ()))
+            }
+        })
+        .await?;
+    Ok(())
+}
+
+async fn migrate_agent_side(
+    handle: &ConductorHandle,
+    cell_id: &CellId,
+    use_chc: bool,
+) -> ConductorResult<MigrateAgentSide<RealRibosome>> {
+    let ribosome = handle.get_ribosome(cell_id.dna_hash())?;
+    let space = handle.get_or_create_space(cell_id.dna_hash())?;
+    let workspace = space
+        .source_chain_workspace(
+            handle.keystore().clone(),
+            cell_id.agent_pubkey().clone(),
+            Arc::new(ribosome.dna_def().as_content().clone()),
+        )
+        .await?;
+    let chc = if use_chc {
+        handle.chc(handle.keystore().clone(), cell_id)
+    } else {
+        None
+    };
+    let network = handle
+        .holochain_p2p()
+        .to_dna(cell_id.dna_hash().clone(), chc);
+    Ok(MigrateAgentSide::new(workspace, network, ribosome))
+}
@@ -221,18 +221,6 @@ async fn common_genesis_test_app(
 ) -> ConductorApiResult<SweetApp> {
     let hardcoded_zome = InlineIntegrityZome::new_unique(Vec::new(), 0);
 
-    // Just a strong reminder that we need to be careful once we start using existing Cells:
-    // When a Cell panics or fails validation in general, we want to disable all Apps touching that Cell.
-    // However, if the panic/failure happens during Genesis, we want to completely
-    // destroy the app which is attempting to Create that Cell, but *NOT* any other apps
-    // which might be touching that Cell.
-    //
-    // It probably works out to be the same either way, since if we are creating a Cell,
-    // no other app could be possibly referencing it, but just in case we have some kind of complex
-    // behavior like installing two apps which reference each others' Cells at the same time,
-    // we need to be aware of this distinction.
-    holochain_types::app::we_must_remember_to_rework_cell_panic_handling_after_implementing_use_existing_cell_resolution();
-
     // Create one DNA which always works, and another from a zome that gets passed in
     let (dna_hardcoded, _, _) = mk_dna(("hardcoded", hardcoded_zome)).await;
     let (dna_custom, _, _) = mk_dna(custom_zomes).await;
@@ -1182,3 +1170,42 @@ async fn test_init_concurrency() {
     assert_eq!(num_calls_clone.fetch_add(0, Ordering::SeqCst), 100);
     assert_eq!(num_inits_clone.fetch_add(0, Ordering::SeqCst), 1);
 }
+
+#[cfg(feature = "sqlite-encrypted")]
+#[tokio::test(flavor = "multi_thread")]
+async fn databases_are_encrypted_and_the_key_can_be_rotated() {
+    holochain_trace::test_run().ok();
+    let (dna, _, _) = mk_dna(simple_crud_zome()).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna]).await.unwrap();
+    let zome = app.cells()[0].zome("coordinator");
+
+    let conductor_db = conductor
+        .db_path()
+        .join("conductor")
+        .join("conductor.sqlite3");
+    let readable_without_key = |path: &std::path::Path| {
+        rusqlite::Connection::open(path)
+            .unwrap()
+            .query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(()))
+            .is_ok()
+    };
+    assert!(!readable_without_key(&conductor_db));
+
+    let before: ActionHash = conductor
+        .call(&zome, "create_string", "1".to_string())
+        .await;
+    conductor.rotate_database_key().await.unwrap();
+    let after: ActionHash = conductor
+        .call(&zome, "create_string", "2".to_string())
+        .await;
+    assert!(!readable_without_key(&conductor_db));
+
+    // The rotated key is the one loaded on restart.
+    conductor.shutdown().await;
+    conductor.startup().await;
+    for hash in [before, after] {
+        let record: Option<Record> = conductor.call(&zome, "read", hash).await;
+        assert!(record.is_some());
+    }
+}
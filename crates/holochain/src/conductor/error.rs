@@ -4,6 +4,7 @@ use crate::conductor::cell::error::CellError;
 use crate::conductor::conductor::CellStatus;
 use crate::core::workflow::error::WorkflowError;
 use holochain_conductor_api::conductor::ConductorConfigError;
+use holochain_conductor_api::InterfaceDriver;
 use holochain_sqlite::error::DatabaseError;
 use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::WasmErrorInner;
@@ -41,9 +42,20 @@ pub enum ConductorError {
     #[error("Cell was referenced, but is missing from the conductor. CellId: {0:?}")]
     CellMissing(CellId),
 
+    #[error("No Chain Head Coordinator is configured for this conductor")]
+    ChcNotConfigured,
+
+    #[error(
+        "The Chain Head Coordinator has records which the source chain of cell {0:?} does not"
+    )]
+    ChcOutOfSync(CellId),
+
     #[error("Error while cloning cell: {0}")]
     CloneCellError(String),
 
+    #[error("The migrate_agent callback of zome {0} failed: {1}")]
+    MigrateAgentFailed(ZomeName, String),
+
     #[error(transparent)]
     ConductorConfigError(#[from] ConductorConfigError),
 
@@ -74,6 +86,12 @@ pub enum ConductorError {
     #[error("Attempted to add two app interfaces with the same id: {0:?}")]
     AppInterfaceIdCollision(AppInterfaceId),
 
+    #[error("No app interface is listening with {0:?}")]
+    AppInterfaceNotFound(InterfaceDriver),
+
+    #[error("No admin interface is listening with {0:?}")]
+    AdminInterfaceNotFound(InterfaceDriver),
+
     // Box is to avoid cycle in error definition
     #[error(transparent)]
     InterfaceError(#[from] Box<InterfaceError>),
@@ -96,6 +114,9 @@ pub enum ConductorError {
     #[error("Tried to perform an operation on an app that was not running: {0}")]
     AppNotRunning(InstalledAppId),
 
+    #[error("No live capability grant was found for action {1} on cell {0:?}")]
+    CapGrantNotFound(CellId, ActionHash),
+
     #[error(transparent)]
     HolochainP2pError(#[from] holochain_p2p::HolochainP2pError),
 
@@ -129,6 +150,15 @@ pub enum ConductorError {
     #[error(transparent)]
     RibosomeError(#[from] crate::core::ribosome::error::RibosomeError),
 
+    #[error(transparent)]
+    DpkiServiceError(#[from] super::conductor::DpkiServiceError),
+
+    #[error(transparent)]
+    AppStoreServiceError(#[from] super::conductor::AppStoreServiceError),
+
+    #[error("The app bundle {0} is not in the app store")]
+    AppBundleNotInStore(AppHash),
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
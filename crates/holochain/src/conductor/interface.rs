@@ -2,8 +2,8 @@
 //! and dispatch them to the appropriate handlers within Holochain.
 //! They also allow emitting responses and one-way Signals.
 //!
-//! Currently the only InterfaceDriver is a Websocket-based one, whose
-//! implementation can be found in the `websocket` module here.
+//! Every InterfaceDriver serves websockets, whether over TCP, TLS or a Unix
+//! domain socket. The implementation can be found in the `websocket` module here.
 
 use crate::conductor::api::*;
 use error::InterfaceError;
@@ -16,6 +16,10 @@ use tokio::sync::broadcast;
 pub mod error;
 pub mod websocket;
 
+/// Closes an interface's listener, and every connection to it, when dropped
+/// or sent to.
+pub type InterfaceCloser = tokio::sync::oneshot::Sender<()>;
+
 /// Represents runtime data about an existing App interface.
 /// Other stateful information like websocket ports can be found in
 /// `ConductorState::app_interfaces`
@@ -24,6 +28,8 @@ pub enum AppInterfaceRuntime {
     Websocket {
         /// The channel for this interface to send Signals across
         signal_tx: broadcast::Sender<Signal>,
+        /// Closes the interface when dropped
+        closer: InterfaceCloser,
     },
 
     #[cfg(any(test, feature = "test_utils"))]
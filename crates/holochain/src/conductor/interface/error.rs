@@ -27,6 +27,10 @@ pub enum InterfaceError {
     WebsocketError(#[from] holochain_websocket::WebsocketError),
     #[error("Failed to find free port")]
     PortError,
+    #[error("Could not load the interface's TLS certificate: {0}")]
+    TlsCertificate(String),
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
 }
 
 impl From<String> for InterfaceError {
@@ -1,5 +1,5 @@
-//! Module for establishing Websocket-based Interfaces,
-//! i.e. those configured with `InterfaceDriver::Websocket`
+//! Module for establishing Websocket-based Interfaces, i.e. those configured
+//! with `InterfaceDriver::Websocket` or `InterfaceDriver::UnixSocket`
 
 use super::error::InterfaceError;
 use super::error::InterfaceResult;
@@ -7,17 +7,30 @@ use crate::conductor::interface::*;
 use crate::conductor::manager::ManagedTaskResult;
 use crate::conductor::manager::TaskManagerClient;
 use futures::FutureExt;
+use holochain_conductor_api::signal_subscription::CheckedSignal;
+use holochain_conductor_api::signal_subscription::SignalFilterSet;
+use holochain_conductor_api::AllowedOrigins;
+use holochain_conductor_api::InterfaceTlsConfig;
+use holochain_conductor_api::{AdminSignal, AppRequest, AppResponse};
 use holochain_serialized_bytes::SerializedBytes;
+use holochain_types::app::InstalledAppId;
 use holochain_types::signal::Signal;
+use holochain_websocket::native_tls;
+use holochain_websocket::HandshakeCheck;
+use holochain_websocket::HandshakeRequest;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
+use holochain_websocket::ListenerStream;
 use holochain_websocket::WebsocketConfig;
 use holochain_websocket::WebsocketListener;
 use holochain_websocket::WebsocketMessage;
 use holochain_websocket::WebsocketReceiver;
 use holochain_websocket::WebsocketSender;
+use std::collections::HashMap;
 use std::convert::TryFrom;
-
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::broadcast;
 use tokio::task::JoinHandle;
@@ -32,38 +45,123 @@ pub(crate) const SIGNAL_BUFFER_SIZE: usize = 50;
 /// The maximum number of connections allowed to the admin interface
 pub const MAX_CONNECTIONS: usize = 400;
 
-/// Create a WebsocketListener to be used in interfaces
+/// Create a WebsocketListener to be used in interfaces, listening as described
+/// by the driver, and refusing clients which fail the handshake check if one
+/// is given. Also returns the driver with the port that was bound, which
+/// differs from the one passed in if that asked for a free port to be chosen.
 pub async fn spawn_websocket_listener(
-    port: u16,
-) -> InterfaceResult<(
-    ListenerHandle,
-    impl futures::stream::Stream<Item = ListenerItem>,
-)> {
+    driver: &InterfaceDriver,
+    handshake_check: Option<HandshakeCheck>,
+) -> InterfaceResult<(ListenerHandle, ListenerStream, InterfaceDriver)> {
     trace!("Initializing Admin interface");
-    let listener = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
-    trace!("LISTENING AT: {}", listener.0.local_addr());
-    Ok(listener)
+    let config = Arc::new(WebsocketConfig {
+        handshake_check,
+        ..Default::default()
+    });
+    let (handle, listener, driver) = match driver {
+        InterfaceDriver::Websocket {
+            port,
+            bind_address,
+            tls,
+        } => {
+            let addr = SocketAddr::new(
+                bind_address.unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST)),
+                *port,
+            );
+            let (handle, listener) = match tls {
+                Some(tls) => {
+                    let identity = load_tls_identity(tls)?;
+                    let (handle, listener) = WebsocketListener::bind_tls_with_handle(
+                        url2!("wss://{}", addr),
+                        config,
+                        identity,
+                    )
+                    .await?;
+                    (handle, futures::StreamExt::boxed(listener))
+                }
+                None => {
+                    let (handle, listener) =
+                        WebsocketListener::bind_with_handle(url2!("ws://{}", addr), config).await?;
+                    (handle, futures::StreamExt::boxed(listener))
+                }
+            };
+            let port = handle
+                .local_addr()
+                .port()
+                .ok_or(InterfaceError::PortError)?;
+            let driver = InterfaceDriver::Websocket {
+                port,
+                bind_address: *bind_address,
+                tls: tls.clone(),
+            };
+            (handle, listener, driver)
+        }
+        #[cfg(unix)]
+        InterfaceDriver::UnixSocket { path, mode } => {
+            let (handle, listener) =
+                WebsocketListener::bind_unix_with_handle(path, *mode, config).await?;
+            (handle, futures::StreamExt::boxed(listener), driver.clone())
+        }
+        #[cfg(not(unix))]
+        InterfaceDriver::UnixSocket { .. } => {
+            return Err(InterfaceError::Other(
+                "Unix domain sockets are not supported on this platform".to_string(),
+            ))
+        }
+    };
+    trace!("LISTENING AT: {}", handle.local_addr());
+    Ok((handle, listener, driver))
+}
+
+/// Read the certificate and private key which an interface serves secure
+/// websockets with.
+fn load_tls_identity(tls: &InterfaceTlsConfig) -> InterfaceResult<native_tls::Identity> {
+    let read = |path: &std::path::Path| {
+        std::fs::read(path).map_err(|e| {
+            InterfaceError::TlsCertificate(format!("could not read {}: {}", path.display(), e))
+        })
+    };
+    native_tls::Identity::from_pkcs8(&read(&tls.cert_path)?, &read(&tls.key_path)?)
+        .map_err(|e| InterfaceError::TlsCertificate(e.to_string()))
 }
 
-/// Create an Admin Interface, which only receives AdminRequest messages
-/// from the external client
+/// Spawn a task which closes the listener, and with it all child
+/// connections, when the conductor stops or the returned closer is dropped.
+fn spawn_listener_closer(
+    tm: &TaskManagerClient,
+    name: &str,
+    handle: ListenerHandle,
+) -> InterfaceCloser {
+    let (closer, closed) = tokio::sync::oneshot::channel();
+    tm.add_conductor_task_ignored(name, |stop| {
+        handle
+            .close_on(async move {
+                tokio::select! {
+                    _ = stop => {}
+                    _ = closed => {}
+                }
+                true
+            })
+            .map(Ok)
+    });
+    closer
+}
+
+/// Create an Admin Interface, which receives AdminRequest messages
+/// from the external client and sends it any AdminSignals.
+/// The interface is closed when the returned closer is dropped.
 pub fn spawn_admin_interface_tasks<A: InterfaceApi>(
     tm: TaskManagerClient,
     handle: ListenerHandle,
     listener: impl futures::stream::Stream<Item = ListenerItem> + Send + 'static,
     api: A,
-    port: u16,
-) {
+    signal_broadcaster: broadcast::Sender<AdminSignal>,
+) -> InterfaceCloser {
+    let name = format!("admin interface, {}", handle.local_addr());
     // Task that will kill the listener and all child connections.
-    tm.add_conductor_task_ignored("admin interface websocket closer", |stop| {
-        handle.close_on(stop.map(|_| true)).map(Ok)
-    });
+    let closer = spawn_listener_closer(&tm, "admin interface websocket closer", handle);
 
-    tm.add_conductor_task_ignored(&format!("admin interface, port {}", port), |_stop| {
+    tm.add_conductor_task_ignored(&name, |_stop| {
         async move {
             let mut active_connections = Vec::new();
             futures::pin_mut!(listener);
@@ -72,7 +170,7 @@ pub fn spawn_admin_interface_tasks<A: InterfaceApi>(
                 active_connections.retain_mut(|handle: &mut JoinHandle<()>| !handle.is_finished());
 
                 match connection {
-                    Ok((_, rx_from_iface)) => {
+                    Ok((tx_to_iface, rx_from_iface)) => {
                         if active_connections.len() >= MAX_CONNECTIONS {
                             warn!("Connection limit reached, dropping newly opened connection. num_connections={}", active_connections.len());
                             // Max connections so drop this connection
@@ -80,10 +178,17 @@ pub fn spawn_admin_interface_tasks<A: InterfaceApi>(
                             continue;
                         };
                         debug!("Accepting new connection with number of existing connections {}", active_connections.len());
-                        active_connections.push(tokio::task::spawn(recv_incoming_admin_msgs(
-                            api.clone(),
-                            rx_from_iface,
-                        )));
+                        let signals = spawn_outgoing_admin_signals(
+                            signal_broadcaster.subscribe(),
+                            tx_to_iface,
+                        );
+                        let api = api.clone();
+                        active_connections.push(tokio::task::spawn(async move {
+                            recv_incoming_admin_msgs(api, rx_from_iface).await;
+                            // Dropping the sender along with the receiver
+                            // closes the connection.
+                            signals.abort();
+                        }));
                     }
                     Err(err) => {
                         warn!("Admin socket connection failed: {}", err);
@@ -93,31 +198,63 @@ pub fn spawn_admin_interface_tasks<A: InterfaceApi>(
             ManagedTaskResult::Ok(())
         }
     });
+    closer
+}
+
+/// Check the handshake request of a client connecting to an app interface.
+/// The client must send an allowed `Origin` header, and a token which
+/// `authenticate` accepts as the `token` query parameter.
+pub(crate) fn check_app_handshake(
+    request: &HandshakeRequest,
+    allowed_origins: &AllowedOrigins,
+    authenticate: impl FnOnce(&str) -> bool,
+) -> Result<(), String> {
+    let origin = request
+        .headers()
+        .get("Origin")
+        .map(|origin| origin.to_str())
+        .transpose()
+        .map_err(|_| "The Origin header is not valid".to_string())?;
+    if !allowed_origins.is_allowed(origin) {
+        return Err(match origin {
+            Some(origin) => format!("Origin {} is not allowed", origin),
+            None => "An Origin header is required".to_string(),
+        });
+    }
+    // Tokens are hex, so they never need decoding.
+    let token = request
+        .uri()
+        .query()
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .find_map(|param| param.strip_prefix("token="));
+    match token {
+        Some(token) if authenticate(token) => Ok(()),
+        Some(_) => Err("The token is not valid".to_string()),
+        None => Err("A token is required".to_string()),
+    }
 }
 
 /// Create an App Interface, which includes the ability to receive signals
-/// from Cells via a broadcast channel
-pub async fn spawn_app_interface_task<A: InterfaceApi>(
+/// from Cells via a broadcast channel. Clients must pass the handshake check
+/// to connect.
+/// Returns the driver with the port that was bound, and a closer which
+/// closes the interface when dropped.
+pub async fn spawn_app_interface_task<A>(
     tm: TaskManagerClient,
-    port: u16,
+    driver: &InterfaceDriver,
+    handshake_check: HandshakeCheck,
     api: A,
     signal_broadcaster: broadcast::Sender<Signal>,
-) -> InterfaceResult<u16> {
+) -> InterfaceResult<(InterfaceDriver, InterfaceCloser)>
+where
+    A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse> + AppInterfaceApi,
+{
     trace!("Initializing App interface");
-    let (handle, mut listener) = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
-    trace!("LISTENING AT: {}", handle.local_addr());
-    let port = handle
-        .local_addr()
-        .port()
-        .ok_or(InterfaceError::PortError)?;
+    let (handle, mut listener, driver) =
+        spawn_websocket_listener(driver, Some(handshake_check)).await?;
     // Task that will kill the listener and all child connections.
-    tm.add_conductor_task_ignored("app interface websocket closer", |stop| {
-        handle.close_on(stop.map(|_| true)).map(Ok)
-    });
+    let closer = spawn_listener_closer(&tm, "app interface websocket closer", handle);
     tm.add_conductor_task_ignored("app interface new connection handler", |_stop| {
         async move {
             // establish a new connection to a client
@@ -133,7 +270,7 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
                         );
                     }
                     Err(err) => {
-                        warn!("Admin socket connection failed: {}", err);
+                        warn!("App socket connection failed: {}", err);
                     }
                 }
             }
@@ -141,7 +278,39 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
             ManagedTaskResult::Ok(())
         }
     });
-    Ok(port)
+    Ok((driver, closer))
+}
+
+/// Forwards admin signals to an admin interface client until either
+/// side closes, or the returned task is aborted.
+fn spawn_outgoing_admin_signals(
+    mut rx_from_conductor: broadcast::Receiver<AdminSignal>,
+    mut tx_to_iface: WebsocketSender,
+) -> JoinHandle<()> {
+    tokio::task::spawn(async move {
+        loop {
+            let signal = match rx_from_conductor.recv().await {
+                Ok(signal) => signal,
+                Err(broadcast::error::RecvError::Lagged(n)) => {
+                    warn!("Admin interface connection missed {} signals", n);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            trace!(msg = "Sending admin signal!", ?signal);
+            let result = async {
+                let bytes = SerializedBytes::try_from(signal)?;
+                tx_to_iface.signal(bytes).await?;
+                InterfaceResult::Ok(())
+            }
+            .await;
+            if let Err(err) = result {
+                // The connection has most likely been closed
+                debug!(?err, "stopped sending admin signals");
+                break;
+            }
+        }
+    })
 }
 
 /// Polls for messages coming in from the external client.
@@ -161,19 +330,61 @@ async fn recv_incoming_admin_msgs<A: InterfaceApi>(api: A, rx_from_iface: Websoc
         .await;
 }
 
+/// The signal subscriptions of a single app interface connection, by app.
+/// While empty, every signal is sent.
+type ConnectionSignalSubscriptions =
+    Arc<parking_lot::RwLock<HashMap<InstalledAppId, SignalFilterSet>>>;
+
+/// Whether a signal should be sent over a connection with these
+/// subscriptions. A subscription for an app only lets through app signals
+/// from that app's cells.
+async fn signal_is_subscribed<A: AppInterfaceApi>(
+    api: &A,
+    subscriptions: &ConnectionSignalSubscriptions,
+    signal: &Signal,
+) -> bool {
+    let allowed_by: Vec<InstalledAppId> = {
+        let subscriptions = subscriptions.read();
+        if subscriptions.is_empty() {
+            return true;
+        }
+        let checked = CheckedSignal::new(signal);
+        subscriptions
+            .iter()
+            .filter(|(_, filters)| filters.allows_checked(&checked))
+            .map(|(installed_app_id, _)| installed_app_id.clone())
+            .collect()
+    };
+    match signal {
+        Signal::App { cell_id, .. } => {
+            for installed_app_id in &allowed_by {
+                if api.app_has_cell(installed_app_id, cell_id).await {
+                    return true;
+                }
+            }
+            false
+        }
+        Signal::System(_) => !allowed_by.is_empty(),
+    }
+}
+
 /// Polls for messages coming in from the external client while simultaneously
 /// polling for signals being broadcast from the Cells associated with this
 /// App interface.
-fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
+fn spawn_recv_incoming_msgs_and_outgoing_signals<A>(
     api: A,
     rx_from_iface: WebsocketReceiver,
     rx_from_cell: broadcast::Receiver<Signal>,
     tx_to_iface: WebsocketSender,
-) {
+) where
+    A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse> + AppInterfaceApi,
+{
     use futures::stream::StreamExt;
 
     trace!("CONNECTION: {}", rx_from_iface.remote_addr());
 
+    let subscriptions = ConnectionSignalSubscriptions::default();
+
     let rx_from_cell = futures::stream::unfold(rx_from_cell, |mut rx_from_cell| async move {
         if let Ok(item) = rx_from_cell.recv().await {
             Some((item, rx_from_cell))
@@ -182,9 +393,21 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
         }
     });
 
-    tokio::task::spawn(rx_from_cell.for_each_concurrent(4096, move |signal| {
+    let signal_subscriptions = subscriptions.clone();
+    let signal_api = api.clone();
+    let signals = tokio::task::spawn(rx_from_cell.for_each_concurrent(4096, move |signal| {
         let mut tx_to_iface = tx_to_iface.clone();
+        let api = signal_api.clone();
+        let subscriptions = signal_subscriptions.clone();
         async move {
+            if !signal_is_subscribed(&api, &subscriptions, &signal).await {
+                trace!(msg = "Dropping unsubscribed signal", ?signal);
+                return;
+            }
+            if !api.allows_signal(&signal).await {
+                trace!(msg = "Dropping signal from another app", ?signal);
+                return;
+            }
             trace!(msg = "Sending signal!", ?signal);
             if let Err(err) = async move {
                 let bytes = SerializedBytes::try_from(signal)?;
@@ -198,14 +421,46 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
         }
     }));
 
-    tokio::task::spawn(rx_from_iface.for_each_concurrent(4096, move |msg| {
-        let api = api.clone();
-        async move {
-            if let Err(err) = handle_incoming_message(msg, api).await {
-                error!(?err, "error handling websocket message");
-            }
-        }
-    }));
+    tokio::task::spawn(async move {
+        rx_from_iface
+            .for_each_concurrent(4096, move |msg| {
+                let api = api.clone();
+                let subscriptions = subscriptions.clone();
+                async move {
+                    if let Err(err) = handle_incoming_app_message(msg, api, subscriptions).await {
+                        error!(?err, "error handling websocket message");
+                    }
+                }
+            })
+            .await;
+        // Dropping the sender along with the receiver closes the connection.
+        signals.abort();
+    });
+}
+
+/// Handles messages on app interfaces, applying any signal subscription
+/// to the connection once the api has accepted it.
+async fn handle_incoming_app_message<A>(
+    ws_msg: WebsocketMessage,
+    api: A,
+    subscriptions: ConnectionSignalSubscriptions,
+) -> InterfaceResult<()>
+where
+    A: InterfaceApi<ApiRequest = AppRequest, ApiResponse = AppResponse>,
+{
+    let (bytes, respond) = ws_msg;
+    let request = AppRequest::try_from(bytes);
+    let subscription = match &request {
+        Ok(AppRequest::SubscribeSignals(subscription)) => Some((**subscription).clone()),
+        _ => None,
+    };
+    let response = api.handle_request(request).await?;
+    if let (Some(subscription), AppResponse::SignalsSubscribed) = (subscription, &response) {
+        subscriptions
+            .write()
+            .insert(subscription.installed_app_id, subscription.filters);
+    }
+    Ok(respond.respond(response.try_into()?).await?)
 }
 
 /// Handles messages on all interfaces
@@ -230,6 +485,7 @@ pub mod test {
     use crate::conductor::api::AdminRequest;
     use crate::conductor::api::AdminResponse;
     use crate::conductor::api::RealAdminInterfaceApi;
+    use crate::conductor::api::RealAppInterfaceApi;
     use crate::conductor::conductor::ConductorBuilder;
     use crate::conductor::state::ConductorState;
     use crate::conductor::Conductor;
@@ -239,12 +495,14 @@ pub mod test {
     use crate::test_utils::install_app_in_conductor;
     use ::fixt::prelude::*;
     use futures::future::FutureExt;
+    use holochain_conductor_api::signal_subscription::SignalSubscription;
     use holochain_p2p::{AgentPubKeyExt, DnaHashExt};
     use holochain_serialized_bytes::prelude::*;
     use holochain_sqlite::prelude::*;
     use holochain_state::prelude::test_db_dir;
     use holochain_trace;
     use holochain_types::prelude::*;
+    use holochain_types::signal::test_signal;
     use holochain_types::test_utils::fake_agent_pubkey_1;
     use holochain_types::test_utils::fake_dna_zomes;
     use holochain_wasm_test_utils::TestWasm;
@@ -298,7 +556,7 @@ pub mod test {
 
         conductor_handle
             .clone()
-            .install_app_legacy("test app".to_string(), cell_data)
+            .install_app_legacy("test app".to_string(), cell_data, false)
             .await
             .unwrap();
 
@@ -804,7 +1062,12 @@ pub mod test {
         holochain_trace::test_run().ok();
         let (_tmpdir, conductor_handle) = setup_admin().await;
         let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
-        let msg = AdminRequest::AttachAppInterface { port: None };
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            driver: None,
+            allowed_origins: AllowedOrigins::Any,
+            installed_app_id: None,
+        };
         let msg = msg.try_into().unwrap();
         let respond = |bytes: SerializedBytes| {
             let response: AdminResponse = bytes.try_into().unwrap();
@@ -817,6 +1080,192 @@ pub mod test {
         conductor_handle.shutdown().await.unwrap().unwrap();
     }
 
+    #[test]
+    fn app_handshake_needs_token_and_allowed_origin() {
+        let request = |query: &str, origin: Option<&str>| {
+            let mut request = HandshakeRequest::builder().uri(format!("/{}", query));
+            if let Some(origin) = origin {
+                request = request.header("Origin", origin);
+            }
+            request.body(()).unwrap()
+        };
+        let authenticate = |token: &str| token == "abc";
+        let origins = AllowedOrigins::from("http://localhost:8888".to_string());
+
+        for (query, origin, allowed_origins, ok) in [
+            ("?token=abc", None, &AllowedOrigins::Any, true),
+            (
+                "?a=b&token=abc",
+                Some("http://x"),
+                &AllowedOrigins::Any,
+                true,
+            ),
+            ("", None, &AllowedOrigins::Any, false),
+            ("?token=abd", None, &AllowedOrigins::Any, false),
+            ("?token=abc", Some("http://localhost:8888"), &origins, true),
+            ("?token=abc", Some("http://localhost:8889"), &origins, false),
+            ("?token=abc", None, &origins, false),
+        ] {
+            let result =
+                check_app_handshake(&request(query, origin), allowed_origins, authenticate);
+            assert_eq!(result.is_ok(), ok, "{} {:?} {:?}", query, origin, result);
+        }
+
+        // The token isn't used up by a client from a disallowed origin.
+        let mut used = false;
+        let _ = check_app_handshake(&request("?token=abc", None), &origins, |_| {
+            used = true;
+            true
+        });
+        assert!(!used);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn app_interface_restricted_to_app() {
+        holochain_trace::test_run().ok();
+        let dna = fake_dna_zomes(&Uuid::new_v4().to_string(), vec![]);
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![dna], vec![(cell_id.clone(), None)]).await;
+        let signal = Signal::App {
+            cell_id: cell_id.clone(),
+            zome_name: "zome".into(),
+            signal: AppSignal::new(ExternIO::encode(()).unwrap()),
+        };
+
+        let app_info = |app_api: RealAppInterfaceApi| async move {
+            app_api
+                .handle_app_request(AppRequest::AppInfo {
+                    installed_app_id: "test app".to_string(),
+                })
+                .await
+        };
+
+        let app_api = RealAppInterfaceApi::new(conductor_handle.clone());
+        assert_matches!(
+            app_info(app_api.clone()).await,
+            AppResponse::AppInfo(Some(_))
+        );
+        assert!(app_api.allows_signal(&signal).await);
+
+        let app_api =
+            RealAppInterfaceApi::restricted_to_app(conductor_handle.clone(), "test app".into());
+        assert_matches!(
+            app_info(app_api.clone()).await,
+            AppResponse::AppInfo(Some(_))
+        );
+        assert!(app_api.allows_signal(&signal).await);
+
+        let app_api =
+            RealAppInterfaceApi::restricted_to_app(conductor_handle.clone(), "other app".into());
+        assert_matches!(app_info(app_api.clone()).await, AppResponse::Error(_));
+        assert!(!app_api.allows_signal(&signal).await);
+        assert!(app_api.allows_signal(&test_signal("hi")).await);
+        let response = app_api
+            .handle_app_request(AppRequest::NetworkInfo(Box::new(
+                NetworkInfoRequestPayload {
+                    agent_pub_key: cell_id.agent_pubkey().clone(),
+                    dnas: vec![cell_id.dna_hash().clone()],
+                    last_time_queried: None,
+                },
+            )))
+            .await;
+        assert_matches!(response, AppResponse::Error(_));
+
+        // A cell which the app no longer has stops passing the check, even
+        // though it was seen before.
+        let app_api =
+            RealAppInterfaceApi::restricted_to_app(conductor_handle.clone(), "test app".into());
+        assert!(app_api.allows_signal(&signal).await);
+        conductor_handle
+            .clone()
+            .uninstall_app(&"test app".to_string())
+            .await
+            .unwrap();
+        assert!(!app_api.allows_signal(&signal).await);
+
+        conductor_handle.shutdown().await.unwrap().unwrap();
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn subscribe_signals() {
+        holochain_trace::test_run().ok();
+        let dna = fake_dna_zomes(&Uuid::new_v4().to_string(), vec![]);
+        let cell_id = CellId::from((dna.dna_hash().clone(), fake_agent_pubkey_1()));
+        let (_tmpdir, conductor_handle) =
+            setup_admin_fake_cells(vec![dna], vec![(cell_id.clone(), None)]).await;
+        let app_api = RealAppInterfaceApi::new(conductor_handle.clone());
+        let subscriptions = ConnectionSignalSubscriptions::default();
+        let signal = test_signal("hi");
+        let is_subscribed = |signal: Signal| {
+            let app_api = app_api.clone();
+            let subscriptions = subscriptions.clone();
+            async move { signal_is_subscribed(&app_api, &subscriptions, &signal).await }
+        };
+        assert!(is_subscribed(signal.clone()).await);
+
+        let subscribe = |installed_app_id: &str, filters: SignalFilterSet, accepted: bool| {
+            let msg = AppRequest::SubscribeSignals(Box::new(SignalSubscription::new(
+                installed_app_id.to_string(),
+                filters,
+            )));
+            let respond = move |bytes: SerializedBytes| {
+                let response: AppResponse = bytes.try_into().unwrap();
+                if accepted {
+                    assert_matches!(response, AppResponse::SignalsSubscribed);
+                } else {
+                    assert_matches!(response, AppResponse::Error(_));
+                }
+                async { Ok(()) }.boxed().into()
+            };
+            (msg.try_into().unwrap(), Respond::Request(Box::new(respond)))
+        };
+
+        // - Subscriptions for apps which aren't installed are not applied
+        handle_incoming_app_message(
+            subscribe("not installed", SignalFilterSet::block_all(), false),
+            app_api.clone(),
+            subscriptions.clone(),
+        )
+        .await
+        .unwrap();
+        assert!(is_subscribed(signal.clone()).await);
+
+        handle_incoming_app_message(
+            subscribe("test app", SignalFilterSet::block_all(), true),
+            app_api.clone(),
+            subscriptions.clone(),
+        )
+        .await
+        .unwrap();
+        assert!(!is_subscribed(signal.clone()).await);
+
+        // - A subscription only lets through app signals from its app's cells
+        handle_incoming_app_message(
+            subscribe("test app", SignalFilterSet::allow_all(), true),
+            app_api.clone(),
+            subscriptions.clone(),
+        )
+        .await
+        .unwrap();
+        let app_signal = |cell_id: CellId| Signal::App {
+            cell_id,
+            zome_name: "zome".into(),
+            signal: AppSignal::new(ExternIO::encode(()).unwrap()),
+        };
+        assert!(is_subscribed(signal).await);
+        assert!(is_subscribed(app_signal(cell_id.clone())).await);
+        assert!(
+            !is_subscribed(app_signal(CellId::new(
+                cell_id.dna_hash().clone(),
+                fake_agent_pubkey_2()
+            )))
+            .await
+        );
+
+        conductor_handle.shutdown().await.unwrap().unwrap();
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn dump_state() {
         holochain_trace::test_run().ok();
@@ -875,6 +1324,7 @@ pub mod test {
                     .map(TestZomes::from)
                     .map(|z| z.coordinator.into_inner())
                     .collect(),
+                rate_limits: Default::default(),
             },
             zomes.into_iter().flat_map(|t| Vec::<DnaWasm>::from(t)),
         )
@@ -39,6 +39,8 @@ pub struct KitsuneHostImpl {
     strat: ArqStrat,
     lair_tag: Option<Arc<str>>,
     lair_client: Option<lair_keystore_api::LairClient>,
+    #[cfg(any(test, feature = "test_utils"))]
+    network_faults: kitsune_p2p::fault::NetworkFaults,
 }
 
 /// Manual Debug implementation to skip non debuggable fields.
@@ -68,8 +70,16 @@ impl KitsuneHostImpl {
             strat,
             lair_tag,
             lair_client,
+            #[cfg(any(test, feature = "test_utils"))]
+            network_faults: Default::default(),
         })
     }
+
+    /// The faults injected on messages sent by this host's node.
+    #[cfg(any(test, feature = "test_utils"))]
+    pub fn network_faults(&self) -> kitsune_p2p::fault::NetworkFaults {
+        self.network_faults.clone()
+    }
 }
 
 impl KitsuneHost for KitsuneHostImpl {
@@ -295,4 +305,9 @@ impl KitsuneHost for KitsuneHostImpl {
     fn lair_client(&self) -> Option<lair_keystore_api::LairClient> {
         self.lair_client.clone()
     }
+
+    #[cfg(any(test, feature = "test_utils"))]
+    fn network_faults(&self) -> Option<kitsune_p2p::fault::NetworkFaults> {
+        Some(self.network_faults.clone())
+    }
 }
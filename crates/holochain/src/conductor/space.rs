@@ -37,7 +37,7 @@ use holochain_state::{
     host_fn_workspace::SourceChainWorkspace,
     mutations,
     prelude::{from_blob, StateQueryResult},
-    query::{map_sql_dht_op_common, StateQueryError},
+    query::{map_sql_dht_op_common, map_sql_warrant_op, StateQueryError},
     source_chain::{SourceChain, SourceChainResult},
 };
 use holochain_types::prelude::CellId;
@@ -74,6 +74,9 @@ pub struct Spaces {
     pub(crate) conductor_db: DbWrite<DbKindConductor>,
     pub(crate) wasm_db: DbWrite<DbKindWasm>,
     network_config: KitsuneP2pConfig,
+    /// Counts the updates to the conductor state, so that anything derived
+    /// from it can tell when to look again.
+    state_version: Arc<std::sync::atomic::AtomicU64>,
 }
 
 #[derive(Clone)]
@@ -155,6 +158,7 @@ impl Spaces {
             conductor_db,
             wasm_db,
             network_config: config.network.clone().unwrap_or_default(),
+            state_version: Default::default(),
         })
     }
 
@@ -168,6 +172,11 @@ impl Spaces {
         holochain_state::block::unblock(&self.conductor_db, input).await
     }
 
+    /// All the blocks, including those which have ended.
+    pub async fn list_blocks(&self) -> DatabaseResult<Vec<Block>> {
+        holochain_state::block::list_blocks(&self.conductor_db).await
+    }
+
     async fn node_agents_in_spaces(
         &self,
         node_id: NodeId,
@@ -223,18 +232,30 @@ impl Spaces {
                 )
                 .await?
             }
-            // @todo
-            BlockTargetId::Ip(_) => {
-                vec![]
+            // IPs aren't tied to any cells, so only blocks on the IP itself,
+            // or a range containing it, apply.
+            BlockTargetId::Ip(_) | BlockTargetId::IpRange(_) => {
+                return self
+                    .conductor_db
+                    .read_async(move |txn| {
+                        holochain_state::block::query_is_blocked(&txn, target_id, timestamp)
+                    })
+                    .await;
             }
         };
 
         // If node_agents_in_spaces is not yet initialized, we can't know anything about
-        // which cells are blocked, so avoid the race condition by returning false
+        // which cells are blocked, so avoid the race condition by only honouring
+        // blocks on the target itself.
         // TODO: actually fix the preflight, because this could be a loophole for someone
         //       to evade a block in some circumstances
         if cell_ids.is_empty() {
-            return Ok(false);
+            return self
+                .conductor_db
+                .read_async(move |txn| {
+                    holochain_state::block::query_is_blocked(&txn, target_id, timestamp)
+                })
+                .await;
         }
 
         self.conductor_db
@@ -320,9 +341,18 @@ impl Spaces {
                 Result::<_, ConductorError>::Ok((new_state, output))
             })
             .await?;
+        self.state_version
+            .fetch_add(1, std::sync::atomic::Ordering::AcqRel);
         Ok(output)
     }
 
+    /// The number of times the conductor state has been updated. Read it
+    /// before reading the state, so that a change in between is seen later.
+    pub fn state_version(&self) -> u64 {
+        self.state_version
+            .load(std::sync::atomic::Ordering::Acquire)
+    }
+
     /// Get something from every space
     pub fn get_from_spaces<R, F: FnMut(&Space) -> R>(&self, f: F) -> Vec<R> {
         self.map
@@ -564,10 +594,12 @@ impl Spaces {
     ) -> ConductorResult<Vec<(holo_hash::DhtOpHash, holochain_types::dht_op::DhtOp)>> {
         let mut sql = "
             SELECT DhtOp.hash, DhtOp.type AS dht_type,
-            Action.blob AS action_blob, Entry.blob AS entry_blob
+            Action.blob AS action_blob, Entry.blob AS entry_blob,
+            Warrant.blob AS warrant_blob
             FROM DHtOp
-            JOIN Action ON DhtOp.action_hash = Action.hash
+            LEFT JOIN Action ON DhtOp.action_hash = Action.hash
             LEFT JOIN Entry ON Action.entry_hash = Entry.hash
+            LEFT JOIN Warrant ON DhtOp.hash = Warrant.hash
             WHERE
             DhtOp.hash = ?
         "
@@ -590,9 +622,13 @@ impl Spaces {
                     let mut stmt = txn.prepare_cached(&sql)?;
                     let mut rows = stmt.query([hash])?;
                     if let Some(row) = rows.next()? {
+                        let hash: DhtOpHash = row.get("hash")?;
+                        if let Some(op) = map_sql_warrant_op(row)? {
+                            out.push((hash, op));
+                            continue;
+                        }
                         let action = from_blob::<SignedAction>(row.get("action_blob")?)?;
                         let op_type: DhtOpType = row.get("dht_type")?;
-                        let hash: DhtOpHash = row.get("hash")?;
                         // Check the entry isn't private before gossiping it.
                         let mut entry: Option<Entry> = None;
                         if action
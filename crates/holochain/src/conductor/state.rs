@@ -1,6 +1,7 @@
 //! Structs which allow the Conductor's state to be persisted across
 //! startups and shutdowns
 
+use holochain_conductor_api::config::AllowedOrigins;
 use holochain_conductor_api::config::InterfaceDriver;
 use holochain_conductor_api::signal_subscription::SignalSubscription;
 use holochain_types::prelude::*;
@@ -189,6 +190,14 @@ impl ConductorState {
         Ok((app, delta))
     }
 
+    /// Whether any app coordinates this cell with the conductor's Chain Head
+    /// Coordinator.
+    pub fn cell_uses_chc(&self, cell_id: &CellId) -> bool {
+        self.installed_apps
+            .values()
+            .any(|app| app.uses_chc(cell_id))
+    }
+
     /// Returns the interface configuration with the given ID if present
     pub fn interface_by_id(&self, id: &AppInterfaceId) -> Option<AppInterfaceConfig> {
         self.app_interfaces.get(id).cloned()
@@ -198,10 +207,8 @@ impl ConductorState {
 /// Here, interfaces are user facing and make available zome functions to
 /// GUIs, browser based web UIs, local native UIs, other local applications and scripts.
 /// We currently have:
-/// * websockets
-///
-/// We will also soon develop
-/// * Unix domain sockets
+/// * websockets, optionally over TLS
+/// * websockets over Unix domain sockets
 ///
 /// The cells (referenced by ID) that are to be made available via that interface should be listed.
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -212,14 +219,29 @@ pub struct AppInterfaceConfig {
 
     /// The driver for the interface, e.g. Websocket
     pub driver: InterfaceDriver,
+
+    /// The web origins which clients may connect from
+    #[serde(default)]
+    pub allowed_origins: AllowedOrigins,
+
+    /// The app which the interface is restricted to, if any
+    #[serde(default)]
+    pub installed_app_id: Option<InstalledAppId>,
 }
 
 impl AppInterfaceConfig {
     /// Create config for a websocket interface
     pub fn websocket(port: u16) -> Self {
+        Self::new(InterfaceDriver::websocket(port))
+    }
+
+    /// Create config for an interface with any driver
+    pub fn new(driver: InterfaceDriver) -> Self {
         Self {
             signal_subscriptions: HashMap::new(),
-            driver: InterfaceDriver::Websocket { port },
+            driver,
+            allowed_origins: AllowedOrigins::Any,
+            installed_app_id: None,
         }
     }
 }
@@ -0,0 +1,108 @@
+use crate::conductor::api::AdminInterfaceApi;
+use crate::conductor::api::RealAdminInterfaceApi;
+use crate::conductor::conductor::DpkiServiceExt;
+use crate::sweettest::*;
+use holochain_conductor_api::conductor::DpkiConfig;
+use holochain_conductor_api::AdminRequest;
+use holochain_conductor_api::AdminResponse;
+use holochain_types::prelude::*;
+use holochain_wasm_test_utils::TestWasm;
+use matches::assert_matches;
+use tempfile::tempdir;
+
+/// Config for the conductor to install the test DPKI DNA, from a bundle
+/// written into the given directory.
+async fn dpki_config(dir: &std::path::Path) -> DpkiConfig {
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Deepkey]).await;
+    let dna_path = dir.join("dpki.dna");
+    DnaBundle::from_dna_file(dna)
+        .await
+        .unwrap()
+        .write_to_file(&dna_path)
+        .await
+        .unwrap();
+    DpkiConfig {
+        instance_id: "dpki".into(),
+        dna_path,
+        network_seed: None,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dpki_registers_and_revokes_generated_keys() {
+    let tmp = tempdir().unwrap();
+    let mut config = SweetConductorConfig::standard();
+    config.dpki = Some(dpki_config(tmp.path()).await);
+    let mut conductor = SweetConductor::from_config(config).await;
+
+    // - The DPKI app is installed and its cell is running
+    let dpki = conductor.dpki().unwrap();
+    let dpki_cell_id = dpki.cell_ids().into_iter().next().cloned().unwrap();
+    assert!(conductor.live_cell_ids().contains(&dpki_cell_id));
+    let dpki_cell = conductor.get_sweet_cell(dpki_cell_id.clone()).unwrap();
+
+    // - Keys which DPKI doesn't know of are valid, since they may not
+    //   have come from DPKI, or their registration may not have arrived
+    let unregistered = conductor.keystore().new_sign_keypair_random().await.unwrap();
+    assert!(dpki
+        .is_key_valid(unregistered, Timestamp::now())
+        .await
+        .unwrap());
+
+    // - Keys generated over the admin API are registered with DPKI
+    let before_registration = Timestamp::now();
+    let admin_api = RealAdminInterfaceApi::new(conductor.raw_handle());
+    let agent = match admin_api
+        .handle_admin_request(AdminRequest::GenerateAgentPubKey)
+        .await
+    {
+        AdminResponse::AgentPubKeyGenerated(agent) => agent,
+        other => panic!("unexpected response {:?}", other),
+    };
+    consistency_10s([&dpki_cell]).await;
+    let registered: KeyState = conductor
+        .call(
+            &dpki_cell.zome(DPKI_ZOME_NAME),
+            DPKI_KEY_STATE_FN_NAME,
+            KeyStateQuery {
+                key: agent.clone(),
+                timestamp: Timestamp::now(),
+            },
+        )
+        .await;
+    assert_matches!(registered, KeyState::Valid(_));
+
+    // - A registered key is only valid from when it was registered
+    assert!(!dpki
+        .is_key_valid(agent.clone(), before_registration)
+        .await
+        .unwrap());
+
+    // - A revoked key is only invalid from when it was revoked
+    let before_revocation = Timestamp::now();
+    assert!(dpki
+        .is_key_valid(agent.clone(), before_revocation)
+        .await
+        .unwrap());
+    dpki.remove_key(agent.clone()).await.unwrap();
+    consistency_10s([&dpki_cell]).await;
+    assert!(dpki
+        .is_key_valid(agent.clone(), before_revocation)
+        .await
+        .unwrap());
+    assert!(!dpki
+        .is_key_valid(agent.clone(), Timestamp::now())
+        .await
+        .unwrap());
+
+    // - On restart the same DPKI cell is used, and is running again
+    conductor.shutdown().await;
+    conductor.startup().await;
+    let dpki = conductor.dpki().unwrap();
+    assert_eq!(
+        dpki.cell_ids().into_iter().cloned().collect::<Vec<_>>(),
+        vec![dpki_cell_id.clone()]
+    );
+    assert!(conductor.live_cell_ids().contains(&dpki_cell_id));
+    assert!(!dpki.is_key_valid(agent, Timestamp::now()).await.unwrap());
+}
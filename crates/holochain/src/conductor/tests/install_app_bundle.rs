@@ -1,7 +1,8 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use crate::{conductor::error::ConductorError, sweettest::*};
-use fixt::prelude::strum_macros;
+use fixt::prelude::*;
+use holo_hash::fixt::AppHashFixturator;
 use holo_hash::{AgentPubKey, DnaHash};
 use holochain_types::prelude::{
     mapvec, AppBundle, AppBundleError, AppBundleSource, AppManifestCurrentBuilder,
@@ -52,6 +53,7 @@ async fn clone_only_provisioning_creates_no_cell_and_allows_cloning() {
             source: AppBundleSource::Bundle(bundle),
             installed_app_id: Some("app_1".into()),
             network_seed: None,
+            use_chc: false,
             membrane_proofs: HashMap::new(),
         }
     }
@@ -162,6 +164,7 @@ async fn reject_duplicate_app_for_same_agent() {
             source: AppBundleSource::Bundle(bundle),
             installed_app_id: Some("app_1".into()),
             network_seed: None,
+            use_chc: false,
             membrane_proofs: HashMap::new(),
         })
         .await
@@ -182,6 +185,7 @@ async fn reject_duplicate_app_for_same_agent() {
             installed_app_id: Some("app_2".into()),
             membrane_proofs: HashMap::new(),
             network_seed: None,
+            use_chc: false,
         })
         .await;
     assert_matches!(
@@ -207,6 +211,7 @@ async fn reject_duplicate_app_for_same_agent() {
             installed_app_id: Some("app_2".into()),
             membrane_proofs: HashMap::new(),
             network_seed: None,
+            use_chc: false,
         })
         .await;
     assert_matches!(
@@ -226,6 +231,7 @@ async fn reject_duplicate_app_for_same_agent() {
             installed_app_id: Some("app_2".into()),
             membrane_proofs: HashMap::new(),
             network_seed: Some("network".into()),
+            use_chc: false,
         })
         .await;
     assert!(valid_install_of_second_app.is_ok());
@@ -277,6 +283,7 @@ async fn can_install_app_a_second_time_using_nothing_but_the_manifest_from_app_i
             source: AppBundleSource::Bundle(bundle),
             installed_app_id: Some("app_1".into()),
             network_seed: Some("final seed".into()),
+            use_chc: false,
             membrane_proofs: HashMap::new(),
         })
         .await
@@ -319,6 +326,7 @@ async fn can_install_app_a_second_time_using_nothing_but_the_manifest_from_app_i
             source: AppBundleSource::Bundle(bundle),
             installed_app_id: Some("app_2".into()),
             network_seed: None,
+            use_chc: false,
             membrane_proofs: HashMap::new(),
         })
         .await
@@ -383,6 +391,7 @@ async fn network_seed_regression() {
             source: AppBundleSource::Bundle(bundle1),
             installed_app_id: Some("no-seed".into()),
             network_seed: None,
+            use_chc: false,
             membrane_proofs: HashMap::new(),
         })
         .await
@@ -395,12 +404,330 @@ async fn network_seed_regression() {
             source: AppBundleSource::Bundle(bundle2),
             installed_app_id: Some("yes-seed".into()),
             network_seed: Some("seed".into()),
+            use_chc: false,
             membrane_proofs: HashMap::new(),
         })
         .await
         .unwrap();
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn use_existing_and_create_if_not_exists_share_a_cell() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let alice = SweetAgents::one(conductor.keystore()).await;
+
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let path = PathBuf::from(format!("{}", dna.dna_hash()));
+    let installed_dna_hash = DnaHash::with_data_sync(dna.dna_def());
+    let cell_id = CellId::new(dna.dna_hash().to_owned(), alice.clone());
+
+    let install = |app_id: &str, provisioning: CellProvisioning| {
+        let conductor = conductor.clone();
+        let dna = dna.clone();
+        let path = path.clone();
+        let installed_dna_hash = installed_dna_hash.clone();
+        let agent_key = alice.clone();
+        let app_id = app_id.to_string();
+        async move {
+            let roles = vec![AppRoleManifest {
+                name: "profiles".into(),
+                dna: AppRoleDnaManifest {
+                    location: Some(DnaLocation::Bundled(path.clone())),
+                    modifiers: DnaModifiersOpt::none(),
+                    installed_hash: Some(installed_dna_hash.into()),
+                    clone_limit: 0,
+                },
+                provisioning: Some(provisioning),
+            }];
+            let manifest = AppManifestCurrentBuilder::default()
+                .name("test_app".into())
+                .description(None)
+                .roles(roles)
+                .build()
+                .unwrap();
+            let resources = vec![(path, DnaBundle::from_dna_file(dna).await.unwrap())];
+            let bundle = AppBundle::new(manifest.into(), resources, PathBuf::from("."))
+                .await
+                .unwrap();
+            conductor
+                .clone()
+                .install_app_bundle(InstallAppPayload {
+                    agent_key,
+                    source: AppBundleSource::Bundle(bundle),
+                    installed_app_id: Some(app_id),
+                    network_seed: None,
+                    use_chc: false,
+                    membrane_proofs: HashMap::new(),
+                })
+                .await
+        }
+    };
+
+    // - There is no cell to use yet
+    assert_matches!(
+        install("app_0", CellProvisioning::UseExisting { deferred: false })
+            .await
+            .unwrap_err(),
+        ConductorError::AppBundleError(AppBundleError::CellResolutionFailure(_, _))
+    );
+
+    // - The first app creates the cell, the others reuse it
+    let app_1 = install(
+        "app_1",
+        CellProvisioning::CreateIfNotExists { deferred: false },
+    )
+    .await
+    .unwrap();
+    let app_2 = install("app_2", CellProvisioning::UseExisting { deferred: false })
+        .await
+        .unwrap();
+    let app_3 = install(
+        "app_3",
+        CellProvisioning::CreateIfNotExists { deferred: false },
+    )
+    .await
+    .unwrap();
+    for app in [&app_1, &app_2, &app_3] {
+        assert_eq!(app.all_cells().collect::<Vec<_>>(), vec![&cell_id]);
+    }
+
+    // - Creating the same cell outright is still rejected
+    assert_matches!(
+        install("app_4", CellProvisioning::Create { deferred: false })
+            .await
+            .unwrap_err(),
+        ConductorError::CellAlreadyExists(id) if id == cell_id
+    );
+
+    conductor.enable_app("app_1".into()).await.unwrap();
+    conductor.enable_app("app_2".into()).await.unwrap();
+
+    // - Uninstalling the app which created the cell leaves it running for the other app
+    conductor
+        .clone()
+        .uninstall_app(&"app_1".to_string())
+        .await
+        .unwrap();
+    assert!(conductor.running_cell_ids(None).contains(&cell_id));
+
+    conductor
+        .clone()
+        .uninstall_app(&"app_2".to_string())
+        .await
+        .unwrap();
+    assert!(!conductor.running_cell_ids(None).contains(&cell_id));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn shared_cells_are_found_by_the_dna_hash_with_modifiers_applied() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let alice = SweetAgents::one(conductor.keystore()).await;
+
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let path = PathBuf::from(format!("{}", dna.dna_hash()));
+    let installed_dna_hash = DnaHash::with_data_sync(dna.dna_def());
+    let seeded_dna = dna.clone().with_network_seed("seed".into()).await;
+    let cell_id = CellId::new(seeded_dna.dna_hash().to_owned(), alice.clone());
+
+    let install = |app_id: &str,
+                   provisioning: CellProvisioning,
+                   modifiers: DnaModifiersOpt,
+                   network_seed: Option<&str>| {
+        let conductor = conductor.clone();
+        let dna = dna.clone();
+        let path = path.clone();
+        let installed_dna_hash = installed_dna_hash.clone();
+        let agent_key = alice.clone();
+        let app_id = app_id.to_string();
+        let network_seed = network_seed.map(ToString::to_string);
+        async move {
+            let roles = vec![AppRoleManifest {
+                name: "profiles".into(),
+                dna: AppRoleDnaManifest {
+                    location: Some(DnaLocation::Bundled(path.clone())),
+                    modifiers,
+                    installed_hash: Some(installed_dna_hash.into()),
+                    clone_limit: 0,
+                },
+                provisioning: Some(provisioning),
+            }];
+            let manifest = AppManifestCurrentBuilder::default()
+                .name("test_app".into())
+                .description(None)
+                .roles(roles)
+                .build()
+                .unwrap();
+            let resources = vec![(path, DnaBundle::from_dna_file(dna).await.unwrap())];
+            let bundle = AppBundle::new(manifest.into(), resources, PathBuf::from("."))
+                .await
+                .unwrap();
+            conductor
+                .clone()
+                .install_app_bundle(InstallAppPayload {
+                    agent_key,
+                    source: AppBundleSource::Bundle(bundle),
+                    installed_app_id: Some(app_id),
+                    network_seed,
+                    use_chc: false,
+                    membrane_proofs: HashMap::new(),
+                })
+                .await
+        }
+    };
+
+    // - Two apps installed with the same network seed share one cell
+    let app_1 = install(
+        "app_1",
+        CellProvisioning::CreateIfNotExists { deferred: false },
+        DnaModifiersOpt::none(),
+        Some("seed"),
+    )
+    .await
+    .unwrap();
+    let app_2 = install(
+        "app_2",
+        CellProvisioning::CreateIfNotExists { deferred: false },
+        DnaModifiersOpt::none(),
+        Some("seed"),
+    )
+    .await
+    .unwrap();
+
+    // - An app which uses the existing cell finds it through its own modifiers
+    let app_3 = install(
+        "app_3",
+        CellProvisioning::UseExisting { deferred: false },
+        DnaModifiersOpt::none().with_network_seed("seed".into()),
+        None,
+    )
+    .await
+    .unwrap();
+    for app in [&app_1, &app_2, &app_3] {
+        assert_eq!(app.all_cells().collect::<Vec<_>>(), vec![&cell_id]);
+    }
+
+    // - Without the network seed there is no cell to use
+    assert_matches!(
+        install(
+            "app_4",
+            CellProvisioning::UseExisting { deferred: false },
+            DnaModifiersOpt::none(),
+            None,
+        )
+        .await
+        .unwrap_err(),
+        ConductorError::AppBundleError(AppBundleError::CellResolutionFailure(_, _))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn failed_genesis_leaves_shared_cells_alone() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let alice = SweetAgents::one(conductor.keystore()).await;
+
+    let (good_dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let (bad_dna, _, _) =
+        SweetDnaFile::unique_from_test_wasms(vec![TestWasm::GenesisSelfCheckInvalid]).await;
+    let cell_id = CellId::new(good_dna.dna_hash().to_owned(), alice.clone());
+
+    let install = |app_id: &str, roles: Vec<(&str, DnaFile, CellProvisioning)>| {
+        let conductor = conductor.clone();
+        let agent_key = alice.clone();
+        let app_id = app_id.to_string();
+        let roles: Vec<_> = roles
+            .into_iter()
+            .map(|(name, dna, provisioning)| (name.to_string(), dna, provisioning))
+            .collect();
+        async move {
+            let mut role_manifests = Vec::new();
+            let mut resources = Vec::new();
+            for (name, dna, provisioning) in roles {
+                let path = PathBuf::from(format!("{}", dna.dna_hash()));
+                role_manifests.push(AppRoleManifest {
+                    name,
+                    dna: AppRoleDnaManifest {
+                        location: Some(DnaLocation::Bundled(path.clone())),
+                        modifiers: DnaModifiersOpt::none(),
+                        installed_hash: Some(DnaHash::with_data_sync(dna.dna_def()).into()),
+                        clone_limit: 0,
+                    },
+                    provisioning: Some(provisioning),
+                });
+                resources.push((path, DnaBundle::from_dna_file(dna).await.unwrap()));
+            }
+            let manifest = AppManifestCurrentBuilder::default()
+                .name("test_app".into())
+                .description(None)
+                .roles(role_manifests)
+                .build()
+                .unwrap();
+            let bundle = AppBundle::new(manifest.into(), resources, PathBuf::from("."))
+                .await
+                .unwrap();
+            conductor
+                .clone()
+                .install_app_bundle(InstallAppPayload {
+                    agent_key,
+                    source: AppBundleSource::Bundle(bundle),
+                    installed_app_id: Some(app_id),
+                    network_seed: None,
+                    use_chc: false,
+                    membrane_proofs: HashMap::new(),
+                })
+                .await
+        }
+    };
+
+    install(
+        "app_1",
+        vec![(
+            "shared",
+            good_dna.clone(),
+            CellProvisioning::CreateIfNotExists { deferred: false },
+        )],
+    )
+    .await
+    .unwrap();
+    conductor.enable_app("app_1".into()).await.unwrap();
+
+    // - Genesis fails for the cell which the second app creates
+    assert_matches!(
+        install(
+            "app_2",
+            vec![
+                (
+                    "shared",
+                    good_dna.clone(),
+                    CellProvisioning::UseExisting { deferred: false },
+                ),
+                (
+                    "bad",
+                    bad_dna.clone(),
+                    CellProvisioning::Create { deferred: false },
+                ),
+            ],
+        )
+        .await
+        .unwrap_err(),
+        ConductorError::GenesisFailed { errors } if errors.len() == 1
+    );
+
+    // - Only the failed app is gone, the app it shared a cell with keeps running
+    assert_eq!(
+        conductor.list_running_apps().await.unwrap(),
+        vec!["app_1".to_string()]
+    );
+    assert_eq!(conductor.list_apps(None).await.unwrap().len(), 1);
+    assert!(conductor.running_cell_ids(None).contains(&cell_id));
+
+    // - The shared cell's chain is untouched
+    let dump = conductor
+        .dump_full_cell_state(&cell_id, None)
+        .await
+        .unwrap();
+    assert_eq!(dump.source_chain_dump.records.len(), 3);
+}
+
 /// Test all possible combinations of Locations and network seeds:
 #[tokio::test(flavor = "multi_thread")]
 #[cfg(feature = "glacial_tests")]
@@ -648,6 +975,7 @@ impl TestCase {
                 source,
                 installed_app_id: Some(case_str.clone()),
                 network_seed,
+                use_chc: false,
                 membrane_proofs: HashMap::new(),
             })
             .await
@@ -657,3 +985,111 @@ impl TestCase {
         (installed_hash, case)
     }
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn install_app_by_hash_from_app_store() {
+    let conductor = SweetConductor::from_standard_config().await;
+    let alice = SweetAgents::one(conductor.keystore()).await;
+
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Create]).await;
+    let path = PathBuf::from(format!("{}", dna.dna_hash()));
+    let roles = vec![AppRoleManifest {
+        name: "name".into(),
+        dna: AppRoleDnaManifest {
+            location: Some(DnaLocation::Bundled(path.clone())),
+            modifiers: DnaModifiersOpt::none(),
+            installed_hash: None,
+            clone_limit: 0,
+        },
+        provisioning: Some(CellProvisioning::Create { deferred: false }),
+    }];
+    let manifest = AppManifestCurrentBuilder::default()
+        .name("test_app".into())
+        .description(None)
+        .roles(roles)
+        .build()
+        .unwrap();
+    let resources = vec![(path, DnaBundle::from_dna_file(dna.clone()).await.unwrap())];
+    let bundle = AppBundle::new(manifest.into(), resources, PathBuf::from("."))
+        .await
+        .unwrap();
+    let expected_hash = bundle.app_hash().unwrap();
+
+    let app_hash = conductor
+        .store_app_bundle(AppBundleSource::Bundle(bundle))
+        .await
+        .unwrap();
+    assert_eq!(app_hash, expected_hash);
+
+    // - An unknown hash can't be installed
+    let unknown = fixt!(AppHash);
+    let err = conductor
+        .clone()
+        .install_app_bundle(InstallAppPayload {
+            agent_key: alice.clone(),
+            source: AppBundleSource::Hash(unknown.clone()),
+            installed_app_id: Some("app_0".into()),
+            network_seed: None,
+            use_chc: false,
+            membrane_proofs: HashMap::new(),
+        })
+        .await
+        .unwrap_err();
+    assert_matches!(err, ConductorError::AppBundleNotInStore(h) if h == unknown);
+
+    conductor
+        .clone()
+        .install_app_bundle(InstallAppPayload {
+            agent_key: alice.clone(),
+            source: AppBundleSource::Hash(app_hash),
+            installed_app_id: Some("app_1".into()),
+            network_seed: None,
+            use_chc: false,
+            membrane_proofs: HashMap::new(),
+        })
+        .await
+        .unwrap();
+
+    let cell_id = CellId::new(dna.dna_hash().to_owned(), alice.clone());
+    let state = conductor.get_state().await.unwrap();
+    let app = state.get_app(&"app_1".to_string()).unwrap();
+    assert_eq!(app.all_cells().collect::<Vec<_>>(), vec![&cell_id]);
+
+    // - An app which only pins the hash of a DNA gets it from the store,
+    //   without having to bundle it again
+    let roles = vec![AppRoleManifest {
+        name: "name".into(),
+        dna: AppRoleDnaManifest {
+            location: Some(DnaLocation::Path(PathBuf::from("/nonexistent.dna"))),
+            modifiers: DnaModifiersOpt::none().with_network_seed("other".into()),
+            installed_hash: Some(dna.dna_hash().clone().into()),
+            clone_limit: 0,
+        },
+        provisioning: Some(CellProvisioning::Create { deferred: false }),
+    }];
+    let manifest = AppManifestCurrentBuilder::default()
+        .name("pinned_app".into())
+        .description(None)
+        .roles(roles)
+        .build()
+        .unwrap();
+    let bundle = AppBundle::new(manifest.into(), vec![], PathBuf::from("."))
+        .await
+        .unwrap();
+    let app_hash = conductor
+        .store_app_bundle(AppBundleSource::Bundle(bundle))
+        .await
+        .unwrap();
+    conductor
+        .clone()
+        .install_app_bundle(InstallAppPayload {
+            agent_key: alice,
+            source: AppBundleSource::Hash(app_hash),
+            installed_app_id: Some("app_2".into()),
+            network_seed: None,
+            use_chc: false,
+            membrane_proofs: HashMap::new(),
+        })
+        .await
+        .unwrap();
+}
@@ -0,0 +1,124 @@
+use crate::conductor::error::ConductorError;
+use crate::sweettest::*;
+use hdk::prelude::*;
+use holochain_types::app::MigrateAgentPayload;
+use holochain_types::dna::DnaFile;
+use matches::assert_matches;
+
+/// A DNA whose `migrate_agent` callback gives the specified result on both
+/// sides, with a function to read back the chain.
+async fn migratable_dna(result: MigrateAgentCallbackResult) -> DnaFile {
+    let zomes = SweetInlineZomes::new(vec![], 0)
+        .function("migrate_agent", move |_api, _: MigrateAgent| {
+            Ok(result.clone())
+        })
+        .function("chain", |api, ()| Ok(api.query(ChainQueryFilter::new())?));
+    SweetDnaFile::unique_from_inline_zomes(zomes).await.0
+}
+
+async fn chain(conductor: &SweetConductor, cell: &SweetCell) -> Vec<Action> {
+    let records: Vec<Record> = conductor
+        .call(&cell.zome(SweetInlineZomes::COORDINATOR), "chain", ())
+        .await;
+    records.into_iter().map(|r| r.action().clone()).collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migrate_agent_links_old_and_new_chains() {
+    let old_dna = migratable_dna(MigrateAgentCallbackResult::Pass).await;
+    let new_dna = migratable_dna(MigrateAgentCallbackResult::Pass).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor
+        .setup_app("app", &[old_dna.clone()])
+        .await
+        .unwrap();
+    let (old_cell,) = app.into_tuple();
+    conductor.register_dna(new_dna.clone()).await.unwrap();
+    // Run init before migrating, so that reading the chain doesn't write to it
+    chain(&conductor, &old_cell).await;
+
+    let new_cell_id = conductor
+        .clone()
+        .migrate_agent(MigrateAgentPayload {
+            app_id: "app".into(),
+            role_name: old_dna.dna_hash().to_string(),
+            new_dna_hash: new_dna.dna_hash().clone(),
+            membrane_proof: None,
+        })
+        .await
+        .unwrap();
+    assert_eq!(new_cell_id.dna_hash(), new_dna.dna_hash());
+    assert_eq!(new_cell_id.agent_pubkey(), old_cell.agent_pubkey());
+
+    // - The old chain ends by pointing at the new DNA
+    assert_matches!(
+        chain(&conductor, &old_cell).await.last(),
+        Some(Action::CloseChain(CloseChain { new_dna_hash, .. })) if new_dna_hash == new_dna.dna_hash()
+    );
+
+    // - The new chain starts, after genesis, by pointing at the old DNA
+    let new_cell = conductor.get_sweet_cell(new_cell_id.clone()).unwrap();
+    assert_matches!(
+        chain(&conductor, &new_cell).await.get(3),
+        Some(Action::OpenChain(OpenChain { prev_dna_hash, .. })) if prev_dna_hash == old_dna.dna_hash()
+    );
+
+    // - The app's role is now based on the new cell
+    let app_info = conductor
+        .get_app_info(&"app".to_string())
+        .await
+        .unwrap()
+        .unwrap();
+    let role_cells = &app_info.cell_info[&old_dna.dna_hash().to_string()];
+    assert_matches!(
+        &role_cells[0],
+        holochain_conductor_api::CellInfo::Provisioned(cell) if cell.cell_id == new_cell_id
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migrate_agent_fails_if_a_callback_fails() {
+    let old_dna = migratable_dna(MigrateAgentCallbackResult::Pass).await;
+    let new_dna = migratable_dna(MigrateAgentCallbackResult::Fail("not today".into())).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor
+        .setup_app("app", &[old_dna.clone()])
+        .await
+        .unwrap();
+    let (old_cell,) = app.into_tuple();
+    conductor.register_dna(new_dna.clone()).await.unwrap();
+    // Run init before migrating, so that reading the chain doesn't write to it
+    chain(&conductor, &old_cell).await;
+
+    let result = conductor
+        .clone()
+        .migrate_agent(MigrateAgentPayload {
+            app_id: "app".into(),
+            role_name: old_dna.dna_hash().to_string(),
+            new_dna_hash: new_dna.dna_hash().clone(),
+            membrane_proof: None,
+        })
+        .await;
+    assert_matches!(
+        result,
+        Err(ConductorError::MigrateAgentFailed(_, reason)) if reason == "not today"
+    );
+
+    // - The old chain was not closed
+    assert!(!chain(&conductor, &old_cell)
+        .await
+        .iter()
+        .any(|action| matches!(action, Action::CloseChain(_))));
+
+    // - The app's role is still based on the old cell
+    let app_info = conductor
+        .get_app_info(&"app".to_string())
+        .await
+        .unwrap()
+        .unwrap();
+    let role_cells = &app_info.cell_info[&old_dna.dna_hash().to_string()];
+    assert_matches!(
+        &role_cells[0],
+        holochain_conductor_api::CellInfo::Provisioned(cell) if cell.cell_id == *old_cell.cell_id()
+    );
+}
@@ -1,6 +1,8 @@
 mod app_info;
 mod cell_cloning;
+mod dpki;
 mod install_app_bundle;
+mod migrate_agent;
 mod network_info;
 mod request_dna_def;
 mod signed_zome_call;
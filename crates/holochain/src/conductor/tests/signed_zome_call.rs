@@ -268,3 +268,123 @@ async fn signed_zome_call_wildcard() {
         .unwrap();
     assert_matches!(response, holochain_zome_types::ZomeCallResponse::Ok(_));
 }
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg(feature = "test_utils")]
+async fn revoked_zome_call_capability() {
+    use holochain_conductor_api::ZomeCall;
+    use holochain_state::nonce::fresh_nonce;
+    use holochain_zome_types::{
+        CapAccess, ExternIO, Timestamp, ZomeCallCapGrant, ZomeCallUnsigned,
+    };
+    use matches::assert_matches;
+
+    let zome = TestWasm::Create;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![zome]).await;
+    let role_name: RoleName = "dna".to_string();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let agent_pub_key = SweetAgents::one(conductor.keystore()).await;
+    let app = conductor
+        .setup_app_for_agent(
+            "app",
+            agent_pub_key.clone(),
+            [&(role_name.clone(), dna.clone())],
+        )
+        .await
+        .unwrap();
+    let cell_id = app.cells()[0].cell_id();
+
+    let cap_access_public_key = fixt!(AgentPubKey, fixt::Predictable, 1);
+    let mut buf = arbitrary::Unstructured::new(&[]);
+    let cap_access_secret = CapSecret::arbitrary(&mut buf).unwrap();
+
+    let cap_grant = ZomeCallCapGrant {
+        tag: "signing_key".into(),
+        functions: GrantedFunctions::All,
+        access: CapAccess::Transferable {
+            secret: cap_access_secret,
+        },
+    };
+    conductor
+        .grant_zome_call_capability(GrantZomeCallCapabilityPayload {
+            cell_id: cell_id.clone(),
+            cap_grant: cap_grant.clone(),
+        })
+        .await
+        .unwrap();
+
+    let grants = conductor
+        .list_capability_grants(cell_id, false)
+        .await
+        .unwrap();
+    let grant_info = grants
+        .into_iter()
+        .find(|info| info.cap_grant == cap_grant)
+        .unwrap();
+    assert_eq!(grant_info.revoked_at, None);
+
+    let call_with_secret = || async {
+        let (nonce, expires_at) = fresh_nonce(Timestamp::now()).unwrap();
+        conductor
+            .call_zome(
+                ZomeCall::try_from_unsigned_zome_call(
+                    &conductor.keystore(),
+                    ZomeCallUnsigned {
+                        provenance: cap_access_public_key.clone(),
+                        cell_id: cell_id.clone(),
+                        zome_name: zome.coordinator_zome_name(),
+                        fn_name: "get_entry".into(),
+                        cap_secret: Some(cap_access_secret),
+                        payload: ExternIO::encode(()).unwrap(),
+                        nonce,
+                        expires_at,
+                    },
+                )
+                .await
+                .unwrap(),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+    };
+
+    assert_matches!(
+        call_with_secret().await,
+        holochain_zome_types::ZomeCallResponse::Ok(_)
+    );
+
+    conductor
+        .revoke_zome_call_capability(cell_id.clone(), grant_info.action_hash.clone())
+        .await
+        .unwrap();
+
+    // the revocation takes effect immediately
+    assert_matches!(
+        call_with_secret().await,
+        holochain_zome_types::ZomeCallResponse::Unauthorized(..)
+    );
+
+    // revoked grants are only listed on request
+    let grants = conductor
+        .list_capability_grants(cell_id, false)
+        .await
+        .unwrap();
+    assert!(grants
+        .iter()
+        .all(|info| info.action_hash != grant_info.action_hash));
+    let grants = conductor
+        .list_capability_grants(cell_id, true)
+        .await
+        .unwrap();
+    let revoked = grants
+        .into_iter()
+        .find(|info| info.action_hash == grant_info.action_hash)
+        .unwrap();
+    assert!(revoked.revoked_at.is_some());
+
+    // a grant cannot be revoked twice
+    assert!(conductor
+        .revoke_zome_call_capability(cell_id.clone(), grant_info.action_hash)
+        .await
+        .is_err());
+}
@@ -18,11 +18,23 @@ pub fn spawn_sys_validation_consumer(
     let trigger_self = tx.clone();
     let workspace = Arc::new(workspace);
     let space = Arc::new(space);
+    let task_manager = conductor.task_manager();
+    let dna_hash = space.dna_hash.clone();
+    // The agents of the DPKI DNA itself aren't registered with DPKI, so its
+    // ops aren't checked against it.
+    let dpki = move || {
+        conductor.dpki().filter(|dpki| {
+            !dpki
+                .cell_ids()
+                .iter()
+                .any(|cell_id| cell_id.dna_hash() == &*dna_hash)
+        })
+    };
 
     super::queue_consumer_dna_bound(
         "sys_validation_consumer",
         space.dna_hash.clone(),
-        conductor.task_manager(),
+        task_manager,
         (tx.clone(), rx),
         move || {
             sys_validation_workflow(
@@ -31,6 +43,7 @@ pub fn spawn_sys_validation_consumer(
                 trigger_app_validation.clone(),
                 trigger_self.clone(),
                 network.clone(),
+                dpki(),
             )
         },
     );
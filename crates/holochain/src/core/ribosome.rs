@@ -76,6 +76,8 @@ use guest_callback::init::InitHostAccess;
 use guest_callback::migrate_agent::MigrateAgentHostAccess;
 use guest_callback::post_commit::PostCommitHostAccess;
 use guest_callback::validate::ValidateHostAccess;
+use guest_callback::weigh::WeighHostAccess;
+use guest_callback::weigh::WeighInvocation;
 use holo_hash::AgentPubKey;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::HolochainP2pDna;
@@ -147,6 +149,7 @@ pub enum HostContext {
     MigrateAgent(MigrateAgentHostAccess),
     PostCommit(PostCommitHostAccess), // MAYBE: add emit_signal access here?
     Validate(ValidateHostAccess),
+    Weigh(WeighHostAccess),
     ZomeCall(ZomeCallHostAccess),
 }
 
@@ -161,6 +164,7 @@ impl From<&HostContext> for HostFnAccess {
             HostContext::EntryDefs(access) => access.into(),
             HostContext::MigrateAgent(access) => access.into(),
             HostContext::PostCommit(access) => access.into(),
+            HostContext::Weigh(access) => access.into(),
         }
     }
 }
@@ -589,6 +593,9 @@ pub struct ZomeCallHostAccess {
     pub network: HolochainP2pDna,
     pub signal_tx: SignalBroadcaster,
     pub call_zome_handle: CellConductorReadHandle,
+    /// The time at which the zome call being made expires, after which
+    /// nothing may keep the call running.
+    pub expires_at: Timestamp,
 }
 
 impl std::fmt::Debug for ZomeCallHostAccess {
@@ -729,6 +736,14 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
         invocation: ValidateInvocation,
     ) -> RibosomeResult<ValidateResult>;
 
+    /// Runs the `weigh` callback of the integrity zome which owns an action.
+    /// Zomes which don't define the callback give the default weight.
+    fn run_weigh(
+        &self,
+        access: WeighHostAccess,
+        invocation: WeighInvocation,
+    ) -> RibosomeResult<RateWeight>;
+
     /// Runs the specified zome fn. Returns the cursor used by HDK,
     /// so that it can be passed on to source chain manager for transactional writes
     fn call_zome_function(
@@ -740,9 +755,56 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
     fn zome_types(&self) -> &Arc<GlobalZomeTypes>;
 }
 
-/// Placeholder for weighing. Currently produces zero weight.
-pub fn weigh_placeholder() -> EntryRateWeight {
-    EntryRateWeight::default()
+/// The number of bytes of serialized entry data which make up one unit of
+/// [`RateBytes`].
+pub const RATE_BYTES_UNIT: usize = 16 * 1024;
+
+/// Weigh an action for rate limiting by running the `weigh` callback of the
+/// integrity zome which owns it. Actions which aren't owned by any zome, such
+/// as those for system entries, get the default weight.
+pub fn weigh(
+    ribosome: &impl RibosomeT,
+    zome_index: Option<ZomeIndex>,
+    input: WeighInput,
+) -> RibosomeResult<RateWeight> {
+    match zome_index.and_then(|zome_index| ribosome.get_integrity_zome(&zome_index)) {
+        Some(zome) => ribosome.run_weigh(WeighHostAccess, WeighInvocation::new(zome, input)),
+        None => Ok(RateWeight::default()),
+    }
+}
+
+/// Weigh a new entry action for rate limiting. The normalized size of the
+/// entry is added to the weight given by the integrity zome.
+pub fn weigh_entry(
+    ribosome: &impl RibosomeT,
+    entry_type: &EntryType,
+    input: WeighInput,
+) -> RibosomeResult<EntryRateWeight> {
+    let rate_bytes = match &input {
+        WeighInput::Create(_, entry) | WeighInput::Update(_, entry) => entry_rate_bytes(entry),
+        WeighInput::Link(_) | WeighInput::Delete(_) => 0,
+    };
+    let zome_index = match entry_type {
+        EntryType::App(app_entry_def) => Some(app_entry_def.zome_index()),
+        _ => None,
+    };
+    let RateWeight { bucket_id, units } = weigh(ribosome, zome_index, input)?;
+    Ok(EntryRateWeight {
+        bucket_id,
+        units,
+        rate_bytes,
+    })
+}
+
+/// The normalized size of an entry for rate limiting, i.e. the number of whole
+/// [`RATE_BYTES_UNIT`]s in its serialized data.
+pub fn entry_rate_bytes(entry: &Entry) -> RateBytes {
+    match entry {
+        Entry::App(bytes) | Entry::CounterSign(_, bytes) => {
+            (bytes.bytes().len() / RATE_BYTES_UNIT).min(RateBytes::MAX as usize) as RateBytes
+        }
+        _ => 0,
+    }
 }
 
 #[cfg(test)]
@@ -43,8 +43,11 @@ impl From<MigrateAgentHostAccess> for HostContext {
 
 impl From<&MigrateAgentHostAccess> for HostFnAccess {
     fn from(_: &MigrateAgentHostAccess) -> Self {
+        // The callback runs against a chain which is being closed or has only
+        // just been opened, so it may read but not write. The zome name, and
+        // the DNA being migrated to or from, are available through the
+        // deterministic bindings and by querying the chain.
         let mut access = Self::none();
-        // TODO: insert zome_name
         access.read_workspace_deterministic = Permission::Allow;
         access.agent_info = Permission::Allow;
         access.bindings_deterministic = Permission::Allow;
@@ -0,0 +1,157 @@
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::InvocationAuth;
+use crate::core::ribosome::ZomesToInvoke;
+use derive_more::Constructor;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::prelude::*;
+
+#[derive(Clone, Constructor, Debug)]
+pub struct WeighHostAccess;
+
+#[derive(Clone, Constructor)]
+pub struct WeighInvocation {
+    /// The integrity zome which owns the action being weighed.
+    pub zome: IntegrityZome,
+    pub input: WeighInput,
+}
+
+impl From<WeighHostAccess> for HostContext {
+    fn from(host_access: WeighHostAccess) -> Self {
+        Self::Weigh(host_access)
+    }
+}
+
+impl From<&WeighHostAccess> for HostFnAccess {
+    fn from(_: &WeighHostAccess) -> Self {
+        // Weighing must be deterministic, as every validator re-weighs the
+        // actions it receives and rejects any whose claimed weight differs.
+        let mut access = Self::none();
+        access.bindings_deterministic = Permission::Allow;
+        access
+    }
+}
+
+impl Invocation for WeighInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        ZomesToInvoke::OneIntegrity(self.zome.clone())
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec!["weigh".into()].into()
+    }
+    fn host_input(self) -> Result<ExternIO, SerializedBytesError> {
+        ExternIO::encode(self.input)
+    }
+    fn auth(&self) -> InvocationAuth {
+        InvocationAuth::LocalCallback
+    }
+}
+
+impl TryFrom<WeighInvocation> for ExternIO {
+    type Error = SerializedBytesError;
+    fn try_from(weigh_invocation: WeighInvocation) -> Result<Self, Self::Error> {
+        ExternIO::encode(weigh_invocation.input)
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+mod slow_tests {
+    use super::WeighHostAccess;
+    use super::WeighInvocation;
+    use crate::core::ribosome::RibosomeT;
+    use crate::fixt::curve::Zomes;
+    use crate::fixt::RealRibosomeFixturator;
+    use crate::sweettest::*;
+    use ::fixt::prelude::*;
+    use holochain_types::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    fn link_input() -> WeighInput {
+        WeighInput::Link(CreateLink {
+            author: fixt!(AgentPubKey),
+            timestamp: Timestamp::now(),
+            action_seq: 5,
+            prev_action: fixt!(ActionHash),
+            base_address: fixt!(EntryHash).into(),
+            target_address: fixt!(EntryHash).into(),
+            zome_index: 0.into(),
+            link_type: 0.into(),
+            tag: ().into(),
+            weight: (),
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_weigh_unimplemented() {
+        let ribosome = RealRibosomeFixturator::new(Zomes(vec![TestWasm::Foo]))
+            .next()
+            .unwrap();
+        let zome = ribosome.get_integrity_zome(&0.into()).unwrap();
+
+        let result = ribosome
+            .run_weigh(WeighHostAccess, WeighInvocation::new(zome, link_input()))
+            .unwrap();
+        assert_eq!(result, RateWeight::default());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_weigh_implemented() {
+        let ribosome = RealRibosomeFixturator::new(Zomes(vec![TestWasm::RateLimits]))
+            .next()
+            .unwrap();
+        let zome = ribosome.get_integrity_zome(&0.into()).unwrap();
+
+        let result = ribosome
+            .run_weigh(WeighHostAccess, WeighInvocation::new(zome, link_input()))
+            .unwrap();
+        assert_eq!(
+            result,
+            RateWeight {
+                bucket_id: 0,
+                units: 10,
+            }
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn authoring_rejects_rate_limit_overflow() {
+        let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::RateLimits]).await;
+        let dna_def = DnaDef {
+            rate_limits: RateLimits::from([(
+                0,
+                RateBucketLimit {
+                    capacity: 30,
+                    period_secs: 3600,
+                },
+            )]),
+            ..dna.dna_def().clone()
+        };
+        let dna = DnaFile::new(dna_def, dna.code().values().cloned()).await;
+
+        let mut conductor = SweetConductor::from_standard_config().await;
+        let (alice,) = conductor
+            .setup_app("app", [&dna])
+            .await
+            .unwrap()
+            .into_tuple();
+        let zome = alice.zome(TestWasm::RateLimits);
+
+        // Bucket 0 has room for three actions of 10 units.
+        let post: ActionHash = conductor.call(&zome, "create_post", "1".to_string()).await;
+        let _: ActionHash = conductor.call(&zome, "create_post", "2".to_string()).await;
+        let _: ActionHash = conductor
+            .call(&zome, "create_comment_link", post.clone())
+            .await;
+        let overflow: Result<ActionHash, _> = conductor
+            .call_fallible(&zome, "create_post", "3".to_string())
+            .await;
+        assert!(overflow.is_err());
+
+        // Deletes are weighed into bucket 1, which has no limit.
+        let _: ActionHash = conductor.call(&zome, "delete_post", post.clone()).await;
+        let record: Option<Record> = conductor.call(&zome, "get_post", post).await;
+        assert!(record.is_some());
+    }
+}
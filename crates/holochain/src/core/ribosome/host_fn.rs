@@ -81,14 +81,15 @@ host_fn_api_impls! {
 
     fn call (Vec<zt::call::Call>) -> Vec<zt::ZomeCallResponse>;
 
-    // @todo List all the local capability claims.
-    fn capability_claims (()) -> ();
+    // List all the live local capability claims.
+    fn capability_claims (zt::capability::CapClaimQuery) -> Vec<zt::capability::CapClaim>;
 
-    // @todo List all the local capability grants.
-    fn capability_grants (()) -> ();
+    // List all the local capability grants.
+    fn capability_grants (zt::capability::CapGrantQuery) -> Vec<zt::capability::CapGrantInfo>;
 
-    // @todo Get the capability for the current zome call.
-    fn capability_info (()) -> ();
+    // Get the grant that authorized the current zome call.
+    // `None` if the call was authorized as the chain author.
+    fn capability_info (()) -> Option<zt::capability::CapGrantInfo>;
 
     // The EntryDefId determines how a create is handled on the host side.
     // CapGrant and CapClaim are handled natively.
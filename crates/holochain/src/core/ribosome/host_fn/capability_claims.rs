@@ -1,13 +1,94 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
 
-/// lists all the local claims filtered by tag
+/// lists all the local claims filtered by tag and grantor
+/// deleted claims are never listed
 pub fn capability_claims(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: (),
-) -> Result<(), RuntimeError> {
-    unimplemented!();
+    call_context: Arc<CallContext>,
+    input: CapClaimQuery,
+) -> Result<Vec<CapClaim>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => tokio_helper::block_forever_on(async move {
+            call_context
+                .host_context
+                .workspace()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain to list capability claims")
+                .cap_claims(input)
+                .await
+                .map_err(|source_chain_error| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                })
+        }),
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "capability_claims".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::sweettest::SweetConductorBatch;
+    use crate::sweettest::SweetDnaFile;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn capability_claims_lists_live_claims() {
+        holochain_trace::test_run().ok();
+        let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Capability]).await;
+        let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+        let apps = conductors.setup_app("app", &[dna]).await.unwrap();
+        conductors.exchange_peer_info().await;
+        let ((alice,), (bob,)) = apps.into_tuples();
+        let alice_zome = alice.zome(TestWasm::Capability);
+        let bob_zome = bob.zome(TestWasm::Capability);
+
+        // Alice issues Bob an assigned grant and sends him the secret.
+        let _: () = conductors[0]
+            .call(&alice_zome, "send_assigned_cap_claim", bob.agent_pubkey().clone())
+            .await;
+
+        let claims: Vec<CapClaim> = conductors[1]
+            .call(&bob_zome, "list_cap_claims", CapClaimQuery::default())
+            .await;
+        assert_eq!(claims.len(), 1);
+        assert_eq!(claims[0].tag(), "has_cap_claim");
+        assert_eq!(claims[0].grantor(), alice.agent_pubkey());
+
+        let claims: Vec<CapClaim> = conductors[1]
+            .call(
+                &bob_zome,
+                "list_cap_claims",
+                CapClaimQuery {
+                    grantor: Some(bob.agent_pubkey().clone()),
+                    ..Default::default()
+                },
+            )
+            .await;
+        assert!(claims.is_empty());
+
+        // Alice holds no claims of her own.
+        let claims: Vec<CapClaim> = conductors[0]
+            .call(&alice_zome, "list_cap_claims", CapClaimQuery::default())
+            .await;
+        assert!(claims.is_empty());
+    }
 }
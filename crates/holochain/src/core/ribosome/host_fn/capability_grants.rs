@@ -1,16 +1,45 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
+use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::*;
 use std::sync::Arc;
 
 /// list all the grants stored locally in the chain filtered by tag
-/// this is only the current grants as per local CRUD
+/// revoked grants are only included if the query asks for them
 pub fn capability_grants(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: (),
-) -> Result<(), RuntimeError> {
-    unimplemented!();
+    call_context: Arc<CallContext>,
+    input: CapGrantQuery,
+) -> Result<Vec<CapGrantInfo>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => tokio_helper::block_forever_on(async move {
+            call_context
+                .host_context
+                .workspace()
+                .source_chain()
+                .as_ref()
+                .expect("Must have source chain to list capability grants")
+                .cap_grant_info(input)
+                .await
+                .map_err(|source_chain_error| -> RuntimeError {
+                    wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                })
+        }),
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "capability_grants".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
 }
 
 #[cfg(test)]
@@ -58,6 +87,76 @@ pub mod wasm_test {
         assert_eq!(entry_secret, secret);
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ribosome_capability_grants() {
+        holochain_trace::test_run().ok();
+        let RibosomeTestFixture {
+            conductor, alice, ..
+        } = RibosomeTestFixture::new(TestWasm::Capability).await;
+
+        // The unrestricted grant from init is the only one to begin with.
+        let grants: Vec<CapGrantInfo> = conductor
+            .call(&alice, "list_cap_grants", CapGrantQuery::default())
+            .await;
+        assert_eq!(grants.len(), 1);
+        assert_eq!(grants[0].cap_grant.access, CapAccess::Unrestricted);
+
+        let secret: CapSecret = conductor.call(&alice, "cap_secret", ()).await;
+        let original_hash: ActionHash = conductor
+            .call(&alice, "transferable_cap_grant", secret)
+            .await;
+        let rolled_hash: ActionHash = conductor
+            .call(&alice, "roll_cap_grant", original_hash.clone())
+            .await;
+
+        // The rolled grant is revoked by its update.
+        let grants: Vec<CapGrantInfo> = conductor
+            .call(&alice, "list_cap_grants", CapGrantQuery::default())
+            .await;
+        let live: Vec<_> = grants.iter().map(|info| info.action_hash.clone()).collect();
+        assert_eq!(live.len(), 2);
+        assert!(live.contains(&rolled_hash));
+        assert!(!live.contains(&original_hash));
+
+        let grants: Vec<CapGrantInfo> = conductor
+            .call(
+                &alice,
+                "list_cap_grants",
+                CapGrantQuery {
+                    tag: None,
+                    include_revoked: true,
+                },
+            )
+            .await;
+        assert_eq!(grants.len(), 3);
+        let original = grants
+            .iter()
+            .find(|info| info.action_hash == original_hash)
+            .unwrap();
+        assert!(original.revoked_at.unwrap() >= original.created_at);
+
+        let _: ActionHash = conductor
+            .call(&alice, "delete_cap_grant", rolled_hash)
+            .await;
+        let grants: Vec<CapGrantInfo> = conductor
+            .call(&alice, "list_cap_grants", CapGrantQuery::default())
+            .await;
+        assert_eq!(grants.len(), 1);
+
+        // Tags filter the listed grants.
+        let grants: Vec<CapGrantInfo> = conductor
+            .call(
+                &alice,
+                "list_cap_grants",
+                CapGrantQuery {
+                    tag: Some("no such tag".into()),
+                    include_revoked: true,
+                },
+            )
+            .await;
+        assert!(grants.is_empty());
+    }
+
     // MAYBE: [ B-03669 ] can move this to an integration test (may need to switch to using a RibosomeStore)
     #[tokio::test(flavor = "multi_thread")]
     async fn ribosome_authorized_call() -> anyhow::Result<()> {
@@ -1,14 +1,115 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::InvocationAuth;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use holochain_types::prelude::*;
 use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
 
-/// return the access info used for this call
-/// also return who is originated the call (pubkey)
+/// return the grant that authorized this call
+/// `None` means the call was authorized as the chain author,
+/// the provenance of the call is available via `call_info`
 pub fn capability_info(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
+    call_context: Arc<CallContext>,
     _input: (),
-) -> Result<(), RuntimeError> {
-    unimplemented!();
+) -> Result<Option<CapGrantInfo>, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            bindings: Permission::Allow,
+            ..
+        } => {
+            let (provenance, cap_secret) = match call_context.auth() {
+                InvocationAuth::Cap(provenance, cap_secret) => (provenance, cap_secret),
+                InvocationAuth::LocalCallback => return Ok(None),
+            };
+            let check_function = (
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+            );
+            tokio_helper::block_forever_on(async move {
+                let source_chain = call_context.host_context.workspace().source_chain().clone();
+                let source_chain = source_chain
+                    .as_ref()
+                    .expect("Must have source chain if bindings access is given");
+                // The grant is looked up along with the action that committed
+                // it, so that duplicate grants can't be mistaken for each other.
+                let cap_grant = source_chain
+                    .valid_cap_grant_info(check_function, provenance, cap_secret)
+                    .await
+                    .map_err(|e| -> RuntimeError {
+                        wasm_error!(WasmErrorInner::Host(e.to_string())).into()
+                    })?;
+                match cap_grant {
+                    Some((_, info)) => Ok(info),
+                    // The grant that authorized the call may have been revoked
+                    // since, in which case there is no grant to return.
+                    None => Err(wasm_error!(WasmErrorInner::Host(
+                        "The capability grant that authorized this call has been revoked"
+                            .to_string()
+                    ))
+                    .into()),
+                }
+            })
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "capability_info".into()
+            )
+            .to_string()
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod wasm_test {
+    use crate::sweettest::SweetConductorBatch;
+    use crate::sweettest::SweetDnaFile;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[derive(serde::Serialize, serde::Deserialize, Debug)]
+    pub struct CapFor(CapSecret, AgentPubKey);
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn capability_info_returns_authorizing_grant() {
+        holochain_trace::test_run().ok();
+        let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Capability]).await;
+        let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+        let apps = conductors.setup_app("app", &[dna]).await.unwrap();
+        conductors.exchange_peer_info().await;
+        let ((alice,), (bob,)) = apps.into_tuples();
+        let alice_zome = alice.zome(TestWasm::Capability);
+        let bob_zome = bob.zome(TestWasm::Capability);
+
+        // Calls by the chain author are not authorized by any grant.
+        let info: Option<CapGrantInfo> = conductors[1].call(&bob_zome, "cap_info", ()).await;
+        assert_eq!(info, None);
+
+        let secret: CapSecret = conductors[1].call(&bob_zome, "cap_secret", ()).await;
+        let grant_hash: ActionHash = conductors[1]
+            .call(&bob_zome, "transferable_cap_grant", secret)
+            .await;
+
+        let response: ZomeCallResponse = conductors[0]
+            .call(
+                &alice_zome,
+                "try_cap_info",
+                CapFor(secret, bob.agent_pubkey().clone()),
+            )
+            .await;
+        let info: Option<CapGrantInfo> = match response {
+            ZomeCallResponse::Ok(io) => io.decode().unwrap(),
+            other => panic!("unexpected response {:?}", other),
+        };
+        let info = info.unwrap();
+        assert_eq!(info.action_hash, grant_hash);
+        assert_eq!(info.revoked_at, None);
+        assert_eq!(info.cap_grant.access, CapAccess::from(secret));
+    }
 }
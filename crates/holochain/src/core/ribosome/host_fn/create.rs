@@ -1,4 +1,4 @@
-use crate::core::ribosome::weigh_placeholder;
+use crate::core::ribosome::weigh_entry;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
 use crate::core::ribosome::RibosomeError;
@@ -11,7 +11,7 @@ use std::sync::Arc;
 /// create record
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn create<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: CreateInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -27,10 +27,10 @@ pub fn create<'a>(
                 chain_top_ordering,
             } = input;
 
-            let weight = weigh_placeholder();
-
             // Countersigned entries have different action handling.
             match entry {
+                // Countersigned entries are not weighed, as every party to the
+                // session must produce the same weight.
                 Entry::CounterSign(_, _) => tokio_helper::block_forever_on(async move {
                     call_context
                         .host_context
@@ -38,7 +38,7 @@ pub fn create<'a>(
                         .source_chain()
                         .as_ref()
                         .expect("Must have source chain if write_workspace access is given")
-                        .put_countersigned(entry, chain_top_ordering, weight)
+                        .put_countersigned(entry, chain_top_ordering, EntryRateWeight::default())
                         .await
                         .map_err(|source_chain_error| -> RuntimeError {
                             wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
@@ -64,9 +64,30 @@ pub fn create<'a>(
 
                     // build an action for the entry being committed
                     let action_builder = builder::Create {
-                        entry_type,
+                        entry_type: entry_type.clone(),
                         entry_hash,
                     };
+                    let source_chain = call_context
+                        .host_context
+                        .workspace_write()
+                        .source_chain()
+                        .as_ref()
+                        .expect("Must have source chain if write_workspace access is given");
+                    let action = source_chain.unweighed_action(action_builder).map_err(
+                        |source_chain_error| -> RuntimeError {
+                            wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                        },
+                    )?;
+
+                    // weigh the action with the integrity zome that owns the entry type
+                    let weight = weigh_entry(
+                        ribosome.as_ref(),
+                        &entry_type,
+                        WeighInput::Create(action.clone(), entry.clone()),
+                    )
+                    .map_err(|ribosome_error| -> RuntimeError {
+                        wasm_error!(WasmErrorInner::Host(ribosome_error.to_string())).into()
+                    })?;
 
                     // return the hash of the committed entry
                     // note that validation is handled by the workflow
@@ -74,13 +95,8 @@ pub fn create<'a>(
                     // being atomic
                     tokio_helper::block_forever_on(async move {
                         // push the action and the entry into the source chain
-                        call_context
-                            .host_context
-                            .workspace_write()
-                            .source_chain()
-                            .as_ref()
-                            .expect("Must have source chain if write_workspace access is given")
-                            .put_weightless(action_builder, Some(entry), chain_top_ordering)
+                        source_chain
+                            .put_unweighed(action, Some(entry), chain_top_ordering, weight)
                             .await
                             .map_err(|source_chain_error| -> RuntimeError {
                                 wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
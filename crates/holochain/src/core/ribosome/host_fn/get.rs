@@ -33,6 +33,7 @@ pub fn get<'a>(
                             &call_context.host_context.workspace(),
                             call_context.host_context.network().clone(),
                         )
+                        .with_cache_metadata_check()
                         .dht_get(any_dht_hash, get_options)
                         .await
                     }))
@@ -30,6 +30,7 @@ pub fn get_details<'a>(
                             &call_context.host_context.workspace(),
                             call_context.host_context.network().to_owned(),
                         )
+                        .with_cache_metadata_check()
                         .get_details(any_dht_hash, get_options)
                         .await
                     }))
@@ -1,12 +1,124 @@
 use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
-use std::sync::Arc;
+use holochain_types::access::Permission;
+use holochain_util::tokio_helper;
 use holochain_wasmer_host::prelude::*;
+use holochain_zome_types::Timestamp;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The longest a zome may ask to sleep for in a single call to `sleep`.
+pub const MAX_SLEEP_DURATION: Duration = Duration::from_secs(60);
 
+/// Pause the calling zome for the given duration, e.g. to back off while
+/// polling a remote cell.
+///
+/// The wasm thread is parked on a timer rather than spinning, and the tokio
+/// worker it runs on is handed back to the runtime while it waits.
 pub fn sleep(
     _ribosome: Arc<impl RibosomeT>,
-    _call_context: Arc<CallContext>,
-    _input: core::time::Duration,
+    call_context: Arc<CallContext>,
+    input: Duration,
 ) -> Result<(), RuntimeError> {
-    unimplemented!()
+    let host_error =
+        |reason: String| -> RuntimeError { wasm_error!(WasmErrorInner::Host(reason)).into() };
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            non_determinism: Permission::Allow,
+            ..
+        } => {
+            if input > MAX_SLEEP_DURATION {
+                return Err(host_error(format!(
+                    "Cannot sleep for {:?}, the maximum is {:?}",
+                    input, MAX_SLEEP_DURATION
+                )));
+            }
+            if let HostContext::ZomeCall(host_access) = call_context.host_context() {
+                let wake_at = (Timestamp::now() + input).map_err(|e| host_error(e.to_string()))?;
+                if wake_at > host_access.expires_at {
+                    return Err(host_error(format!(
+                        "Cannot sleep for {:?}, the zome call expires at {}",
+                        input, host_access.expires_at
+                    )));
+                }
+            }
+            tokio_helper::block_forever_on(tokio::time::sleep(input));
+            Ok(())
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "sleep".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use super::*;
+    use crate::fixt::CallContextFixturator;
+    use crate::fixt::RealRibosomeFixturator;
+    use crate::fixt::ValidateHostAccessFixturator;
+    use crate::fixt::ZomeCallHostAccessFixturator;
+    use ::fixt::prelude::*;
+
+    fn call_context(host_context: HostContext) -> Arc<CallContext> {
+        let mut call_context = CallContextFixturator::new(Unpredictable).next().unwrap();
+        call_context.host_context = host_context;
+        Arc::new(call_context)
+    }
+
+    fn zome_call_expiring_at(expires_at: Timestamp) -> HostContext {
+        let mut host_access = fixt!(ZomeCallHostAccess);
+        host_access.expires_at = expires_at;
+        HostContext::ZomeCall(host_access)
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn sleep_test() {
+        let ribosome = Arc::new(
+            RealRibosomeFixturator::new(crate::fixt::curve::Zomes(vec![]))
+                .next()
+                .unwrap(),
+        );
+        let wake_after = Duration::from_millis(50);
+
+        // - A zome call can sleep
+        let context = call_context(zome_call_expiring_at(Timestamp::max()));
+        let started = std::time::Instant::now();
+        sleep(ribosome.clone(), context, wake_after).unwrap();
+        assert!(started.elapsed() >= wake_after);
+
+        // - But not for longer than the maximum
+        assert!(sleep(
+            ribosome.clone(),
+            call_context(zome_call_expiring_at(Timestamp::max())),
+            MAX_SLEEP_DURATION + Duration::from_secs(1),
+        )
+        .is_err());
+
+        // - Nor past the expiry of the zome call
+        let expires_at = (Timestamp::now() + Duration::from_secs(1)).unwrap();
+        assert!(sleep(
+            ribosome.clone(),
+            call_context(zome_call_expiring_at(expires_at)),
+            Duration::from_secs(10),
+        )
+        .is_err());
+
+        // - Deterministic contexts can't sleep at all
+        assert!(sleep(
+            ribosome,
+            call_context(HostContext::Validate(fixt!(ValidateHostAccess))),
+            wake_after,
+        )
+        .is_err());
+    }
 }
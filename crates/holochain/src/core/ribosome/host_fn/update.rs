@@ -1,5 +1,5 @@
 use super::delete::get_original_entry_data;
-use crate::core::ribosome::weigh_placeholder;
+use crate::core::ribosome::weigh_entry;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
 use crate::core::ribosome::RibosomeError;
@@ -11,7 +11,7 @@ use std::sync::Arc;
 
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn update<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: UpdateInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -30,10 +30,10 @@ pub fn update<'a>(
             let (original_entry_address, entry_type) =
                 get_original_entry_data(call_context.clone(), original_action_address.clone())?;
 
-            let weight = weigh_placeholder();
-
             // Countersigned entries have different action handling.
             match entry {
+                // Countersigned entries are not weighed, as every party to the
+                // session must produce the same weight.
                 Entry::CounterSign(_, _) => tokio_helper::block_forever_on(async move {
                     call_context
                         .host_context
@@ -41,7 +41,7 @@ pub fn update<'a>(
                         .source_chain()
                         .as_ref()
                         .expect("Must have source chain if write_workspace access is given")
-                        .put_countersigned(entry, chain_top_ordering, weight)
+                        .put_countersigned(entry, chain_top_ordering, EntryRateWeight::default())
                         .await
                         .map_err(|source_chain_error| -> RuntimeError {
                             wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
@@ -55,23 +55,39 @@ pub fn update<'a>(
                     let action_builder = builder::Update {
                         original_entry_address,
                         original_action_address,
-                        entry_type,
+                        entry_type: entry_type.clone(),
                         entry_hash,
                     };
-                    let workspace = call_context.host_context.workspace_write();
+                    let source_chain = call_context
+                        .host_context
+                        .workspace_write()
+                        .source_chain()
+                        .as_ref()
+                        .expect("Must have source chain if write_workspace access is given");
+                    let action = source_chain.unweighed_action(action_builder).map_err(
+                        |source_chain_error| -> RuntimeError {
+                            wasm_error!(WasmErrorInner::Host(source_chain_error.to_string())).into()
+                        },
+                    )?;
+
+                    // weigh the action with the integrity zome that owns the entry type
+                    let weight = weigh_entry(
+                        ribosome.as_ref(),
+                        &entry_type,
+                        WeighInput::Update(action.clone(), entry.clone()),
+                    )
+                    .map_err(|ribosome_error| -> RuntimeError {
+                        wasm_error!(WasmErrorInner::Host(ribosome_error.to_string())).into()
+                    })?;
 
                     // return the hash of the updated entry
                     // note that validation is handled by the workflow
                     // if the validation fails this update will be rolled back by virtue of the DB transaction
                     // being atomic
                     tokio_helper::block_forever_on(async move {
-                        let source_chain = workspace
-                            .source_chain()
-                            .as_ref()
-                            .expect("Must have source chain if write_workspace access is given");
                         // push the action and the entry into the source chain
                         let action_hash = source_chain
-                            .put_weightless(action_builder, Some(entry), chain_top_ordering)
+                            .put_unweighed(action, Some(entry), chain_top_ordering, weight)
                             .await
                             .map_err(|source_chain_error| -> RuntimeError {
                                 wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
@@ -24,6 +24,8 @@ use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
+use crate::core::ribosome::guest_callback::weigh::WeighHostAccess;
+use crate::core::ribosome::guest_callback::weigh::WeighInvocation;
 use crate::core::ribosome::guest_callback::CallIterator;
 use crate::core::ribosome::host_fn::accept_countersigning_preflight_request::accept_countersigning_preflight_request;
 use crate::core::ribosome::host_fn::agent_info::agent_info;
@@ -522,6 +524,7 @@ impl RealRibosome {
             },
             integrity_zomes: Default::default(),
             coordinator_zomes: Default::default(),
+            rate_limits: Default::default(),
         };
         let empty_dna_file = DnaFile::new(empty_dna_def, vec![]).await;
         let empty_ribosome = RealRibosome::new(empty_dna_file)?;
@@ -1032,6 +1035,18 @@ impl RibosomeT for RealRibosome {
         do_callback!(self, host_access, invocation, MigrateAgentCallbackResult)
     }
 
+    fn run_weigh(
+        &self,
+        host_access: WeighHostAccess,
+        invocation: WeighInvocation,
+    ) -> RibosomeResult<RateWeight> {
+        match self.call_iterator(host_access.into(), invocation).next() {
+            Ok(Some((_zome, extern_io))) => Ok(extern_io.decode()?),
+            Ok(None) => Ok(RateWeight::default()),
+            Err((_zome, ribosome_error)) => Err(ribosome_error),
+        }
+    }
+
     fn zome_types(&self) -> &Arc<GlobalZomeTypes> {
         &self.zome_types
     }
@@ -4,6 +4,7 @@
 use super::queue_consumer::TriggerSender;
 use super::workflow::incoming_dht_ops_workflow::incoming_dht_ops_workflow;
 use super::workflow::sys_validation_workflow::SysValidationWorkspace;
+use crate::conductor::conductor::DpkiService;
 use crate::conductor::space::Space;
 use holochain_cascade::Cascade;
 use holochain_cascade::CascadeSource;
@@ -48,10 +49,34 @@ pub async fn verify_action_signature(sig: &Signature, action: &Action) -> SysVal
     }
 }
 
-/// Verify the author key was valid at the time
-/// of signing with dpki
-/// TODO: This is just a stub until we have dpki.
-pub async fn author_key_is_valid(_author: &AgentPubKey) -> SysValidationResult<()> {
+/// Verify a warrant's signature was actually signed by its author
+pub async fn verify_warrant_signature(
+    sig: &Signature,
+    warrant: &Warrant,
+) -> SysValidationResult<()> {
+    if warrant.author.verify_signature(sig, warrant).await? {
+        Ok(())
+    } else {
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::CounterfeitWarrant((*sig).clone(), (*warrant).clone()),
+        ))
+    }
+}
+
+/// Verify with DPKI that the author key was valid at the time of signing.
+/// Without DPKI every key is valid.
+pub async fn author_key_is_valid(
+    author: &AgentPubKey,
+    timestamp: Timestamp,
+    dpki: Option<&dyn DpkiService>,
+) -> SysValidationResult<()> {
+    if let Some(dpki) = dpki {
+        if !dpki.is_key_valid(author.clone(), timestamp).await? {
+            return Err(SysValidationError::ValidationOutcome(
+                ValidationOutcome::DpkiAgentInvalid(author.clone(), timestamp),
+            ));
+        }
+    }
     Ok(())
 }
 
@@ -225,10 +250,55 @@ pub async fn check_chain_rollback(
     }
 }
 
-/// Placeholder for future spam check.
-/// Check action timestamps don't exceed MAX_PUBLISH_FREQUENCY
-pub async fn check_spam(_action: &Action) -> SysValidationResult<()> {
-    Ok(())
+/// Check that an action doesn't overflow the rate limiting bucket it was
+/// weighed into. The weight of the author's other actions in the same bucket
+/// is summed by walking back along the chain, only as far as the start of the
+/// bucket's period.
+pub async fn check_rate_limit(
+    action: &Action,
+    rate_limits: &RateLimits,
+    cascade: &impl Cascade,
+) -> SysValidationResult<()> {
+    let (bucket_id, mut fill) = rate_fill(action);
+    let limit = match rate_limits.get(&bucket_id) {
+        Some(limit) if fill > 0 => limit,
+        _ => return Ok(()),
+    };
+    let window_start = action.timestamp().saturating_sub(&limit.period());
+    let mut prev_action_hash = action.prev_action().cloned();
+    while let Some(hash) = prev_action_hash.take() {
+        let (prev_action, _) = cascade
+            .retrieve_action(hash.clone(), Default::default())
+            .await?
+            .ok_or_else(|| ValidationOutcome::DepMissingFromDht(hash.into()))?;
+        let prev_action = prev_action.action();
+        if prev_action.timestamp() <= window_start {
+            break;
+        }
+        let (prev_bucket_id, prev_fill) = rate_fill(prev_action);
+        if prev_bucket_id == bucket_id {
+            fill = fill.saturating_add(prev_fill);
+        }
+        prev_action_hash = prev_action.prev_action().cloned();
+    }
+    if fill <= limit.capacity {
+        Ok(())
+    } else {
+        Err(ValidationOutcome::RateLimitExceeded(bucket_id, fill, limit.capacity).into())
+    }
+}
+
+/// The bucket an action was weighed into, and how much it fills that bucket.
+fn rate_fill(action: &Action) -> (RateBucketId, RateBucketCapacity) {
+    let RateWeight { bucket_id, units } = action.rate_data();
+    let rate_bytes = action
+        .entry_rate_data()
+        .map(|weight| weight.rate_bytes)
+        .unwrap_or_default();
+    (
+        bucket_id,
+        RateBucketCapacity::from(units) + RateBucketCapacity::from(rate_bytes),
+    )
 }
 
 /// Check that created agents are always paired with an AgentValidationPkg and vice versa
@@ -348,6 +418,11 @@ pub fn check_entry_visibility(op: &DhtOp) -> SysValidationResult<()> {
         .into())
     };
 
+    if op.get_type() == DhtOpType::RegisterWarrant {
+        // A warrant carries the warranted action but never its entry data.
+        return Ok(());
+    }
+
     match (op.action().entry_type().map(|t| t.visibility()), op.entry()) {
         (Some(Public), Present(_)) => Ok(()),
         (Some(Private), Hidden) => Ok(()),
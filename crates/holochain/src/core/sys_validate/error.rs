@@ -4,6 +4,7 @@ use std::convert::TryFrom;
 use super::SourceChainError;
 use super::MAX_ENTRY_SIZE;
 use crate::conductor::api::error::ConductorApiError;
+use crate::conductor::conductor::DpkiServiceError;
 use crate::conductor::entry_def_store::error::EntryDefStoreError;
 use crate::core::validation::OutcomeOrError;
 use crate::core::workflow::error::WorkflowError;
@@ -42,6 +43,8 @@ pub enum SysValidationError {
     SourceChainError(#[from] SourceChainError),
     #[error("Dna is missing for this hash {0:?}. Cannot validate without dna.")]
     DnaMissing(DnaHash),
+    #[error(transparent)]
+    DpkiServiceError(#[from] DpkiServiceError),
     // NOTE: can remove this if SysValidationResult is replaced with SysValidationOutcome
     #[error(transparent)]
     ValidationOutcome(#[from] ValidationOutcome),
@@ -103,12 +106,16 @@ impl<E> TryFrom<OutcomeOrError<ValidationOutcome, E>> for ValidationOutcome {
 pub enum ValidationOutcome {
     #[error("The record with signature {0:?} and action {1:?} was found to be counterfeit")]
     Counterfeit(Signature, Action),
+    #[error("The warrant with signature {0:?} and content {1:?} was found to be counterfeit")]
+    CounterfeitWarrant(Signature, Warrant),
     #[error("The action {1:?} is not found in the countersigning session data {0:?}")]
     ActionNotInCounterSigningSession(CounterSigningSessionData, NewEntryAction),
     #[error(transparent)]
     CounterSigningError(#[from] CounterSigningError),
     #[error("The dependency {0:?} was not found on the DHT")]
     DepMissingFromDht(AnyDhtHash),
+    #[error("DPKI says the key {0} was not valid at {1}")]
+    DpkiAgentInvalid(AgentPubKey, Timestamp),
     #[error("The app entry def {0:?} entry def id was out of range")]
     EntryDefId(AppEntryDef),
     #[error("The entry has a different hash to the action's entry hash")]
@@ -139,6 +146,8 @@ pub enum ValidationOutcome {
     PreflightResponseSignature(PreflightResponse),
     #[error(transparent)]
     PrevActionError(#[from] PrevActionError),
+    #[error("Rate limiting bucket {0} was overflowed, with {1} units in a bucket with a capacity of {2}")]
+    RateLimitExceeded(RateBucketId, RateBucketCapacity, RateBucketCapacity),
     #[error("Private entry data should never be included in any op other than StoreEntry.")]
     PrivateEntryLeaked,
     #[error(
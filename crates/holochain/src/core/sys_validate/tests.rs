@@ -17,6 +17,7 @@
 //! - Check the AppEntryDef is valid for the zome and the EntryDefId and ZomeIndex are in range.
 //! - Check that StoreEntry never contains a private entry type
 //! - Test that a given sequence of actions constitutes a valid chain w.r.t. its backlinks
+//! - Actions which overflow their rate limiting bucket are rejected
 //!
 //! TO TEST:
 //! - Create and Update Agent can only be preceded by AgentValidationPkg
@@ -1009,3 +1010,104 @@ async fn valid_chain_fact_test() {
 
     sys_validate_record(&last, &cascade).await.unwrap();
 }
+
+/// Actions which overflow their rate limiting bucket are rejected
+#[tokio::test(flavor = "multi_thread")]
+async fn check_rate_limit_overflow() {
+    let mut g = random_generator();
+    let start = Timestamp::now();
+
+    // An old action outside of any period, followed by four actions a second
+    // apart, all weighing 10 units in bucket 0.
+    let timestamps = std::iter::once((start - Duration::from_secs(3600)).unwrap())
+        .chain((0..4).map(|i| (start + Duration::from_secs(i)).unwrap()));
+    let mut prev_action = ActionHash::arbitrary(&mut g).unwrap();
+    let mut chain = Vec::new();
+    for (i, timestamp) in timestamps.enumerate() {
+        let mut action = CreateLink::arbitrary(&mut g).unwrap();
+        action.author = fake_agent_pubkey_1();
+        action.action_seq = 3 + i as u32;
+        action.timestamp = timestamp;
+        action.prev_action = prev_action;
+        action.weight = RateWeight {
+            bucket_id: 0,
+            units: 10,
+        };
+        let record = Record::new(
+            SignedActionHashed::with_presigned(
+                ActionHashed::from_content_sync(action.into()),
+                Signature::arbitrary(&mut g).unwrap(),
+            ),
+            None,
+        );
+        prev_action = record.action_address().clone();
+        chain.push(record);
+    }
+    let last = chain.last().unwrap().action().clone();
+    let recent = chain[chain.len() - 3..].to_vec();
+    let cascade = MockCascade::with_records(chain);
+
+    let limits = |capacity, period_secs| {
+        RateLimits::from([(
+            0,
+            RateBucketLimit {
+                capacity,
+                period_secs,
+            },
+        )])
+    };
+
+    // Buckets without a limit are unlimited
+    check_rate_limit(&last, &RateLimits::new(), &cascade)
+        .await
+        .unwrap();
+    check_rate_limit(&last, &limits(40, 60), &cascade)
+        .await
+        .unwrap();
+    assert_matches!(
+        check_rate_limit(&last, &limits(30, 60), &cascade).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::RateLimitExceeded(0, 40, 30)
+        ))
+    );
+    // Only the actions within the period count towards the limit
+    check_rate_limit(&last, &limits(30, 2), &cascade)
+        .await
+        .unwrap();
+
+    // The chain is only walked back as far as the start of the period, so
+    // older actions don't need to be held.
+    let cascade = MockCascade::with_records(recent);
+    check_rate_limit(&last, &limits(30, 2), &cascade)
+        .await
+        .unwrap();
+}
+
+/// Keys revoked in DPKI are only invalid from the time they were revoked
+#[tokio::test(flavor = "multi_thread")]
+async fn check_author_key_is_valid_with_dpki() {
+    use crate::conductor::conductor::MockDpkiService;
+    use futures::FutureExt;
+
+    let author = fake_agent_pubkey_1();
+    let revoked_at = Timestamp::now();
+    let before = (revoked_at - Duration::from_secs(1)).unwrap();
+    let after = (revoked_at + Duration::from_secs(1)).unwrap();
+
+    let mut dpki = MockDpkiService::new();
+    dpki.expect_is_key_valid()
+        .returning(move |_, timestamp| async move { Ok(timestamp < revoked_at) }.boxed());
+
+    // Without DPKI every key is valid
+    author_key_is_valid(&author, after, None).await.unwrap();
+
+    author_key_is_valid(&author, before, Some(&dpki))
+        .await
+        .unwrap();
+    assert_matches!(
+        author_key_is_valid(&author, after, Some(&dpki)).await,
+        Err(SysValidationError::ValidationOutcome(
+            ValidationOutcome::DpkiAgentInvalid(a, t)
+        )) if a == author && t == after
+    );
+}
@@ -65,6 +65,7 @@ pub enum DhtOpOrder {
     RegisterDeletedEntryAction(holochain_zome_types::timestamp::Timestamp),
     RegisterAddLink(holochain_zome_types::timestamp::Timestamp),
     RegisterRemoveLink(holochain_zome_types::timestamp::Timestamp),
+    RegisterWarrant(holochain_zome_types::timestamp::Timestamp),
 }
 
 /// Op data that will be ordered by [DhtOpOrder]
@@ -109,6 +110,7 @@ impl From<&DhtOp> for DhtOpOrder {
             DhtOp::RegisterDeletedEntryAction(_, h) => RegisterDeletedEntryAction(h.timestamp),
             DhtOp::RegisterAddLink(_, h) => RegisterAddLink(h.timestamp),
             DhtOp::RegisterRemoveLink(_, h) => RegisterRemoveLink(h.timestamp),
+            DhtOp::RegisterWarrant(_, w) => RegisterWarrant(w.timestamp),
         }
     }
 }
@@ -5,6 +5,7 @@ use std::sync::Arc;
 
 use super::error::WorkflowResult;
 use super::sys_validation_workflow::validation_query;
+use crate::conductor::conductor::CellStatus;
 use crate::conductor::entry_def_store::get_entry_def;
 use crate::conductor::Conductor;
 use crate::conductor::ConductorHandle;
@@ -13,6 +14,8 @@ use crate::core::queue_consumer::WorkComplete;
 use crate::core::ribosome::guest_callback::validate::ValidateHostAccess;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
+use crate::core::ribosome::weigh;
+use crate::core::ribosome::weigh_entry;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomesToInvoke;
 use crate::core::SysValidationError;
@@ -24,6 +27,7 @@ use futures::stream::StreamExt;
 use holo_hash::DhtOpHash;
 use holochain_cascade::Cascade;
 use holochain_cascade::CascadeImpl;
+use holochain_keystore::AgentPubKeyExt;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::actor::GetOptions as NetworkGetOptions;
 use holochain_p2p::HolochainP2pDna;
@@ -36,6 +40,7 @@ use holochain_types::prelude::*;
 use holochain_zome_types::op::EntryCreationAction;
 use holochain_zome_types::op::Op;
 use rusqlite::Transaction;
+use std::collections::BTreeSet;
 use std::collections::HashSet;
 use tracing::*;
 pub use types::Outcome;
@@ -102,6 +107,8 @@ async fn app_validation_workflow_inner(
     let iter = sorted_ops.into_iter().map({
         let network = network.clone();
         let workspace = workspace.clone();
+        let conductor = conductor.clone();
+        let dna_hash = dna_hash.clone();
         move |so| {
             let network = network.clone();
             let conductor = conductor.clone();
@@ -123,15 +130,41 @@ async fn app_validation_workflow_inner(
                     )
                 });
 
+                // The proof of invalidity a warrant carries, or which a warrant
+                // would carry if this op is rejected.
+                let proof = match &op {
+                    DhtOp::RegisterWarrant(_, warrant) => warrant.proof.clone(),
+                    _ => WarrantProof::InvalidChainOp {
+                        action: SignedAction(action, op.signature().clone()),
+                        op_hash: op_hash.clone(),
+                    },
+                };
+                let is_warrant = matches!(op_type, DhtOpType::RegisterWarrant);
+
                 // Validate this op
                 let cascade = workspace.full_cascade(network.clone());
-                let r = match dhtop_to_op(op, &cascade).await {
-                    Ok(op) => {
-                        validate_op_outer(dna_hash, &op, &conductor, &workspace, &network).await
+                let r = match op {
+                    DhtOp::RegisterWarrant(_, warrant) => {
+                        validate_warrant(
+                            dna_hash, &warrant, &conductor, &workspace, &network, &cascade,
+                        )
+                        .await
                     }
-                    Err(e) => Err(e),
+                    op => match dhtop_to_op(op, &cascade).await {
+                        Ok(op) => {
+                            validate_op_outer(dna_hash, &op, &conductor, &workspace, &network).await
+                        }
+                        Err(e) => Err(e),
+                    },
                 };
-                (op_hash, dependency, op_light, r, activity)
+                (
+                    op_hash,
+                    dependency,
+                    op_light,
+                    r,
+                    activity,
+                    (proof, is_warrant),
+                )
             }
         }
     });
@@ -173,15 +206,20 @@ async fn app_validation_workflow_inner(
             "Committing {} ops",
             chunk.iter().map(|c| c.len()).sum::<usize>()
         );
-        let (t, a, r, activity) = workspace
+        let (t, a, r, activity, invalid, warranted) = workspace
             .dht_db
             .write_async(move |txn| {
                 let mut total = 0;
                 let mut awaiting = 0;
                 let mut rejected = 0;
                 let mut agent_activity = Vec::new();
+                // Proofs of invalid ops which we will issue warrants for.
+                let mut invalid = Vec::new();
+                // Proofs of invalid ops from warrants which we have verified.
+                let mut warranted = Vec::new();
                 for outcome in chunk.into_iter().flatten() {
-                    let (op_hash, dependency, op_light, outcome, activity) = outcome;
+                    let (op_hash, dependency, op_light, outcome, activity, (proof, is_warrant)) =
+                        outcome;
                     // Get the outcome or return the error
                     let outcome = outcome.or_else(|outcome_or_err| outcome_or_err.try_into())?;
 
@@ -207,6 +245,9 @@ async fn app_validation_workflow_inner(
                             } else {
                                 put_integration_limbo(txn, &op_hash, ValidationStatus::Valid)?;
                             }
+                            if is_warrant {
+                                warranted.push(proof);
+                            }
                         }
                         Outcome::AwaitingDeps(deps) => {
                             awaiting += 1;
@@ -224,13 +265,31 @@ async fn app_validation_workflow_inner(
                             } else {
                                 put_integration_limbo(txn, &op_hash, ValidationStatus::Rejected)?;
                             }
+                            if !is_warrant {
+                                invalid.push(proof);
+                            }
                         }
                     }
                 }
-                WorkflowResult::Ok((total, awaiting, rejected, agent_activity))
+                WorkflowResult::Ok((
+                    total,
+                    awaiting,
+                    rejected,
+                    agent_activity,
+                    invalid,
+                    warranted,
+                ))
             })
             .await?;
 
+        // Warrant the authors of the ops we rejected.
+        if !invalid.is_empty() {
+            issue_warrants(&dna_hash, &conductor, invalid).await?;
+        }
+        if conductor.get_config().auto_block_warranted_agents {
+            block_warranted_agents(&dna_hash, &conductor, warranted).await?;
+        }
+
         // Once the database transaction is committed, add agent activity to the cache
         // that is ready for integration.
         for (author, seq, has_no_dependency) in activity {
@@ -455,10 +514,135 @@ async fn dhtop_to_op(op: DhtOp, cascade: &impl Cascade) -> AppValidationOutcome<
                 create_link,
             })
         }
+        DhtOp::RegisterWarrant(_, _) => return Err(AppValidationError::WarrantOp.into()),
     };
     Ok(op)
 }
 
+/// Validate a warrant by re-validating the op it claims is invalid.
+/// The warrant is valid if, and only if, that op is rejected.
+async fn validate_warrant(
+    dna_hash: Arc<DnaHash>,
+    warrant: &Warrant,
+    conductor_handle: &ConductorHandle,
+    workspace: &AppValidationWorkspace,
+    network: &HolochainP2pDna,
+    cascade: &impl Cascade,
+) -> AppValidationOutcome<Outcome> {
+    let WarrantProof::InvalidChainOp { action, op_hash } = &warrant.proof;
+
+    // Find which of the action's ops the warrant is about.
+    let op_type = action_to_op_types(action.action())
+        .into_iter()
+        .find(|op_type| {
+            UniqueForm::op_hash(*op_type, action.action().clone())
+                .map_or(false, |(_, hash)| hash == *op_hash)
+        });
+    let op_type = match op_type {
+        Some(op_type) => op_type,
+        None => return Outcome::exit_with_rejected("The warranted op is not an op of the action"),
+    };
+
+    // Public entries are needed to rebuild the warranted op.
+    let entry = match action.action().entry_data() {
+        Some((entry_hash, entry_type)) if entry_type.visibility().is_public() => Some(
+            cascade
+                .retrieve_entry(entry_hash.clone(), Default::default())
+                .await?
+                .map(|(e, _)| e.into_content())
+                .ok_or_else(|| Outcome::awaiting(entry_hash))?,
+        ),
+        _ => None,
+    };
+    let op = DhtOp::from_type(op_type, action.clone(), entry)?;
+
+    let outcome = match dhtop_to_op(op, cascade).await {
+        Ok(op) => validate_op_outer(dna_hash, &op, conductor_handle, workspace, network).await,
+        Err(e) => Err(e),
+    };
+    match outcome.or_else(|outcome_or_err| outcome_or_err.try_into())? {
+        Outcome::Accepted => Outcome::exit_with_rejected("The warranted op is valid"),
+        Outcome::Rejected(_) => Ok(Outcome::Accepted),
+        Outcome::AwaitingDeps(deps) => Ok(Outcome::AwaitingDeps(deps)),
+    }
+}
+
+/// Issue warrants against the authors of ops which failed app validation.
+/// The warrants are authored by one of our agents in the DNA and stored in
+/// the authored database, from where they are published to the agent
+/// activity authorities of the warranted authors.
+async fn issue_warrants(
+    dna_hash: &DnaHash,
+    conductor: &ConductorHandle,
+    proofs: Vec<WarrantProof>,
+) -> WorkflowResult<()> {
+    // Ordered, so that the same agent always authors the warrants and
+    // warranting the same action again produces the same op.
+    let validators: BTreeSet<_> = conductor
+        .running_cell_ids(Some(CellStatus::Joined))
+        .into_iter()
+        .filter(|cell_id| cell_id.dna_hash() == dna_hash)
+        .map(|cell_id| cell_id.agent_pubkey().clone())
+        .collect();
+    let validator = match validators.iter().next() {
+        Some(validator) => validator.clone(),
+        None => return Ok(()),
+    };
+
+    let mut ops = Vec::with_capacity(proofs.len());
+    for proof in proofs {
+        // The warrant takes its timestamp from the warranted action, so that
+        // warranting the same action again produces the same op.
+        let WarrantProof::InvalidChainOp { action, .. } = &proof;
+        let timestamp = action.action().timestamp();
+        let warrant = Warrant::new(proof, validator.clone(), timestamp);
+        // Never warrant ourselves.
+        if validators.contains(warrant.warrantee()) {
+            continue;
+        }
+        let signature = validator.sign(conductor.keystore(), &warrant).await?;
+        let op = DhtOp::from_warrant(SignedWarrant(warrant, signature));
+        ops.push(DhtOpHashed::from_content_sync(op));
+    }
+
+    conductor
+        .get_or_create_authored_db(dna_hash)?
+        .write_async(move |txn| {
+            for op in &ops {
+                insert_op(txn, op)?;
+                set_validation_status(txn, op.as_hash(), ValidationStatus::Valid)?;
+                set_when_integrated(txn, op.as_hash(), Timestamp::now())?;
+            }
+            WorkflowResult::Ok(())
+        })
+        .await
+}
+
+/// Block the authors of the ops which verified warrants were issued for,
+/// from now until the configured block duration has passed.
+async fn block_warranted_agents(
+    dna_hash: &DnaHash,
+    conductor: &ConductorHandle,
+    proofs: Vec<WarrantProof>,
+) -> WorkflowResult<()> {
+    let start = Timestamp::now();
+    let end = (start + conductor.get_config().warranted_agent_block_duration())?;
+    for WarrantProof::InvalidChainOp { action, op_hash } in proofs {
+        let cell_id = CellId::new(dna_hash.clone(), action.action().author().clone());
+        // Don't block ourselves.
+        if conductor.running_cell_ids(None).contains(&cell_id) {
+            continue;
+        }
+        conductor
+            .block(Block::new(
+                BlockTarget::Cell(cell_id, CellBlockReason::InvalidOp(op_hash)),
+                InclusiveTimestampInterval::try_new(start, end)?,
+            ))
+            .await?;
+    }
+    Ok(())
+}
+
 async fn validate_op_outer(
     dna_hash: Arc<DnaHash>,
     op: &Op,
@@ -530,6 +714,8 @@ where
         }) => create_link_zomes_to_invoke(action, ribosome)?,
     };
 
+    check_weight(op, ribosome)?;
+
     let invocation = ValidateInvocation::new(zomes_to_invoke, op)
         .map_err(|e| AppValidationError::RibosomeError(e.into()))?;
     let outcome = run_validation_callback_inner(
@@ -545,6 +731,55 @@ where
     Ok(outcome)
 }
 
+/// Check that the rate limiting weight claimed by an action is the weight
+/// given to it by the `weigh` callback of the integrity zome which owns it.
+/// Each action is only re-weighed by the authorities for one of its ops.
+pub fn check_weight(op: &Op, ribosome: &impl RibosomeT) -> AppValidationOutcome<()> {
+    let weight_matches = match op {
+        // Countersigned entries are not weighed.
+        Op::StoreEntry(StoreEntry {
+            entry: Entry::CounterSign(_, _),
+            ..
+        }) => true,
+        Op::StoreEntry(StoreEntry { action, entry }) => match &action.hashed.content {
+            EntryCreationAction::Create(create) => {
+                let input = WeighInput::Create(create.clone().unweighed(), entry.clone());
+                weigh_entry(ribosome, &create.entry_type, input)? == create.weight
+            }
+            EntryCreationAction::Update(update) => {
+                let input = WeighInput::Update(update.clone().unweighed(), entry.clone());
+                weigh_entry(ribosome, &update.entry_type, input)? == update.weight
+            }
+        },
+        Op::RegisterCreateLink(RegisterCreateLink { create_link }) => {
+            let create_link = &create_link.hashed.content;
+            let input = WeighInput::Link(create_link.clone().unweighed());
+            weigh(ribosome, Some(create_link.zome_index), input)? == create_link.weight
+        }
+        Op::RegisterDelete(RegisterDelete {
+            delete,
+            original_action,
+            ..
+        }) => {
+            let delete = &delete.hashed.content;
+            let zome_index = match original_action.entry_type() {
+                EntryType::App(app_entry_def) => Some(app_entry_def.zome_index()),
+                _ => None,
+            };
+            let input = WeighInput::Delete(delete.clone().unweighed());
+            weigh(ribosome, zome_index, input)? == delete.weight
+        }
+        _ => true,
+    };
+    if weight_matches {
+        Ok(())
+    } else {
+        Outcome::exit_with_rejected(
+            "The rate limiting weight of the action does not match the weight given by its zome",
+        )
+    }
+}
+
 /// Check the AppEntryDef is valid for the zome.
 /// Check the EntryDefId and ZomeIndex are in range.
 pub async fn check_entry_def(
@@ -31,6 +31,8 @@ pub enum AppValidationError {
     // Sys validation that requires calls to zomes happen during app validation
     #[error(transparent)]
     SysValidationError(#[from] SysValidationError),
+    #[error("A warrant is validated through the op it warrants, and has no Op of its own")]
+    WarrantOp,
     #[error("The app entry type {0:?} zome index was out of range")]
     ZomeIndex(ZomeIndex),
 }
@@ -13,12 +13,17 @@ use crate::core::ribosome::guest_callback::post_commit::send_post_commit;
 use crate::core::ribosome::RibosomeT;
 use crate::core::ribosome::ZomeCallHostAccess;
 use crate::core::ribosome::ZomeCallInvocation;
+use crate::core::sys_validate::check_rate_limit;
 use crate::core::workflow::error::WorkflowError;
+use crate::core::SysValidationError;
 use holochain_keystore::MetaLairClient;
+use holochain_p2p::ChcImpl;
 use holochain_p2p::HolochainP2pDna;
+use holochain_p2p::HolochainP2pDnaT;
 use holochain_state::host_fn_workspace::HostFnWorkspace;
 use holochain_state::host_fn_workspace::SourceChainWorkspace;
 use holochain_state::source_chain::SourceChainError;
+use holochain_state::source_chain::SourceChainResult;
 use holochain_zome_types::record::Record;
 
 use holochain_types::prelude::*;
@@ -65,6 +70,7 @@ where
         .ok();
     let should_write = args.is_root_zome_call;
     let conductor_handle = args.conductor_handle.clone();
+    let cell_id = args.cell_id.clone();
     let result =
         call_zome_workflow_inner(workspace.clone(), network.clone(), keystore.clone(), args)
             .await?;
@@ -75,10 +81,25 @@ where
     if should_write {
         let is_empty = workspace.source_chain().is_empty()?;
         let countersigning_op = workspace.source_chain().countersigning_op()?;
-        match HostFnWorkspace::from(workspace.clone())
+        let mut flushed = HostFnWorkspace::from(workspace.clone())
             .flush(&network)
-            .await
-        {
+            .await;
+        let chc = match &flushed {
+            Err(SourceChainError::ChcHeadMoved(..)) => network.chc(),
+            _ => None,
+        };
+        if let Some(chc) = chc {
+            flushed = repair_from_chc_and_flush(
+                &conductor_handle,
+                cell_id,
+                chc,
+                &workspace,
+                &network,
+                flushed,
+            )
+            .await?;
+        }
+        match flushed {
             Ok(flushed_actions) => {
                 // Q: what is the purpose of checking for an empty chain? When would this ever happen? The chain should
                 //    be genesis'd by now, right?
@@ -128,6 +149,37 @@ where
     Ok(result)
 }
 
+/// After a write was rejected because the source chain is behind the CHC,
+/// graft the records which the CHC has beyond the chain onto it, and flush
+/// the writes again, rebased onto the new head.
+///
+/// This is only done once, and only if the writes may be rebased. Otherwise
+/// the rejection is returned, and it is up to the caller to retry the call.
+async fn repair_from_chc_and_flush(
+    conductor_handle: &ConductorHandle,
+    cell_id: CellId,
+    chc: ChcImpl,
+    workspace: &SourceChainWorkspace,
+    network: &HolochainP2pDna,
+    rejected: SourceChainResult<Vec<SignedActionHashed>>,
+) -> WorkflowResult<SourceChainResult<Vec<SignedActionHashed>>> {
+    conductor_handle
+        .clone()
+        .repair_source_chain_from_chc(cell_id, chc)
+        .await
+        .map_err(Box::new)?;
+    Ok(
+        match workspace
+            .source_chain()
+            .rebase_onto_persisted_head()
+            .await?
+        {
+            Some(rebased) => rebased.flush(network).await,
+            None => rejected,
+        },
+    )
+}
+
 async fn call_zome_workflow_inner<Ribosome>(
     workspace: SourceChainWorkspace,
     network: HolochainP2pDna,
@@ -156,6 +208,7 @@ where
         network.clone(),
         signal_tx,
         call_zome_handle,
+        invocation.expires_at,
     );
     let (ribosome, result) =
         call_zome_function_authorized(ribosome, host_access, invocation).await?;
@@ -205,7 +258,11 @@ where
 {
     match invocation.is_authorized(&host_access).await? {
         ZomeCallAuthorization::Authorized => {
-            tokio::task::spawn_blocking(|| {
+            // Carry the span into the wasm thread, so host calls made from
+            // there are traced as part of this zome call.
+            let span = tracing::Span::current();
+            tokio::task::spawn_blocking(move || {
+                let _g = span.enter();
                 let r = ribosome.call_zome_function(host_access, invocation);
                 Ok((ribosome, r))
             })
@@ -236,6 +293,8 @@ where
     let cascade =
         holochain_cascade::CascadeImpl::from_workspace_and_network(&workspace, network.clone());
 
+    let rate_limits = &ribosome.dna_def().rate_limits;
+
     let to_app_validate = {
         // collect all the records we need to validate in wasm
         let scratch_records = workspace.source_chain().scratch_records()?;
@@ -248,6 +307,13 @@ where
                 // If the validation failed, exit with an InvalidCommit
                 // If it was ok continue
                 .or_else(|outcome_or_err| outcome_or_err.invalid_call_zome_commit())?;
+            // Refuse to author actions which overflow their rate limiting bucket
+            match check_rate_limit(record.action(), rate_limits, &cascade).await {
+                Err(SysValidationError::ValidationOutcome(outcome)) => {
+                    return Err(SourceChainError::InvalidCommit(outcome.to_string()).into());
+                }
+                result => result?,
+            }
             to_app_validate.push(record);
         }
 
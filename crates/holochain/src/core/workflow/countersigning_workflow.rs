@@ -12,14 +12,13 @@ use holochain_state::prelude::{
 use holochain_types::dht_op::DhtOp;
 use holochain_types::signal::{Signal, SystemSignal};
 use holochain_zome_types::Timestamp;
-use holochain_zome_types::{Entry, SignedAction, ZomeCallResponse};
+use holochain_zome_types::{Entry, EntryRateWeight, SignedAction, ZomeCallResponse};
 use kitsune_p2p_types::tx2::tx2_utils::Share;
 use rusqlite::{named_params, Transaction};
 
 use crate::conductor::interface::SignalBroadcaster;
 use crate::conductor::space::Space;
 use crate::core::queue_consumer::{QueueTriggers, TriggerSender, WorkComplete};
-use crate::core::ribosome::weigh_placeholder;
 
 use holochain_p2p::event::CountersigningSessionNegotiationMessage;
 
@@ -69,7 +68,8 @@ pub(crate) fn incoming_countersigning(
             if let Entry::CounterSign(session_data, _) = entry {
                 let entry_hash = EntryHash::with_data_sync(entry);
                 // Get the required actions for this session.
-                let weight = weigh_placeholder();
+                // Countersigned entries are not weighed.
+                let weight = EntryRateWeight::default();
                 let action_set = session_data.build_action_set(entry_hash, weight)?;
 
                 // Get the expires time for this session.
@@ -252,7 +252,8 @@ pub(crate) async fn countersigning_success(
             if let Some((cs_entry_hash, cs)) = current_countersigning_session(txn, Arc::new(author.clone()))? {
                 // Check we have the right session.
                 if cs_entry_hash == entry_hash {
-                    let weight = weigh_placeholder();
+                    // Countersigned entries are not weighed.
+                    let weight = EntryRateWeight::default();
                     let stored_actions = cs.build_action_set(entry_hash, weight)?;
                     if stored_actions.len() == incoming_actions.len() {
                         // Check all stored action hashes match an incoming action hash.
@@ -2,6 +2,7 @@
 
 use super::error::WorkflowResult;
 use super::sys_validation_workflow::counterfeit_check;
+use super::sys_validation_workflow::counterfeit_warrant_check;
 use crate::{
     conductor::{conductor::RwShare, space::Space},
     core::queue_consumer::TriggerSender,
@@ -233,6 +234,9 @@ pub async fn incoming_dht_ops_workflow(
 #[instrument(skip(op))]
 /// If this op fails the counterfeit check it should be dropped
 async fn should_keep(op: &DhtOp) -> WorkflowResult<()> {
+    if let DhtOp::RegisterWarrant(signature, warrant) = op {
+        return Ok(counterfeit_warrant_check(signature, warrant).await?);
+    }
     let action = op.action();
     let signature = op.signature();
     Ok(counterfeit_check(signature, &action).await?)
@@ -190,6 +190,7 @@ async fn create_and_insert_op(
             }
             delete_link.into()
         }
+        DhtOpType::RegisterWarrant => unreachable!("Warrant ops are not produced from an action"),
     };
 
     if facts.sequential {
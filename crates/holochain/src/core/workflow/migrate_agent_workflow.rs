@@ -0,0 +1,138 @@
+//! Migrate Agent Workflow: move an agent from one DNA to another, linking the
+//! two source chains so that the agent's lineage can be followed across them.
+//!
+//! - The old chain is closed with a `CloseChain` pointing at the new DNA
+//! - The new chain is opened with an `OpenChain` pointing at the old DNA
+//!
+//! The `migrate_agent` callback is run on each side, after the action has been
+//! put into the scratch space, so that the callback can see it when querying
+//! its chain. Nothing is written unless every callback on both sides passes.
+//!
+//! The old chain is written before the new one, so that the agent can never
+//! be left with two open chains. If opening the new chain fails after the old
+//! one was closed, the migration can be retried, and only the new side is run.
+
+use super::error::WorkflowResult;
+use crate::conductor::ConductorHandle;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
+use crate::core::ribosome::RibosomeT;
+use derive_more::Constructor;
+use holochain_p2p::HolochainP2pDna;
+use holochain_state::host_fn_workspace::HostFnWorkspace;
+use holochain_state::host_fn_workspace::SourceChainWorkspace;
+use holochain_types::prelude::*;
+use holochain_zome_types::action::builder;
+
+/// One of the two chains taking part in a migration.
+#[derive(Constructor)]
+pub struct MigrateAgentSide<Ribosome>
+where
+    Ribosome: RibosomeT + 'static,
+{
+    pub workspace: SourceChainWorkspace,
+    pub network: HolochainP2pDna,
+    pub ribosome: Ribosome,
+}
+
+pub async fn migrate_agent_workflow<Ribosome>(
+    close: MigrateAgentSide<Ribosome>,
+    open: MigrateAgentSide<Ribosome>,
+    conductor_handle: ConductorHandle,
+) -> WorkflowResult<MigrateAgentResult>
+where
+    Ribosome: RibosomeT + Clone + 'static,
+{
+    let old_dna_hash = close.ribosome.dna_def().as_hash().clone();
+    let new_dna_hash = open.ribosome.dna_def().as_hash().clone();
+
+    let already_closed = close
+        .workspace
+        .source_chain()
+        .query(ChainQueryFilter::new().action_type(ActionType::CloseChain))
+        .await?
+        .iter()
+        .any(|record| {
+            matches!(record.action(), Action::CloseChain(CloseChain { new_dna_hash: hash, .. }) if *hash == new_dna_hash)
+        });
+    if !already_closed {
+        close
+            .workspace
+            .source_chain()
+            .put(
+                builder::CloseChain { new_dna_hash },
+                None,
+                ChainTopOrdering::Strict,
+            )
+            .await?;
+        let result =
+            migrate_agent_workflow_inner(&close, MigrateAgent::Close, conductor_handle.clone())
+                .await?;
+        if result != MigrateAgentResult::Pass {
+            return Ok(result);
+        }
+    }
+
+    open.workspace
+        .source_chain()
+        .put(
+            builder::OpenChain {
+                prev_dna_hash: old_dna_hash,
+            },
+            None,
+            ChainTopOrdering::Strict,
+        )
+        .await?;
+    let result = migrate_agent_workflow_inner(&open, MigrateAgent::Open, conductor_handle).await?;
+
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+
+    // only commit if both sides were successful
+    if result == MigrateAgentResult::Pass {
+        // The old chain is closed first, so that if opening the new chain
+        // fails the agent isn't left with two open chains.
+        HostFnWorkspace::from(close.workspace)
+            .flush(&close.network)
+            .await?;
+        HostFnWorkspace::from(open.workspace)
+            .flush(&open.network)
+            .await?;
+    }
+    Ok(result)
+}
+
+/// Run the callback for one side of the migration, and validate the action
+/// which links it to the other side.
+async fn migrate_agent_workflow_inner<Ribosome>(
+    side: &MigrateAgentSide<Ribosome>,
+    migrate_agent: MigrateAgent,
+    conductor_handle: ConductorHandle,
+) -> WorkflowResult<MigrateAgentResult>
+where
+    Ribosome: RibosomeT + Clone + 'static,
+{
+    let MigrateAgentSide {
+        workspace,
+        network,
+        ribosome,
+    } = side;
+
+    let result = {
+        let host_access = MigrateAgentHostAccess::new(workspace.clone().into());
+        let invocation =
+            MigrateAgentInvocation::new(ribosome.dna_def().as_content().clone(), migrate_agent);
+        ribosome.run_migrate_agent(host_access, invocation)?
+    };
+
+    if result == MigrateAgentResult::Pass {
+        super::inline_validation(
+            workspace.clone(),
+            network.clone(),
+            conductor_handle,
+            ribosome.clone(),
+        )
+        .await?;
+    }
+    Ok(result)
+}
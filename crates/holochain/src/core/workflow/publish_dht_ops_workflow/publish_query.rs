@@ -6,6 +6,7 @@ use holo_hash::DhtOpHash;
 use holochain_p2p::DhtOpHashExt;
 use holochain_sqlite::db::DbKindAuthored;
 use holochain_sqlite::prelude::ReadAccess;
+use holochain_state::query::map_sql_warrant_op;
 use holochain_state::query::prelude::*;
 use holochain_types::dht_op::DhtOp;
 use holochain_types::dht_op::DhtOpType;
@@ -45,21 +46,24 @@ where
                 "
             SELECT
             Action.blob as action_blob,
-            LENGTH(Action.blob) AS action_size,
+            COALESCE(LENGTH(Warrant.blob), LENGTH(Action.blob)) AS action_size,
             CASE
               WHEN DhtOp.type IN ('StoreEntry', 'StoreRecord') THEN LENGTH(Entry.blob)
               ELSE 0
             END AS entry_size,
             Entry.blob as entry_blob,
+            Warrant.blob as warrant_blob,
             DhtOp.type as dht_type,
             DhtOp.hash as dht_hash
-            FROM Action
-            JOIN
-            DhtOp ON DhtOp.action_hash = Action.hash
+            FROM DhtOp
+            LEFT JOIN
+            Action ON DhtOp.action_hash = Action.hash
             LEFT JOIN
             Entry ON Action.entry_hash = Entry.hash
+            LEFT JOIN
+            Warrant ON DhtOp.hash = Warrant.hash
             WHERE
-            Action.author = :author
+            COALESCE(Warrant.author, Action.author) = :author
             AND
             (DhtOp.type != :store_entry OR Action.private_entry = 0)
             AND
@@ -81,10 +85,14 @@ where
                     // will be NULL if the op has no associated entry
                     let entry_size: Option<usize> = row.get("entry_size")?;
                     let op_size = (action_size + entry_size.unwrap_or(0)).into();
-                    let action = from_blob::<SignedAction>(row.get("action_blob")?)?;
-                    let op_type: DhtOpType = row.get("dht_type")?;
                     let hash: DhtOpHash = row.get("dht_hash")?;
                     let op_hash_sized = OpHashSized::new(hash.to_kitsune(), Some(op_size));
+                    // Warrants we issued are published alongside our own ops.
+                    if let Some(op) = map_sql_warrant_op(row)? {
+                        return WorkflowResult::Ok((op.dht_basis(), op_hash_sized, op));
+                    }
+                    let action = from_blob::<SignedAction>(row.get("action_blob")?)?;
+                    let op_type: DhtOpType = row.get("dht_type")?;
                     let entry = match action.0.entry_type().map(|et| et.visibility()) {
                         Some(EntryVisibility::Public) => {
                             let entry: Option<Vec<u8>> = row.get("entry_blob")?;
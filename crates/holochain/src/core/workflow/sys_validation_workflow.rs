@@ -1,13 +1,13 @@
 //! The workflow and queue consumer for sys validation
 
-use super::*;
+use super::error::WorkflowResult;
+use crate::conductor::conductor::DpkiService;
 use crate::conductor::space::Space;
 use crate::core::queue_consumer::TriggerSender;
 use crate::core::queue_consumer::WorkComplete;
 use crate::core::sys_validate::check_and_hold_store_record;
 use crate::core::sys_validate::*;
 use crate::core::validation::*;
-use error::WorkflowResult;
 use holo_hash::DhtOpHash;
 use holochain_cascade::Cascade;
 use holochain_cascade::CascadeImpl;
@@ -46,7 +46,8 @@ mod tests;
     space,
     trigger_app_validation,
     sys_validation_trigger,
-    network
+    network,
+    dpki
 ))]
 pub async fn sys_validation_workflow(
     workspace: Arc<SysValidationWorkspace>,
@@ -54,9 +55,11 @@ pub async fn sys_validation_workflow(
     trigger_app_validation: TriggerSender,
     sys_validation_trigger: TriggerSender,
     network: HolochainP2pDna,
+    dpki: Option<Arc<dyn DpkiService>>,
 ) -> WorkflowResult<WorkComplete> {
     let complete =
-        sys_validation_workflow_inner(workspace, space, network, sys_validation_trigger).await?;
+        sys_validation_workflow_inner(workspace, space, network, sys_validation_trigger, dpki)
+            .await?;
 
     // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
 
@@ -71,6 +74,7 @@ async fn sys_validation_workflow_inner(
     space: Arc<Space>,
     network: HolochainP2pDna,
     sys_validation_trigger: TriggerSender,
+    dpki: Option<Arc<dyn DpkiService>>,
 ) -> WorkflowResult<WorkComplete> {
     let db = workspace.dht_db.clone();
     let sorted_ops = validation_query::get_ops_to_sys_validate(&db).await?;
@@ -91,6 +95,7 @@ async fn sys_validation_workflow_inner(
                 IncomingDhtOpSender::new(space.clone(), sys_validation_trigger.clone());
             let workspace = workspace.clone();
             let cascade = cascade.clone();
+            let dpki = dpki.clone();
             async move {
                 let (op, op_hash) = so.into_inner();
                 let op_type = op.get_type();
@@ -99,7 +104,14 @@ async fn sys_validation_workflow_inner(
                 let dependency = get_dependency(op_type, &action);
                 let dna_def = DnaDefHashed::from_content_sync((*workspace.dna_def()).clone());
 
-                let r = validate_op(&op, &dna_def, &cascade, Some(incoming_dht_ops_sender)).await;
+                let r = validate_op(
+                    &op,
+                    &dna_def,
+                    &cascade,
+                    Some(incoming_dht_ops_sender),
+                    dpki.as_deref(),
+                )
+                .await;
                 r.map(|o| (op_hash, o, dependency))
             }
         }
@@ -222,8 +234,9 @@ pub(crate) async fn validate_op(
     dna_def: &DnaDefHashed,
     cascade: &impl Cascade,
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
+    dpki: Option<&dyn DpkiService>,
 ) -> WorkflowResult<Outcome> {
-    match validate_op_inner(op, cascade, dna_def, incoming_dht_ops_sender).await {
+    match validate_op_inner(op, cascade, dna_def, incoming_dht_ops_sender, dpki).await {
         Ok(_) => Ok(Outcome::Accepted),
         // Handle the errors that result in pending or awaiting deps
         Err(SysValidationError::ValidationOutcome(e)) => {
@@ -250,11 +263,12 @@ pub(crate) async fn validate_op(
 fn handle_failed(error: &ValidationOutcome) -> Outcome {
     use Outcome::*;
     match error {
-        ValidationOutcome::Counterfeit(_, _) => {
+        ValidationOutcome::Counterfeit(_, _) | ValidationOutcome::CounterfeitWarrant(_, _) => {
             unreachable!("Counterfeit ops are dropped before sys validation")
         }
         ValidationOutcome::ActionNotInCounterSigningSession(_, _) => Rejected,
         ValidationOutcome::DepMissingFromDht(_) => MissingDhtDep,
+        ValidationOutcome::DpkiAgentInvalid(_, _) => Rejected,
         ValidationOutcome::EntryDefId(_) => Rejected,
         ValidationOutcome::EntryHash => Rejected,
         ValidationOutcome::EntryTooLarge(_) => Rejected,
@@ -270,6 +284,7 @@ fn handle_failed(error: &ValidationOutcome) -> Outcome {
             _ => Rejected,
         },
         ValidationOutcome::PrivateEntryLeaked => Rejected,
+        ValidationOutcome::RateLimitExceeded(_, _, _) => Rejected,
         ValidationOutcome::PreflightResponseSignature(_) => Rejected,
         ValidationOutcome::UpdateTypeMismatch(_, _) => Rejected,
         ValidationOutcome::VerifySignature(_, _) => Rejected,
@@ -284,8 +299,18 @@ async fn validate_op_inner(
     cascade: &impl Cascade,
     dna_def: &DnaDefHashed,
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
+    dpki: Option<&dyn DpkiService>,
 ) -> SysValidationResult<()> {
     check_entry_visibility(op)?;
+    match op {
+        DhtOp::RegisterWarrant(_, warrant) => {
+            author_key_is_valid(&warrant.author, warrant.timestamp, dpki).await?
+        }
+        _ => {
+            let action = op.action();
+            author_key_is_valid(action.author(), action.timestamp(), dpki).await?
+        }
+    }
     match op {
         DhtOp::StoreRecord(_, action, entry) => {
             store_record(action, cascade).await?;
@@ -383,6 +408,11 @@ async fn validate_op_inner(
             register_delete_link(action, cascade, incoming_dht_ops_sender).await?;
             Ok(())
         }
+        DhtOp::RegisterWarrant(_, _) => {
+            // Signatures are checked when the warrant is received, and the
+            // warranted op is re-validated during app validation.
+            Ok(())
+        }
     }
 }
 
@@ -474,14 +504,24 @@ async fn sys_validate_record_inner(
     }
 }
 
-/// Check if the op has valid signature and author.
+/// Check if the op has a valid signature.
 /// Ops that fail this check should be dropped.
 pub async fn counterfeit_check(signature: &Signature, action: &Action) -> SysValidationResult<()> {
     verify_action_signature(signature, action).await?;
-    author_key_is_valid(action.author()).await?;
     Ok(())
 }
 
+/// Check that a warrant, and the action it carries as proof, were both
+/// signed by their authors. Counterfeit warrants are dropped.
+pub async fn counterfeit_warrant_check(
+    signature: &Signature,
+    warrant: &Warrant,
+) -> SysValidationResult<()> {
+    verify_warrant_signature(signature, warrant).await?;
+    let SignedAction(action, action_signature) = warrant.signed_action();
+    counterfeit_check(action_signature, action).await
+}
+
 async fn register_agent_activity(
     action: &Action,
     cascade: &impl Cascade,
@@ -503,6 +543,7 @@ async fn register_agent_activity(
         )
         .await?;
     }
+    check_rate_limit(action, &dna_def.rate_limits, cascade).await?;
     // not appropriate for sys validation
     // check_chain_rollback(action, workspace).await?;
     Ok(())
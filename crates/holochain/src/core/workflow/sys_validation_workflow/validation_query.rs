@@ -1,5 +1,6 @@
 use holo_hash::DhtOpHash;
 use holochain_sqlite::db::DbKindDht;
+use holochain_state::query::map_sql_warrant_op;
 use holochain_state::query::prelude::*;
 use holochain_types::db::DbRead;
 use holochain_types::dht_op::DhtOp;
@@ -33,13 +34,16 @@ async fn get_ops_to_validate(
         SELECT
         Action.blob as action_blob,
         Entry.blob as entry_blob,
+        Warrant.blob as warrant_blob,
         DhtOp.type as dht_type,
         DhtOp.hash as dht_hash
         FROM DhtOp
-        JOIN
+        LEFT JOIN
         Action ON DhtOp.action_hash = Action.hash
         LEFT JOIN
         Entry ON Action.entry_hash = Entry.hash
+        LEFT JOIN
+        Warrant ON DhtOp.hash = Warrant.hash
         "
     .to_string();
     if system {
@@ -82,9 +86,12 @@ async fn get_ops_to_validate(
     db.read_async(move |txn| {
         let mut stmt = txn.prepare(&sql)?;
         let r = stmt.query_and_then([], |row| {
+            let hash: DhtOpHash = row.get("dht_hash")?;
+            if let Some(op) = map_sql_warrant_op(row)? {
+                return WorkflowResult::Ok(DhtOpHashed::with_pre_hashed(op, hash));
+            }
             let action = from_blob::<SignedAction>(row.get("action_blob")?)?;
             let op_type: DhtOpType = row.get("dht_type")?;
-            let hash: DhtOpHash = row.get("dht_hash")?;
             let entry: Option<Vec<u8>> = row.get("entry_blob")?;
             let entry = match entry {
                 Some(entry) => Some(from_blob::<Entry>(entry)?),
@@ -29,10 +29,11 @@ pub async fn pending_receipts(
             move |txn| {
                 let mut stmt = txn.prepare(
                     "
-            SELECT Action.author, DhtOp.hash, DhtOp.validation_status,
-            DhtOp.when_integrated
+            SELECT COALESCE(Warrant.author, Action.author) AS author,
+            DhtOp.hash, DhtOp.validation_status, DhtOp.when_integrated
             From DhtOp
-            JOIN Action ON DhtOp.action_hash = Action.hash
+            LEFT JOIN Action ON DhtOp.action_hash = Action.hash
+            LEFT JOIN Warrant ON DhtOp.hash = Warrant.hash
             WHERE
             DhtOp.require_receipt = 1
             AND
@@ -15,10 +15,13 @@ use ::fixt::prelude::StdRng;
 use hdk::prelude::*;
 use holo_hash::DnaHash;
 use holochain_keystore::MetaLairClient;
+use holochain_p2p::AgentPubKeyExt;
 use holochain_state::prelude::test_db_dir;
 use holochain_state::test_utils::TestDir;
 use holochain_types::prelude::*;
 use holochain_websocket::*;
+use kitsune_p2p::fault::{node_id_from_url, NetworkFaults};
+use kitsune_p2p_block::NodeId;
 use rand::Rng;
 use std::path::Path;
 use std::sync::Arc;
@@ -303,8 +306,10 @@ impl SweetConductor {
                 (InstalledCell::new(cell_id, r.role.clone()), None)
             })
             .collect();
+        // If the conductor has a CHC, every app is coordinated with it.
+        let use_chc = self.raw_handle().has_chc();
         self.raw_handle()
-            .install_app_legacy(installed_app_id.clone(), installed_cells)
+            .install_app_legacy(installed_app_id.clone(), installed_cells, use_chc)
             .await?;
 
         self.raw_handle().enable_app(installed_app_id).await?;
@@ -584,6 +589,37 @@ impl SweetConductor {
         crate::conductor::p2p_agent_store::exchange_peer_info_sparse(all, connectivity).await;
     }
 
+    /// The faults injected on the messages this conductor sends to other nodes.
+    /// These are cleared when the conductor restarts.
+    pub fn network_faults(&self) -> NetworkFaults {
+        self.raw_handle().network_faults()
+    }
+
+    /// The ids which other conductors' [`NetworkFaults`] use to refer to this
+    /// conductor, taken from the urls its running cells are reachable at.
+    /// Empty until some app is installed and its agent info is known.
+    pub async fn node_ids(&self) -> HashSet<NodeId> {
+        let handle = self.raw_handle();
+        let agents: HashSet<_> = handle
+            .running_cell_ids(None)
+            .into_iter()
+            .map(|cell_id| cell_id.agent_pubkey().clone())
+            .collect();
+        handle
+            .get_agent_infos(None)
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|info| agents.contains(&AgentPubKey::from_kitsune(&info.agent)))
+            .flat_map(|info| {
+                info.url_list
+                    .iter()
+                    .filter_map(|url| node_id_from_url(url.as_str()))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
     /// Wait for at least one gossip round to have completed for the given cell
     ///
     /// Note that this is really a crutch. If gossip starts fast enough then this is unnecessary
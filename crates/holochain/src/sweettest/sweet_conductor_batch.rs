@@ -7,6 +7,8 @@ use ::fixt::prelude::StdRng;
 use futures::future;
 use hdk::prelude::*;
 use holochain_types::prelude::*;
+use kitsune_p2p::fault::LinkFault;
+use kitsune_p2p_block::NodeId;
 use std::time::Duration;
 
 /// A collection of SweetConductors, with methods for operating on the entire collection
@@ -221,6 +223,61 @@ impl SweetConductorBatch {
         crate::conductor::p2p_agent_store::reveal_peer_info(observer_envs, seen_envs).await;
     }
 
+    /// Split the conductors into groups which can't send messages to each
+    /// other, given by index. Conductors not named in any group make up one
+    /// more group together.
+    ///
+    /// Faults already set on links within a group are left as they are.
+    /// Gossip backs off from peers it fails to reach, so tests which heal a
+    /// partition will want to tune down the gossip round timeout and retry delay.
+    /// Apps must be installed before partitioning, since conductors are told
+    /// apart by the urls their agents are reachable at.
+    pub async fn partition(&self, groups: &[&[usize]]) {
+        let group_of = |i: usize| {
+            groups
+                .iter()
+                .position(|group| group.contains(&i))
+                .unwrap_or(groups.len())
+        };
+        for to in 0..self.0.len() {
+            let node_ids = self.node_ids(to).await;
+            for from in (0..self.0.len()).filter(|from| group_of(*from) != group_of(to)) {
+                let faults = self.0[from].network_faults();
+                for node_id in node_ids.iter() {
+                    faults.set_link(node_id.clone(), LinkFault::partitioned());
+                }
+            }
+        }
+    }
+
+    /// Inject faults on the messages one conductor sends to another.
+    /// This only affects messages in one direction: set the reverse link as
+    /// well to affect both.
+    pub async fn set_link_fault(&self, from: usize, to: usize, fault: LinkFault) {
+        let faults = self.0[from].network_faults();
+        for node_id in self.node_ids(to).await {
+            faults.set_link(node_id, fault);
+        }
+    }
+
+    /// Remove every fault injected on the links between conductors,
+    /// including any partition.
+    pub fn heal(&self) {
+        for c in self.0.iter() {
+            c.network_faults().heal();
+        }
+    }
+
+    async fn node_ids(&self, i: usize) -> HashSet<NodeId> {
+        let node_ids = self.0[i].node_ids().await;
+        assert!(
+            !node_ids.is_empty(),
+            "conductor {} has no agents on the network to inject faults for",
+            i
+        );
+        node_ids
+    }
+
     /// Force trigger all dht ops that haven't received
     /// enough validation receipts yet.
     pub async fn force_all_publish_dht_ops(&self) {
@@ -21,7 +21,7 @@ impl From<KitsuneP2pConfig> for SweetConductorConfig {
         ConductorConfig {
             network: Some(network),
             admin_interfaces: Some(vec![AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port: 0 },
+                driver: InterfaceDriver::websocket(0),
             }]),
             ..Default::default()
         }
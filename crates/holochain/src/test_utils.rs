@@ -317,7 +317,7 @@ pub async fn install_app(
     }
     conductor_handle
         .clone()
-        .install_app_legacy(name.to_string(), cell_data)
+        .install_app_legacy(name.to_string(), cell_data, false)
         .await
         .unwrap();
 
@@ -375,7 +375,7 @@ pub async fn install_app_in_conductor(
 
     conductor_handle
         .clone()
-        .install_app_legacy(installed_app_id.clone(), cell_data)
+        .install_app_legacy(installed_app_id.clone(), cell_data, false)
         .await
         .unwrap();
 
@@ -427,7 +427,7 @@ pub async fn setup_app_inner(
     let conductor_handle = ConductorBuilder::new()
         .config(ConductorConfig {
             admin_interfaces: Some(vec![AdminInterfaceConfig {
-                driver: InterfaceDriver::Websocket { port: 0 },
+                driver: InterfaceDriver::websocket(0),
             }]),
             network,
             ..Default::default()
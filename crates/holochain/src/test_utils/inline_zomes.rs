@@ -104,6 +104,16 @@ pub fn simple_crud_zome() -> InlineZomeSet {
             ))?;
             Ok(hash)
         })
+        .function("create_string_relaxed", move |api, s: AppString| {
+            let entry = Entry::app(s.try_into().unwrap()).unwrap();
+            let hash = api.create(CreateInput::new(
+                InlineZomeSet::get_entry_location(&api, EntryDefIndex(0)),
+                EntryVisibility::Public,
+                entry,
+                ChainTopOrdering::Relaxed,
+            ))?;
+            Ok(hash)
+        })
         .function("create_unit", move |api, ()| {
             let entry = Entry::app(().try_into().unwrap()).unwrap();
             let hash = api.create(CreateInput::new(
@@ -172,6 +172,7 @@ async fn get_from_another_agent() {
             network_seed: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            rate_limits: Default::default(),
         },
         vec![TestWasm::Create.into()],
     )
@@ -304,6 +305,7 @@ async fn get_links_from_another_agent() {
             network_seed: "ba1d046d-ce29-4778-914b-47e6010d2faf".to_string(),
             properties: SerializedBytes::try_from(()).unwrap(),
             zomes: vec![TestWasm::Create.into()].into(),
+            rate_limits: Default::default(),
         },
         vec![TestWasm::Create.into()],
     )
@@ -510,6 +512,7 @@ async fn run_fixt_network(
                             deletes: btreeset! {},
                             updates: btreeset! {},
                             invalid_actions: btreeset! {},
+                            links: btreeset! {},
                             entry_dht_status: None,
                         };
                         respond.respond(Ok(async move { Ok(metadata.try_into().unwrap()) }
@@ -132,6 +132,7 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
             },
             integrity_zomes: vec![TestZomes::from(TestWasm::Anchor).integrity.into_inner()],
             coordinator_zomes: vec![TestZomes::from(TestWasm::Anchor).coordinator.into_inner()],
+            rate_limits: Default::default(),
         },
         vec![TestWasm::Anchor.into()],
     )
@@ -178,14 +179,8 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
 
     // Setup websocket handle and app interface
     let (mut client, _) = websocket_client(&handle).await.unwrap();
-    let request = AdminRequest::AttachAppInterface { port: None };
-    let response = client.request(request);
-    let response = response.await.unwrap();
-    let app_port = match response {
-        AdminResponse::AppInterfaceAttached { port } => port,
-        _ => panic!("Attach app interface failed: {:?}", response),
-    };
-    let (mut app_interface, _) = websocket_client_by_port(app_port).await.unwrap();
+    let app_port = attach_app_interface(&mut client, None).await;
+    let (mut app_interface, _) = app_websocket_client(&mut client, app_port).await.unwrap();
 
     // /////////////
     // END CONDUCTOR
@@ -28,6 +28,7 @@ use futures::Future;
 use holochain_conductor_api::conductor::ConductorConfig;
 use holochain_conductor_api::conductor::KeystoreConfig;
 use holochain_conductor_api::AdminInterfaceConfig;
+use holochain_conductor_api::AllowedOrigins;
 use holochain_conductor_api::InterfaceDriver;
 use matches::assert_matches;
 use serde::Serialize;
@@ -159,15 +160,46 @@ pub async fn call_zome_fn<S>(
 }
 
 pub async fn attach_app_interface(client: &mut WebsocketSender, port: Option<u16>) -> u16 {
-    let request = AdminRequest::AttachAppInterface { port };
+    let request = AdminRequest::AttachAppInterface {
+        port,
+        driver: None,
+        allowed_origins: AllowedOrigins::Any,
+        installed_app_id: None,
+    };
     let response = client.request(request);
     let response = check_timeout(response, 3000).await;
     match response {
-        AdminResponse::AppInterfaceAttached { port } => port,
+        AdminResponse::AppInterfaceAttached { port, .. } => port,
         _ => panic!("Attach app interface failed: {:?}", response),
     }
 }
 
+pub async fn issue_app_authentication_token(client: &mut WebsocketSender) -> String {
+    let request = AdminRequest::IssueAppAuthenticationToken {
+        expiry_seconds: None,
+        installed_app_id: None,
+    };
+    let response = client.request(request);
+    let response = check_timeout(response, 3000).await;
+    match response {
+        AdminResponse::AppAuthenticationTokenIssued { token, .. } => token,
+        _ => panic!("Issue app authentication token failed: {:?}", response),
+    }
+}
+
+/// Connect to an app interface with a token issued over the admin interface.
+pub async fn app_websocket_client(
+    admin_tx: &mut WebsocketSender,
+    app_port: u16,
+) -> Result<(WebsocketSender, WebsocketReceiver)> {
+    let token = issue_app_authentication_token(admin_tx).await;
+    Ok(holochain_websocket::connect(
+        url2::url2!("ws://127.0.0.1:{}?token={}", app_port, token),
+        std::sync::Arc::new(holochain_websocket::WebsocketConfig::default()),
+    )
+    .await?)
+}
+
 pub async fn retry_admin_interface(
     port: u16,
     mut attempts: usize,
@@ -276,6 +308,7 @@ pub async fn register_and_install_dna_named(
         source: AppBundleSource::Bundle(bundle),
         installed_app_id: Some(name),
         network_seed: None,
+        use_chc: false,
         membrane_proofs: std::collections::HashMap::new(),
     };
     let request = AdminRequest::InstallApp(Box::new(payload));
@@ -336,7 +369,7 @@ pub async fn check_started(holochain: &mut Child) {
 pub fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
     ConductorConfig {
         admin_interfaces: Some(vec![AdminInterfaceConfig {
-            driver: InterfaceDriver::Websocket { port },
+            driver: InterfaceDriver::websocket(port),
         }]),
         environment_path: environment_path.into(),
         keystore: KeystoreConfig::DangerTestKeystore,
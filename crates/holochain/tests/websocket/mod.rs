@@ -15,6 +15,9 @@ use holochain::{
     },
     fixt::*,
 };
+use holochain_conductor_api::AllowedOrigins;
+use holochain_conductor_api::AppRequest;
+use holochain_conductor_api::AppResponse;
 use holochain_trace;
 use holochain_types::{
     prelude::*,
@@ -182,7 +185,7 @@ async fn call_zome() {
     // Attach App Interface
     let app_port = attach_app_interface(&mut admin_tx, None).await;
 
-    let (mut app_tx, _) = websocket_client_by_port(app_port).await.unwrap();
+    let (mut app_tx, _) = app_websocket_client(&mut admin_tx, app_port).await.unwrap();
 
     // Call Zome
     tracing::info!("Calling zome");
@@ -224,11 +227,11 @@ async fn call_zome() {
     let response = admin_tx.request(request);
     let response = check_timeout(response, 3000).await;
     let app_port = match response {
-        AdminResponse::AppInterfacesListed(ports) => *ports.first().unwrap(),
+        AdminResponse::AppInterfacesListed(drivers) => drivers.first().unwrap().port().unwrap(),
         _ => panic!("Unexpected response"),
     };
 
-    let (mut app_tx, _) = websocket_client_by_port(app_port).await.unwrap();
+    let (mut app_tx, _) = app_websocket_client(&mut admin_tx, app_port).await.unwrap();
 
     // Call Zome again on the existing app interface port
     tracing::info!("Calling zome again");
@@ -386,8 +389,8 @@ async fn emit_signals() {
     ///////////////////////////////////////////////////////
     // Emit signals (the real test!)
 
-    let (mut app_tx_1, app_rx_1) = websocket_client_by_port(app_port).await.unwrap();
-    let (_, app_rx_2) = websocket_client_by_port(app_port).await.unwrap();
+    let (mut app_tx_1, app_rx_1) = app_websocket_client(&mut admin_tx, app_port).await.unwrap();
+    let (_, app_rx_2) = app_websocket_client(&mut admin_tx, app_port).await.unwrap();
 
     call_zome_fn(
         &mut app_tx_1,
@@ -486,6 +489,381 @@ async fn list_app_interfaces_succeeds() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn app_interfaces_need_a_token_and_allowed_origin() -> Result<()> {
+    holochain_trace::test_run().ok();
+    let tmp_dir = TempDir::new().unwrap();
+    let config = create_config(0, tmp_dir.path().to_path_buf());
+    let conductor_handle = Conductor::builder().config(config).build().await?;
+    let (mut admin_tx, _) = websocket_client(&conductor_handle).await?;
+
+    let origin = "http://localhost:8888";
+    let response: AdminResponse = admin_tx
+        .request(AdminRequest::AttachAppInterface {
+            port: None,
+            driver: None,
+            allowed_origins: AllowedOrigins::from(origin.to_string()),
+            installed_app_id: None,
+        })
+        .await?;
+    let app_port = match response {
+        AdminResponse::AppInterfaceAttached { port, .. } => port,
+        _ => panic!("Unexpected response {:?}", response),
+    };
+    let connect_with = |token: Option<&str>, origin: Option<&str>| {
+        let config = WebsocketConfig {
+            origin: origin.map(String::from),
+            ..Default::default()
+        };
+        let url = match token {
+            Some(token) => url2!("ws://127.0.0.1:{}?token={}", app_port, token),
+            None => url2!("ws://127.0.0.1:{}", app_port),
+        };
+        connect(url, Arc::new(config))
+    };
+
+    assert!(connect_with(None, Some(origin)).await.is_err());
+    let token = issue_app_authentication_token(&mut admin_tx).await;
+    assert!(connect_with(Some(&token), None).await.is_err());
+    assert!(connect_with(Some(&token), Some("http://localhost:8889"))
+        .await
+        .is_err());
+    let (mut app_tx, _) = connect_with(Some(&token), Some(origin)).await?;
+    let response: AppResponse = app_tx
+        .request(AppRequest::AppInfo {
+            installed_app_id: "none".to_string(),
+        })
+        .await?;
+    assert_matches!(response, AppResponse::AppInfo(None));
+    // Tokens can only be used once.
+    assert!(connect_with(Some(&token), Some(origin)).await.is_err());
+
+    // Nor can they be used once they have expired.
+    let response: AdminResponse = admin_tx
+        .request(AdminRequest::IssueAppAuthenticationToken {
+            expiry_seconds: Some(0),
+            installed_app_id: None,
+        })
+        .await?;
+    let token = match response {
+        AdminResponse::AppAuthenticationTokenIssued { token, .. } => token,
+        _ => panic!("Unexpected response {:?}", response),
+    };
+    assert!(connect_with(Some(&token), Some(origin)).await.is_err());
+
+    // Tokens for an app only connect to interfaces for that app, and the
+    // others only to interfaces open to every app.
+    let response: AdminResponse = admin_tx
+        .request(AdminRequest::AttachAppInterface {
+            port: None,
+            driver: None,
+            allowed_origins: AllowedOrigins::Any,
+            installed_app_id: Some("some app".to_string()),
+        })
+        .await?;
+    let restricted_port = match response {
+        AdminResponse::AppInterfaceAttached { port, .. } => port,
+        _ => panic!("Unexpected response {:?}", response),
+    };
+    let token = issue_app_authentication_token(&mut admin_tx).await;
+    let connect_to_restricted = |token: &str| {
+        connect(
+            url2!("ws://127.0.0.1:{}?token={}", restricted_port, token),
+            Arc::new(WebsocketConfig::default()),
+        )
+    };
+    assert!(connect_to_restricted(&token).await.is_err());
+    let response: AdminResponse = admin_tx
+        .request(AdminRequest::IssueAppAuthenticationToken {
+            expiry_seconds: None,
+            installed_app_id: Some("some app".to_string()),
+        })
+        .await?;
+    let app_token = match response {
+        AdminResponse::AppAuthenticationTokenIssued { token, .. } => token,
+        _ => panic!("Unexpected response {:?}", response),
+    };
+    assert!(connect_with(Some(&app_token), Some(origin)).await.is_err());
+    connect_to_restricted(&app_token).await?;
+    // Refused tokens can still be used where they belong.
+    connect_with(Some(&token), Some(origin)).await?;
+
+    conductor_handle.shutdown().await??;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn interfaces_can_be_detached_and_removed() -> Result<()> {
+    use holochain_conductor_api::AdminInterfaceConfig;
+    use holochain_conductor_api::InterfaceDriver;
+
+    holochain_trace::test_run().ok();
+    let tmp_dir = TempDir::new().unwrap();
+    let config = create_config(0, tmp_dir.path().to_path_buf());
+    let conductor_handle = Conductor::builder().config(config).build().await?;
+    let first_admin_port = admin_port(&conductor_handle).await;
+    let (mut admin_tx, mut admin_rx) = websocket_client(&conductor_handle).await?;
+
+    // Detaching an app interface closes its connections and frees the port.
+    let app_port = attach_app_interface(&mut admin_tx, None).await;
+    let (_app_tx, mut app_rx) = app_websocket_client(&mut admin_tx, app_port).await?;
+    let response: AdminResponse = admin_tx
+        .request(AdminRequest::DetachAppInterface {
+            driver: InterfaceDriver::websocket(app_port),
+        })
+        .await?;
+    assert_matches!(response, AdminResponse::AppInterfaceDetached);
+    let closed = tokio::time::timeout(Duration::from_secs(5), app_rx.next()).await?;
+    assert!(closed.is_none());
+    assert!(app_websocket_client(&mut admin_tx, app_port).await.is_err());
+    let response: AdminResponse = admin_tx.request(AdminRequest::ListAppInterfaces).await?;
+    assert_matches!(response, AdminResponse::AppInterfacesListed(ports) if ports.is_empty());
+    std::net::TcpListener::bind(("127.0.0.1", app_port))?;
+
+    let response: AdminResponse = admin_tx
+        .request(AdminRequest::DetachAppInterface {
+            driver: InterfaceDriver::websocket(app_port),
+        })
+        .await?;
+    assert_matches!(response, AdminResponse::Error(_));
+
+    // The last admin interface can't be removed.
+    let response: AdminResponse = admin_tx
+        .request(AdminRequest::RemoveAdminInterface {
+            driver: InterfaceDriver::websocket(first_admin_port),
+        })
+        .await?;
+    assert_matches!(response, AdminResponse::Error(_));
+
+    // But one can be removed over another.
+    let second_admin_port = std::net::TcpListener::bind("127.0.0.1:0")?
+        .local_addr()?
+        .port();
+    let response: AdminResponse = admin_tx
+        .request(AdminRequest::AddAdminInterfaces(vec![
+            AdminInterfaceConfig {
+                driver: InterfaceDriver::Websocket {
+                    port: second_admin_port,
+                    bind_address: None,
+                    tls: None,
+                },
+            },
+        ]))
+        .await?;
+    assert_matches!(response, AdminResponse::AdminInterfacesAdded);
+    let (mut second_admin_tx, _) = websocket_client_by_port(second_admin_port).await?;
+    let response: AdminResponse = second_admin_tx
+        .request(AdminRequest::RemoveAdminInterface {
+            driver: InterfaceDriver::websocket(first_admin_port),
+        })
+        .await?;
+    assert_matches!(response, AdminResponse::AdminInterfaceRemoved);
+    let closed = tokio::time::timeout(Duration::from_secs(5), admin_rx.next()).await?;
+    assert!(closed.is_none());
+    assert!(websocket_client_by_port(first_admin_port).await.is_err());
+    std::net::TcpListener::bind(("127.0.0.1", first_admin_port))?;
+
+    conductor_handle.shutdown().await??;
+    Ok(())
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn interfaces_can_use_any_driver() -> Result<()> {
+    use holochain_conductor_api::AdminInterfaceConfig;
+    use holochain_conductor_api::InterfaceDriver;
+    use holochain_conductor_api::InterfaceTlsConfig;
+    use std::net::IpAddr;
+    use std::net::Ipv6Addr;
+    use std::net::SocketAddr;
+    use std::os::unix::fs::PermissionsExt;
+
+    holochain_trace::test_run().ok();
+    let tmp_dir = TempDir::new().unwrap();
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let cert_pem = cert.serialize_pem().unwrap();
+    let tls = InterfaceTlsConfig {
+        cert_path: tmp_dir.path().join("cert.pem"),
+        key_path: tmp_dir.path().join("key.pem"),
+    };
+    std::fs::write(&tls.cert_path, &cert_pem)?;
+    std::fs::write(&tls.key_path, cert.serialize_private_key_pem())?;
+
+    // The admin interfaces need known ports to be connected to.
+    let free_port = |addr: IpAddr| {
+        std::net::TcpListener::bind(SocketAddr::new(addr, 0))
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port()
+    };
+    let drivers = |name: &str, port: &dyn Fn(IpAddr) -> u16| {
+        vec![
+            InterfaceDriver::Websocket {
+                port: port(Ipv6Addr::LOCALHOST.into()),
+                bind_address: Some(Ipv6Addr::LOCALHOST.into()),
+                tls: None,
+            },
+            InterfaceDriver::Websocket {
+                port: port(std::net::Ipv4Addr::LOCALHOST.into()),
+                bind_address: None,
+                tls: Some(tls.clone()),
+            },
+            InterfaceDriver::UnixSocket {
+                path: tmp_dir.path().join(format!("{}.sock", name)),
+                mode: Some(0o600),
+            },
+        ]
+    };
+    let connect_to = |driver: InterfaceDriver, query: String| {
+        let cert_pem = cert_pem.clone();
+        async move {
+            let config = Arc::new(WebsocketConfig::default());
+            match driver {
+                InterfaceDriver::Websocket {
+                    port, tls: Some(_), ..
+                } => {
+                    let connector = native_tls::TlsConnector::builder()
+                        .add_root_certificate(
+                            native_tls::Certificate::from_pem(cert_pem.as_bytes()).unwrap(),
+                        )
+                        .build()
+                        .unwrap();
+                    connect_tls(
+                        url2!("wss://localhost:{}{}", port, query),
+                        config,
+                        connector,
+                    )
+                    .await
+                }
+                InterfaceDriver::Websocket {
+                    port,
+                    bind_address: Some(addr),
+                    ..
+                } => {
+                    let addr = SocketAddr::new(addr, port);
+                    connect(url2!("ws://{}{}", addr, query), config).await
+                }
+                InterfaceDriver::UnixSocket { path, .. } => {
+                    connect_unix_with_url(&path, url2!("ws://localhost{}", query), config).await
+                }
+                driver => panic!("Unexpected driver {:?}", driver),
+            }
+            .unwrap()
+            .0
+        }
+    };
+
+    let admin_drivers = drivers("admin", &free_port);
+    let mut config = create_config(0, tmp_dir.path().to_path_buf());
+    config.admin_interfaces = Some(
+        admin_drivers
+            .iter()
+            .cloned()
+            .map(|driver| AdminInterfaceConfig { driver })
+            .collect(),
+    );
+    let conductor_handle = Conductor::builder().config(config).build().await?;
+    // None of the admin interfaces can be reached at `ws://127.0.0.1`.
+    assert_eq!(conductor_handle.get_arbitrary_admin_websocket_port(), None);
+    assert_eq!(
+        std::fs::metadata(tmp_dir.path().join("admin.sock"))?
+            .permissions()
+            .mode()
+            & 0o777,
+        0o600
+    );
+
+    // Attach an app interface with each driver through the admin interface
+    // with the same driver.
+    let mut attached = Vec::new();
+    for (admin_driver, driver) in admin_drivers.iter().cloned().zip(drivers("app", &|_| 0)) {
+        let mut admin = connect_to(admin_driver, String::new()).await;
+        let response: AdminResponse = admin
+            .request(AdminRequest::AttachAppInterface {
+                port: None,
+                driver: Some(driver),
+                allowed_origins: AllowedOrigins::Any,
+                installed_app_id: None,
+            })
+            .await?;
+        let driver = match response {
+            AdminResponse::AppInterfaceAttached { port, driver } => {
+                assert_eq!(port, driver.port().unwrap_or(0));
+                driver
+            }
+            _ => panic!("Unexpected response {:?}", response),
+        };
+        attached.push(driver);
+    }
+
+    let mut admin = connect_to(
+        InterfaceDriver::UnixSocket {
+            path: tmp_dir.path().join("admin.sock"),
+            mode: None,
+        },
+        String::new(),
+    )
+    .await;
+    let response: AdminResponse = admin.request(AdminRequest::ListAppInterfaces).await?;
+    let mut listed = match response {
+        AdminResponse::AppInterfacesListed(drivers) => drivers,
+        _ => panic!("Unexpected response {:?}", response),
+    };
+    listed.sort_by_key(|driver| format!("{:?}", driver));
+    let mut expected = attached.clone();
+    expected.sort_by_key(|driver| format!("{:?}", driver));
+    assert_eq!(listed, expected);
+
+    for driver in attached.clone() {
+        let token = issue_app_authentication_token(&mut admin).await;
+        let mut app = connect_to(driver, format!("?token={}", token)).await;
+        let response: AppResponse = app
+            .request(AppRequest::AppInfo {
+                installed_app_id: "none".to_string(),
+            })
+            .await?;
+        assert_matches!(response, AppResponse::AppInfo(None));
+    }
+
+    // Interfaces with any driver can be detached or removed, and Unix
+    // domain sockets are deleted when they close.
+    let socket_deleted = |name: &str| {
+        let path = tmp_dir.path().join(name);
+        async move {
+            for _ in 0..50 {
+                if !path.exists() {
+                    return true;
+                }
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            false
+        }
+    };
+    for driver in attached {
+        let response: AdminResponse = admin
+            .request(AdminRequest::DetachAppInterface { driver })
+            .await?;
+        assert_matches!(response, AdminResponse::AppInterfaceDetached);
+    }
+    assert!(socket_deleted("app.sock").await);
+    // The socket's own connection may close before the response arrives.
+    let mut admin = connect_to(admin_drivers[0].clone(), String::new()).await;
+    let response: AdminResponse = admin
+        .request(AdminRequest::RemoveAdminInterface {
+            driver: InterfaceDriver::UnixSocket {
+                path: tmp_dir.path().join("admin.sock"),
+                mode: None,
+            },
+        })
+        .await?;
+    assert_matches!(response, AdminResponse::AdminInterfaceRemoved);
+    assert!(socket_deleted("admin.sock").await);
+
+    conductor_handle.shutdown().await??;
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn conductor_admin_interface_ends_with_shutdown() -> Result<()> {
     if let Err(e) = conductor_admin_interface_ends_with_shutdown_inner().await {
@@ -21,6 +21,7 @@ fn merge_hashes(
 ) -> AgentActivityResponse<ActionHash> {
     let mut valid = HashSet::new();
     let mut rejected = HashSet::new();
+    let mut merged_warrants = HashSet::new();
     let mut merged_highest_observed = None;
     for result in results {
         let AgentActivityResponse {
@@ -28,11 +29,13 @@ fn merge_hashes(
             highest_observed,
             valid_activity,
             rejected_activity,
+            warrants,
             ..
         } = result;
         if the_agent != agent {
             continue;
         }
+        merged_warrants.extend(warrants);
 
         match (merged_highest_observed.take(), highest_observed) {
             (None, None) => {}
@@ -84,6 +87,7 @@ fn merge_hashes(
         valid_activity,
         rejected_activity,
         highest_observed: merged_highest_observed,
+        warrants: merged_warrants.into_iter().collect(),
     }
 }
 
@@ -146,17 +150,20 @@ fn merge_status_only(
     results: Vec<AgentActivityResponse<ActionHash>>,
 ) -> AgentActivityResponse<ActionHash> {
     let mut merged_status = None;
+    let mut merged_warrants = HashSet::new();
     let mut merged_highest_observed = None;
     for result in results {
         let AgentActivityResponse {
             status,
             agent: the_agent,
             highest_observed,
+            warrants,
             ..
         } = result;
         if the_agent != agent {
             continue;
         }
+        merged_warrants.extend(warrants);
         match (merged_highest_observed.take(), highest_observed) {
             (None, None) => {}
             (Some(h), None) | (None, Some(h)) => {
@@ -234,5 +241,6 @@ fn merge_status_only(
         valid_activity: ChainItems::NotRequested,
         rejected_activity: ChainItems::NotRequested,
         highest_observed: merged_highest_observed,
+        warrants: merged_warrants.into_iter().collect(),
     }
 }
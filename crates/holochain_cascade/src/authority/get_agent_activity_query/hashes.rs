@@ -31,6 +31,7 @@ pub struct State {
     valid: Vec<ActionHashed>,
     rejected: Vec<ActionHashed>,
     pending: Vec<ActionHashed>,
+    warrants: Vec<SignedWarrant>,
     status: Option<ChainStatus>,
 }
 
@@ -38,6 +39,8 @@ pub struct State {
 pub enum Item {
     Integrated(ActionHashed),
     Pending(ActionHashed),
+    Warrant(SignedWarrant),
+    PendingWarrant,
 }
 
 impl Query for GetAgentActivityQuery {
@@ -48,12 +51,18 @@ impl Query for GetAgentActivityQuery {
     fn query(&self) -> String {
         "
             SELECT Action.hash, DhtOp.validation_status, Action.blob AS action_blob,
-            DhtOp.when_integrated
+            DhtOp.when_integrated, NULL AS warrant_blob, Action.seq AS seq
             FROM Action
             JOIN DhtOp ON DhtOp.action_hash = Action.hash
             WHERE Action.author = :author
             AND DhtOp.type = :op_type
-            ORDER BY Action.seq ASC
+            UNION ALL
+            SELECT NULL, DhtOp.validation_status, NULL, DhtOp.when_integrated,
+            Warrant.blob, NULL
+            FROM Warrant
+            JOIN DhtOp ON DhtOp.hash = Warrant.hash
+            WHERE Warrant.warrantee = :author
+            ORDER BY seq ASC
         "
         .to_string()
     }
@@ -77,9 +86,18 @@ impl Query for GetAgentActivityQuery {
     fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
         Arc::new(move |row| {
             let validation_status: Option<ValidationStatus> = row.get("validation_status")?;
+            let integrated: Option<Timestamp> = row.get("when_integrated")?;
+            let warrant: Option<Vec<u8>> = row.get("warrant_blob")?;
+            if let Some(warrant) = warrant {
+                let item = if integrated.is_some() {
+                    Item::Warrant(from_blob::<SignedWarrant>(warrant)?)
+                } else {
+                    Item::PendingWarrant
+                };
+                return Ok(Judged::raw(item, validation_status));
+            }
             let hash: ActionHash = row.get("hash")?;
             from_blob::<SignedAction>(row.get("action_blob")?).and_then(|action| {
-                let integrated: Option<Timestamp> = row.get("when_integrated")?;
                 let action = ActionHashed::with_pre_hashed(action.0, hash);
                 let item = if integrated.is_some() {
                     Item::Integrated(action)
@@ -125,6 +143,7 @@ impl Query for GetAgentActivityQuery {
                 state.rejected.push(action);
             }
             (_, Item::Pending(data)) => state.pending.push(data),
+            (Some(ValidationStatus::Valid), Item::Warrant(warrant)) => state.warrants.push(warrant),
             _ => (),
         }
         Ok(state)
@@ -168,6 +187,7 @@ impl Query for GetAgentActivityQuery {
             rejected_activity,
             status,
             highest_observed,
+            warrants: state.warrants,
         })
     }
 }
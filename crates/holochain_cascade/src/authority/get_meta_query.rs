@@ -0,0 +1,196 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use holo_hash::ActionHash;
+use holo_hash::AnyDhtHash;
+use holo_hash::AnyDhtHashPrimitive;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Row;
+use holochain_state::query::prelude::*;
+use holochain_state::query::StateQueryError;
+use holochain_types::dht_op::DhtOpType;
+use holochain_types::metadata::EntryDhtStatus;
+use holochain_types::metadata::MetadataSet;
+use holochain_types::metadata::TimedActionHash;
+use holochain_zome_types::Action;
+use holochain_zome_types::HasValidationStatus;
+use holochain_zome_types::Judged;
+use holochain_zome_types::MetadataRequest;
+use holochain_zome_types::SignedAction;
+use holochain_zome_types::ValidationStatus;
+
+/// Collect the hashes of every integrated action which says something about
+/// a basis hash, without the actions or entries themselves.
+#[derive(Debug, Clone)]
+pub struct GetMetaQuery {
+    hash: AnyDhtHash,
+    request: MetadataRequest,
+}
+
+impl GetMetaQuery {
+    pub fn new(hash: AnyDhtHash, request: MetadataRequest) -> Self {
+        Self { hash, request }
+    }
+}
+
+pub struct Item {
+    op_type: DhtOpType,
+    action_hash: ActionHash,
+    action: SignedAction,
+}
+
+#[derive(Debug, Default)]
+pub struct State {
+    meta: MetadataSet,
+    valid_creates: HashSet<ActionHash>,
+    deleted: HashSet<ActionHash>,
+    any_rejected: bool,
+}
+
+impl Query for GetMetaQuery {
+    type Item = Judged<Item>;
+    type State = State;
+    type Output = MetadataSet;
+
+    fn query(&self) -> String {
+        "
+        SELECT Action.hash AS action_hash, Action.blob AS action_blob,
+        DhtOp.type AS dht_type, DhtOp.validation_status AS status
+        FROM DhtOp
+        JOIN Action On DhtOp.action_hash = Action.hash
+        WHERE DhtOp.type IN (
+            :store_record, :store_entry, :updated_content, :updated_record,
+            :deleted_by, :deleted_entry_action, :add_link, :remove_link
+        )
+        AND
+        DhtOp.basis_hash = :hash
+        AND
+        DhtOp.when_integrated IS NOT NULL
+        "
+        .into()
+    }
+
+    fn params(&self) -> Vec<Params> {
+        let params = named_params! {
+            ":store_record": DhtOpType::StoreRecord,
+            ":store_entry": DhtOpType::StoreEntry,
+            ":updated_content": DhtOpType::RegisterUpdatedContent,
+            ":updated_record": DhtOpType::RegisterUpdatedRecord,
+            ":deleted_by": DhtOpType::RegisterDeletedBy,
+            ":deleted_entry_action": DhtOpType::RegisterDeletedEntryAction,
+            ":add_link": DhtOpType::RegisterAddLink,
+            ":remove_link": DhtOpType::RegisterRemoveLink,
+            ":hash": self.hash,
+        };
+        params.to_vec()
+    }
+
+    fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
+        let f = |row: &Row| {
+            let action =
+                from_blob::<SignedAction>(row.get(row.as_ref().column_index("action_blob")?)?)?;
+            let action_hash = row.get(row.as_ref().column_index("action_hash")?)?;
+            let op_type = row.get(row.as_ref().column_index("dht_type")?)?;
+            let validation_status = row.get(row.as_ref().column_index("status")?)?;
+            Ok(Judged::raw(
+                Item {
+                    op_type,
+                    action_hash,
+                    action,
+                },
+                validation_status,
+            ))
+        };
+        Arc::new(f)
+    }
+
+    fn init_fold(&self) -> StateQueryResult<Self::State> {
+        Ok(Default::default())
+    }
+
+    fn fold(&self, mut state: Self::State, dht_op: Self::Item) -> StateQueryResult<Self::State> {
+        let status = dht_op.validation_status();
+        let Item {
+            op_type,
+            action_hash,
+            action,
+        } = dht_op.data;
+        let timed = TimedActionHash {
+            timestamp: action.0.timestamp(),
+            action_hash: action_hash.clone(),
+        };
+        let request = &self.request;
+        match (status, op_type) {
+            (Some(ValidationStatus::Valid), DhtOpType::StoreRecord | DhtOpType::StoreEntry) => {
+                state.valid_creates.insert(action_hash);
+                if request.all_valid_actions {
+                    state.meta.actions.insert(timed);
+                }
+            }
+            (Some(ValidationStatus::Rejected), _) => {
+                state.any_rejected = true;
+                if request.all_invalid_actions {
+                    state.meta.invalid_actions.insert(timed);
+                }
+            }
+            (
+                Some(ValidationStatus::Valid),
+                DhtOpType::RegisterUpdatedContent | DhtOpType::RegisterUpdatedRecord,
+            ) => {
+                if request.all_updates {
+                    state.meta.updates.insert(timed);
+                }
+            }
+            (
+                Some(ValidationStatus::Valid),
+                DhtOpType::RegisterDeletedBy | DhtOpType::RegisterDeletedEntryAction,
+            ) => {
+                if let Action::Delete(delete) = &action.0 {
+                    state.deleted.insert(delete.deletes_address.clone());
+                }
+                if request.all_deletes {
+                    state.meta.deletes.insert(timed);
+                }
+            }
+            (
+                Some(ValidationStatus::Valid),
+                DhtOpType::RegisterAddLink | DhtOpType::RegisterRemoveLink,
+            ) => {
+                if request.all_links {
+                    state.meta.links.insert(timed);
+                }
+            }
+            // Ops which were abandoned, or haven't been validated, don't say
+            // anything about the hash yet.
+            (Some(ValidationStatus::Abandoned) | None, _) => (),
+            (_, op_type) => return Err(StateQueryError::UnexpectedOp(op_type)),
+        }
+        Ok(state)
+    }
+
+    fn render<S>(&self, mut state: Self::State, _stores: S) -> StateQueryResult<Self::Output>
+    where
+        S: Store,
+    {
+        let is_entry = matches!(
+            self.hash.clone().into_primitive(),
+            AnyDhtHashPrimitive::Entry(_)
+        );
+        if self.request.entry_dht_status && is_entry {
+            state.meta.entry_dht_status = if state
+                .valid_creates
+                .iter()
+                .any(|hash| !state.deleted.contains(hash))
+            {
+                Some(EntryDhtStatus::Live)
+            } else if !state.valid_creates.is_empty() {
+                Some(EntryDhtStatus::Dead)
+            } else if state.any_rejected {
+                Some(EntryDhtStatus::Rejected)
+            } else {
+                None
+            };
+        }
+        Ok(state.meta)
+    }
+}
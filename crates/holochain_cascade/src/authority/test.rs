@@ -128,6 +128,52 @@ async fn get_record() {
     assert_eq!(result, expected);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn get_meta() {
+    holochain_trace::test_run().ok();
+    let db = test_dht_db();
+
+    let td = EntryTestData::create();
+    let options = holochain_p2p::event::GetMetaOptions {
+        metadata_request: MetadataRequest {
+            entry_dht_status: true,
+            ..Default::default()
+        },
+    };
+    let hashes = |set: &std::collections::BTreeSet<TimedActionHash>| -> Vec<ActionHash> {
+        set.iter().map(|t| t.action_hash.clone()).collect()
+    };
+
+    fill_db(&db.to_db(), td.store_entry_op.clone()).await;
+
+    let result = handle_get_meta(db.to_db().into(), td.hash.clone().into(), options.clone())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&result.actions), vec![td.create_hash.clone()]);
+    assert!(result.deletes.is_empty());
+    assert!(result.updates.is_empty());
+    assert_eq!(result.entry_dht_status, Some(EntryDhtStatus::Live));
+
+    fill_db(&db.to_db(), td.update_content_op.clone()).await;
+    fill_db(&db.to_db(), td.create_link_op.clone()).await;
+
+    let updated = handle_get_meta(db.to_db().into(), td.hash.clone().into(), options.clone())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&updated.updates), vec![td.update_hash.clone()]);
+    assert_eq!(updated.links.len(), 1);
+    assert!(result.is_subset(&updated));
+    assert!(!updated.is_subset(&result));
+
+    fill_db(&db.to_db(), td.delete_entry_action_op.clone()).await;
+
+    let deleted = handle_get_meta(db.to_db().into(), td.hash.clone().into(), options.clone())
+        .await
+        .unwrap();
+    assert_eq!(hashes(&deleted.deletes), vec![td.delete_hash.clone()]);
+    assert_eq!(deleted.entry_dht_status, Some(EntryDhtStatus::Dead));
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn retrieve_record() {
     holochain_trace::test_run().ok();
@@ -223,6 +269,7 @@ async fn get_agent_activity() {
         rejected_activity: ChainItems::NotRequested,
         status: ChainStatus::Valid(td.chain_head.clone()),
         highest_observed: Some(td.highest_observed.clone()),
+        warrants: Vec::new(),
     };
     assert_eq!(result, expected);
 
@@ -243,3 +290,89 @@ async fn get_agent_activity() {
 
     assert_eq!(result, expected);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn get_agent_activity_warrants() {
+    use ::fixt::prelude::*;
+    use holochain_types::dht_op::DhtOp;
+    use holochain_zome_types::warrant::*;
+    use holochain_zome_types::*;
+
+    holochain_trace::test_run().ok();
+    let db = test_dht_db();
+
+    let td = ActivityTestData::valid_chain_scenario();
+
+    for hash_op in td.hash_ops.iter().cloned() {
+        fill_db(&db.to_db(), hash_op).await;
+    }
+
+    let warrant = |op: &DhtOpHashed, timestamp| {
+        let proof = WarrantProof::InvalidChainOp {
+            action: SignedAction(op.action(), op.signature().clone()),
+            op_hash: op.as_hash().clone(),
+        };
+        let warrant = Warrant::new(proof, fixt!(AgentPubKey), timestamp);
+        SignedWarrant(warrant, fixt!(Signature))
+    };
+    let valid = warrant(&td.hash_ops[1], Timestamp::now());
+    let rejected = warrant(&td.hash_ops[2], Timestamp::now());
+    // A warrant against an action which this authority doesn't hold.
+    let mut create = fixt!(Create);
+    create.author = td.agent.clone();
+    let unheld_action = Action::Create(create);
+    let unheld = warrant(
+        &DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+            fixt!(Signature),
+            unheld_action.clone(),
+        )),
+        Timestamp::now(),
+    );
+    for warrant in [valid.clone(), unheld.clone()] {
+        fill_db(
+            &db.to_db(),
+            DhtOpHashed::from_content_sync(DhtOp::from_warrant(warrant)),
+        )
+        .await;
+    }
+    fill_db_rejected(
+        &db.to_db(),
+        DhtOpHashed::from_content_sync(DhtOp::from_warrant(rejected)),
+    )
+    .await;
+
+    let options = actor::GetActivityOptions {
+        include_valid_activity: true,
+        include_rejected_activity: false,
+        include_full_actions: false,
+        ..Default::default()
+    };
+
+    let result = handle_get_agent_activity(
+        db.to_db().into(),
+        td.agent.clone(),
+        QueryFilter::new(),
+        (&options).into(),
+    )
+    .await
+    .unwrap();
+
+    // Only the valid warrants are returned, and they aren't counted as activity.
+    let warrants = result.warrants;
+    assert_eq!(warrants.len(), 2);
+    assert!(warrants.contains(&valid));
+    assert!(warrants.contains(&unheld));
+    assert_eq!(result.valid_activity, td.valid_hashes);
+
+    // The warranted action isn't stored along with its warrant.
+    let unheld_action_hash = ActionHash::with_data_sync(&unheld_action);
+    let stored: bool = db.to_db().test_read(move |txn| {
+        txn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM Action WHERE hash = ?)",
+            [unheld_action_hash],
+            |row| row.get(0),
+        )
+        .unwrap()
+    });
+    assert!(!stored);
+}
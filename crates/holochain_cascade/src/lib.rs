@@ -103,6 +103,7 @@ pub struct CascadeImpl<Network: Send + Sync = HolochainP2pDna> {
     scratch: Option<SyncScratch>,
     network: Option<Network>,
     private_data: Option<Arc<AgentPubKey>>,
+    check_cache_metadata: bool,
 }
 
 impl<Network> CascadeImpl<Network>
@@ -162,6 +163,20 @@ where
             private_data: self.private_data,
             cache: Some(cache_db),
             network: Some(network),
+            check_cache_metadata: self.check_cache_metadata,
+        }
+    }
+
+    /// Before re-fetching a record which is already cached, ask the
+    /// authorities for its metadata, and skip the fetch if nothing is new.
+    ///
+    /// This trades an extra round trip for a smaller response, so it only
+    /// pays off for records with large entries or many actions. The `get`
+    /// and `get_details` host functions turn it on.
+    pub fn with_cache_metadata_check(self) -> Self {
+        Self {
+            check_cache_metadata: true,
+            ..self
         }
     }
 }
@@ -176,6 +191,7 @@ impl CascadeImpl<HolochainP2pDna> {
             cache: None,
             scratch: None,
             private_data: None,
+            check_cache_metadata: false,
         }
     }
 
@@ -203,6 +219,7 @@ impl CascadeImpl<HolochainP2pDna> {
             private_data,
             scratch,
             network: Some(network),
+            check_cache_metadata: false,
         }
     }
 
@@ -221,6 +238,7 @@ impl CascadeImpl<HolochainP2pDna> {
             scratch,
             network: None,
             private_data: author,
+            check_cache_metadata: false,
         }
     }
 }
@@ -512,6 +530,9 @@ where
         options: NetworkGetOptions,
     ) -> CascadeResult<()> {
         let network = some_or_return!(self.network.as_ref());
+        if self.check_cache_metadata && self.cache_is_up_to_date(network, &hash, &options).await? {
+            return Ok(());
+        }
         let results = network
             .get(hash, options.clone())
             .instrument(debug_span!("fetch_record::network_get"))
@@ -521,6 +542,57 @@ where
         Ok(())
     }
 
+    /// Ask the authorities for the metadata on a hash and compare it with
+    /// what is already in the cache. If no authority knows of anything the
+    /// cache doesn't, then the full ops don't need to be fetched again.
+    #[instrument(skip(self, network, options))]
+    async fn cache_is_up_to_date(
+        &self,
+        network: &Network,
+        hash: &AnyDhtHash,
+        options: &NetworkGetOptions,
+    ) -> CascadeResult<bool> {
+        let cache = some_or_return!(self.cache.as_ref(), false);
+        let request = MetadataRequest {
+            all_valid_actions: true,
+            all_invalid_actions: true,
+            all_deletes: true,
+            all_updates: true,
+            all_links: false,
+            follow_redirects: false,
+            entry_dht_status: false,
+        };
+        let query = authority::get_meta_query::GetMetaQuery::new(hash.clone(), request.clone());
+        let cached = cache
+            .read_async(move |txn| query.run(Txn::from(&txn)))
+            .await?;
+        if cached.actions.is_empty() {
+            return Ok(false);
+        }
+
+        let options = holochain_p2p::actor::GetMetaOptions {
+            remote_agent_count: options.remote_agent_count,
+            timeout_ms: options.timeout_ms,
+            as_race: options.as_race,
+            race_timeout_ms: options.race_timeout_ms,
+            metadata_request: request,
+        };
+        // Authorities which can't serve metadata don't stop the record from
+        // being fetched in full.
+        let responses = match network
+            .get_meta(hash.clone(), options)
+            .instrument(debug_span!("fetch_record::network_get_meta"))
+            .await
+        {
+            Ok(responses) => responses,
+            Err(e) => {
+                debug!(?e, "Could not get the metadata of a cached record");
+                return Ok(false);
+            }
+        };
+        Ok(!responses.is_empty() && responses.iter().all(|meta| meta.is_subset(&cached)))
+    }
+
     #[instrument(skip(self, options))]
     async fn fetch_links(
         &self,
@@ -1086,6 +1158,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         } = merged_response;
         let valid_activity = match valid_activity {
             ChainItems::Hashes(hashes) => {
@@ -1140,6 +1213,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         };
         Ok(r)
     }
@@ -8,6 +8,7 @@ use holochain_state::prelude::test_authored_db;
 use holochain_state::prelude::test_cache_db;
 use holochain_state::prelude::test_dht_db;
 use holochain_state::scratch::Scratch;
+use holochain_types::metadata::MetadataSet;
 use holochain_zome_types::ChainTopOrdering;
 use holochain_zome_types::Details;
 use holochain_zome_types::EntryDetails;
@@ -447,6 +448,48 @@ async fn check_can_handle_rejected_ops_in_cache() {
     assert_rejected(&td_entry, &td_record, &cascade, GetOptions::latest()).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn cache_metadata_check_skips_refetch() {
+    holochain_trace::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+
+    // Data
+    let td_record = RecordTestData::create();
+    fill_db(&cache.to_db(), td_record.any_store_record_op.clone()).await;
+    let hash = td_record.any_action_hash.clone();
+
+    // Network
+    // - The authorities have nothing which the cache doesn't.
+    let network = |gets| {
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+        mock.expect_get_meta()
+            .returning(|_, _| Ok(vec![MetadataSet::default()]));
+        mock.expect_get().times(gets).returning(|_, _| Ok(vec![]));
+        MockNetwork::new(mock)
+    };
+
+    // - By default, the record is fetched again.
+    let cascade = CascadeImpl::empty().with_network(network(1), cache.to_db());
+    let r = cascade
+        .dht_get(hash.clone().into(), GetOptions::latest())
+        .await
+        .unwrap();
+    assert!(r.is_some());
+
+    // - With the check, only the metadata is.
+    let cascade = CascadeImpl::empty()
+        .with_network(network(0), cache.to_db())
+        .with_cache_metadata_check();
+    let r = cascade
+        .dht_get(hash.into(), GetOptions::latest())
+        .await
+        .unwrap();
+    assert!(r.is_some());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 #[ignore = "todo"]
 async fn check_all_queries_still_work() {
@@ -0,0 +1,30 @@
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+/// Run a reference Chain Head Coordinator server.
+struct Args {
+    /// The interface and port to bind to.
+    #[clap(short, long, default_value = "127.0.0.1:0")]
+    interface: std::net::SocketAddr,
+
+    /// Persist records in an SQLite database at this path.
+    /// If not set, records are only kept in memory.
+    #[clap(long)]
+    database: Option<std::path::PathBuf>,
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    if std::env::var_os("RUST_LOG").is_some() {
+        holochain_trace::init_fmt(holochain_trace::Output::Log).ok();
+    }
+    let args = Args::parse();
+
+    match holochain_chc_server::run(args.interface, args.database).await {
+        Ok((driver, addr, _shutdown)) => {
+            println!("# HC CHC - ADDR: http://{}/v1/", addr);
+            driver.await;
+        }
+        Err(err) => eprintln!("{}", err),
+    }
+}
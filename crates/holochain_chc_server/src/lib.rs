@@ -0,0 +1,296 @@
+//! A reference Chain Head Coordinator (CHC) server.
+//!
+//! A CHC keeps the one true copy of an agent's source chain, so that several
+//! conductors running as the same agent can't fork it. This server speaks the
+//! HTTP protocol which the conductor's `ChcRemote` client expects:
+//!
+//! - `POST /v1/{dna_hash}/{agent}/add_records` with a JSON `AddRecordsRequest`.
+//!   The records must extend the stored chain. If they don't, the response is
+//!   `409` with the JSON `(seq, hash)` of the current head. Requests which
+//!   could never be accepted, e.g. because a signature is wrong, get `498`
+//!   with a JSON message.
+//! - `POST /v1/{dna_hash}/{agent}/get_record_data` with a JSON
+//!   `GetRecordsRequest`, signed by the agent. The response is the records
+//!   after the `since_hash`, or `498` if the `since_hash` isn't in the chain.
+//!
+//! Records are stored per DNA and agent in SQLite.
+
+#![warn(missing_docs)]
+// Fixes some warnings introduced by `warp`
+#![allow(opaque_hidden_inferred_bound)]
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use holo_hash::{ActionHash, AgentPubKey, AgentPubKeyB64, DnaHash, DnaHashB64};
+use holochain_keystore::AgentPubKeyExt;
+use holochain_types::chc::{
+    AddRecordPayload, AddRecordsRequest, ChcError, GetRecordsRequest, GET_RECORDS_EXPIRES_AFTER,
+};
+use holochain_zome_types::prelude::*;
+use parking_lot::Mutex;
+use warp::http::StatusCode;
+use warp::hyper::body::Bytes;
+use warp::Filter;
+use warp::Reply;
+
+pub mod store;
+
+pub use store::ChcStore;
+
+/// The largest request body the server will accept.
+const SIZE_LIMIT: u64 = 16 * 1024 * 1024;
+
+/// The non-standard status code used for requests which can't be fulfilled.
+const INVALID_REQUEST: u16 = 498;
+
+/// How far ahead of the server's clock a client's clock may be when it sets
+/// the expiry of a `get_record_data` request.
+const MAX_CLOCK_DRIFT: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// A future which runs the server until it is shut down.
+pub type ChcServerDriver = futures::future::BoxFuture<'static, ()>;
+
+/// Call to shut down the server.
+pub type ChcServerShutdown = Box<dyn FnOnce() + 'static + Send + Sync>;
+
+/// Errors from handling a CHC request.
+#[allow(missing_docs)]
+#[derive(Debug, thiserror::Error)]
+pub enum ChcServerError {
+    #[error(transparent)]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error(transparent)]
+    Serialization(#[from] SerializedBytesError),
+
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Keystore(#[from] holochain_keystore::KeystoreError),
+
+    #[error(transparent)]
+    Chc(#[from] ChcError),
+
+    #[error(transparent)]
+    Join(#[from] tokio::task::JoinError),
+
+    /// The records don't extend the chain, whose head is at this sequence
+    /// number and hash.
+    #[error("The chain has moved on to {1} (sequence #{0})")]
+    InvalidChain(u32, ActionHash),
+
+    /// The request can never be accepted.
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    /// The request wasn't signed by the agent whose chain it is for.
+    #[error("Request is not signed by the agent")]
+    Unauthorized,
+}
+
+/// Result type for [`ChcServerError`].
+pub type ChcServerResult<T> = Result<T, ChcServerError>;
+
+/// Run a CHC server, storing records in an SQLite database at the given
+/// path, or only in memory if there is no path.
+pub async fn run(
+    addr: impl Into<SocketAddr> + 'static,
+    database_path: Option<PathBuf>,
+) -> Result<(ChcServerDriver, SocketAddr, ChcServerShutdown), String> {
+    let store = ChcStore::open(database_path.as_deref())
+        .map_err(|e| format!("Failed to open store: {:?}", e))?;
+    let store = Arc::new(Mutex::new(store));
+
+    let routes = add_records(store.clone()).or(get_record_data(store));
+
+    let (s, r) = tokio::sync::oneshot::channel();
+    let shutdown = Box::new(move || {
+        let _ = s.send(());
+    });
+
+    match warp::serve(routes).try_bind_with_graceful_shutdown(addr, async move {
+        let _ = r.await;
+    }) {
+        Ok((addr, server)) => {
+            let driver = futures::future::FutureExt::boxed(server);
+            Ok((driver, addr, shutdown))
+        }
+        Err(e) => Err(format!("Failed to bind socket: {:?}", e)),
+    }
+}
+
+type SharedStore = Arc<Mutex<ChcStore>>;
+
+/// Run an operation on the store on the blocking thread pool.
+async fn blocking<R: Send + 'static>(
+    store: SharedStore,
+    f: impl FnOnce(&mut ChcStore) -> ChcServerResult<R> + Send + 'static,
+) -> ChcServerResult<R> {
+    tokio::task::spawn_blocking(move || f(&mut store.lock())).await?
+}
+
+fn with_store(
+    store: SharedStore,
+) -> impl Filter<Extract = (SharedStore,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || store.clone())
+}
+
+fn add_records(
+    store: SharedStore,
+) -> impl Filter<Extract = impl warp::Reply + Sized, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(warp::path!("v1" / String / String / "add_records"))
+        .and(warp::body::content_length_limit(SIZE_LIMIT))
+        .and(warp::body::bytes())
+        .and(with_store(store))
+        .then(|dna_hash, agent, body, store| async move {
+            reply(handle_add_records(dna_hash, agent, body, store).await)
+        })
+}
+
+fn get_record_data(
+    store: SharedStore,
+) -> impl Filter<Extract = impl warp::Reply + Sized, Error = warp::Rejection> + Clone {
+    warp::post()
+        .and(warp::path!("v1" / String / String / "get_record_data"))
+        .and(warp::body::content_length_limit(SIZE_LIMIT))
+        .and(warp::body::bytes())
+        .and(with_store(store))
+        .then(|dna_hash, agent, body, store| async move {
+            reply(handle_get_record_data(dna_hash, agent, body, store).await)
+        })
+}
+
+fn reply(result: ChcServerResult<warp::reply::Response>) -> warp::reply::Response {
+    let status = |code| StatusCode::from_u16(code).expect("valid status code");
+    match result {
+        Ok(response) => response,
+        Err(ChcServerError::InvalidChain(seq, hash)) => {
+            warp::reply::with_status(warp::reply::json(&(seq, hash)), StatusCode::CONFLICT)
+                .into_response()
+        }
+        Err(e @ ChcServerError::Unauthorized) => {
+            warp::reply::with_status(e.to_string(), StatusCode::UNAUTHORIZED).into_response()
+        }
+        Err(
+            e @ (ChcServerError::InvalidRequest(_)
+            | ChcServerError::Serialization(_)
+            | ChcServerError::Json(_)),
+        ) => warp::reply::with_status(warp::reply::json(&e.to_string()), status(INVALID_REQUEST))
+            .into_response(),
+        Err(e) => {
+            tracing::error!(?e, "Failed to handle CHC request");
+            warp::reply::with_status(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR)
+                .into_response()
+        }
+    }
+}
+
+fn parse_cell(dna_hash: &str, agent: &str) -> ChcServerResult<(DnaHash, AgentPubKey)> {
+    let dna_hash = DnaHashB64::from_b64_str(dna_hash)
+        .map_err(|e| ChcServerError::InvalidRequest(format!("Invalid DNA hash: {}", e)))?;
+    let agent = AgentPubKeyB64::from_b64_str(agent)
+        .map_err(|e| ChcServerError::InvalidRequest(format!("Invalid agent: {}", e)))?;
+    Ok((dna_hash.into(), agent.into()))
+}
+
+async fn handle_add_records(
+    dna_hash: String,
+    agent: String,
+    body: Bytes,
+    store: SharedStore,
+) -> ChcServerResult<warp::reply::Response> {
+    let (dna_hash, agent) = parse_cell(&dna_hash, &agent)?;
+    let records: AddRecordsRequest = serde_json::from_slice(&body)?;
+    for record in &records {
+        verify_record(&agent, record).await?;
+    }
+    blocking(store, move |store| {
+        store.add_records(&dna_hash, &agent, records)
+    })
+    .await?;
+    Ok(warp::reply::json(&()).into_response())
+}
+
+/// Check that the record was authored and signed by the agent,
+/// and that its hash is correct.
+async fn verify_record(agent: &AgentPubKey, record: &AddRecordPayload) -> ChcServerResult<()> {
+    let invalid = |reason: &str| {
+        Err(ChcServerError::InvalidRequest(format!(
+            "Record {}: {}",
+            record.action.as_hash(),
+            reason
+        )))
+    };
+    let action = record.action.action();
+    if ActionHash::with_data_sync(action) != *record.action.as_hash() {
+        return invalid("the hash does not match the action");
+    }
+    if action.author() != agent {
+        return invalid("the action was not authored by the agent");
+    }
+    if !agent
+        .verify_signature(record.action.signature(), action)
+        .await?
+    {
+        return invalid("the action signature is invalid");
+    }
+    if let Some((entry, signature)) = &record.encrypted_entry {
+        if !agent
+            .verify_signature_raw(signature, entry.0.clone().into())
+            .await?
+        {
+            return invalid("the entry signature is invalid");
+        }
+    }
+    Ok(())
+}
+
+async fn handle_get_record_data(
+    dna_hash: String,
+    agent: String,
+    body: Bytes,
+    store: SharedStore,
+) -> ChcServerResult<warp::reply::Response> {
+    let (dna_hash, agent) = parse_cell(&dna_hash, &agent)?;
+    let request: GetRecordsRequest = serde_json::from_slice(&body)?;
+    if !agent
+        .verify_signature_raw(&request.signature, request.payload.signable_bytes()?)
+        .await?
+    {
+        return Err(ChcServerError::Unauthorized);
+    }
+    let payload = request.payload;
+    let now = Timestamp::now();
+    let latest_expiry = (now + (GET_RECORDS_EXPIRES_AFTER + MAX_CLOCK_DRIFT))
+        .map_err(|e| ChcServerError::InvalidRequest(e.to_string()))?;
+    if payload.expires_at <= now || payload.expires_at > latest_expiry {
+        return Err(ChcServerError::InvalidRequest(
+            "The request has expired, or expires too far in the future".into(),
+        ));
+    }
+    let records = blocking(store, move |store| {
+        if !store.witness_nonce(&agent, &payload.nonce, payload.expires_at, now)? {
+            return Err(ChcServerError::InvalidRequest(
+                "The nonce has already been used".into(),
+            ));
+        }
+        store.get_records(&dna_hash, &agent, payload.since_hash.as_ref())
+    })
+    .await?;
+    match records {
+        Some(records) => {
+            let records: Vec<_> = records
+                .into_iter()
+                .map(|r| (r.action, r.encrypted_entry))
+                .collect();
+            Ok(warp::reply::json(&records).into_response())
+        }
+        None => Err(ChcServerError::InvalidRequest(
+            "The since_hash is not in the chain".into(),
+        )),
+    }
+}
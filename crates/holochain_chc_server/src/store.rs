@@ -0,0 +1,300 @@
+//! SQLite storage of each agent's chain.
+
+use std::path::Path;
+
+use holo_hash::{ActionHash, AgentPubKey, DnaHash};
+use holochain_types::chain::ChainItem;
+use holochain_types::chc::AddRecordPayload;
+use holochain_zome_types::prelude::{Nonce256Bits, Timestamp};
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::{ChcServerError, ChcServerResult};
+
+/// The chains of every agent the server has been sent records for.
+pub struct ChcStore(Connection);
+
+impl ChcStore {
+    /// Open the database at this path, or an in-memory database if
+    /// there is no path.
+    pub fn open(path: Option<&Path>) -> ChcServerResult<Self> {
+        let conn = match path {
+            Some(path) => Connection::open(path)?,
+            None => Connection::open_in_memory()?,
+        };
+        conn.execute_batch(
+            "
+            PRAGMA journal_mode = WAL;
+            CREATE TABLE IF NOT EXISTS ChcRecord (
+                dna_hash        BLOB    NOT NULL,
+                agent           BLOB    NOT NULL,
+                seq             INTEGER NOT NULL,
+                action_hash     BLOB    NOT NULL,
+                blob            BLOB    NOT NULL,
+                PRIMARY KEY (dna_hash, agent, seq)
+            );
+            CREATE TABLE IF NOT EXISTS ChcNonce (
+                agent           BLOB    NOT NULL,
+                nonce           BLOB    NOT NULL,
+                expires_at      INTEGER NOT NULL,
+                PRIMARY KEY (agent, nonce)
+            );
+            ",
+        )?;
+        Ok(Self(conn))
+    }
+
+    /// The hash and sequence number of the last record in the chain.
+    pub fn head(
+        &self,
+        dna_hash: &DnaHash,
+        agent: &AgentPubKey,
+    ) -> ChcServerResult<Option<(ActionHash, u32)>> {
+        head(&self.0, dna_hash, agent)
+    }
+
+    /// Append the records to the chain.
+    ///
+    /// The records must extend the current head of the chain, otherwise
+    /// nothing is added and [`ChcServerError::InvalidChain`] is returned with
+    /// the current head.
+    pub fn add_records(
+        &mut self,
+        dna_hash: &DnaHash,
+        agent: &AgentPubKey,
+        records: Vec<AddRecordPayload>,
+    ) -> ChcServerResult<()> {
+        let txn = self.0.transaction()?;
+        let head = head(&txn, dna_hash, agent)?;
+
+        let mut prev = head.clone();
+        for (i, record) in records.iter().enumerate() {
+            let action = &record.action;
+            let extends = match &prev {
+                Some((prev_hash, prev_seq)) => {
+                    action.prev_hash() == Some(prev_hash) && action.seq() == prev_seq + 1
+                }
+                None => action.prev_hash().is_none() && action.seq() == 0,
+            };
+            if !extends {
+                // Only the first record can conflict with what is already
+                // stored. Past that, the request itself is broken.
+                return Err(match head {
+                    Some((hash, seq)) if i == 0 => ChcServerError::InvalidChain(seq, hash),
+                    _ => ChcServerError::InvalidRequest(format!(
+                        "Record {} does not extend the chain",
+                        action.get_hash()
+                    )),
+                });
+            }
+            prev = Some((action.get_hash().clone(), action.seq()));
+        }
+
+        for record in records {
+            txn.execute(
+                "INSERT INTO ChcRecord (dna_hash, agent, seq, action_hash, blob)
+                VALUES (?, ?, ?, ?, ?)",
+                params![
+                    dna_hash.get_raw_39(),
+                    agent.get_raw_39(),
+                    record.action.seq(),
+                    record.action.get_hash().get_raw_39(),
+                    holochain_serialized_bytes::encode(&record)?,
+                ],
+            )?;
+        }
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Record that the agent has used this nonce in a request which is
+    /// valid until `expires_at`, returning false if it was already used.
+    ///
+    /// Nonces are forgotten once they expire, because requests using them
+    /// are rejected from then on anyway.
+    pub fn witness_nonce(
+        &mut self,
+        agent: &AgentPubKey,
+        nonce: &Nonce256Bits,
+        expires_at: Timestamp,
+        now: Timestamp,
+    ) -> ChcServerResult<bool> {
+        let txn = self.0.transaction()?;
+        txn.execute(
+            "DELETE FROM ChcNonce WHERE expires_at <= ?",
+            [now.as_micros()],
+        )?;
+        let added = txn.execute(
+            "INSERT OR IGNORE INTO ChcNonce (agent, nonce, expires_at) VALUES (?, ?, ?)",
+            params![agent.get_raw_39(), nonce.as_ref(), expires_at.as_micros()],
+        )?;
+        txn.commit()?;
+        Ok(added == 1)
+    }
+
+    /// All the records after the one with the given hash, or the whole chain
+    /// if no hash is given. Returns `None` if the hash is not in the chain.
+    pub fn get_records(
+        &self,
+        dna_hash: &DnaHash,
+        agent: &AgentPubKey,
+        since_hash: Option<&ActionHash>,
+    ) -> ChcServerResult<Option<Vec<AddRecordPayload>>> {
+        let since_seq = match since_hash {
+            Some(hash) => {
+                let seq = self
+                    .0
+                    .query_row(
+                        "SELECT seq FROM ChcRecord
+                        WHERE dna_hash = ? AND agent = ? AND action_hash = ?",
+                        params![dna_hash.get_raw_39(), agent.get_raw_39(), hash.get_raw_39()],
+                        |row| row.get::<_, i64>(0),
+                    )
+                    .optional()?;
+                match seq {
+                    Some(seq) => seq,
+                    None => return Ok(None),
+                }
+            }
+            None => -1,
+        };
+        let mut stmt = self.0.prepare(
+            "SELECT blob FROM ChcRecord
+            WHERE dna_hash = ? AND agent = ? AND seq > ?
+            ORDER BY seq ASC",
+        )?;
+        let blobs = stmt
+            .query_map(
+                params![dna_hash.get_raw_39(), agent.get_raw_39(), since_seq],
+                |row| row.get::<_, Vec<u8>>(0),
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        let records = blobs
+            .into_iter()
+            .map(|blob| holochain_serialized_bytes::decode(&blob))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(records))
+    }
+}
+
+fn head(
+    conn: &Connection,
+    dna_hash: &DnaHash,
+    agent: &AgentPubKey,
+) -> ChcServerResult<Option<(ActionHash, u32)>> {
+    Ok(conn
+        .query_row(
+            "SELECT action_hash, seq FROM ChcRecord
+            WHERE dna_hash = ? AND agent = ?
+            ORDER BY seq DESC LIMIT 1",
+            params![dna_hash.get_raw_39(), agent.get_raw_39()],
+            |row| Ok((row.get::<_, Vec<u8>>(0)?, row.get::<_, u32>(1)?)),
+        )
+        .optional()?
+        .map(|(hash, seq)| (ActionHash::from_raw_39_panicky(hash), seq)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_types::test_utils::chain::{TestChainHash, TestChainItem};
+    use holochain_zome_types::test_utils::{fake_agent_pubkey_1, fake_dna_hash};
+    use isotest::Iso;
+
+    fn records(seqs: &[u32]) -> Vec<AddRecordPayload> {
+        seqs.iter()
+            .map(|seq| AddRecordPayload {
+                action: TestChainItem::new(*seq).real(),
+                encrypted_entry: None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_linear_chain_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("chc.sqlite3");
+        let dna_hash = fake_dna_hash(1);
+        let agent = fake_agent_pubkey_1();
+        let hash = |seq| TestChainHash(seq).real();
+
+        let mut store = ChcStore::open(Some(&path)).unwrap();
+        assert_eq!(store.head(&dna_hash, &agent).unwrap(), None);
+
+        // - The chain must start at genesis
+        assert!(matches!(
+            store.add_records(&dna_hash, &agent, records(&[1, 2])),
+            Err(ChcServerError::InvalidRequest(_))
+        ));
+
+        store
+            .add_records(&dna_hash, &agent, records(&[0, 1, 2]))
+            .unwrap();
+        assert_eq!(store.head(&dna_hash, &agent).unwrap(), Some((hash(2), 2)));
+
+        // - Records which don't extend the head are rejected with the head
+        assert!(matches!(
+            store.add_records(&dna_hash, &agent, records(&[1, 2, 3])),
+            Err(ChcServerError::InvalidChain(2, h)) if h == hash(2)
+        ));
+        // - As are requests which aren't a chain themselves
+        assert!(matches!(
+            store.add_records(&dna_hash, &agent, records(&[3, 5])),
+            Err(ChcServerError::InvalidRequest(_))
+        ));
+        assert_eq!(store.head(&dna_hash, &agent).unwrap(), Some((hash(2), 2)));
+
+        store
+            .add_records(&dna_hash, &agent, records(&[3, 4]))
+            .unwrap();
+
+        // - The chain survives reopening the database
+        drop(store);
+        let store = ChcStore::open(Some(&path)).unwrap();
+        let since = |seq: Option<u32>| {
+            store
+                .get_records(&dna_hash, &agent, seq.map(hash).as_ref())
+                .unwrap()
+                .map(|records| {
+                    records
+                        .into_iter()
+                        .map(|r| r.action.seq())
+                        .collect::<Vec<_>>()
+                })
+        };
+        assert_eq!(since(None), Some(vec![0, 1, 2, 3, 4]));
+        assert_eq!(since(Some(2)), Some(vec![3, 4]));
+        assert_eq!(since(Some(4)), Some(vec![]));
+        assert_eq!(since(Some(9)), None);
+
+        // - Other agents' chains are separate
+        let other = holochain_zome_types::test_utils::fake_agent_pubkey_2();
+        assert_eq!(store.head(&dna_hash, &other).unwrap(), None);
+    }
+
+    #[test]
+    fn test_nonces_are_used_once_until_expiry() {
+        let mut store = ChcStore::open(None).unwrap();
+        let agent = fake_agent_pubkey_1();
+        let other = holochain_zome_types::test_utils::fake_agent_pubkey_2();
+        let nonce = Nonce256Bits::from([1; 32]);
+        let now = Timestamp::from_micros(1_000);
+        let expires_at = Timestamp::from_micros(2_000);
+
+        assert!(store
+            .witness_nonce(&agent, &nonce, expires_at, now)
+            .unwrap());
+        assert!(!store
+            .witness_nonce(&agent, &nonce, expires_at, now)
+            .unwrap());
+        // - Each agent's nonces are separate
+        assert!(store
+            .witness_nonce(&other, &nonce, expires_at, now)
+            .unwrap());
+        // - Once expired, the nonce is forgotten
+        let later = Timestamp::from_micros(3_000);
+        let expires_later = Timestamp::from_micros(4_000);
+        assert!(store
+            .witness_nonce(&agent, &nonce, expires_later, later)
+            .unwrap());
+    }
+}
@@ -1,9 +1,10 @@
 use holo_hash::*;
 use holochain_types::prelude::*;
+use holochain_zome_types::block::BlockTarget;
 use holochain_zome_types::cell::CellId;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 
-use crate::{AppInfo, FullStateDump, StorageInfo};
+use crate::{AllowedOrigins, AppInfo, FullStateDump, InterfaceDriver, StorageInfo};
 
 /// Represents the available conductor functions to call over an admin interface.
 ///
@@ -27,6 +28,23 @@ pub enum AdminRequest {
     /// [`AdminResponse::AdminInterfacesAdded`]
     AddAdminInterfaces(Vec<crate::config::AdminInterfaceConfig>),
 
+    /// Close the admin interface listening with a driver, along with every
+    /// connection to it, freeing its port or socket file. The last admin
+    /// interface can't be removed. If the request is made over the interface
+    /// being removed, its connection may close before the response arrives.
+    ///
+    /// Admin interfaces in the conductor config are opened again when the
+    /// conductor restarts.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AdminInterfaceRemoved`]
+    RemoveAdminInterface {
+        /// Where the interface is listening. Only the port and bind address,
+        /// or the socket path, have to match.
+        driver: crate::config::InterfaceDriver,
+    },
+
     /// Register a DNA for later app installation.
     ///
     /// Stores the given DNA into the Holochain DNA database and returns the hash of it.
@@ -72,6 +90,23 @@ pub enum AdminRequest {
     /// [`EnableApp`]: AdminRequest::EnableApp
     InstallApp(Box<InstallAppPayload>),
 
+    /// Put an [`AppBundle`] into the conductor's app store, without installing it.
+    ///
+    /// Every DNA in the bundle is stored too. The app can then be installed
+    /// by passing the returned hash as the source of an [`InstallApp`] call,
+    /// so that the same bundle can be installed reproducibly on many conductors.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppBundleStored`]
+    ///
+    /// [`InstallApp`]: AdminRequest::InstallApp
+    StoreAppBundle {
+        /// Where to get the bundle from. Must not itself be a hash.
+        #[serde(flatten)]
+        source: AppBundleSource,
+    },
+
     /// Uninstalls the app specified by argument `installed_app_id` from the conductor.
     ///
     /// The app will be removed from the list of installed apps, and any cells
@@ -161,18 +196,51 @@ pub enum AdminRequest {
     /// a free port is chosen by the conductor.
     /// The response will contain the port chosen by the conductor if `None` was passed.
     ///
+    /// The interface is a websocket on `127.0.0.1` unless a `driver` is passed,
+    /// in which case `port` is ignored and the driver's own port is used.
+    ///
+    /// Clients must present a token issued with
+    /// [`AdminRequest::IssueAppAuthenticationToken`] to connect. If
+    /// `allowed_origins` is passed, they must also send one of its origins
+    /// in the `Origin` header. If `installed_app_id` is passed, only
+    /// requests about that app are accepted on the interface.
+    ///
     /// [`AppRequest`]: super::AppRequest
     AttachAppInterface {
         /// Optional port number
         port: Option<u16>,
+        /// Optional means of exposing the interface, such as a websocket on
+        /// another address, over TLS, or over a Unix domain socket
+        #[serde(default)]
+        driver: Option<InterfaceDriver>,
+        /// The web origins which clients may connect from. Defaults to any.
+        #[serde(default)]
+        allowed_origins: AllowedOrigins,
+        /// Optional app to restrict the interface to
+        #[serde(default)]
+        installed_app_id: Option<InstalledAppId>,
+    },
+
+    /// Close the app interface listening with a driver, as listed by
+    /// [`AdminRequest::ListAppInterfaces`], along with every connection to
+    /// it, freeing its port or socket file. The interface is not opened
+    /// again when the conductor restarts.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppInterfaceDetached`]
+    DetachAppInterface {
+        /// Where the interface is listening. Only the port and bind address,
+        /// or the socket path, have to match.
+        driver: crate::config::InterfaceDriver,
     },
 
     /// List all the app interfaces currently attached with [`AttachAppInterface`].
     ///
     /// # Returns
     ///
-    /// [`AdminResponse::AppInterfacesListed`], a list of websocket ports that can
-    /// process [`AppRequest`]s.
+    /// [`AdminResponse::AppInterfacesListed`], the drivers of the interfaces
+    /// that can process [`AppRequest`]s.
     ///
     /// [`AttachAppInterface`]: AdminRequest::AttachAppInterface
     /// [`AppRequest`]: super::AppRequest
@@ -317,6 +385,34 @@ pub enum AdminRequest {
     /// [`AdminResponse::ZomeCallCapabilityGranted`]
     GrantZomeCallCapability(Box<GrantZomeCallCapabilityPayload>),
 
+    /// List the zome call capability grants on a cell's source chain.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::CapabilityGrantsInfo`]
+    ListCapabilityGrants {
+        /// The cell whose grants to list.
+        cell_id: CellId,
+        /// Whether to also list grants that have been updated or deleted.
+        include_revoked: bool,
+    },
+
+    /// Revoke a zome call capability grant by authoring a `Delete` of it
+    /// on the cell's source chain.
+    ///
+    /// The grant no longer authorizes any zome call once this request
+    /// has returned.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ZomeCallCapabilityRevoked`]
+    RevokeZomeCallCapability {
+        /// The cell on whose source chain the grant was committed.
+        cell_id: CellId,
+        /// The hash of the action that created the grant.
+        action_hash: ActionHash,
+    },
+
     /// Delete a clone cell that was previously disabled.
     ///
     /// # Returns
@@ -326,8 +422,136 @@ pub enum AdminRequest {
 
     /// Info about storage used by apps
     StorageInfo,
+
+    /// Migrate the agent of an app's cell to a new DNA, which must already be
+    /// registered with the conductor.
+    ///
+    /// The cell's source chain is closed with a `CloseChain` action, and a new
+    /// chain on the new DNA is opened with an `OpenChain` action, each
+    /// pointing at the other DNA. The `migrate_agent` callback is run in every
+    /// zome on both sides, and the migration only happens if all of them pass.
+    /// The role is then based on the new cell. Clone cells of the role are
+    /// not migrated.
+    ///
+    /// The app must be enabled.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AgentMigrated`]
+    MigrateAgent(Box<MigrateAgentPayload>),
+
+    /// Start coordinating the source chain of an existing cell with the
+    /// conductor's Chain Head Coordinator (CHC), as set by `chc_url` in the
+    /// conductor config.
+    ///
+    /// The CHC must not have any records which the cell's chain doesn't have.
+    /// Any records which the CHC is missing are added to it. Every app which
+    /// contains the cell coordinates it from then on. Commits to the cell
+    /// fail while it is being attached, since they would not reach the CHC.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ChcAttached`]
+    AttachChc {
+        /// The cell to coordinate with the CHC.
+        cell_id: CellId,
+    },
+
+    /// Stop coordinating the source chain of a cell with the conductor's
+    /// Chain Head Coordinator (CHC).
+    ///
+    /// The CHC must not have any records which the cell's chain doesn't have,
+    /// so that none are lost.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::ChcDetached`]
+    DetachChc {
+        /// The cell to stop coordinating with the CHC.
+        cell_id: CellId,
+    },
+
+    /// Block a cell, a node, a node in a DNA, an IP address or an IP range
+    /// for an interval of time.
+    ///
+    /// Blocks are kept in the conductor database, so they survive restarts.
+    /// Every incoming message is checked against them, so a block also applies
+    /// to connections which are already open. IP addresses and ranges can
+    /// only be checked when the network transport knows the address of the
+    /// peer, which tx5 doesn't, so they don't apply to tx5 connections.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::Blocked`]
+    Block {
+        /// What to block, and why.
+        target: BlockTarget,
+        /// When the block applies.
+        interval: InclusiveTimestampInterval,
+    },
+
+    /// Lift a block from a target for an interval of time.
+    ///
+    /// The target's reason must match the block's exactly. Any part of the
+    /// block which is outside of the interval remains.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::Unblocked`]
+    Unblock {
+        /// What to unblock, with the reason it was blocked for.
+        target: BlockTarget,
+        /// When to lift the block.
+        interval: InclusiveTimestampInterval,
+    },
+
+    /// List every block, including those which have ended.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::BlocksListed`]
+    ListBlocks,
+
+    /// Re-encrypt the conductor's databases with a newly generated key.
+    ///
+    /// The database key is stored encrypted with a secret held in the
+    /// keystore. This is only possible if the conductor was built with the
+    /// `sqlite-encrypted` feature.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::DatabaseKeyRotated`]
+    RotateDatabaseKey,
+
+    /// Issue a token which a client can use once to connect to an app
+    /// interface, by passing it as the `token` query parameter of the
+    /// websocket handshake request, e.g. `ws://localhost:8888?token=...`.
+    ///
+    /// A token issued for an app is only accepted by the app interfaces
+    /// attached with that `installed_app_id`, and a token issued without
+    /// one only by the app interfaces open to every app.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppAuthenticationTokenIssued`]
+    IssueAppAuthenticationToken {
+        /// How many seconds the token may be used for.
+        /// Defaults to [`DEFAULT_APP_AUTHENTICATION_TOKEN_EXPIRY_SECONDS`].
+        #[serde(default)]
+        expiry_seconds: Option<u64>,
+        /// The app whose interfaces the token is for, if any
+        #[serde(default)]
+        installed_app_id: Option<InstalledAppId>,
+    },
 }
 
+/// How long an app authentication token may be used for, unless another
+/// expiry is requested.
+pub const DEFAULT_APP_AUTHENTICATION_TOKEN_EXPIRY_SECONDS: u64 = 30;
+
+/// A single-use token for connecting to an app interface.
+pub type AppAuthenticationToken = String;
+
 /// Represents the possible responses to an [`AdminRequest`]
 /// and follows a general convention of `noun_verb` as opposed to
 /// the `verb_noun` of `AdminRequest`.
@@ -360,6 +584,11 @@ pub enum AdminResponse {
     /// of the newly installed DNAs.
     AppInstalled(AppInfo),
 
+    /// The successful response to an [`AdminRequest::StoreAppBundle`].
+    ///
+    /// Contains the hash under which the bundle was stored.
+    AppBundleStored(AppHash),
+
     /// The successful response to an [`AdminRequest::UninstallApp`].
     ///
     /// It means the app was uninstalled successfully.
@@ -370,6 +599,9 @@ pub enum AdminResponse {
     /// It means the `AdminInterface`s have successfully been added.
     AdminInterfacesAdded,
 
+    /// The successful response to an [`AdminRequest::RemoveAdminInterface`].
+    AdminInterfaceRemoved,
+
     /// The successful response to an [`AdminRequest::GenerateAgentPubKey`].
     ///
     /// Contains a new [`AgentPubKey`] generated by the keystore.
@@ -396,12 +628,19 @@ pub enum AdminResponse {
     /// If no port was specified in the request, contains the port number that was
     /// selected by the conductor for running this app interface.
     AppInterfaceAttached {
-        /// Networking port of the new `AppInterfaceApi`
+        /// Networking port of the new `AppInterfaceApi`, or 0 if it isn't
+        /// listening on a port
         port: u16,
+        /// The driver of the new `AppInterfaceApi`, with the port it is
+        /// listening on
+        driver: InterfaceDriver,
     },
 
-    /// The list of attached app interfaces.
-    AppInterfacesListed(Vec<u16>),
+    /// The successful response to an [`AdminRequest::DetachAppInterface`].
+    AppInterfaceDetached,
+
+    /// The drivers of the attached app interfaces.
+    AppInterfacesListed(Vec<InterfaceDriver>),
 
     /// The successful response to an [`AdminRequest::EnableApp`].
     ///
@@ -459,11 +698,76 @@ pub enum AdminResponse {
     /// The successful response to an [`AdminRequest::GrantZomeCallCapability`].
     ZomeCallCapabilityGranted,
 
+    /// The successful response to an [`AdminRequest::ListCapabilityGrants`].
+    CapabilityGrantsInfo(Vec<CapGrantInfo>),
+
+    /// The successful response to an [`AdminRequest::RevokeZomeCallCapability`].
+    ZomeCallCapabilityRevoked,
+
     /// The successful response to an [`AdminRequest::DeleteCloneCell`].
     CloneCellDeleted,
 
     /// The successful response to an [`AdminRequest::StorageInfo`].
     StorageInfo(StorageInfo),
+    /// The successful response to an [`AdminRequest::MigrateAgent`].
+    ///
+    /// Contains the id of the new cell the agent was migrated to.
+    AgentMigrated(CellId),
+
+    /// The successful response to an [`AdminRequest::AttachChc`].
+    ChcAttached,
+
+    /// The successful response to an [`AdminRequest::DetachChc`].
+    ChcDetached,
+
+    /// The successful response to an [`AdminRequest::Block`].
+    Blocked,
+
+    /// The successful response to an [`AdminRequest::Unblock`].
+    Unblocked,
+
+    /// The successful response to an [`AdminRequest::ListBlocks`].
+    ///
+    /// Overlapping blocks on a target with the same reason are merged.
+    BlocksListed(Vec<BlockInfo>),
+
+    /// The successful response to an [`AdminRequest::RotateDatabaseKey`].
+    DatabaseKeyRotated,
+
+    /// The successful response to an [`AdminRequest::IssueAppAuthenticationToken`].
+    AppAuthenticationTokenIssued {
+        /// The token to connect with
+        token: AppAuthenticationToken,
+        /// When the token can no longer be used
+        expires_at: Timestamp,
+    },
+}
+
+/// A block on a target, as listed by [`AdminRequest::ListBlocks`].
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct BlockInfo {
+    /// What is blocked, and why.
+    pub target: BlockTarget,
+    /// When the block applies.
+    pub interval: InclusiveTimestampInterval,
+}
+
+/// Events which the conductor sends to every connected admin interface
+/// client as they happen, rather than in response to a request.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+#[serde(rename_all = "snake_case", tag = "type", content = "data")]
+pub enum AdminSignal {
+    /// A commit was rejected because a cell's source chain had fallen behind
+    /// its Chain Head Coordinator (CHC). The missing records were fetched
+    /// from the CHC, validated and grafted onto the chain. If the commit
+    /// could not be rebased onto the repaired chain, the zome call failed
+    /// and can now be retried.
+    ChcRepaired {
+        /// The cell whose source chain was repaired.
+        cell_id: CellId,
+        /// The hashes of the records which were grafted onto the chain.
+        grafted: Vec<ActionHash>,
+    },
 }
 
 /// Error type that goes over the websocket wire.
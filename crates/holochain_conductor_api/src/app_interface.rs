@@ -1,3 +1,4 @@
+use crate::signal_subscription::SignalSubscription;
 use crate::ExternalApiWireError;
 use holo_hash::AgentPubKey;
 use holochain_keystore::LairResult;
@@ -77,6 +78,19 @@ pub enum AppRequest {
     ///
     /// [`AppResponse::ListWasmHostFunctions`]
     ListWasmHostFunctions,
+
+    /// Choose which signals this connection receives for an app.
+    ///
+    /// Each connection starts out receiving every signal. Once a subscription
+    /// has been made for any app, a signal is only sent if the filters of at
+    /// least one subscribed app let it through. Subscribing again for the same
+    /// app replaces its filters.
+    /// See [`SignalFilterSet`](crate::signal_subscription::SignalFilterSet).
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::SignalsSubscribed`]
+    SubscribeSignals(Box<SignalSubscription>),
 }
 
 /// Represents the possible responses to an [`AppRequest`].
@@ -122,6 +136,11 @@ pub enum AppResponse {
 
     /// All the wasm host functions supported by this conductor.
     ListWasmHostFunctions(Vec<String>),
+
+    /// The successful response to an [`AppRequest::SubscribeSignals`].
+    ///
+    /// Signals sent over this connection are now filtered by the new subscription.
+    SignalsSubscribed,
 }
 
 /// The data provided over an app interface in order to make a zome call
@@ -43,6 +43,7 @@ pub struct ConductorConfig {
 
     /// Optional DPKI configuration if conductor is using a DPKI app to initalize and manage
     /// keys for new instances.
+    /// Without it, every key is treated as valid.
     pub dpki: Option<DpkiConfig>,
 
     /// Setup admin interfaces to control this conductor through a websocket connection.
@@ -68,6 +69,28 @@ pub struct ConductorConfig {
     /// [sqlite documentation]: https://www.sqlite.org/pragma.html#pragma_synchronous
     #[serde(default)]
     pub db_sync_strategy: DbSyncStrategy,
+
+    /// Block agents which have been warranted by other validators.
+    ///
+    /// When set, an agent is blocked in a DNA as soon as this conductor
+    /// verifies a warrant issued against them, by re-validating the op the
+    /// warrant claims is invalid.
+    #[serde(default)]
+    pub auto_block_warranted_agents: bool,
+
+    /// How long an agent stays blocked after a warrant against them has
+    /// been verified, in seconds. Defaults to a day.
+    #[serde(default)]
+    pub warranted_agent_block_secs: Option<u64>,
+
+    /// Serve metrics at `http://<this address>/metrics` for Prometheus to
+    /// scrape, e.g. `127.0.0.1:9100`.
+    ///
+    /// The conductor refuses to start if metrics are also configured through
+    /// environment variables. Serving metrics requires a conductor built with
+    /// the `metrics_prometheus` feature.
+    #[serde(default)]
+    pub prometheus_metrics_bind: Option<std::net::SocketAddr>,
     //
     //
     // Which signals to emit
@@ -75,6 +98,10 @@ pub struct ConductorConfig {
     // pub signals: SignalConfig,
 }
 
+/// How long an agent stays blocked after a warrant against them has been
+/// verified, if not configured.
+pub const DEFAULT_WARRANTED_AGENT_BLOCK_SECS: u64 = 60 * 60 * 24;
+
 /// Helper function to load a config from a YAML string.
 fn config_from_yaml<T>(yaml: &str) -> ConductorConfigResult<T>
 where
@@ -95,6 +122,15 @@ impl ConductorConfig {
         config_from_yaml(&config_yaml)
     }
 
+    /// How long an agent stays blocked after a warrant against them has
+    /// been verified.
+    pub fn warranted_agent_block_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(
+            self.warranted_agent_block_secs
+                .unwrap_or(DEFAULT_WARRANTED_AGENT_BLOCK_SECS),
+        )
+    }
+
     /// Get tuning params for this config (default if not set)
     pub fn kitsune_tuning_params(&self) -> KitsuneP2pTuningParams {
         self.network
@@ -148,6 +184,9 @@ pub mod tests {
                 keystore: KeystoreConfig::DangerTestKeystore,
                 admin_interfaces: None,
                 db_sync_strategy: DbSyncStrategy::default(),
+                auto_block_warranted_agents: false,
+                warranted_agent_block_secs: None,
+                prometheus_metrics_bind: None,
                 #[cfg(feature = "chc")]
                 chc_url: None,
             }
@@ -169,7 +208,8 @@ pub mod tests {
 
     dpki:
       instance_id: some_id
-      init_params: some_params
+      dna_path: /path/to/dpki.dna
+      init_params: "{}"
 
     admin_interfaces:
       - driver:
@@ -219,14 +259,18 @@ pub mod tests {
                 environment_path: PathBuf::from("/path/to/env").into(),
                 dpki: Some(DpkiConfig {
                     instance_id: "some_id".into(),
-                    init_params: "some_params".into()
+                    dna_path: PathBuf::from("/path/to/dpki.dna"),
+                    network_seed: None,
                 }),
                 keystore: KeystoreConfig::LairServerInProc { lair_root: None },
                 admin_interfaces: Some(vec![AdminInterfaceConfig {
-                    driver: InterfaceDriver::Websocket { port: 1234 }
+                    driver: InterfaceDriver::websocket(1234)
                 }]),
                 network: Some(network_config),
                 db_sync_strategy: DbSyncStrategy::Fast,
+                auto_block_warranted_agents: false,
+                warranted_agent_block_secs: None,
+                prometheus_metrics_bind: None,
                 #[cfg(feature = "chc")]
                 chc_url: None,
             }
@@ -256,9 +300,55 @@ pub mod tests {
                 },
                 admin_interfaces: None,
                 db_sync_strategy: DbSyncStrategy::Fast,
+                auto_block_warranted_agents: false,
+                warranted_agent_block_secs: None,
+                prometheus_metrics_bind: None,
                 #[cfg(feature = "chc")]
                 chc_url: None,
             }
         );
     }
+
+    #[test]
+    fn test_config_interface_drivers() {
+        let yaml = r#"---
+    environment_path: /path/to/env
+    keystore:
+      type: danger_test_keystore
+    admin_interfaces:
+      - driver:
+          type: websocket
+          port: 1234
+          bind_address: "::"
+          tls:
+            cert_path: /path/to/cert.pem
+            key_path: /path/to/key.pem
+      - driver:
+          type: unix_socket
+          path: /path/to/admin.sock
+          mode: 0o660
+    "#;
+        let config: ConductorConfig = config_from_yaml(yaml).unwrap();
+        assert_eq!(
+            config.admin_interfaces.unwrap(),
+            vec![
+                AdminInterfaceConfig {
+                    driver: InterfaceDriver::Websocket {
+                        port: 1234,
+                        bind_address: Some(std::net::Ipv6Addr::UNSPECIFIED.into()),
+                        tls: Some(InterfaceTlsConfig {
+                            cert_path: PathBuf::from("/path/to/cert.pem"),
+                            key_path: PathBuf::from("/path/to/key.pem"),
+                        }),
+                    }
+                },
+                AdminInterfaceConfig {
+                    driver: InterfaceDriver::UnixSocket {
+                        path: PathBuf::from("/path/to/admin.sock"),
+                        mode: Some(0o660),
+                    }
+                },
+            ]
+        );
+    }
 }
@@ -1,13 +1,20 @@
-// Legacy config that will probably change
-#![allow(missing_docs)]
+use std::path::PathBuf;
 
 use serde::Deserialize;
 use serde::Serialize;
 
-/// Configure which app instance ID to treat as the DPKI application handler
-/// as well as what parameters to pass it on its initialization.
+/// Configure the DPKI service.
+///
+/// The conductor installs the DPKI DNA as an app on first startup, under a
+/// dedicated agent key, and uses it to register, rotate and revoke the keys
+/// it manages, and to check the validity of authors' keys during validation.
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct DpkiConfig {
+    /// The installed app ID to run the DPKI DNA under.
     pub instance_id: String,
-    pub init_params: String,
+    /// Path to the DPKI DNA bundle.
+    pub dna_path: PathBuf,
+    /// Network seed for the DPKI DNA, to join a particular DPKI network.
+    #[serde(default)]
+    pub network_seed: Option<String>,
 }
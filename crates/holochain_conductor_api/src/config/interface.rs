@@ -1,11 +1,15 @@
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::BTreeSet;
+use std::net::IpAddr;
+use std::path::PathBuf;
 
 /// Information neeeded to spawn an admin interface
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct AdminInterfaceConfig {
-    /// By what means the interface will be exposed.
-    /// Currently the only option is a local websocket running on a configurable port.
+    /// By what means the interface will be exposed: a websocket on a
+    /// configurable address and port, optionally over TLS, or a websocket
+    /// over a Unix domain socket.
     pub driver: InterfaceDriver,
     // How long will this interface be accessible between authentications?
     // TODO: implement once we have authentication
@@ -21,6 +25,7 @@ pub struct AdminInterfaceConfig {
 ///
 /// [`ConductorConfig`]: crate::conductor::ConductorConfig
 /// [`ConductorState`]: https://docs.rs/holochain/latest/holochain/conductor/state/struct.ConductorState.html
+#[serde_with::serde_as]
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum InterfaceDriver {
@@ -28,14 +33,110 @@ pub enum InterfaceDriver {
     Websocket {
         /// The port on which to establish the WebsocketListener
         port: u16,
+        /// The address to bind to, which may be IPv4 or IPv6.
+        /// Defaults to `127.0.0.1`, so that only local clients can connect.
+        /// Serialized as a string, since msgpack would otherwise encode an
+        /// enum but expect a string when decoding.
+        #[serde_as(as = "Option<serde_with::DisplayFromStr>")]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        bind_address: Option<IpAddr>,
+        /// Serve secure websockets (`wss://`) with this certificate.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tls: Option<InterfaceTlsConfig>,
     },
+    /// An interface implemented via websockets over a Unix domain socket
+    UnixSocket {
+        /// The path of the socket file. A socket left there by an earlier
+        /// listener is replaced, but any other file is an error.
+        path: PathBuf,
+        /// The permissions to give the socket file, e.g. `0o660`.
+        /// Defaults to the permissions allowed by the conductor's umask.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mode: Option<u32>,
+    },
+}
+
+/// The certificate for an interface serving secure websockets.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct InterfaceTlsConfig {
+    /// Path to the PEM encoded certificate chain, starting with the
+    /// certificate for this interface.
+    pub cert_path: PathBuf,
+    /// Path to the PEM encoded PKCS #8 private key for the certificate.
+    pub key_path: PathBuf,
 }
 
 impl InterfaceDriver {
-    /// Get the port for this driver.
-    pub fn port(&self) -> u16 {
+    /// A plain websocket on the loopback address.
+    pub fn websocket(port: u16) -> Self {
+        InterfaceDriver::Websocket {
+            port,
+            bind_address: None,
+            tls: None,
+        }
+    }
+
+    /// Get the port for this driver, if it listens on one.
+    pub fn port(&self) -> Option<u16> {
         match self {
-            InterfaceDriver::Websocket { port } => *port,
+            InterfaceDriver::Websocket { port, .. } => Some(*port),
+            InterfaceDriver::UnixSocket { .. } => None,
         }
     }
+
+    /// Whether both drivers listen at the same place: the same port and
+    /// bind address, or the same socket path.
+    pub fn listens_at(&self, other: &InterfaceDriver) -> bool {
+        let localhost = IpAddr::from(std::net::Ipv4Addr::LOCALHOST);
+        match (self, other) {
+            (
+                InterfaceDriver::Websocket {
+                    port, bind_address, ..
+                },
+                InterfaceDriver::Websocket {
+                    port: other_port,
+                    bind_address: other_bind_address,
+                    ..
+                },
+            ) => {
+                port == other_port
+                    && bind_address.unwrap_or(localhost) == other_bind_address.unwrap_or(localhost)
+            }
+            (
+                InterfaceDriver::UnixSocket { path, .. },
+                InterfaceDriver::UnixSocket {
+                    path: other_path, ..
+                },
+            ) => path == other_path,
+            _ => false,
+        }
+    }
+}
+
+/// The web origins which may connect to an app interface, checked against
+/// the `Origin` header of the websocket handshake request.
+#[derive(Clone, Deserialize, Serialize, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AllowedOrigins {
+    /// Clients may connect from any origin, or send no `Origin` header.
+    #[default]
+    Any,
+    /// Clients must send one of these origins, e.g. `http://localhost:8888`.
+    Origins(BTreeSet<String>),
+}
+
+impl AllowedOrigins {
+    /// Whether a client which sent this `Origin` header, if any, may connect.
+    pub fn is_allowed(&self, origin: Option<&str>) -> bool {
+        match self {
+            AllowedOrigins::Any => true,
+            AllowedOrigins::Origins(origins) => origin.map_or(false, |o| origins.contains(o)),
+        }
+    }
+}
+
+impl From<String> for AllowedOrigins {
+    fn from(origin: String) -> Self {
+        AllowedOrigins::Origins(BTreeSet::from([origin]))
+    }
 }
@@ -1,16 +1,30 @@
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::app::InstalledAppId;
+use holochain_types::signal::Signal;
 use holochain_zome_types::cell::CellId;
-use std::collections::HashMap;
+use holochain_zome_types::signal::AppSignal;
+use holochain_zome_types::zome::ZomeName;
+use once_cell::unsync::OnceCell;
+use std::collections::{HashMap, HashSet};
 
 /// Declares updated Signal subscription settings for an App.
 /// This message is part of the AppInterfaceApi
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
 pub struct SignalSubscription {
     /// The app for which to manage subscription
-    installed_app_id: InstalledAppId,
+    pub installed_app_id: InstalledAppId,
     /// Fine-grained per-cell filters
-    filters: SignalFilterSet,
+    pub filters: SignalFilterSet,
+}
+
+impl SignalSubscription {
+    /// Constructor
+    pub fn new(installed_app_id: InstalledAppId, filters: SignalFilterSet) -> Self {
+        Self {
+            installed_app_id,
+            filters,
+        }
+    }
 }
 
 /// Associate a SignalFilter with each Cell in an App.
@@ -46,11 +60,54 @@ impl SignalFilterSet {
     pub fn block_all() -> Self {
         SignalFilterSet::Include(HashMap::new())
     }
+
+    /// Whether this set lets the signal through.
+    ///
+    /// App signals are checked against the filter for the Cell which emitted
+    /// them. System signals don't come from any one Cell, so they are checked
+    /// against the filters of every Cell.
+    pub fn allows(&self, signal: &Signal) -> bool {
+        self.allows_checked(&CheckedSignal::new(signal))
+    }
+
+    /// Same as [`SignalFilterSet::allows`], for a signal which may be
+    /// checked against several sets.
+    pub fn allows_checked(&self, signal: &CheckedSignal) -> bool {
+        let matches = |filters: &HashMap<CellId, SignalFilter>| match signal.signal {
+            Signal::App { cell_id, .. } => filters
+                .get(cell_id)
+                .map(|filter| filter.matches_checked(signal))
+                .unwrap_or(false),
+            Signal::System(_) => filters
+                .values()
+                .any(|filter| filter.matches_checked(signal)),
+        };
+        match self {
+            Self::Include(filters) => matches(filters),
+            Self::Exclude(filters) => !matches(filters),
+        }
+    }
 }
 
 /// Specifies fine-grained filter controls for the signals
+///
+/// A filter "matches" a signal when the signal passes every one of its
+/// criteria. Whether a match lets the signal through or blocks it depends on
+/// whether the filter is in an Include or an Exclude [`SignalFilterSet`].
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-pub struct SignalFilter;
+pub struct SignalFilter {
+    /// Match app signals, emitted by zomes via `emit_signal`
+    pub app_signals: bool,
+    /// Match system signals, emitted by the conductor
+    pub system_signals: bool,
+    /// Only match app signals emitted by these zomes.
+    /// Empty means any zome.
+    pub zomes: HashSet<ZomeName>,
+    /// Only match app signals whose payload is a map with a `type` field
+    /// set to one of these tags, as produced by a `#[serde(tag = "type")]`
+    /// enum. Empty means any payload.
+    pub signal_types: HashSet<String>,
+}
 
 impl Default for SignalFilter {
     fn default() -> Self {
@@ -62,6 +119,157 @@ impl Default for SignalFilter {
 impl SignalFilter {
     /// A passthrough filter which filters nothing
     pub fn empty() -> Self {
-        SignalFilter
+        SignalFilter {
+            app_signals: true,
+            system_signals: true,
+            zomes: HashSet::new(),
+            signal_types: HashSet::new(),
+        }
+    }
+
+    /// Whether the signal meets all the criteria of this filter
+    pub fn matches(&self, signal: &Signal) -> bool {
+        self.matches_checked(&CheckedSignal::new(signal))
+    }
+
+    /// Same as [`SignalFilter::matches`], for a signal which may be checked
+    /// against several filters.
+    pub fn matches_checked(&self, signal: &CheckedSignal) -> bool {
+        match signal.signal {
+            Signal::App { zome_name, .. } => {
+                self.app_signals
+                    && (self.zomes.is_empty() || self.zomes.contains(zome_name))
+                    && (self.signal_types.is_empty()
+                        || signal
+                            .signal_type()
+                            .map(|t| self.signal_types.contains(t))
+                            .unwrap_or(false))
+            }
+            Signal::System(_) => self.system_signals,
+        }
+    }
+}
+
+/// A signal being checked against filters. The `type` tag of an app
+/// signal's payload is decoded the first time a filter asks for it, and
+/// never if none does.
+pub struct CheckedSignal<'a> {
+    signal: &'a Signal,
+    signal_type: OnceCell<Option<String>>,
+}
+
+impl<'a> CheckedSignal<'a> {
+    /// Prepare a signal to be checked
+    pub fn new(signal: &'a Signal) -> Self {
+        Self {
+            signal,
+            signal_type: OnceCell::new(),
+        }
+    }
+
+    /// The `type` tag of an app signal payload, if it has one
+    fn signal_type(&self) -> Option<&str> {
+        self.signal_type
+            .get_or_init(|| match self.signal {
+                Signal::App { signal, .. } => signal_type(signal),
+                Signal::System(_) => None,
+            })
+            .as_deref()
+    }
+}
+
+/// The `type` tag of an app signal payload, if it has one
+fn signal_type(signal: &AppSignal) -> Option<String> {
+    #[derive(Debug, serde::Deserialize)]
+    struct Tagged {
+        #[serde(rename = "type")]
+        signal_type: String,
+    }
+    signal
+        .clone()
+        .into_inner()
+        .decode::<Tagged>()
+        .ok()
+        .map(|t| t.signal_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_types::signal::test_signal;
+    use holochain_zome_types::ExternIO;
+
+    #[derive(serde::Serialize, Debug)]
+    #[serde(tag = "type")]
+    enum MySignal {
+        Ping,
+        Pong,
+    }
+
+    fn app_signal(cell_id: &CellId, zome: &str, payload: MySignal) -> Signal {
+        Signal::App {
+            cell_id: cell_id.clone(),
+            zome_name: zome.into(),
+            signal: AppSignal::new(ExternIO::encode(payload).unwrap()),
+        }
+    }
+
+    #[test]
+    fn filter_sets() {
+        let cell_1 = CellId::new(
+            holo_hash::DnaHash::from_raw_32(vec![1; 32]),
+            holo_hash::AgentPubKey::from_raw_32(vec![1; 32]),
+        );
+        let cell_2 = CellId::new(
+            holo_hash::DnaHash::from_raw_32(vec![2; 32]),
+            holo_hash::AgentPubKey::from_raw_32(vec![2; 32]),
+        );
+        let ping_1 = app_signal(&cell_1, "chat", MySignal::Ping);
+        let pong_1 = app_signal(&cell_1, "chat", MySignal::Pong);
+        let other_zome_1 = app_signal(&cell_1, "profiles", MySignal::Ping);
+        let ping_2 = app_signal(&cell_2, "chat", MySignal::Ping);
+        let system = test_signal("system");
+
+        assert!(SignalFilterSet::allow_all().allows(&ping_1));
+        assert!(SignalFilterSet::allow_all().allows(&system));
+        assert!(!SignalFilterSet::block_all().allows(&ping_1));
+        assert!(!SignalFilterSet::block_all().allows(&system));
+
+        // - Only pings from the chat zome of cell 1
+        let include = SignalFilterSet::Include(
+            [(
+                cell_1.clone(),
+                SignalFilter {
+                    system_signals: false,
+                    zomes: ["chat".into()].into_iter().collect(),
+                    signal_types: ["Ping".to_string()].into_iter().collect(),
+                    ..SignalFilter::empty()
+                },
+            )]
+            .into_iter()
+            .collect(),
+        );
+        assert!(include.allows(&ping_1));
+        assert!(!include.allows(&pong_1));
+        assert!(!include.allows(&other_zome_1));
+        assert!(!include.allows(&ping_2));
+        assert!(!include.allows(&system));
+
+        // - Everything except app signals from cell 1
+        let exclude = SignalFilterSet::Exclude(
+            [(
+                cell_1,
+                SignalFilter {
+                    system_signals: false,
+                    ..SignalFilter::empty()
+                },
+            )]
+            .into_iter()
+            .collect(),
+        );
+        assert!(!exclude.allows(&ping_1));
+        assert!(!exclude.allows(&other_zome_1));
+        assert!(exclude.allows(&ping_2));
+        assert!(exclude.allows(&system));
     }
 }
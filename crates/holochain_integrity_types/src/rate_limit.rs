@@ -99,3 +99,32 @@ impl From<EntryRateWeight> for RateWeight {
         }
     }
 }
+
+/// The limit placed on a single rate limiting bucket.
+///
+/// Every weighed action adds its units (and, for entries, its [`RateBytes`])
+/// to the bucket it was assigned to by the `weigh` callback. An action
+/// overflows the bucket if the total weight of its author's actions in that
+/// bucket, over the `period_secs` ending at the action's timestamp, exceeds
+/// the `capacity`. Actions which overflow a bucket are rejected.
+#[derive(
+    Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RateBucketLimit {
+    /// The total weight the bucket can hold within one period.
+    pub capacity: RateBucketCapacity,
+    /// The length of the period, in seconds, over which weight is summed.
+    pub period_secs: u64,
+}
+
+impl RateBucketLimit {
+    /// The length of the period over which weight is summed.
+    pub fn period(&self) -> core::time::Duration {
+        core::time::Duration::from_secs(self.period_secs)
+    }
+}
+
+/// The rate limits declared by a DNA, keyed by bucket.
+/// Buckets without a limit are unlimited.
+pub type RateLimits = std::collections::BTreeMap<RateBucketId, RateBucketLimit>;
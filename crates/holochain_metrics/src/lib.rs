@@ -30,6 +30,15 @@
 //!     - `HOLOCHAIN_INFLUXIVE_EXTERNAL_BUCKET=[my influxdb bucket name]`
 //!     - `HOLOCHAIN_INFLUXIVE_EXTERNAL_TOKEN=[my influxdb auth token]`
 //!   - Metrics will be set up to report to this already running InfluxDB.
+//! - A Prometheus scrape endpoint.
+//!   - Enable via environment variable:
+//!     `HOLOCHAIN_METRICS_PROMETHEUS_BIND=[address to listen on, e.g. 127.0.0.1:9100]`
+//!   - Metrics will be served in the Prometheus text format at
+//!     `http://[address]/metrics`. Nothing is downloaded or run besides
+//!     this HTTP endpoint, so this works offline.
+//!   - This can't be combined with the InfluxDB environment variables.
+//!     If both are set, metrics are disabled and an error is logged.
+//!   - The holochain conductor can also enable this from its config file.
 //!
 //! ## Metric Naming Conventions
 //!
@@ -88,6 +97,9 @@
 //! | `kitsune.peer.send.duration` | `f64_histogram` | `s` | When kitsune sends data to a remote peer. |- `remote.id`: the base64 remote peer id.<br />- `is.error`: if the send failed. |
 //! | `kitsune.peer.send.byte.count` | `u64_histogram` | `By` | When kitsune sends data to a remote peer. |- `remote.id`: the base64 remote peer id.<br />- `is.error`: if the send failed. |
 
+#[cfg(feature = "prometheus")]
+pub mod prometheus;
+
 #[cfg(feature = "influxive")]
 const DASH_NETWORK_STATS: &[u8] = include_bytes!("dashboards/networkstats.json");
 
@@ -118,13 +130,28 @@ pub enum HolochainMetricsConfig {
     #[cfg(feature = "influxive")]
     /// Use influxive as a child service to write metrics.
     InfluxiveChildSvc(Box<influxive::InfluxiveChildSvcConfig>),
+
+    #[cfg(feature = "prometheus")]
+    /// Serve metrics at `http://<bind>/metrics` for Prometheus to scrape.
+    Prometheus {
+        /// The address to listen on.
+        bind: std::net::SocketAddr,
+    },
 }
 
+#[cfg(feature = "prometheus")]
+const E_PROMETHEUS_BIND: &str = "HOLOCHAIN_METRICS_PROMETHEUS_BIND";
+
+#[cfg(feature = "influxive")]
 const E_CHILD_SVC: &str = "HOLOCHAIN_INFLUXIVE_CHILD_SVC";
 
+#[cfg(feature = "influxive")]
 const E_EXTERNAL: &str = "HOLOCHAIN_INFLUXIVE_EXTERNAL";
+#[cfg(feature = "influxive")]
 const E_EXTERNAL_HOST: &str = "HOLOCHAIN_INFLUXIVE_EXTERNAL_HOST";
+#[cfg(feature = "influxive")]
 const E_EXTERNAL_BUCKET: &str = "HOLOCHAIN_INFLUXIVE_EXTERNAL_BUCKET";
+#[cfg(feature = "influxive")]
 const E_EXTERNAL_TOKEN: &str = "HOLOCHAIN_INFLUXIVE_EXTERNAL_TOKEN";
 
 impl HolochainMetricsConfig {
@@ -133,6 +160,28 @@ impl HolochainMetricsConfig {
     /// The output of this function is largely controlled by environment
     /// variables, please see the [crate-level documentation](crate) for usage.
     pub fn new(root_path: &std::path::Path) -> Self {
+        #[cfg(feature = "prometheus")]
+        {
+            if let Ok(bind) = std::env::var(E_PROMETHEUS_BIND) {
+                #[cfg(feature = "influxive")]
+                if std::env::var_os(E_CHILD_SVC).is_some() || std::env::var_os(E_EXTERNAL).is_some()
+                {
+                    tracing::error!(
+                        env = %E_PROMETHEUS_BIND,
+                        "can't be set along with the InfluxDB environment variables, metrics are disabled"
+                    );
+                    return Self::Disabled;
+                }
+                match bind.parse() {
+                    Ok(bind) => return Self::Prometheus { bind },
+                    Err(err) => {
+                        tracing::error!(env = %E_PROMETHEUS_BIND, ?err, "invalid");
+                        return Self::Disabled;
+                    }
+                }
+            }
+        }
+
         #[cfg(feature = "influxive")]
         {
             if std::env::var_os(E_CHILD_SVC).is_some() {
@@ -202,6 +251,10 @@ impl HolochainMetricsConfig {
             Self::InfluxiveChildSvc(config) => {
                 Self::init_influxive_child_svc(*config).await;
             }
+            #[cfg(feature = "prometheus")]
+            Self::Prometheus { bind } => {
+                Self::init_prometheus(bind);
+            }
         }
     }
 
@@ -247,4 +300,32 @@ impl HolochainMetricsConfig {
             }
         }
     }
+
+    #[cfg(feature = "prometheus")]
+    fn init_prometheus(bind: std::net::SocketAddr) {
+        tracing::info!(%bind, "initializing holochain_metrics");
+
+        // Errors from the opentelemetry SDK, such as conflicting instruments,
+        // go to the global error handler. That is left to the host, which
+        // may have installed one for its own exporters.
+
+        let meter_provider = match self::prometheus::PrometheusMeterProvider::new() {
+            Ok(meter_provider) => meter_provider,
+            Err(err) => {
+                tracing::warn!(?err, "unable to initialize prometheus metrics");
+                return;
+            }
+        };
+        match meter_provider.serve(bind) {
+            Ok(addr) => {
+                // setup opentelemetry to use our metrics collector
+                opentelemetry_api::global::set_meter_provider(meter_provider);
+
+                tracing::info!(%addr, "prometheus metrics served at /metrics");
+            }
+            Err(err) => {
+                tracing::warn!(?err, "unable to serve prometheus metrics");
+            }
+        }
+    }
 }
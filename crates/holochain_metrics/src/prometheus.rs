@@ -0,0 +1,492 @@
+//! Opentelemetry metrics served over HTTP in the Prometheus text format.
+//!
+//! Metrics are aggregated by the opentelemetry SDK and exported with
+//! `opentelemetry-prometheus` when Prometheus scrapes `/metrics`.
+//!
+//! Holochain reports metrics through `ts_opentelemetry_api`, the fork of
+//! `opentelemetry_api` which influxive is built on, while the SDK and the
+//! exporter are built on `opentelemetry_api` itself. The two APIs only differ
+//! in name, and in the fork's sync instruments taking a context, so this
+//! module passes every instrument and measurement from one to the other.
+
+use opentelemetry_api::metrics::*;
+use opentelemetry_api::Array;
+use opentelemetry_api::KeyValue;
+use opentelemetry_api::Value;
+use std::any::Any;
+use std::borrow::Cow;
+use std::sync::Arc;
+use upstream_opentelemetry_api as upstream;
+use upstream_opentelemetry_api::metrics::MeterProvider as _;
+
+/// Histogram bucket bounds for durations, in seconds.
+const DURATION_BOUNDS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Histogram bucket bounds for sizes, in bytes.
+const BYTE_BOUNDS: &[f64] = &[
+    256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0, 4194304.0, 16777216.0,
+];
+
+fn upstream_attributes(attributes: &[KeyValue]) -> Vec<upstream::KeyValue> {
+    attributes
+        .iter()
+        .map(|kv| upstream::KeyValue::new(kv.key.as_str().to_owned(), upstream_value(&kv.value)))
+        .collect()
+}
+
+fn upstream_value(value: &Value) -> upstream::Value {
+    match value {
+        Value::Bool(v) => upstream::Value::Bool(*v),
+        Value::I64(v) => upstream::Value::I64(*v),
+        Value::F64(v) => upstream::Value::F64(*v),
+        Value::String(v) => upstream::Value::String(v.as_str().to_owned().into()),
+        Value::Array(Array::Bool(v)) => upstream::Value::Array(v.clone().into()),
+        Value::Array(Array::I64(v)) => upstream::Value::Array(v.clone().into()),
+        Value::Array(Array::F64(v)) => upstream::Value::Array(v.clone().into()),
+        Value::Array(Array::String(v)) => upstream::Value::Array(
+            v.iter()
+                .map(|s| s.as_str().to_owned().into())
+                .collect::<Vec<upstream::StringValue>>()
+                .into(),
+        ),
+    }
+}
+
+fn attributes(attributes: &[upstream::KeyValue]) -> Vec<KeyValue> {
+    attributes
+        .iter()
+        .map(|kv| KeyValue::new(kv.key.as_str().to_owned(), value(&kv.value)))
+        .collect()
+}
+
+fn value(value: &upstream::Value) -> Value {
+    use upstream::Array as A;
+    use upstream::Value as V;
+    match value {
+        V::Bool(v) => Value::Bool(*v),
+        V::I64(v) => Value::I64(*v),
+        V::F64(v) => Value::F64(*v),
+        V::String(v) => Value::String(v.as_str().to_owned().into()),
+        V::Array(A::Bool(v)) => Value::Array(v.clone().into()),
+        V::Array(A::I64(v)) => Value::Array(v.clone().into()),
+        V::Array(A::F64(v)) => Value::Array(v.clone().into()),
+        V::Array(A::String(v)) => Value::Array(
+            v.iter()
+                .map(|s| s.as_str().to_owned().into())
+                .collect::<Vec<opentelemetry_api::StringValue>>()
+                .into(),
+        ),
+    }
+}
+
+fn metrics_error(err: upstream::metrics::MetricsError) -> MetricsError {
+    MetricsError::Other(err.to_string())
+}
+
+/// A sync instrument of the SDK.
+struct SdkSync<I>(I);
+
+impl<T> SyncCounter<T> for SdkSync<upstream::metrics::Counter<T>> {
+    fn add(&self, _cx: &opentelemetry_api::Context, value: T, attributes: &[KeyValue]) {
+        self.0.add(value, &upstream_attributes(attributes))
+    }
+}
+
+impl<T> SyncUpDownCounter<T> for SdkSync<upstream::metrics::UpDownCounter<T>> {
+    fn add(&self, _cx: &opentelemetry_api::Context, value: T, attributes: &[KeyValue]) {
+        self.0.add(value, &upstream_attributes(attributes))
+    }
+}
+
+impl<T> SyncHistogram<T> for SdkSync<upstream::metrics::Histogram<T>> {
+    fn record(&self, _cx: &opentelemetry_api::Context, value: T, attributes: &[KeyValue]) {
+        self.0.record(value, &upstream_attributes(attributes))
+    }
+}
+
+/// An observable instrument of the SDK.
+struct SdkObservable<T>(Arc<dyn upstream::metrics::AsyncInstrument<T>>);
+
+impl<T> AsyncInstrument<T> for SdkObservable<T> {
+    fn observe(&self, measurement: T, attributes: &[KeyValue]) {
+        self.0
+            .observe(measurement, &upstream_attributes(attributes))
+    }
+
+    // The SDK identifies its instruments by this when they are observed in
+    // a callback, so it has to be the SDK's own.
+    fn as_any(&self) -> Arc<dyn Any> {
+        self.0.as_any()
+    }
+}
+
+/// An observable instrument of the SDK, as passed to a callback.
+struct SdkObserving<'a, T>(&'a dyn upstream::metrics::AsyncInstrument<T>);
+
+impl<'a, T> AsyncInstrument<T> for SdkObserving<'a, T> {
+    fn observe(&self, measurement: T, attributes: &[KeyValue]) {
+        self.0
+            .observe(measurement, &upstream_attributes(attributes))
+    }
+
+    fn as_any(&self) -> Arc<dyn Any> {
+        self.0.as_any()
+    }
+}
+
+/// One of our observable instruments, as passed to the SDK's observer.
+struct Observed<'a, T>(&'a dyn AsyncInstrument<T>);
+
+impl<'a, T> upstream::metrics::AsyncInstrument<T> for Observed<'a, T> {
+    fn observe(&self, measurement: T, upstream_attributes: &[upstream::KeyValue]) {
+        self.0
+            .observe(measurement, &attributes(upstream_attributes))
+    }
+
+    fn as_any(&self) -> Arc<dyn Any> {
+        self.0.as_any()
+    }
+}
+
+/// The SDK's observer, as passed to a multi-instrument callback.
+/// Instruments which aren't the SDK's are reported to the opentelemetry
+/// error handler by the SDK.
+struct SdkObserver<'a>(&'a dyn upstream::metrics::Observer);
+
+impl<'a> opentelemetry_api::metrics::Observer for SdkObserver<'a> {
+    fn observe_f64(&self, inst: &dyn AsyncInstrument<f64>, measurement: f64, attrs: &[KeyValue]) {
+        self.0
+            .observe_f64(&Observed(inst), measurement, &upstream_attributes(attrs))
+    }
+
+    fn observe_u64(&self, inst: &dyn AsyncInstrument<u64>, measurement: u64, attrs: &[KeyValue]) {
+        self.0
+            .observe_u64(&Observed(inst), measurement, &upstream_attributes(attrs))
+    }
+
+    fn observe_i64(&self, inst: &dyn AsyncInstrument<i64>, measurement: i64, attrs: &[KeyValue]) {
+        self.0
+            .observe_i64(&Observed(inst), measurement, &upstream_attributes(attrs))
+    }
+}
+
+struct SdkRegistration(Box<dyn upstream::metrics::CallbackRegistration>);
+
+impl CallbackRegistration for SdkRegistration {
+    fn unregister(&mut self) -> Result<()> {
+        self.0.unregister().map_err(metrics_error)
+    }
+}
+
+/// Builds an SDK instrument with the name, description and unit given
+/// through the fork's API.
+macro_rules! build {
+    ($meter:expr, $kind:ident, $name:expr, $description:expr, $unit:expr) => {{
+        let mut builder = $meter.$kind($name);
+        if let Some(description) = $description {
+            builder = builder.with_description(description);
+        }
+        if let Some(unit) = $unit {
+            builder = builder.with_unit(upstream::metrics::Unit::new(unit.as_str().to_owned()));
+        }
+        builder
+    }};
+}
+
+macro_rules! sync_instrument {
+    ($fn:ident, $ty:ident<$t:ty>) => {
+        fn $fn(
+            &self,
+            name: Cow<'static, str>,
+            description: Option<Cow<'static, str>>,
+            unit: Option<Unit>,
+        ) -> Result<$ty<$t>> {
+            let instrument = build!(self.0, $fn, name, description, unit)
+                .try_init()
+                .map_err(metrics_error)?;
+            Ok($ty::new(Arc::new(SdkSync(instrument))))
+        }
+    };
+}
+
+macro_rules! observable_instrument {
+    ($fn:ident, $ty:ident<$t:ty>) => {
+        fn $fn(
+            &self,
+            name: Cow<'static, str>,
+            description: Option<Cow<'static, str>>,
+            unit: Option<Unit>,
+            callbacks: Vec<Callback<$t>>,
+        ) -> Result<$ty<$t>> {
+            let mut builder = build!(self.0, $fn, name, description, unit);
+            for callback in callbacks {
+                builder = builder.with_callback(move |inst| callback(&SdkObserving(inst)));
+            }
+            let instrument = builder.try_init().map_err(metrics_error)?;
+            Ok($ty::new(Arc::new(SdkObservable(Arc::new(instrument)))))
+        }
+    };
+}
+
+/// Creates the instruments of a meter with the SDK.
+struct PrometheusInstrumentProvider(upstream::metrics::Meter);
+
+impl InstrumentProvider for PrometheusInstrumentProvider {
+    sync_instrument!(u64_counter, Counter<u64>);
+    sync_instrument!(f64_counter, Counter<f64>);
+    sync_instrument!(i64_up_down_counter, UpDownCounter<i64>);
+    sync_instrument!(f64_up_down_counter, UpDownCounter<f64>);
+    sync_instrument!(f64_histogram, Histogram<f64>);
+    sync_instrument!(u64_histogram, Histogram<u64>);
+    sync_instrument!(i64_histogram, Histogram<i64>);
+    observable_instrument!(u64_observable_counter, ObservableCounter<u64>);
+    observable_instrument!(f64_observable_counter, ObservableCounter<f64>);
+    observable_instrument!(i64_observable_up_down_counter, ObservableUpDownCounter<i64>);
+    observable_instrument!(f64_observable_up_down_counter, ObservableUpDownCounter<f64>);
+    observable_instrument!(u64_observable_gauge, ObservableGauge<u64>);
+    observable_instrument!(i64_observable_gauge, ObservableGauge<i64>);
+    observable_instrument!(f64_observable_gauge, ObservableGauge<f64>);
+
+    fn register_callback(
+        &self,
+        instruments: &[Arc<dyn Any>],
+        callbacks: Box<dyn Fn(&dyn opentelemetry_api::metrics::Observer) + Send + Sync>,
+    ) -> Result<Box<dyn CallbackRegistration>> {
+        let registration = self
+            .0
+            .register_callback(instruments, move |observer| {
+                callbacks(&SdkObserver(observer))
+            })
+            .map_err(metrics_error)?;
+        Ok(Box::new(SdkRegistration(registration)))
+    }
+}
+
+/// Opentelemetry meter provider that keeps metrics for Prometheus to scrape.
+#[derive(Clone)]
+pub struct PrometheusMeterProvider {
+    provider: opentelemetry_sdk::metrics::MeterProvider,
+    registry: prometheus::Registry,
+}
+
+impl PrometheusMeterProvider {
+    /// Construct a meter provider which exports to a new Prometheus registry.
+    pub fn new() -> Result<Self> {
+        use opentelemetry_sdk::metrics::new_view;
+        use opentelemetry_sdk::metrics::Aggregation;
+        use opentelemetry_sdk::metrics::Instrument;
+        use opentelemetry_sdk::metrics::Stream;
+
+        let histogram = |unit: &'static str, bounds: &[f64]| {
+            new_view(
+                Instrument::new()
+                    .name("*")
+                    .unit(upstream::metrics::Unit::new(unit)),
+                Stream::new().aggregation(Aggregation::ExplicitBucketHistogram {
+                    boundaries: bounds.to_vec(),
+                    record_min_max: false,
+                }),
+            )
+            .map_err(metrics_error)
+        };
+
+        let registry = prometheus::Registry::new();
+        let exporter = opentelemetry_prometheus::exporter()
+            .with_registry(registry.clone())
+            .without_target_info()
+            .without_scope_info()
+            .build()
+            .map_err(metrics_error)?;
+        let provider = opentelemetry_sdk::metrics::MeterProvider::builder()
+            .with_reader(exporter)
+            .with_view(histogram("s", DURATION_BOUNDS)?)
+            .with_view(histogram("By", BYTE_BOUNDS)?)
+            .build();
+        Ok(Self { provider, registry })
+    }
+
+    /// Render all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> Result<String> {
+        prometheus::TextEncoder::new()
+            .encode_to_string(&self.registry.gather())
+            .map_err(|err| MetricsError::Other(err.to_string()))
+    }
+
+    /// Serve the metrics at `http://<bind>/metrics` until the process exits.
+    /// Returns the address actually bound, which differs from `bind` if it
+    /// asked for port 0.
+    pub fn serve(&self, bind: std::net::SocketAddr) -> std::io::Result<std::net::SocketAddr> {
+        use hyper::service::make_service_fn;
+        use hyper::service::service_fn;
+        use hyper::Body;
+        use hyper::Request;
+        use hyper::Response;
+        use hyper::StatusCode;
+
+        let listener = std::net::TcpListener::bind(bind)?;
+        listener.set_nonblocking(true)?;
+        let addr = listener.local_addr()?;
+
+        let provider = self.clone();
+        let make_service = make_service_fn(move |_| {
+            let provider = provider.clone();
+            async move {
+                Ok::<_, std::convert::Infallible>(service_fn(move |req: Request<Body>| {
+                    let provider = provider.clone();
+                    async move {
+                        let response = if req.uri().path() != "/metrics" {
+                            Response::builder()
+                                .status(StatusCode::NOT_FOUND)
+                                .body(Body::empty())
+                        } else {
+                            match provider.render() {
+                                Ok(metrics) => Response::builder()
+                                    .header(hyper::header::CONTENT_TYPE, prometheus::TEXT_FORMAT)
+                                    .body(Body::from(metrics)),
+                                Err(err) => {
+                                    tracing::warn!(?err, "failed to render prometheus metrics");
+                                    Response::builder()
+                                        .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                        .body(Body::empty())
+                                }
+                            }
+                        };
+                        Ok::<_, std::convert::Infallible>(response.expect("valid response"))
+                    }
+                }))
+            }
+        });
+
+        let server = hyper::Server::from_tcp(listener)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?
+            .serve(make_service);
+        tokio::task::spawn(async move {
+            if let Err(err) = server.await {
+                tracing::error!(?err, "prometheus metrics server stopped");
+            }
+        });
+
+        Ok(addr)
+    }
+}
+
+impl MeterProvider for PrometheusMeterProvider {
+    fn versioned_meter(
+        &self,
+        name: impl Into<Cow<'static, str>>,
+        version: Option<impl Into<Cow<'static, str>>>,
+        schema_url: Option<impl Into<Cow<'static, str>>>,
+        attributes: Option<Vec<KeyValue>>,
+    ) -> Meter {
+        let meter = self.provider.versioned_meter(
+            name,
+            version,
+            schema_url,
+            attributes.as_deref().map(upstream_attributes),
+        );
+        Meter::new(Arc::new(PrometheusInstrumentProvider(meter)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::io::AsyncWriteExt;
+
+    #[test]
+    fn renders_instruments() {
+        let provider = PrometheusMeterProvider::new().unwrap();
+        let meter = provider.meter("test");
+        let cx = opentelemetry_api::Context::new();
+
+        let sent = meter
+            .u64_histogram("kitsune.peer.send.byte.count")
+            .with_description("Bytes sent")
+            .with_unit(Unit::new("By"))
+            .init();
+        sent.record(&cx, 300, &[KeyValue::new("remote.id", "abc")]);
+        sent.record(&cx, 5000, &[KeyValue::new("remote.id", "abc")]);
+
+        let calls = meter.u64_counter("hc.calls").init();
+        calls.add(&cx, 2, &[]);
+        calls.add(&cx, 3, &[]);
+
+        let _open = meter
+            .i64_observable_gauge("hc.open")
+            .with_callback(|inst| inst.observe(7, &[KeyValue::new("say", "\"hi\"")]))
+            .init();
+
+        let out = provider.render().unwrap();
+        assert!(out.contains("# HELP kitsune_peer_send_byte_count_bytes Bytes sent\n"));
+        assert!(out.contains("# TYPE kitsune_peer_send_byte_count_bytes histogram\n"));
+        assert!(out.contains(
+            "kitsune_peer_send_byte_count_bytes_bucket{remote_id=\"abc\",le=\"256\"} 0\n"
+        ));
+        assert!(out.contains(
+            "kitsune_peer_send_byte_count_bytes_bucket{remote_id=\"abc\",le=\"1024\"} 1\n"
+        ));
+        assert!(out.contains(
+            "kitsune_peer_send_byte_count_bytes_bucket{remote_id=\"abc\",le=\"+Inf\"} 2\n"
+        ));
+        assert!(out.contains("kitsune_peer_send_byte_count_bytes_sum{remote_id=\"abc\"} 5300\n"));
+        assert!(out.contains("kitsune_peer_send_byte_count_bytes_count{remote_id=\"abc\"} 2\n"));
+        assert!(out.contains("# TYPE hc_calls_total counter\nhc_calls_total 5\n"));
+        assert!(out.contains("hc_open{say=\"\\\"hi\\\"\"} 7\n"));
+    }
+
+    #[test]
+    fn observes_instruments_in_multi_instrument_callbacks() {
+        let provider = PrometheusMeterProvider::new().unwrap();
+        let meter = provider.meter("test");
+
+        let open = meter.u64_observable_gauge("hc.open").init();
+        let closed = meter.f64_observable_counter("hc.closed").init();
+        let _registration = meter
+            .register_callback(&[open.as_any(), closed.as_any()], move |observer| {
+                observer.observe_u64(&open, 3, &[]);
+                observer.observe_f64(&closed, 1.5, &[]);
+            })
+            .unwrap();
+
+        let out = provider.render().unwrap();
+        assert!(out.contains("hc_open 3\n"));
+        assert!(out.contains("hc_closed_total 1.5\n"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn serves_metrics() {
+        let provider = PrometheusMeterProvider::new().unwrap();
+        provider
+            .meter("test")
+            .f64_up_down_counter("hc.level")
+            .init()
+            .add(&opentelemetry_api::Context::new(), 1.5, &[]);
+        let addr = provider.serve(([127, 0, 0, 1], 0).into()).unwrap();
+
+        let get = |path: &'static str| async move {
+            let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+            stream
+                .write_all(
+                    format!(
+                        "GET {} HTTP/1.1\r\nHost: x\r\nConnection: close\r\n\r\n",
+                        path
+                    )
+                    .as_bytes(),
+                )
+                .await
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        };
+
+        let response = get("/metrics").await;
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("hc_level 1.5\n"));
+
+        assert!(get("/other").await.starts_with("HTTP/1.1 404"));
+    }
+}
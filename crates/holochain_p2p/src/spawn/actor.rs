@@ -135,12 +135,22 @@ impl WrapEvtSender {
         payload: ExternIO,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        trace_context: Option<String>,
     ) -> impl Future<Output = HolochainP2pResult<SerializedBytes>> + 'static + Send {
         timing_trace!(
             {
                 self.0.call_remote(
-                    dna_hash, from, signature, to_agent, zome_name, fn_name, cap_secret, payload,
-                    nonce, expires_at,
+                    dna_hash,
+                    from,
+                    signature,
+                    to_agent,
+                    zome_name,
+                    fn_name,
+                    cap_secret,
+                    payload,
+                    nonce,
+                    expires_at,
+                    trace_context,
                 )
             },
             "(hp2p:handle) call_remote",
@@ -384,6 +394,7 @@ impl HolochainP2pActor {
         data: Vec<u8>,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        trace_context: Option<String>,
     ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
         let evt_sender = self.evt_sender.clone();
         Ok(async move {
@@ -399,6 +410,7 @@ impl HolochainP2pActor {
                     ExternIO::from(data),
                     nonce,
                     expires_at,
+                    trace_context,
                 )
                 .await;
             res.map_err(kitsune_p2p::KitsuneP2pError::from)
@@ -719,9 +731,19 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 to_agent,
                 nonce,
                 expires_at,
+                trace_context,
             } => self.handle_incoming_call_remote(
-                space, from_agent, signature, to_agent, zome_name, fn_name, cap_secret, data,
-                *nonce, expires_at,
+                space,
+                from_agent,
+                signature,
+                to_agent,
+                zome_name,
+                fn_name,
+                cap_secret,
+                data,
+                *nonce,
+                expires_at,
+                trace_context,
             ),
             crate::wire::WireMessage::CallRemoteMulti {
                 zome_name,
@@ -739,7 +761,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 {
                     Some((signature, to_agent)) => self.handle_incoming_call_remote(
                         space, from_agent, signature, to_agent, zome_name, fn_name, cap_secret,
-                        data, *nonce, expires_at,
+                        data, *nonce, expires_at, None,
                     ),
                     None => Err(HolochainP2pError::RoutingAgentError(to_agent).into()),
                 }
@@ -816,10 +838,20 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 data,
                 nonce,
                 expires_at,
+                trace_context,
             } => {
                 let fut = self.handle_incoming_call_remote(
-                    space, from_agent, signature, to_agent, zome_name, fn_name, cap_secret, data,
-                    *nonce, expires_at,
+                    space,
+                    from_agent,
+                    signature,
+                    to_agent,
+                    zome_name,
+                    fn_name,
+                    cap_secret,
+                    data,
+                    *nonce,
+                    expires_at,
+                    trace_context,
                 );
                 Ok(async move {
                     let _ = fut?.await?;
@@ -845,7 +877,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                     Some((signature, to_agent)) => {
                         let fut = self.handle_incoming_call_remote(
                             space, from_agent, signature, to_agent, zome_name, fn_name, cap_secret,
-                            data, *nonce, expires_at,
+                            data, *nonce, expires_at, None,
                         );
                         Ok(async move {
                             let _ = fut?.await?;
@@ -1028,14 +1060,27 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let space = dna_hash.into_kitsune();
         let to_agent_kitsune = to_agent.clone().into_kitsune();
 
-        let req = crate::wire::WireMessage::call_remote(
-            zome_name, fn_name, from_agent, signature, to_agent, cap_secret, payload, nonce,
-            expires_at,
-        )
-        .encode()?;
-
         let kitsune_p2p = self.kitsune_p2p.clone();
         Ok(async move {
+            // This future is awaited by the caller, so the current span is
+            // the caller's, which the remote end can continue the trace from.
+            #[cfg(feature = "otlp")]
+            let trace_context = holochain_trace::otlp::current_traceparent();
+            #[cfg(not(feature = "otlp"))]
+            let trace_context = None;
+            let req = crate::wire::WireMessage::call_remote(
+                zome_name,
+                fn_name,
+                from_agent,
+                signature,
+                to_agent,
+                cap_secret,
+                payload,
+                nonce,
+                expires_at,
+                trace_context,
+            )
+            .encode()?;
             let result: Vec<u8> = kitsune_p2p
                 .rpc_single(space, to_agent_kitsune, req, None)
                 .await?;
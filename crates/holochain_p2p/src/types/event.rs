@@ -49,12 +49,17 @@ impl Default for GetRequest {
 }
 
 /// GetMeta options help control how the get is processed at various levels.
-#[derive(Debug, serde::Serialize, serde::Deserialize)]
-pub struct GetMetaOptions {}
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GetMetaOptions {
+    /// Which metadata to return.
+    pub metadata_request: MetadataRequest,
+}
 
 impl From<&actor::GetMetaOptions> for GetMetaOptions {
-    fn from(_a: &actor::GetMetaOptions) -> Self {
-        Self {}
+    fn from(a: &actor::GetMetaOptions) -> Self {
+        Self {
+            metadata_request: a.metadata_request.clone(),
+        }
     }
 }
 
@@ -185,6 +190,7 @@ ghost_actor::ghost_chan! {
             payload: ExternIO,
             nonce: Nonce256Bits,
             expires_at: Timestamp,
+            trace_context: Option<String>,
         ) -> SerializedBytes;
 
         /// A remote node is publishing data in a range we claim to be holding.
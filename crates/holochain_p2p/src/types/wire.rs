@@ -36,6 +36,9 @@ pub enum WireMessage {
         data: Vec<u8>,
         nonce: Box<Nonce256Bits>,
         expires_at: Timestamp,
+        /// The caller's span as a W3C `traceparent`, if it is being traced.
+        #[serde(default)]
+        trace_context: Option<String>,
     },
     CallRemoteMulti {
         zome_name: ZomeName,
@@ -111,6 +114,7 @@ impl WireMessage {
         payload: ExternIO,
         nonce: Nonce256Bits,
         expires_at: Timestamp,
+        trace_context: Option<String>,
     ) -> WireMessage {
         Self::CallRemote {
             zome_name,
@@ -122,6 +126,7 @@ impl WireMessage {
             data: payload.into_vec(),
             nonce: Box::new(nonce),
             expires_at,
+            trace_context,
         }
     }
 
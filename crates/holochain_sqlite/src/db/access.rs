@@ -1,6 +1,9 @@
 use crate::db::conn::PConn;
 use crate::db::databases::DATABASE_HANDLES;
 use crate::db::guard::{PConnGuard, PTxnGuard};
+use crate::db::key::database_key;
+#[cfg(feature = "sqlite-encrypted")]
+use crate::db::key::DbKey;
 use crate::db::kind::{DbKind, DbKindT};
 use crate::db::pool::{
     initialize_connection, new_connection_pool, num_read_threads, ConnectionPool, DbSyncLevel,
@@ -73,7 +76,9 @@ impl<Kind: DbKindT> ReadAccess<Kind> for DbRead<Kind> {
 pub struct DbRead<Kind: DbKindT> {
     kind: Kind,
     path: PathBuf,
-    connection_pool: ConnectionPool,
+    #[cfg_attr(not(feature = "sqlite-encrypted"), allow(dead_code))]
+    sync_level: DbSyncLevel,
+    connection_pool: Arc<parking_lot::RwLock<ConnectionPool>>,
     write_semaphore: Arc<Semaphore>,
     read_semaphore: Arc<Semaphore>,
     long_read_semaphore: Arc<Semaphore>,
@@ -163,7 +168,7 @@ impl<Kind: DbKindT> DbRead<Kind> {
     /// TODO: We should eventually swap this for an async solution.
     fn get_connection_from_pool(&self) -> DatabaseResult<PConn> {
         let now = Instant::now();
-        let r = Ok(PConn::new(self.connection_pool.get()?));
+        let r = Ok(PConn::new(self.connection_pool.read().get()?));
         let el = now.elapsed();
         if el.as_millis() > 20 {
             tracing::error!("Connection pool took {:?} to be free'd", el);
@@ -231,6 +236,7 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         sync_level: DbSyncLevel,
         statement_trace_fn: Option<fn(&str)>,
     ) -> DatabaseResult<Self> {
+        let key = path_prefix.and_then(database_key);
         let path = match path_prefix {
             Some(path_prefix) => {
                 let path = path_prefix.join(kind.filename());
@@ -246,7 +252,7 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
                 match Connection::open(&path)
                     // For some reason calling pragma_update is necessary to prove the database file is valid.
                     .and_then(|mut c| {
-                        initialize_connection(&mut c, sync_level)?;
+                        initialize_connection(&mut c, sync_level, key.as_ref())?;
                         c.pragma_update(None, "synchronous", "0".to_string())
                     }) {
                     Ok(_) => (),
@@ -283,7 +289,7 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         };
 
         // Now we know the database file is valid we can open a connection pool.
-        let pool = new_connection_pool(path.as_ref().map(|p| p.as_ref()), sync_level);
+        let pool = new_connection_pool(path.as_ref().map(|p| p.as_ref()), sync_level, key);
         let mut conn = pool.get()?;
         // set to faster write-ahead-log mode
         conn.pragma_update(None, "journal_mode", "WAL".to_string())?;
@@ -297,7 +303,8 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
             num_readers: Arc::new(AtomicUsize::new(0)),
             kind,
             path: path.unwrap_or_default(),
-            connection_pool: pool,
+            sync_level,
+            connection_pool: Arc::new(parking_lot::RwLock::new(pool)),
             statement_trace_fn,
         }))
     }
@@ -322,6 +329,35 @@ impl<Kind: DbKindT + Send + Sync + 'static> DbWrite<Kind> {
         r
     }
 
+    /// Re-encrypt the database with a new key.
+    ///
+    /// This waits for all the reads and writes in progress to finish, and
+    /// replaces the connections in the pool, which still use the old key.
+    #[cfg(feature = "sqlite-encrypted")]
+    pub(crate) async fn rekey(&self, key: DbKey) -> DatabaseResult<()> {
+        let _write = self.acquire_writer_permit().await;
+        let num_readers = num_read_threads() as u32;
+        let _read = self
+            .read_semaphore
+            .clone()
+            .acquire_many_owned(num_readers)
+            .await
+            .map_err(|e| DatabaseError::Other(e.into()))?;
+        let _long_read = self
+            .long_read_semaphore
+            .clone()
+            .acquire_many_owned(num_readers)
+            .await
+            .map_err(|e| DatabaseError::Other(e.into()))?;
+
+        let conn = self.get_connection_from_pool()?;
+        let pool = new_connection_pool(Some(&self.path), self.sync_level, Some(key.clone()));
+        tokio::task::spawn_blocking(move || conn.pragma_update(None, "rekey", key.pragma_value()))
+            .await??;
+        *self.connection_pool.write() = pool;
+        Ok(())
+    }
+
     pub fn available_writer_count(&self) -> usize {
         self.write_semaphore.available_permits()
     }
@@ -1,4 +1,6 @@
 use crate::db::access::DbWrite;
+#[cfg(feature = "sqlite-encrypted")]
+use crate::db::key::DbKey;
 use crate::db::kind::DbKindT;
 use crate::prelude::*;
 use once_cell::sync::Lazy;
@@ -10,7 +12,27 @@ use std::{
 
 /// A map over any database type key'd by the full path to the database.
 pub(super) struct Databases {
-    dbs: parking_lot::RwLock<HashMap<PathBuf, Box<dyn Any + Send + Sync>>>,
+    dbs: parking_lot::RwLock<HashMap<PathBuf, Box<dyn OpenDb>>>,
+}
+
+/// The operations which don't depend on the kind of an open database.
+pub(super) trait OpenDb: Send + Sync {
+    fn as_any(&self) -> &(dyn Any + Send + Sync);
+
+    #[cfg(feature = "sqlite-encrypted")]
+    fn rekey(&self, key: DbKey) -> must_future::MustBoxFuture<'static, DatabaseResult<()>>;
+}
+
+impl<Kind: DbKindT + Send + Sync + 'static> OpenDb for DbWrite<Kind> {
+    fn as_any(&self) -> &(dyn Any + Send + Sync) {
+        self
+    }
+
+    #[cfg(feature = "sqlite-encrypted")]
+    fn rekey(&self, key: DbKey) -> must_future::MustBoxFuture<'static, DatabaseResult<()>> {
+        let db = self.clone();
+        must_future::MustBoxFuture::new(async move { db.rekey(key).await })
+    }
 }
 
 pub(super) static DATABASE_HANDLES: Lazy<Databases> = Lazy::new(|| {
@@ -67,7 +89,7 @@ impl Databases {
             .dbs
             .read()
             .get(&path)
-            .and_then(|d| d.downcast_ref::<DbWrite<Kind>>().cloned());
+            .and_then(|d| d.as_any().downcast_ref::<DbWrite<Kind>>().cloned());
         match ret {
             Some(ret) => Ok(ret),
             None => match self.dbs.write().entry(path) {
@@ -75,6 +97,7 @@ impl Databases {
                 // from the kind so will always be the correct type.
                 std::collections::hash_map::Entry::Occupied(o) => Ok(o
                     .get()
+                    .as_any()
                     .downcast_ref::<DbWrite<Kind>>()
                     .expect("Downcast to db kind failed. This is a bug")
                     .clone()),
@@ -88,4 +111,28 @@ impl Databases {
             },
         }
     }
+
+    /// Run a closure with the paths of all open databases, and with new
+    /// databases prevented from being opened until it returns.
+    #[cfg(feature = "sqlite-encrypted")]
+    pub(super) fn with_paths_locked<R>(&self, f: impl FnOnce(Vec<&PathBuf>) -> R) -> R {
+        let dbs = self.dbs.write();
+        f(dbs.keys().collect())
+    }
+
+    /// Re-encrypt all the open databases under a root directory with a new key.
+    #[cfg(feature = "sqlite-encrypted")]
+    pub(super) async fn rekey_under(&self, root: &Path, key: &DbKey) -> DatabaseResult<()> {
+        let rekeys: Vec<_> = self
+            .dbs
+            .read()
+            .iter()
+            .filter(|(path, _)| path.starts_with(root))
+            .map(|(_, db)| db.rekey(key.clone()))
+            .collect();
+        for rekey in rekeys {
+            rekey.await?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,286 @@
+//! Keys for encrypting databases at rest.
+//!
+//! With the `sqlite-encrypted` feature, every database opened under a root
+//! directory which has a [`DbKey`] set is encrypted with that key. Databases
+//! under other directories, and in-memory databases, are not encrypted.
+
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+#[cfg(feature = "sqlite-encrypted")]
+use crate::db::databases::DATABASE_HANDLES;
+#[cfg(feature = "sqlite-encrypted")]
+use crate::error::DatabaseError;
+use crate::error::DatabaseResult;
+#[cfg(feature = "sqlite-encrypted")]
+use rusqlite::Connection;
+
+/// The key which earlier versions used for every encrypted database.
+#[cfg(feature = "sqlite-encrypted")]
+const LEGACY_KEY: &str = "x'98483C6EB40B6C31A448C22A66DED3B5E5E8D5119CAC8327B655C8B5C483648101010101010101010101010101010101'";
+
+static DATABASE_KEYS: Lazy<parking_lot::RwLock<HashMap<PathBuf, DbKey>>> =
+    Lazy::new(|| parking_lot::RwLock::new(HashMap::new()));
+
+/// A 256 bit key for encrypting databases.
+#[derive(Clone, PartialEq, Eq)]
+pub struct DbKey(Arc<[u8; 32]>);
+
+impl std::fmt::Debug for DbKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("DbKey(..)")
+    }
+}
+
+impl DbKey {
+    /// Generate a new random key.
+    pub fn generate() -> Self {
+        Self::from_bytes(rand::random())
+    }
+
+    /// Construct a key from its bytes.
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(Arc::new(bytes))
+    }
+
+    /// The bytes of the key.
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// The key as a blob literal, which SQLCipher uses as the raw key
+    /// instead of deriving one from it.
+    #[cfg(feature = "sqlite-encrypted")]
+    pub(crate) fn pragma_value(&self) -> String {
+        let hex: String = self.0.iter().map(|b| format!("{:02X}", b)).collect();
+        format!("x'{}'", hex)
+    }
+}
+
+/// Set the key for the databases opened under a root directory.
+///
+/// Databases which are already open keep the key they were opened with,
+/// so this should be called before opening any of them.
+pub fn set_database_key(root: &Path, key: DbKey) {
+    DATABASE_KEYS.write().insert(root.to_path_buf(), key);
+}
+
+/// The key for the databases opened under a root directory, if it has one.
+pub(crate) fn database_key(root: &Path) -> Option<DbKey> {
+    DATABASE_KEYS.read().get(root).cloned()
+}
+
+/// Make sure every database file under a root directory is encrypted with
+/// `key`. Files encrypted with one of the `previous` keys, or with the key
+/// shared by earlier versions, are re-keyed, and unencrypted files are
+/// encrypted.
+///
+/// This must be run before opening any of the databases.
+#[cfg(feature = "sqlite-encrypted")]
+pub fn encrypt_databases(root: &Path, key: &DbKey, previous: &[DbKey]) -> DatabaseResult<()> {
+    for path in database_files(root)? {
+        encrypt_database_file(&path, key, previous)?;
+    }
+    Ok(())
+}
+
+/// Re-encrypt every database under a root directory with a new key, and use
+/// the new key for the databases opened there from now on.
+///
+/// Databases which are open are re-keyed in place, waiting for the reads and
+/// writes already in progress on them to finish.
+#[cfg(feature = "sqlite-encrypted")]
+pub async fn rotate_database_key(root: &Path, key: DbKey) -> DatabaseResult<()> {
+    let previous = database_key(root).ok_or_else(|| {
+        DatabaseError::Other(anyhow::anyhow!(
+            "no key is set for the databases under {:?}",
+            root
+        ))
+    })?;
+    {
+        let root = root.to_path_buf();
+        let key = key.clone();
+        tokio::task::spawn_blocking(move || {
+            // Hold off opening databases while the closed ones are re-keyed,
+            // so none are opened with the wrong key.
+            DATABASE_HANDLES.with_paths_locked(|open| {
+                for path in database_files(&root)? {
+                    if !open.contains(&&path) {
+                        encrypt_database_file(&path, &key, std::slice::from_ref(&previous))?;
+                    }
+                }
+                set_database_key(&root, key);
+                DatabaseResult::Ok(())
+            })
+        })
+        .await??;
+    }
+    DATABASE_HANDLES.rekey_under(root, &key).await
+}
+
+/// All the database files under a directory.
+pub(crate) fn database_files(dir: &Path) -> DatabaseResult<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            files.extend(database_files(&path)?);
+        } else if path.extension().map_or(false, |ext| ext == "sqlite3") {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+/// Open a database file with a key, if it can be read with that key.
+#[cfg(feature = "sqlite-encrypted")]
+fn open_with_key(path: &Path, key: Option<&str>) -> DatabaseResult<Option<Connection>> {
+    let conn = Connection::open(path)?;
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", key)?;
+    }
+    match conn.query_row("SELECT count(*) FROM sqlite_master", [], |_| Ok(())) {
+        Ok(()) => Ok(Some(conn)),
+        Err(rusqlite::Error::SqliteFailure(
+            rusqlite::ffi::Error {
+                code: rusqlite::ErrorCode::NotADatabase,
+                ..
+            },
+            _,
+        )) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(feature = "sqlite-encrypted")]
+fn encrypt_database_file(path: &Path, key: &DbKey, previous: &[DbKey]) -> DatabaseResult<()> {
+    let key_value = key.pragma_value();
+    if open_with_key(path, Some(&key_value))?.is_some() {
+        return Ok(());
+    }
+
+    let previous_values = previous.iter().map(DbKey::pragma_value);
+    for old_value in previous_values.chain(std::iter::once(LEGACY_KEY.to_string())) {
+        if let Some(conn) = open_with_key(path, Some(&old_value))? {
+            tracing::info!(?path, "Re-keying database");
+            conn.pragma_update(None, "rekey", &key_value)?;
+            return Ok(());
+        }
+    }
+
+    if let Some(conn) = open_with_key(path, None)? {
+        tracing::info!(?path, "Encrypting database");
+        let mut encrypted = path.as_os_str().to_owned();
+        encrypted.push("-encrypted");
+        let encrypted = PathBuf::from(encrypted);
+        if encrypted.exists() {
+            std::fs::remove_file(&encrypted)?;
+        }
+        conn.execute(
+            "ATTACH DATABASE ?1 AS encrypted KEY ?2",
+            rusqlite::params![encrypted.to_string_lossy(), key_value],
+        )?;
+        conn.query_row("SELECT sqlcipher_export('encrypted')", [], |_| Ok(()))?;
+        // The schema version isn't exported along with the schema.
+        let user_version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        conn.pragma_update(
+            Some(rusqlite::DatabaseName::Attached("encrypted")),
+            "user_version",
+            user_version,
+        )?;
+        conn.execute("DETACH DATABASE encrypted", [])?;
+        drop(conn);
+        for suffix in ["-wal", "-shm"] {
+            let mut journal = path.as_os_str().to_owned();
+            journal.push(suffix);
+            let journal = PathBuf::from(journal);
+            if journal.exists() {
+                std::fs::remove_file(journal)?;
+            }
+        }
+        std::fs::rename(encrypted, path)?;
+        return Ok(());
+    }
+
+    Err(DatabaseError::UndecryptableDatabase(path.to_path_buf()))
+}
+
+#[cfg(all(test, feature = "sqlite-encrypted"))]
+mod tests {
+    use super::*;
+    use crate::db::{DbKindWasm, DbWrite};
+
+    fn count(path: &Path, key: &DbKey, table: &str) -> Option<i64> {
+        open_with_key(path, Some(&key.pragma_value()))
+            .unwrap()
+            .map(|conn| {
+                conn.query_row(&format!("SELECT count(*) FROM {}", table), [], |row| {
+                    row.get(0)
+                })
+                .unwrap()
+            })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn encrypt_then_rotate() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        std::fs::create_dir(root.join("legacy")).unwrap();
+        let plain = root.join("plain.sqlite3");
+        let legacy = root.join("legacy").join("legacy.sqlite3");
+        for (path, key) in [(&plain, None), (&legacy, Some(LEGACY_KEY))] {
+            let conn = Connection::open(path).unwrap();
+            if let Some(key) = key {
+                conn.pragma_update(None, "key", key).unwrap();
+            }
+            conn.pragma_update(None, "journal_mode", "WAL").unwrap();
+            conn.pragma_update(None, "user_version", 3).unwrap();
+            conn.execute_batch("CREATE TABLE T (x INTEGER); INSERT INTO T VALUES (1);")
+                .unwrap();
+        }
+
+        let key = DbKey::generate();
+        encrypt_databases(root, &key, &[]).unwrap();
+        for path in [&plain, &legacy] {
+            assert!(open_with_key(path, None).unwrap().is_none());
+            assert_eq!(count(path, &key, "T"), Some(1));
+            let conn = open_with_key(path, Some(&key.pragma_value()))
+                .unwrap()
+                .unwrap();
+            let user_version: i64 = conn
+                .query_row("PRAGMA user_version", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(user_version, 3);
+        }
+
+        set_database_key(root, key.clone());
+        let db = DbWrite::open(root, DbKindWasm).unwrap();
+        let insert = |db: DbWrite<DbKindWasm>| async move {
+            db.write_async(|txn| {
+                txn.execute(
+                    "INSERT INTO Wasm (hash, blob) VALUES (randomblob(32), ?)",
+                    [vec![0]],
+                )?;
+                DatabaseResult::Ok(())
+            })
+            .await
+            .unwrap();
+        };
+        insert(db.clone()).await;
+        let wasm = root.join("wasm").join("wasm.sqlite3");
+        assert_eq!(count(&wasm, &key, "Wasm"), Some(1));
+
+        let new_key = DbKey::generate();
+        rotate_database_key(root, new_key.clone()).await.unwrap();
+        insert(db.clone()).await;
+        for (path, table, n) in [(&plain, "T", 1), (&legacy, "T", 1), (&wasm, "Wasm", 2)] {
+            assert_eq!(count(path, &key, table), None);
+            assert_eq!(count(path, &new_key, table), Some(n));
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Reporting and changing the schema versions of the databases in a directory.
+//!
+//! Databases are migrated forward automatically when they are opened. These
+//! functions are for migrating them back down, so that an older version of
+//! Holochain can use them, and must only be used while none of the databases
+//! are open.
+
+use crate::db::key::{database_files, database_key};
+use crate::db::pool::{initialize_connection, DbSyncLevel};
+use crate::error::{DatabaseError, DatabaseResult};
+use crate::schema::{Schema, SchemaKind};
+use rusqlite::Connection;
+use std::path::{Path, PathBuf};
+
+/// The schema version of a database file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DatabaseVersion {
+    /// The path of the database file.
+    pub path: PathBuf,
+    /// The schema of the database.
+    pub schema: SchemaKind,
+    /// The version of the schema the database is at.
+    pub version: u16,
+}
+
+/// The schema version of every database under a root directory.
+pub fn database_versions(root: &Path) -> DatabaseResult<Vec<DatabaseVersion>> {
+    let mut versions = Vec::new();
+    for (path, schema) in schema_files(root)? {
+        let conn = open(root, &path)?;
+        versions.push(DatabaseVersion {
+            version: Schema::database_version(&conn)?,
+            path,
+            schema,
+        });
+    }
+    Ok(versions)
+}
+
+/// Migrate every database with a schema under a root directory to a version
+/// of that schema, for each of the given schemas, returning the databases' new
+/// versions.
+///
+/// Every database under the root is locked first, so that nothing else can
+/// use them while they are migrated, and the migration is refused if any of
+/// them are already in use, for example by a running conductor. Each database
+/// is migrated in one transaction, and if one fails, the error lists the
+/// databases which were already migrated.
+pub fn migrate_databases(
+    root: &Path,
+    versions: &[(SchemaKind, u16)],
+) -> DatabaseResult<Vec<DatabaseVersion>> {
+    let mut locked = schema_files(root)?
+        .into_iter()
+        .map(|(path, schema)| Ok((lock(root, &path)?, path, schema)))
+        .collect::<DatabaseResult<Vec<_>>>()?;
+    let mut migrated = Vec::new();
+    for (conn, path, schema) in locked.iter_mut() {
+        let version = match versions.iter().find(|(kind, _)| kind == schema) {
+            Some((_, version)) => *version,
+            None => continue,
+        };
+        if let Err(e) = schema
+            .schema()
+            .migrate_to(conn, &path.display().to_string(), version)
+        {
+            return Err(DatabaseError::MigrationIncomplete {
+                path: path.clone(),
+                migrated: migrated
+                    .into_iter()
+                    .map(|version: DatabaseVersion| version.path)
+                    .collect(),
+                source: Box::new(e),
+            });
+        }
+        migrated.push(DatabaseVersion {
+            path: path.clone(),
+            schema: *schema,
+            version,
+        });
+    }
+    Ok(migrated)
+}
+
+/// The database files under a root directory, along with their schemas.
+fn schema_files(root: &Path) -> DatabaseResult<Vec<(PathBuf, SchemaKind)>> {
+    Ok(database_files(root)?
+        .into_iter()
+        .filter_map(|path| {
+            let dir = path.strip_prefix(root).ok()?.components().next()?;
+            let name = path.file_name()?.to_str()?;
+            let schema = match dir.as_os_str().to_str()? {
+                "authored" | "dht" | "cache" => SchemaKind::Cell,
+                "conductor" => SchemaKind::Conductor,
+                "wasm" => SchemaKind::Wasm,
+                "p2p" if name.starts_with("p2p_agent_store") => SchemaKind::P2pAgentStore,
+                "p2p" if name.starts_with("p2p_metrics") => SchemaKind::P2pMetrics,
+                _ => return None,
+            };
+            Some((path, schema))
+        })
+        .collect())
+}
+
+fn open(root: &Path, path: &Path) -> DatabaseResult<Connection> {
+    let mut conn = Connection::open(path)?;
+    initialize_connection(
+        &mut conn,
+        DbSyncLevel::default(),
+        database_key(root).as_ref(),
+    )?;
+    Ok(conn)
+}
+
+/// Open a database and hold an exclusive lock on it until the connection is
+/// closed, failing straight away if anything else has it open.
+fn lock(root: &Path, path: &Path) -> DatabaseResult<Connection> {
+    let conn = open(root, path)?;
+    conn.busy_timeout(std::time::Duration::ZERO)?;
+    conn.pragma_update(None, "locking_mode", "EXCLUSIVE")?;
+    match conn.execute_batch("BEGIN EXCLUSIVE; COMMIT;") {
+        Ok(()) => Ok(conn),
+        Err(rusqlite::Error::SqliteFailure(e, _))
+            if e.code == rusqlite::ErrorCode::DatabaseBusy =>
+        {
+            Err(DatabaseError::DatabaseInUse(path.to_path_buf()))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{DbKindConductor, DbKindWasm, DbWrite};
+
+    #[test]
+    fn migrate_conductor_databases_down() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        // Opened without being kept open, unlike with `DbWrite::open`.
+        DbWrite::new(Some(root), DbKindConductor, DbSyncLevel::default(), None).unwrap();
+        DbWrite::new(Some(root), DbKindWasm, DbSyncLevel::default(), None).unwrap();
+
+        let version_of = |schema: SchemaKind| {
+            database_versions(root)
+                .unwrap()
+                .into_iter()
+                .find(|v| v.schema == schema)
+                .unwrap()
+                .version
+        };
+        assert_eq!(
+            version_of(SchemaKind::Conductor),
+            SchemaKind::Conductor.schema().version()
+        );
+        assert_eq!(version_of(SchemaKind::Wasm), 1);
+
+        let migrated = migrate_databases(root, &[(SchemaKind::Conductor, 1)]).unwrap();
+        assert_eq!(migrated.len(), 1);
+        assert_eq!(version_of(SchemaKind::Conductor), 1);
+        assert_eq!(version_of(SchemaKind::Wasm), 1);
+    }
+
+    #[test]
+    fn databases_in_use_are_not_migrated() {
+        let root = tempfile::tempdir().unwrap();
+        let root = root.path();
+        // Keep the database open, as a running conductor would.
+        let db = DbWrite::new(Some(root), DbKindConductor, DbSyncLevel::default(), None).unwrap();
+
+        assert!(matches!(
+            migrate_databases(root, &[(SchemaKind::Conductor, 1)]),
+            Err(DatabaseError::DatabaseInUse(_))
+        ));
+        drop(db);
+        migrate_databases(root, &[(SchemaKind::Conductor, 1)]).unwrap();
+    }
+}
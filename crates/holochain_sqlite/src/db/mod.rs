@@ -4,7 +4,9 @@ mod access;
 mod conn;
 mod databases;
 mod guard;
+mod key;
 mod kind;
+mod migrate;
 mod pool;
 
 #[cfg(test)]
@@ -12,10 +14,14 @@ mod tests;
 
 pub use access::{DbRead, DbWrite, ReadAccess};
 pub use guard::PTxnGuard;
+#[cfg(feature = "sqlite-encrypted")]
+pub use key::{encrypt_databases, rotate_database_key};
+pub use key::{set_database_key, DbKey};
 pub use kind::{
     DbKind, DbKindAuthored, DbKindCache, DbKindConductor, DbKindDht, DbKindOp, DbKindP2pAgents,
     DbKindP2pMetrics, DbKindT, DbKindWasm,
 };
+pub use migrate::{database_versions, migrate_databases, DatabaseVersion};
 pub use pool::{DbSyncLevel, DbSyncStrategy};
 
 #[cfg(feature = "test_utils")]
@@ -1,3 +1,4 @@
+use crate::db::key::DbKey;
 use crate::functions::add_custom_functions;
 use holochain_serialized_bytes::prelude::*;
 use once_cell::sync::Lazy;
@@ -50,13 +51,17 @@ pub enum DbSyncStrategy {
 pub(super) fn new_connection_pool(
     path: Option<&Path>,
     synchronous_level: DbSyncLevel,
+    key: Option<DbKey>,
 ) -> ConnectionPool {
     use r2d2_sqlite::SqliteConnectionManager;
     let manager = match path {
         Some(path) => SqliteConnectionManager::file(path),
         None => SqliteConnectionManager::memory(),
     };
-    let customizer = Box::new(ConnCustomizer { synchronous_level });
+    let customizer = Box::new(ConnCustomizer {
+        synchronous_level,
+        key,
+    });
 
     /*
      * We want
@@ -85,6 +90,7 @@ pub(super) fn new_connection_pool(
 #[derive(Debug)]
 struct ConnCustomizer {
     synchronous_level: DbSyncLevel,
+    key: Option<DbKey>,
 }
 
 impl Default for DbSyncLevel {
@@ -101,7 +107,7 @@ impl Default for DbSyncStrategy {
 
 impl r2d2::CustomizeConnection<Connection, rusqlite::Error> for ConnCustomizer {
     fn on_acquire(&self, conn: &mut Connection) -> Result<(), rusqlite::Error> {
-        initialize_connection(conn, self.synchronous_level)?;
+        initialize_connection(conn, self.synchronous_level, self.key.as_ref())?;
         Ok(())
     }
 }
@@ -109,25 +115,17 @@ impl r2d2::CustomizeConnection<Connection, rusqlite::Error> for ConnCustomizer {
 pub(super) fn initialize_connection(
     conn: &mut Connection,
     synchronous_level: DbSyncLevel,
+    key: Option<&DbKey>,
 ) -> Result<()> {
     // Tell SQLite to wait this long during write contention.
     conn.busy_timeout(SQLITE_BUSY_TIMEOUT)?;
 
     #[cfg(feature = "sqlite-encrypted")]
-    {
-        use std::io::Write;
-        let key = get_encryption_key_shim();
-        let mut hex = *br#"0000000000000000000000000000000000000000000000000000000000000000"#;
-        let mut c = std::io::Cursor::new(&mut hex[..]);
-        for b in &key {
-            write!(c, "{:02X}", b)
-                .map_err(|e| rusqlite::Error::ToSqlConversionFailure(Box::new(e)))?;
-        }
-        let _keyval = std::str::from_utf8(&hex).unwrap();
-        const FAKE_KEY: &str = "x'98483C6EB40B6C31A448C22A66DED3B5E5E8D5119CAC8327B655C8B5C483648101010101010101010101010101010101'";
-        // conn.pragma_update(None, "key", &keyval)?;
-        conn.pragma_update(None, "key", &FAKE_KEY)?;
+    if let Some(key) = key {
+        conn.pragma_update(None, "key", key.pragma_value())?;
     }
+    #[cfg(not(feature = "sqlite-encrypted"))]
+    let _ = key;
 
     // this is recommended to always be off:
     // https://sqlite.org/pragma.html#pragma_trusted_schema
@@ -153,15 +151,6 @@ pub fn num_read_threads() -> usize {
     std::cmp::max(num_threads, 4)
 }
 
-#[cfg(feature = "sqlite-encrypted")]
-/// Simulate getting an encryption key from Lair.
-fn get_encryption_key_shim() -> [u8; 32] {
-    [
-        26, 111, 7, 31, 52, 204, 156, 103, 203, 171, 156, 89, 98, 51, 158, 143, 57, 134, 93, 56,
-        199, 225, 53, 141, 39, 77, 145, 130, 136, 108, 96, 201,
-    ]
-}
-
 #[cfg(feature = "test_utils")]
 pub fn set_connection_timeout(timeout_ms: u64) {
     CONNECTION_TIMEOUT_MS.store(timeout_ms, Ordering::Relaxed);
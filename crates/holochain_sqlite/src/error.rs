@@ -42,6 +42,36 @@ pub enum DatabaseError {
     #[error(transparent)]
     DbConnectionPoolError(#[from] r2d2::Error),
 
+    #[error("The database at {0} could not be decrypted with any known key")]
+    UndecryptableDatabase(PathBuf),
+
+    #[error("The {db_kind} database is at schema version {version}, but this version of Holochain only understands up to version {supported}. It was probably last used by a newer version of Holochain, and must be migrated down by that version before it can be used here.")]
+    SchemaTooNew {
+        db_kind: String,
+        version: u16,
+        supported: u16,
+    },
+
+    #[error("Schema version {version} of the {db_kind} database is unknown, the latest version is {supported}")]
+    UnknownSchemaVersion {
+        db_kind: String,
+        version: u16,
+        supported: u16,
+    },
+
+    #[error("The {db_kind} database can't be migrated down from schema version {version}, because that migration can't be undone")]
+    NoBackwardMigration { db_kind: String, version: u16 },
+
+    #[error("The database at {0} is in use, and can't be migrated while Holochain is running")]
+    DatabaseInUse(PathBuf),
+
+    #[error("Failed to migrate the database at {path}, after migrating {migrated:?}: {source}")]
+    MigrationIncomplete {
+        path: PathBuf,
+        migrated: Vec<PathBuf>,
+        source: Box<DatabaseError>,
+    },
+
     #[error("Empty keys cannot be used with SQLite")]
     EmptyKey,
 
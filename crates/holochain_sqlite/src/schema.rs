@@ -3,7 +3,9 @@
 //! To create a new migration, add a new [`Migration`] object to the `migrations`
 //! vec for a particular schema, and bump the `current_index` by 1.
 //! The `Migration` must specify the actual forward migration script, as well as
-//! an updated schema defining the result of running the migration.
+//! an updated schema defining the result of running the migration. It should
+//! also specify a backward migration script which undoes the forward one, so
+//! that databases can be migrated back down for an older version of Holochain.
 //!
 //! Currently, the updated schema only serves as a point of reference for examining
 //! the current schema. In the future, we should find a way to compare the actual
@@ -17,14 +19,21 @@ use once_cell::sync::Lazy;
 use rusqlite::{Connection, Transaction};
 
 use crate::db::DbKind;
+use crate::error::{DatabaseError, DatabaseResult};
 
 pub static SCHEMA_CELL: Lazy<Schema> = Lazy::new(|| Schema {
     migrations: vec![
         M::initial(include_str!("sql/cell/schema/0.sql")),
         M {
             forward: include_str!("sql/cell/schema/1-up.sql").into(),
+            backward: Some(include_str!("sql/cell/schema/1-down.sql").into()),
             _schema: include_str!("sql/cell/schema/1.sql").into(),
         },
+        M {
+            forward: include_str!("sql/cell/schema/2-up.sql").into(),
+            backward: Some(include_str!("sql/cell/schema/2-down.sql").into()),
+            _schema: include_str!("sql/cell/schema/2.sql").into(),
+        },
     ],
 });
 
@@ -33,8 +42,14 @@ pub static SCHEMA_CONDUCTOR: Lazy<Schema> = Lazy::new(|| Schema {
         M::initial(include_str!("sql/conductor/schema/0.sql")),
         M {
             forward: include_str!("sql/conductor/schema/1.sql").into(),
+            backward: Some(include_str!("sql/conductor/schema/1-down.sql").into()),
             _schema: "".into(),
         },
+        M {
+            forward: include_str!("sql/conductor/schema/2-up.sql").into(),
+            backward: Some(include_str!("sql/conductor/schema/2-down.sql").into()),
+            _schema: include_str!("sql/conductor/schema/2.sql").into(),
+        },
     ],
 });
 
@@ -50,57 +65,199 @@ pub static SCHEMA_P2P_METRICS: Lazy<Schema> = Lazy::new(|| Schema {
     migrations: vec![M::initial(include_str!("sql/p2p_metrics/schema/0.sql"))],
 });
 
+/// The kinds of database which each have their own [`Schema`].
+/// Authored, DHT and cache databases all share the cell schema.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, derive_more::Display)]
+pub enum SchemaKind {
+    /// The schema of authored, DHT and cache databases.
+    #[display(fmt = "cell")]
+    Cell,
+    /// The schema of the conductor database.
+    #[display(fmt = "conductor")]
+    Conductor,
+    /// The schema of the wasm database.
+    #[display(fmt = "wasm")]
+    Wasm,
+    /// The schema of the p2p agent store databases.
+    #[display(fmt = "p2p_agent_store")]
+    P2pAgentStore,
+    /// The schema of the p2p metrics databases.
+    #[display(fmt = "p2p_metrics")]
+    P2pMetrics,
+}
+
+impl SchemaKind {
+    /// Every kind of schema.
+    pub const ALL: [SchemaKind; 5] = [
+        SchemaKind::Cell,
+        SchemaKind::Conductor,
+        SchemaKind::Wasm,
+        SchemaKind::P2pAgentStore,
+        SchemaKind::P2pMetrics,
+    ];
+
+    /// The schema itself.
+    pub fn schema(&self) -> &'static Schema {
+        match self {
+            SchemaKind::Cell => &SCHEMA_CELL,
+            SchemaKind::Conductor => &SCHEMA_CONDUCTOR,
+            SchemaKind::Wasm => &SCHEMA_WASM,
+            SchemaKind::P2pAgentStore => &SCHEMA_P2P_STATE,
+            SchemaKind::P2pMetrics => &SCHEMA_P2P_METRICS,
+        }
+    }
+
+    /// The schema of a kind of database, if it has one.
+    pub fn of(db_kind: &DbKind) -> Option<Self> {
+        match db_kind {
+            DbKind::Authored(_) | DbKind::Dht(_) | DbKind::Cache(_) => Some(SchemaKind::Cell),
+            DbKind::Conductor => Some(SchemaKind::Conductor),
+            DbKind::Wasm => Some(SchemaKind::Wasm),
+            DbKind::P2pAgentStore(_) => Some(SchemaKind::P2pAgentStore),
+            DbKind::P2pMetrics(_) => Some(SchemaKind::P2pMetrics),
+            #[cfg(feature = "test_utils")]
+            DbKind::Test(_) => None,
+        }
+    }
+}
+
+impl std::str::FromStr for SchemaKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.to_string() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown schema {:?}, expected one of: {}",
+                    s,
+                    Self::ALL.map(|kind| kind.to_string()).join(", ")
+                )
+            })
+    }
+}
+
 pub struct Schema {
     migrations: Vec<Migration>,
 }
 
 impl Schema {
+    /// The version of a database which has had every migration of this
+    /// schema applied. This is the latest version this build understands.
+    pub fn version(&self) -> u16 {
+        self.migrations.len() as u16
+    }
+
+    /// The version of the schema a database is at, which is the number of
+    /// migrations which have been applied to it.
+    pub fn database_version(conn: &Connection) -> rusqlite::Result<u16> {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+    }
+
     /// Determine if any database migrations need to run, and run them if so.
-    /// The decision is based on the difference between this Schema's
-    /// current_index and the user_version pragma value in the database itself.
-    /// NB: The current_index is 0-based, and the user_version is 1-based.
-    pub fn initialize(
-        &self,
-        conn: &mut Connection,
-        db_kind: Option<DbKind>,
-    ) -> rusqlite::Result<()> {
-        let user_version: u16 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    /// The decision is based on the difference between this Schema's version,
+    /// which is the number of migrations it has, and the user_version pragma
+    /// value in the database itself, which is the number of migrations which
+    /// have been applied to it.
+    ///
+    /// Databases at a newer version than this schema knows of were written by
+    /// a newer version of Holochain, and are refused rather than read.
+    pub fn initialize(&self, conn: &mut Connection, db_kind: Option<DbKind>) -> DatabaseResult<()> {
+        let user_version = Self::database_version(conn)?;
         let db_kind = db_kind
             .as_ref()
             .map(ToString::to_string)
             .unwrap_or_else(|| "<no name>".to_string());
 
-        let migrations_applied = user_version as usize;
-        let num_migrations = self.migrations.len();
-        match migrations_applied.cmp(&(num_migrations)) {
-            std::cmp::Ordering::Less => {
-                let mut txn = conn.transaction()?;
-                // run forward migrations
-                for v in migrations_applied..num_migrations {
-                    self.migrations[v].run_forward(&mut txn)?;
-                    // set the DB user_version so that next time we don't run
-                    // the same migration
-                    txn.pragma_update(None, "user_version", v + 1)?;
-                }
-                txn.commit()?;
-                tracing::info!(
-                    "database forward migrated: {} from {} to {}",
-                    db_kind,
-                    migrations_applied,
-                    num_migrations - 1,
-                );
-            }
-            std::cmp::Ordering::Equal => {
-                tracing::debug!(
-                    "database needed no migration or initialization, good to go: {}",
-                    db_kind
-                );
+        if user_version > self.version() {
+            return Err(DatabaseError::SchemaTooNew {
+                db_kind,
+                version: user_version,
+                supported: self.version(),
+            });
+        }
+        if user_version == self.version() {
+            tracing::debug!(
+                "database needed no migration or initialization, good to go: {}",
+                db_kind
+            );
+            return Ok(());
+        }
+        self.migrate(conn, &db_kind, user_version, self.version())
+    }
+
+    /// Migrate a database forward or backward to a version of this schema.
+    /// The database is called `name` in logs and errors.
+    ///
+    /// Migrating backward fails without changing the database if any of the
+    /// migrations to undo has no backward script.
+    pub fn migrate_to(
+        &self,
+        conn: &mut Connection,
+        name: &str,
+        version: u16,
+    ) -> DatabaseResult<()> {
+        let user_version = Self::database_version(conn)?;
+        if user_version > self.version() {
+            return Err(DatabaseError::SchemaTooNew {
+                db_kind: name.to_string(),
+                version: user_version,
+                supported: self.version(),
+            });
+        }
+        if version > self.version() {
+            return Err(DatabaseError::UnknownSchemaVersion {
+                db_kind: name.to_string(),
+                version,
+                supported: self.version(),
+            });
+        }
+        self.migrate(conn, name, user_version, version)
+    }
+
+    fn migrate(
+        &self,
+        conn: &mut Connection,
+        db_kind: &str,
+        from: u16,
+        to: u16,
+    ) -> DatabaseResult<()> {
+        let mut txn = conn.transaction()?;
+        if from < to {
+            // run forward migrations
+            for v in from..to {
+                self.migrations[v as usize].run_forward(&mut txn)?;
+                // set the DB user_version so that next time we don't run
+                // the same migration
+                txn.pragma_update(None, "user_version", v + 1)?;
             }
-            std::cmp::Ordering::Greater => {
-                unimplemented!("backward migrations unimplemented");
+            txn.commit()?;
+            tracing::info!(
+                "database forward migrated: {} from {} to {}",
+                db_kind,
+                from,
+                to,
+            );
+        } else if from > to {
+            // run backward migrations, starting with the latest
+            for v in (to..from).rev() {
+                if !self.migrations[v as usize].run_backward(&mut txn)? {
+                    return Err(DatabaseError::NoBackwardMigration {
+                        db_kind: db_kind.to_string(),
+                        version: v + 1,
+                    });
+                }
+                txn.pragma_update(None, "user_version", v)?;
             }
+            txn.commit()?;
+            tracing::info!(
+                "database backward migrated: {} from {} to {}",
+                db_kind,
+                from,
+                to,
+            );
         }
-
         Ok(())
     }
 }
@@ -109,14 +266,17 @@ impl Schema {
 pub struct Migration {
     _schema: Sql,
     forward: Sql,
+    backward: Option<Sql>,
 }
 
 impl Migration {
-    /// The initial migration's forward migration is the entire schema
+    /// The initial migration's forward migration is the entire schema.
+    /// It has no backward migration, since there is nothing to go back to.
     pub fn initial(schema: &str) -> Self {
         Self {
             _schema: schema.into(),
             forward: schema.into(),
+            backward: None,
         }
     }
 
@@ -124,6 +284,18 @@ impl Migration {
         txn.execute_batch(&self.forward)?;
         Ok(())
     }
+
+    /// Undo the forward migration, returning false if there is no backward
+    /// migration to run.
+    pub fn run_backward(&self, txn: &mut Transaction) -> rusqlite::Result<bool> {
+        match &self.backward {
+            Some(backward) => {
+                txn.execute_batch(backward)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
 }
 type M = Migration;
 
@@ -140,6 +312,7 @@ mod tests {
                 M::initial("CREATE TABLE Numbers (num INTEGER);"),
                 M {
                     forward: "CREATE TABLE Names (name TEXT);".into(),
+                    backward: None,
                     _schema: "n/a".into(),
                 },
             ],
@@ -185,6 +358,7 @@ mod tests {
             M::initial("This bad SQL won't run, phew!"),
             M {
                 forward: "CREATE TABLE Names (name TEXT);".into(),
+                backward: None,
                 _schema: "n/a".into(),
             },
         ];
@@ -200,4 +374,75 @@ mod tests {
             1
         );
     }
+
+    #[test]
+    fn test_migrations_backward() {
+        let schema = Schema {
+            migrations: vec![
+                M::initial("CREATE TABLE Numbers (num INTEGER);"),
+                M {
+                    forward: "CREATE TABLE Names (name TEXT);".into(),
+                    backward: Some("DROP TABLE Names;".into()),
+                    _schema: "n/a".into(),
+                },
+            ],
+        };
+        let mut conn = Connection::open_in_memory().unwrap();
+        schema.initialize(&mut conn, None).unwrap();
+
+        schema.migrate_to(&mut conn, "test", 1).unwrap();
+        assert_eq!(Schema::database_version(&conn).unwrap(), 1);
+        assert!(conn
+            .execute("INSERT INTO Names (name) VALUES ('Mike')", ())
+            .is_err());
+
+        schema.migrate_to(&mut conn, "test", 2).unwrap();
+        assert_eq!(Schema::database_version(&conn).unwrap(), 2);
+        conn.execute("INSERT INTO Names (name) VALUES ('Mike')", ())
+            .unwrap();
+
+        // The initial migration can't be undone, and nothing else is undone
+        // when that fails.
+        assert!(matches!(
+            schema.migrate_to(&mut conn, "test", 0),
+            Err(DatabaseError::NoBackwardMigration { version: 1, .. })
+        ));
+        assert_eq!(Schema::database_version(&conn).unwrap(), 2);
+        assert!(matches!(
+            schema.migrate_to(&mut conn, "test", 3),
+            Err(DatabaseError::UnknownSchemaVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn test_newer_database_is_refused() {
+        let schema = Schema {
+            migrations: vec![M::initial("CREATE TABLE Numbers (num INTEGER);")],
+        };
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", 2).unwrap();
+        assert!(matches!(
+            schema.initialize(&mut conn, None),
+            Err(DatabaseError::SchemaTooNew {
+                version: 2,
+                supported: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_schemas_migrate_down_and_up() {
+        for kind in SchemaKind::ALL {
+            let schema = kind.schema();
+            let mut conn = Connection::open_in_memory().unwrap();
+            conn.pragma_update(None, "foreign_keys", "ON").unwrap();
+            schema.initialize(&mut conn, None).unwrap();
+            schema.migrate_to(&mut conn, "test", 1).unwrap();
+            schema
+                .migrate_to(&mut conn, "test", schema.version())
+                .unwrap();
+            assert_eq!(Schema::database_version(&conn).unwrap(), schema.version());
+        }
+    }
 }
@@ -0,0 +1,17 @@
+ALTER TABLE
+  ValidationReceipt RENAME TO ValidationReceipt_1Down;
+CREATE TABLE ValidationReceipt (
+  hash BLOB PRIMARY KEY ON CONFLICT IGNORE,
+  op_hash BLOB NOT NULL,
+  blob BLOB NOT NULL,
+  FOREIGN KEY(op_hash) REFERENCES DhtOp(hash)
+);
+INSERT INTO
+  ValidationReceipt (hash, op_hash, blob)
+SELECT
+  hash,
+  op_hash,
+  blob
+FROM
+  ValidationReceipt_1Down;
+DROP TABLE ValidationReceipt_1Down;
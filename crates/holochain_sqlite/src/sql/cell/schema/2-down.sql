@@ -0,0 +1,103 @@
+-- no-sql-format --
+-- Warrant ops used to be stored along with the warranted action, so any
+-- action which is held only for a warrant is removed as well.
+DELETE FROM
+  Action
+WHERE
+  hash IN (
+    SELECT
+      action_hash
+    FROM
+      DhtOp
+    WHERE
+      hash IN (
+        SELECT
+          hash
+        FROM
+          Warrant
+      )
+  )
+  AND hash NOT IN (
+    SELECT
+      action_hash
+    FROM
+      DhtOp
+    WHERE
+      hash NOT IN (
+        SELECT
+          hash
+        FROM
+          Warrant
+      )
+  );
+-- Earlier versions can't read warrant ops, so they are removed along with
+-- their warrants.
+DELETE FROM
+  DhtOp
+WHERE
+  hash IN (
+    SELECT
+      hash
+    FROM
+      Warrant
+  );
+DROP INDEX IF EXISTS Warrant_warrantee_idx;
+DROP TABLE IF EXISTS Warrant;
+-- Earlier versions delete the ops of an action by a foreign key.
+DROP TRIGGER IF EXISTS DhtOp_delete_with_action;
+CREATE TABLE DhtOp_2Down (
+  hash BLOB PRIMARY KEY ON CONFLICT IGNORE,
+  type TEXT NOT NULL,
+  basis_hash BLOB NOT NULL,
+  action_hash BLOB NOT NULL,
+  require_receipt INTEGER NOT NULL,
+  storage_center_loc INTEGER NOT NULL,
+  authored_timestamp INTEGER NOT NULL,
+  op_order TEXT NOT NULL,
+  validation_status INTEGER NULL,
+  when_integrated INTEGER NULL,
+  withhold_publish INTEGER NULL,
+  receipts_complete INTEGER NULL,
+  last_publish_time INTEGER NULL,
+  validation_stage INTEGER NULL,
+  num_validation_attempts INTEGER NULL,
+  last_validation_attempt INTEGER NULL,
+  dependency BLOB NULL,
+  FOREIGN KEY(action_hash) REFERENCES Action(hash) ON DELETE CASCADE
+);
+INSERT INTO
+  DhtOp_2Down (hash, type, basis_hash, action_hash, require_receipt, storage_center_loc, authored_timestamp, op_order, validation_status, when_integrated, withhold_publish, receipts_complete, last_publish_time, validation_stage, num_validation_attempts, last_validation_attempt, dependency)
+SELECT
+  hash, type, basis_hash, action_hash, require_receipt, storage_center_loc, authored_timestamp, op_order, validation_status, when_integrated, withhold_publish, receipts_complete, last_publish_time, validation_stage, num_validation_attempts, last_validation_attempt, dependency
+FROM
+  DhtOp;
+-- Dropping DhtOp deletes the validation receipts of its ops, so they are
+-- put back once it has been replaced.
+CREATE TEMP TABLE ValidationReceipt_2Down AS
+SELECT
+  hash,
+  op_hash,
+  blob
+FROM
+  ValidationReceipt;
+DROP TABLE DhtOp;
+ALTER TABLE
+  DhtOp_2Down RENAME TO DhtOp;
+INSERT INTO
+  ValidationReceipt (hash, op_hash, blob)
+SELECT
+  hash,
+  op_hash,
+  blob
+FROM
+  ValidationReceipt_2Down;
+DROP TABLE ValidationReceipt_2Down;
+CREATE INDEX IF NOT EXISTS DhtOp_type_dep_idx ON DhtOp (type, dependency);
+CREATE INDEX IF NOT EXISTS DhtOp_type_when_int_idx ON DhtOp (type, when_integrated);
+CREATE INDEX IF NOT EXISTS DhtOp_validation_stage_idx ON DhtOp (validation_stage, type, dependency);
+CREATE INDEX IF NOT EXISTS DhtOp_stage_type_status_idx ON DhtOp (validation_stage, type, validation_status);
+CREATE INDEX IF NOT EXISTS DhtOp_validation_status_idx ON DhtOp (validation_status);
+CREATE INDEX IF NOT EXISTS DhtOp_authored_timestamp_idx ON DhtOp (authored_timestamp);
+CREATE INDEX IF NOT EXISTS DhtOp_storage_center_loc_idx ON DhtOp (storage_center_loc);
+CREATE INDEX IF NOT EXISTS DhtOp_action_hash_idx ON DhtOp (action_hash);
+CREATE INDEX IF NOT EXISTS DhtOp_basis_hash_idx ON DhtOp (basis_hash);
@@ -0,0 +1,72 @@
+-- no-sql-format --
+-- A warrant op is stored without the action it warrants, so the ops of an
+-- action are no longer tied to it by a foreign key, but are still deleted
+-- along with it by a trigger.
+CREATE TABLE DhtOp_2Up (
+  hash BLOB PRIMARY KEY ON CONFLICT IGNORE,
+  type TEXT NOT NULL,
+  basis_hash BLOB NOT NULL,
+  action_hash BLOB NOT NULL,
+  require_receipt INTEGER NOT NULL,
+  storage_center_loc INTEGER NOT NULL,
+  authored_timestamp INTEGER NOT NULL,
+  op_order TEXT NOT NULL,
+  validation_status INTEGER NULL,
+  when_integrated INTEGER NULL,
+  withhold_publish INTEGER NULL,
+  receipts_complete INTEGER NULL,
+  last_publish_time INTEGER NULL,
+  validation_stage INTEGER NULL,
+  num_validation_attempts INTEGER NULL,
+  last_validation_attempt INTEGER NULL,
+  dependency BLOB NULL
+);
+INSERT INTO
+  DhtOp_2Up (hash, type, basis_hash, action_hash, require_receipt, storage_center_loc, authored_timestamp, op_order, validation_status, when_integrated, withhold_publish, receipts_complete, last_publish_time, validation_stage, num_validation_attempts, last_validation_attempt, dependency)
+SELECT
+  hash, type, basis_hash, action_hash, require_receipt, storage_center_loc, authored_timestamp, op_order, validation_status, when_integrated, withhold_publish, receipts_complete, last_publish_time, validation_stage, num_validation_attempts, last_validation_attempt, dependency
+FROM
+  DhtOp;
+-- Dropping DhtOp deletes the validation receipts of its ops, so they are
+-- put back once it has been replaced.
+CREATE TEMP TABLE ValidationReceipt_2Up AS
+SELECT
+  hash,
+  op_hash,
+  blob
+FROM
+  ValidationReceipt;
+DROP TABLE DhtOp;
+ALTER TABLE
+  DhtOp_2Up RENAME TO DhtOp;
+INSERT INTO
+  ValidationReceipt (hash, op_hash, blob)
+SELECT
+  hash,
+  op_hash,
+  blob
+FROM
+  ValidationReceipt_2Up;
+DROP TABLE ValidationReceipt_2Up;
+CREATE INDEX IF NOT EXISTS DhtOp_type_dep_idx ON DhtOp (type, dependency);
+CREATE INDEX IF NOT EXISTS DhtOp_type_when_int_idx ON DhtOp (type, when_integrated);
+CREATE INDEX IF NOT EXISTS DhtOp_validation_stage_idx ON DhtOp (validation_stage, type, dependency);
+CREATE INDEX IF NOT EXISTS DhtOp_stage_type_status_idx ON DhtOp (validation_stage, type, validation_status);
+CREATE INDEX IF NOT EXISTS DhtOp_validation_status_idx ON DhtOp (validation_status);
+CREATE INDEX IF NOT EXISTS DhtOp_authored_timestamp_idx ON DhtOp (authored_timestamp);
+CREATE INDEX IF NOT EXISTS DhtOp_storage_center_loc_idx ON DhtOp (storage_center_loc);
+CREATE INDEX IF NOT EXISTS DhtOp_action_hash_idx ON DhtOp (action_hash);
+CREATE INDEX IF NOT EXISTS DhtOp_basis_hash_idx ON DhtOp (basis_hash);
+CREATE TRIGGER IF NOT EXISTS DhtOp_delete_with_action
+AFTER DELETE ON Action
+BEGIN
+  DELETE FROM DhtOp WHERE action_hash = OLD.hash;
+END;
+CREATE TABLE IF NOT EXISTS Warrant (
+  hash BLOB PRIMARY KEY ON CONFLICT IGNORE,
+  author BLOB NOT NULL,
+  warrantee BLOB NOT NULL,
+  blob BLOB NOT NULL,
+  FOREIGN KEY(hash) REFERENCES DhtOp(hash) ON DELETE CASCADE
+);
+CREATE INDEX IF NOT EXISTS Warrant_warrantee_idx ON Warrant (warrantee);
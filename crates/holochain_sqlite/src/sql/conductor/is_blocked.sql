@@ -1,9 +1,37 @@
--- count rows with a block for any reason against the target
+-- whether there is a block for any reason against the target
+-- or, if the target is an IP address, against any range containing it.
+-- The range lookup walks down the ip_start index from the address and
+-- stops at the first active range which contains it. Ranges can nest, so
+-- the walk can't stop at the first range which starts below the address,
+-- and when no range contains the address it visits every range which
+-- starts below it.
 SELECT
-  COUNT(1) > 0
-FROM
-  BlockSpan
-WHERE
-  target_id = :target_id
-  AND start_us <= :time_us
-  AND :time_us <= end_us
+  EXISTS (
+    SELECT
+      1
+    FROM
+      BlockSpan
+    WHERE
+      target_id = :target_id
+      AND start_us <= :time_us
+      AND :time_us <= end_us
+    UNION
+    SELECT
+      *
+    FROM
+      (
+        SELECT
+          1
+        FROM
+          BlockSpan
+        WHERE
+          ip_start <= :ip
+          AND :ip <= ip_end
+          AND start_us <= :time_us
+          AND :time_us <= end_us
+        ORDER BY
+          ip_start DESC
+        LIMIT
+          1
+      )
+  )
@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS block_span_start_us_idx;
+DROP INDEX IF EXISTS block_span_end_us_idx;
+DROP TABLE IF EXISTS BlockSpan;
@@ -0,0 +1,11 @@
+-- Earlier versions can't read blocks of IP addresses, so they are removed.
+DELETE FROM
+  BlockSpan
+WHERE
+  ip_start IS NOT NULL;
+DROP INDEX IF EXISTS block_span_target_id_idx;
+DROP INDEX IF EXISTS block_span_ip_start_idx;
+ALTER TABLE
+  BlockSpan DROP COLUMN ip_end;
+ALTER TABLE
+  BlockSpan DROP COLUMN ip_start;
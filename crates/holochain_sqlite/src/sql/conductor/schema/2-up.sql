@@ -0,0 +1,76 @@
+-- IP blocks cover a range of addresses, stored as 16 byte IPv6 addresses,
+-- with IPv4 mapped into ::ffff:0:0/96 so that both compare as blobs.
+-- Both are NULL for blocks of anything else.
+ALTER TABLE
+  BlockSpan
+ADD
+  COLUMN ip_start BLOB;
+ALTER TABLE
+  BlockSpan
+ADD
+  COLUMN ip_end BLOB;
+CREATE INDEX IF NOT EXISTS block_span_target_id_idx ON BlockSpan(target_id);
+CREATE INDEX IF NOT EXISTS block_span_ip_start_idx ON BlockSpan(ip_start);
+-- Before this migration an IP block could only hold an IPv4 address, stored
+-- as the MessagePack map {"Ip": [a, b, c, d]}, where each octet takes one
+-- byte, or two if it is 128 or more. Those targets are rewritten as
+-- {"Ip": {"V4": [a, b, c, d]}}, which is how an IP address is stored now,
+-- and given the bounds of their single address mapped into ::ffff:0:0/96.
+WITH old_ip AS (
+  SELECT
+    id,
+    target_id AS t,
+    6 AS p1
+  FROM
+    BlockSpan
+  WHERE
+    substr(target_id, 1, 5) = X'81A2497094'
+),
+p2 AS (
+  SELECT
+    *,
+    p1 + 1 + (substr(t, p1, 1) = X'CC') AS p2
+  FROM
+    old_ip
+),
+p3 AS (
+  SELECT
+    *,
+    p2 + 1 + (substr(t, p2, 1) = X'CC') AS p3
+  FROM
+    p2
+),
+p4 AS (
+  SELECT
+    *,
+    p3 + 1 + (substr(t, p3, 1) = X'CC') AS p4
+  FROM
+    p3
+),
+octets AS (
+  SELECT
+    id,
+    t,
+    substr(t, p1 + (substr(t, p1, 1) = X'CC'), 1) AS a,
+    substr(t, p2 + (substr(t, p2, 1) = X'CC'), 1) AS b,
+    substr(t, p3 + (substr(t, p3, 1) = X'CC'), 1) AS c,
+    substr(t, p4 + (substr(t, p4, 1) = X'CC'), 1) AS d
+  FROM
+    p4
+  WHERE
+    length(t) = p4 + (substr(t, p4, 1) = X'CC')
+)
+UPDATE
+  BlockSpan
+SET
+  target_id = CAST(X'81A2497081A25634' || substr(octets.t, 5) AS BLOB),
+  ip_start = CAST(
+    X'00000000000000000000FFFF' || octets.a || octets.b || octets.c || octets.d AS BLOB
+  ),
+  ip_end = CAST(
+    X'00000000000000000000FFFF' || octets.a || octets.b || octets.c || octets.d AS BLOB
+  )
+FROM
+  octets
+WHERE
+  BlockSpan.id = octets.id;
@@ -0,0 +1,30 @@
+-- no-sql-format --
+
+CREATE TABLE IF NOT EXISTS ConductorState (
+    id              INTEGER        PRIMARY KEY ON CONFLICT REPLACE,
+    blob            BLOB           NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS Nonce (
+    -- Primary key
+    agent BLOB PRIMARY KEY ON CONFLICT REPLACE,
+    nonce BLOB NOT NULL,
+    expires INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS BlockSpan (
+    id              INTEGER        PRIMARY KEY,
+    target_id       BLOB           NOT NULL,
+    target_reason   BLOB           NOT NULL,
+    -- start and end micros
+    -- literal integer from Timestamp in rust
+    start_us        INTEGER        NOT NULL,
+    end_us          INTEGER        NOT NULL,
+    -- first and last address of an IP block, as IPv6
+    ip_start        BLOB,
+    ip_end          BLOB
+);
+CREATE INDEX IF NOT EXISTS block_span_start_us_idx ON BlockSpan(start_us);
+CREATE INDEX IF NOT EXISTS block_span_end_us_idx ON BlockSpan(end_us);
+CREATE INDEX IF NOT EXISTS block_span_target_id_idx ON BlockSpan(target_id);
+CREATE INDEX IF NOT EXISTS block_span_ip_start_idx ON BlockSpan(ip_start);
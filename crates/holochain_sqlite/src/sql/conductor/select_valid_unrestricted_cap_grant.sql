@@ -1,21 +1,25 @@
 SELECT
-  Entry.blob
+  Entry.blob,
+  Action.hash AS action_hash,
+  Action.blob AS action_blob
 FROM
   Entry
   INNER JOIN Action ON Action.author = ?2
   AND Action.entry_hash = Entry.hash
 WHERE
   access_type = ?1
-  AND (
+  AND NOT EXISTS (
     -- cap grant must not have been updated or deleted
     SELECT
-      COUNT(UpdateActions.hash)
+      1
     FROM
-      Action as UpdateActions
+      Action AS RevokingActions
     WHERE
-      UpdateActions.author = ?2
+      RevokingActions.author = ?2
       AND (
-        UpdateActions.original_entry_hash = Entry.hash
-        OR UpdateActions.deletes_entry_hash = Entry.hash
+        RevokingActions.original_action_hash = Action.hash
+        OR RevokingActions.deletes_action_hash = Action.hash
+        OR RevokingActions.original_entry_hash = Entry.hash
+        OR RevokingActions.deletes_entry_hash = Entry.hash
       )
-  ) = 0;
+  );
@@ -7,7 +7,10 @@ use holochain_sqlite::sql::sql_conductor;
 use holochain_types::prelude::DbKindConductor;
 use holochain_types::prelude::Timestamp;
 use holochain_zome_types::block::Block;
+use holochain_zome_types::block::BlockTarget;
 use holochain_zome_types::block::BlockTargetId;
+use holochain_zome_types::block::BlockTargetReason;
+use holochain_zome_types::InclusiveTimestampInterval;
 
 pub async fn block(db: &DbWrite<DbKindConductor>, input: Block) -> DatabaseResult<()> {
     tracing::warn!(?input, "blocking node!");
@@ -21,15 +24,71 @@ pub async fn unblock(db: &DbWrite<DbKindConductor>, input: Block) -> DatabaseRes
         .await
 }
 
+/// All the blocks, including those which have ended.
+pub async fn list_blocks(db: &DbWrite<DbKindConductor>) -> DatabaseResult<Vec<Block>> {
+    db.read_async(|txn| {
+        let mut stmt = txn.prepare(
+            "SELECT target_id, target_reason, start_us, end_us FROM BlockSpan ORDER BY start_us",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, Vec<u8>>(0)?,
+                    row.get::<_, Vec<u8>>(1)?,
+                    row.get::<_, Timestamp>(2)?,
+                    row.get::<_, Timestamp>(3)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut blocks = Vec::with_capacity(rows.len());
+        for (target_id, target_reason, start, end) in rows {
+            let target_id: BlockTargetId = holochain_serialized_bytes::decode(&target_id)?;
+            let target_reason: BlockTargetReason =
+                holochain_serialized_bytes::decode(&target_reason)?;
+            // Every target is stored with a reason for that kind of target,
+            // so a mismatch means the row was written by something else.
+            match BlockTarget::from_parts(target_id.clone(), target_reason.clone()) {
+                Some(target) => blocks.push(Block::new(
+                    target,
+                    InclusiveTimestampInterval::try_new(start, end)?,
+                )),
+                None => tracing::error!(
+                    ?target_id,
+                    ?target_reason,
+                    "Skipping a block whose target and reason don't match"
+                ),
+            }
+        }
+        Ok(blocks)
+    })
+    .await
+}
+
+/// The first and last address covered by an IP target, in the form they
+/// are stored in the database, or `None`s for any other target.
+pub(crate) fn ip_bounds_sql(target_id: &BlockTargetId) -> (Option<Vec<u8>>, Option<Vec<u8>>) {
+    target_id
+        .ip_bounds()
+        .map(|(start, end)| (Some(start.octets().to_vec()), Some(end.octets().to_vec())))
+        .unwrap_or_default()
+}
+
+/// Whether the target is blocked at this time. An IP address is also
+/// blocked by any blocked range which contains it.
 pub fn query_is_blocked(
     txn: &Transaction<'_>,
     target_id: BlockTargetId,
     timestamp: Timestamp,
 ) -> DatabaseResult<bool> {
+    let ip = match target_id {
+        BlockTargetId::Ip(_) => ip_bounds_sql(&target_id).0,
+        _ => None,
+    };
     Ok(txn.query_row(
         sql_conductor::IS_BLOCKED,
         named_params! {
             ":target_id": target_id,
+            ":ip": ip,
             ":time_us": timestamp,
         },
         |row| row.get(0),
@@ -45,6 +104,7 @@ mod test {
     use holochain_zome_types::block::BlockTarget;
     use holochain_zome_types::block::BlockTargetId;
     use holochain_zome_types::block::CellBlockReason;
+    use holochain_zome_types::block::IpBlockReason;
     use holochain_zome_types::InclusiveTimestampInterval;
     use holochain_zome_types::TimestampFixturator;
 
@@ -421,6 +481,134 @@ mod test {
         }
     }
 
+    // IP addresses are blocked by blocks on the address or a range containing it.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn block_ip_range() {
+        let db = test_conductor_db();
+        let always =
+            || InclusiveTimestampInterval::try_new(Timestamp::MIN, Timestamp::MAX).unwrap();
+        let is_blocked = |target_id: BlockTargetId| {
+            db.read_async(move |txn| super::query_is_blocked(&txn, target_id, Timestamp(0)))
+        };
+        let ip = |ip: &str| BlockTargetId::Ip(ip.parse().unwrap());
+
+        for target in [
+            BlockTarget::IpRange(
+                "10.0.0.0/8".parse().unwrap(),
+                IpBlockReason::Kitsune(kitsune_p2p_block::IpBlockReason::DOS),
+            ),
+            // Nested in the range above, so the seek for a higher address
+            // meets it first and has to keep going.
+            BlockTarget::IpRange(
+                "10.200.0.0/16".parse().unwrap(),
+                IpBlockReason::Kitsune(kitsune_p2p_block::IpBlockReason::Abuse),
+            ),
+            BlockTarget::IpRange(
+                "2001:db8::/64".parse().unwrap(),
+                IpBlockReason::Kitsune(kitsune_p2p_block::IpBlockReason::Abuse),
+            ),
+            BlockTarget::Ip(
+                "192.168.0.1".parse().unwrap(),
+                IpBlockReason::Kitsune(kitsune_p2p_block::IpBlockReason::DOS),
+            ),
+        ] {
+            super::block(&db, Block::new(target, always()))
+                .await
+                .unwrap();
+        }
+
+        for (addr, expected) in [
+            ("10.0.0.0", true),
+            ("10.255.255.255", true),
+            ("10.200.1.2", true),
+            ("9.255.255.255", false),
+            ("11.0.0.0", false),
+            ("2001:db8::1", true),
+            ("2001:db8::ffff:ffff:ffff:ffff", true),
+            ("2001:db8:0:1::", false),
+            ("192.168.0.1", true),
+            ("192.168.0.2", false),
+            // IPv4 addresses seen on dual stack sockets
+            ("::ffff:10.1.2.3", true),
+            ("::ffff:192.168.0.1", true),
+        ] {
+            assert_eq!(is_blocked(ip(addr)).await.unwrap(), expected, "{}", addr);
+        }
+
+        // - Unblocking the range unblocks the addresses within it
+        super::unblock(
+            &db,
+            Block::new(
+                BlockTarget::IpRange(
+                    "10.0.0.0/8".parse().unwrap(),
+                    IpBlockReason::Kitsune(kitsune_p2p_block::IpBlockReason::DOS),
+                ),
+                always(),
+            ),
+        )
+        .await
+        .unwrap();
+        assert!(!is_blocked(ip("10.1.2.3")).await.unwrap());
+        assert!(is_blocked(ip("192.168.0.1")).await.unwrap());
+    }
+
+    // Blocks are listed with their targets and merged intervals.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn block_list() {
+        let db = test_conductor_db();
+        let interval = |start, end| {
+            InclusiveTimestampInterval::try_new(Timestamp(start), Timestamp(end)).unwrap()
+        };
+        let cell_id = fixt::fixt!(CellId);
+        let cell = BlockTarget::Cell(cell_id.clone(), CellBlockReason::BadCrypto);
+        let ip = BlockTarget::IpRange(
+            "2001:db8::/64".parse().unwrap(),
+            IpBlockReason::Kitsune(kitsune_p2p_block::IpBlockReason::Abuse),
+        );
+
+        let addr = BlockTarget::Ip(
+            "10.0.0.1".parse().unwrap(),
+            IpBlockReason::Kitsune(kitsune_p2p_block::IpBlockReason::DOS),
+        );
+
+        assert!(super::list_blocks(&db).await.unwrap().is_empty());
+
+        super::block(&db, Block::new(cell.clone(), interval(0, 5)))
+            .await
+            .unwrap();
+        super::block(&db, Block::new(cell, interval(3, 10)))
+            .await
+            .unwrap();
+        super::block(&db, Block::new(ip, interval(20, 30)))
+            .await
+            .unwrap();
+        super::block(&db, Block::new(addr, interval(40, 50)))
+            .await
+            .unwrap();
+
+        let blocks = super::list_blocks(&db).await.unwrap();
+        assert_eq!(blocks.len(), 3);
+        assert!(matches!(
+            blocks[0].target(),
+            BlockTarget::Cell(id, CellBlockReason::BadCrypto) if *id == cell_id
+        ));
+        assert_eq!(
+            (blocks[0].start(), blocks[0].end()),
+            (Timestamp(0), Timestamp(10))
+        );
+        assert!(
+            matches!(blocks[1].target(), BlockTarget::IpRange(net, _) if net.to_string() == "2001:db8::/64")
+        );
+        assert_eq!(
+            (blocks[1].start(), blocks[1].end()),
+            (Timestamp(20), Timestamp(30))
+        );
+        assert!(matches!(
+            blocks[2].target(),
+            BlockTarget::Ip(ip, _) if ip.to_string() == "10.0.0.1"
+        ));
+    }
+
     // Fresh db should not have any blocks.
     #[tokio::test(flavor = "multi_thread")]
     async fn block_empty_db_is_not_blocked() {
@@ -573,4 +761,62 @@ mod test {
             );
         }
     }
+
+    // IPv4 blocks stored before IP blocks could hold any address are
+    // rewritten by the migration, and given the bounds of their address.
+    #[test]
+    fn block_ipv4_from_before_migration() {
+        use holochain_sqlite::rusqlite::Connection;
+        use holochain_sqlite::schema::SCHEMA_CONDUCTOR;
+        use holochain_zome_types::block::BlockTargetReason;
+
+        // How an IP target was encoded when it could only be IPv4.
+        #[derive(Debug, serde::Serialize)]
+        enum OldBlockTargetId {
+            Ip(std::net::Ipv4Addr),
+        }
+
+        let addr: std::net::Ipv4Addr = "192.168.0.1".parse().unwrap();
+        let target = BlockTarget::Ip(
+            addr.into(),
+            IpBlockReason::Kitsune(kitsune_p2p_block::IpBlockReason::DOS),
+        );
+        let target_id = BlockTargetId::from(target.clone());
+
+        let mut conn = Connection::open_in_memory().unwrap();
+        SCHEMA_CONDUCTOR.migrate_to(&mut conn, "test", 2).unwrap();
+        conn.execute(
+            "INSERT INTO BlockSpan (target_id, target_reason, start_us, end_us) VALUES (?1, ?2, 0, 10)",
+            (
+                holochain_serialized_bytes::encode(&OldBlockTargetId::Ip(addr)).unwrap(),
+                BlockTargetReason::from(target),
+            ),
+        )
+        .unwrap();
+        SCHEMA_CONDUCTOR
+            .migrate_to(&mut conn, "test", SCHEMA_CONDUCTOR.version())
+            .unwrap();
+
+        let (stored_id, ip_start, ip_end) = conn
+            .query_row(
+                "SELECT target_id, ip_start, ip_end FROM BlockSpan",
+                [],
+                |row| {
+                    Ok((
+                        row.get::<_, Vec<u8>>(0)?,
+                        row.get::<_, Option<Vec<u8>>>(1)?,
+                        row.get::<_, Option<Vec<u8>>>(2)?,
+                    ))
+                },
+            )
+            .unwrap();
+        assert_eq!(
+            stored_id,
+            holochain_serialized_bytes::encode(&target_id).unwrap()
+        );
+        assert_eq!((ip_start, ip_end), super::ip_bounds_sql(&target_id));
+
+        let txn = conn.transaction().unwrap();
+        assert!(super::query_is_blocked(&txn, target_id, Timestamp(5)).unwrap());
+    }
 }
@@ -60,6 +60,7 @@ pub fn get_dependency(op_type: DhtOpType, action: &Action) -> Dependency {
             }
             _ => Dependency::Null,
         },
+        DhtOpType::RegisterWarrant => Dependency::Null,
     }
 }
 
@@ -136,6 +137,9 @@ pub fn insert_record_scratch(
 pub fn insert_op(txn: &mut Transaction, op: &DhtOpHashed) -> StateMutationResult<()> {
     let hash = op.as_hash();
     let op = op.as_content();
+    if let Some(warrant) = op.as_signed_warrant() {
+        return insert_warrant_op(txn, hash, warrant);
+    }
     let op_light = op.to_light();
     let action = op.action();
     let timestamp = action.timestamp();
@@ -157,6 +161,32 @@ pub fn insert_op(txn: &mut Transaction, op: &DhtOpHashed) -> StateMutationResult
     Ok(())
 }
 
+/// Insert a [`DhtOp::RegisterWarrant`](holochain_types::dht_op::DhtOp::RegisterWarrant)
+/// into the database.
+/// Only the warrant and its op are stored. The warranted action is carried
+/// in the warrant, and isn't added to the actions held by this database.
+fn insert_warrant_op(
+    txn: &mut Transaction,
+    hash: &DhtOpHash,
+    warrant: SignedWarrant,
+) -> StateMutationResult<()> {
+    let op_light = DhtOpLight::RegisterWarrant(
+        ActionHash::with_data_sync(warrant.warrant().signed_action().action()),
+        warrant.warrant().warrantee().clone().into(),
+    );
+    let timestamp = warrant.warrant().timestamp;
+    let op_order = OpOrder::new(DhtOpType::RegisterWarrant, timestamp);
+    insert_op_lite(txn, &op_light, hash, &op_order, &timestamp)?;
+    set_dependency(txn, hash, Dependency::Null)?;
+    sql_insert!(txn, Warrant, {
+        "hash": hash,
+        "author": warrant.warrant().author,
+        "warrantee": warrant.warrant().warrantee(),
+        "blob": to_blob(&warrant)?,
+    })?;
+    Ok(())
+}
+
 /// Insert a [`DhtOpLight`] into an authored database.
 /// This sets the sql fields so the authored database
 /// can be used in queries with other databases.
@@ -312,11 +342,15 @@ fn pluck_overlapping_block_bounds(
 }
 
 fn insert_block_inner(txn: &Transaction<'_>, block: Block) -> DatabaseResult<()> {
+    let target_id = BlockTargetId::from(block.target().clone());
+    let (ip_start, ip_end) = crate::block::ip_bounds_sql(&target_id);
     sql_insert!(txn, BlockSpan, {
-        "target_id": BlockTargetId::from(block.target().clone()),
+        "target_id": target_id,
         "target_reason": BlockTargetReason::from(block.target().clone()),
         "start_us": block.start(),
         "end_us": block.end(),
+        "ip_start": ip_start,
+        "ip_end": ip_end,
     })?;
     Ok(())
 }
@@ -727,6 +761,25 @@ pub fn unlock_chain(txn: &mut Transaction, author: &AgentPubKey) -> StateMutatio
     Ok(())
 }
 
+/// Remove only the lock with the given lock id, leaving any other locks on
+/// the chain in place.
+pub fn remove_chain_lock(
+    txn: &mut Transaction,
+    lock: &[u8],
+    author: &AgentPubKey,
+) -> StateMutationResult<()> {
+    let mut lock = lock.to_vec();
+    lock.extend(author.get_raw_39());
+    txn.execute(
+        "DELETE FROM ChainLock WHERE lock = :lock AND author = :author",
+        named_params! {
+            ":lock": lock,
+            ":author": author,
+        },
+    )?;
+    Ok(())
+}
+
 pub fn delete_all_ephemeral_scheduled_fns(txn: &mut Transaction) -> StateMutationResult<()> {
     txn.execute(
         holochain_sqlite::sql::sql_cell::schedule::DELETE_ALL_EPHEMERAL,
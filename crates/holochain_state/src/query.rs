@@ -24,6 +24,7 @@ use holochain_zome_types::EntryVisibility;
 use holochain_zome_types::Record;
 use holochain_zome_types::SignedAction;
 use holochain_zome_types::SignedActionHashed;
+use holochain_zome_types::SignedWarrant;
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -971,6 +972,9 @@ pub fn get_public_op_from_db(
 }
 
 pub fn map_sql_dht_op_common(row: &Row) -> StateQueryResult<Option<DhtOp>> {
+    if let Some(op) = map_sql_warrant_op(row)? {
+        return Ok(Some(op));
+    }
     let action = from_blob::<SignedAction>(row.get("action_blob")?)?;
     let op_type: DhtOpType = row.get("type")?;
     if action
@@ -998,3 +1002,15 @@ pub fn map_sql_dht_op_common(row: &Row) -> StateQueryResult<Option<DhtOp>> {
     }
     Ok(Some(DhtOp::from_type(op_type, action, entry)?))
 }
+
+/// Get the [`DhtOp::RegisterWarrant`] from a row which selects
+/// `Warrant.blob AS warrant_blob`, if the row is a warrant op.
+pub fn map_sql_warrant_op(row: &Row) -> StateQueryResult<Option<DhtOp>> {
+    let warrant: Option<Vec<u8>> = row.get("warrant_blob")?;
+    match warrant {
+        Some(warrant) => Ok(Some(DhtOp::from_warrant(from_blob::<SignedWarrant>(
+            warrant,
+        )?))),
+        None => Ok(None),
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
@@ -43,7 +44,11 @@ use holochain_zome_types::ActionHashed;
 use holochain_zome_types::ActionType;
 use holochain_zome_types::ActionUnweighed;
 use holochain_zome_types::CapAccess;
+use holochain_zome_types::CapClaim;
+use holochain_zome_types::CapClaimQuery;
 use holochain_zome_types::CapGrant;
+use holochain_zome_types::CapGrantInfo;
+use holochain_zome_types::CapGrantQuery;
 use holochain_zome_types::CapSecret;
 use holochain_zome_types::CellId;
 use holochain_zome_types::ChainQueryFilter;
@@ -242,6 +247,19 @@ impl SourceChain {
         chain_top_ordering: ChainTopOrdering,
         weight: W,
     ) -> SourceChainResult<ActionHash> {
+        let action = self.unweighed_action(action_builder)?;
+        self.put_unweighed(action, maybe_entry, chain_top_ordering, weight)
+            .await
+    }
+
+    /// Build the action which an ActionBuilder would produce if it were put
+    /// at the end of the source chain now, without any weight data, so that
+    /// the action can be weighed before it is put with
+    /// [`SourceChain::put_unweighed`].
+    pub fn unweighed_action<U: ActionUnweighed, B: ActionBuilder<U>>(
+        &self,
+        action_builder: B,
+    ) -> SourceChainResult<U> {
         let HeadInfo {
             action: prev_action,
             seq: chain_head_seq,
@@ -266,8 +284,20 @@ impl SourceChain {
             action_seq,
             prev_action,
         };
+        Ok(action_builder.build(common))
+    }
+
+    /// Put an action built by [`SourceChain::unweighed_action`] at the end of
+    /// the source chain, with the specified weight for rate limiting.
+    pub async fn put_unweighed<W, U: ActionUnweighed<Weight = W>>(
+        &self,
+        action: U,
+        maybe_entry: Option<Entry>,
+        chain_top_ordering: ChainTopOrdering,
+        weight: W,
+    ) -> SourceChainResult<ActionHash> {
         self.put_with_action(
-            action_builder.build(common).weighed(weight).into(),
+            action.weighed(weight).into(),
             maybe_entry,
             chain_top_ordering,
         )
@@ -325,6 +355,20 @@ impl SourceChain {
             .map_err(SourceChainError::other)?;
             if let Err(err @ ChcError::InvalidChain(_, _)) = chc.add_records_request(payload).await
             {
+                // Put the writes back into the scratch, so that they can be
+                // rebased and flushed again once the chain has caught up.
+                let ordering = self.scratch.apply(|scratch| scratch.chain_top_ordering())?;
+                self.scratch.apply(move |scratch| {
+                    for scheduled_fn in scheduled_fns {
+                        scratch.add_scheduled_fn(scheduled_fn);
+                    }
+                    for action in actions {
+                        scratch.add_action(action, ordering);
+                    }
+                    for entry in entries {
+                        scratch.add_entry(entry, ordering);
+                    }
+                })?;
                 return Err(SourceChainError::ChcHeadMoved(
                     "SourceChain::flush".into(),
                     err,
@@ -463,6 +507,53 @@ impl SourceChain {
             result => result,
         }
     }
+
+    /// Move the writes in the scratch onto a new source chain, rebased onto
+    /// the head which the chain has in the database now, for when the chain
+    /// has moved on since this source chain was created, such as when
+    /// records have been grafted onto it from the CHC.
+    ///
+    /// Only writes made with relaxed chain top ordering can be rebased, so
+    /// `None` is returned if any of them were strict.
+    pub async fn rebase_onto_persisted_head(&self) -> SourceChainResult<Option<Self>> {
+        let is_relaxed = self
+            .scratch
+            .apply(|scratch| scratch.chain_top_ordering() == ChainTopOrdering::Relaxed)?;
+        if !is_relaxed {
+            return Ok(None);
+        }
+        let child_chain = Self::new(
+            self.vault.clone(),
+            self.dht_db.clone(),
+            self.dht_db_cache.clone(),
+            self.keystore.clone(),
+            (*self.author).clone(),
+        )
+        .await?;
+        let (scheduled_fns, actions, entries) = self.scratch.apply(|scratch| {
+            (
+                scratch.drain_scheduled_fns().collect::<Vec<_>>(),
+                scratch.drain_actions().collect::<Vec<_>>(),
+                scratch.drain_entries().collect::<Vec<_>>(),
+            )
+        })?;
+        let actions = match child_chain.head_info.clone() {
+            Some(head) => rebase_actions_on(&self.keystore, actions, head).await?,
+            None => actions,
+        };
+        child_chain.scratch.apply(move |scratch| {
+            for scheduled_fn in scheduled_fns {
+                scratch.add_scheduled_fn(scheduled_fn);
+            }
+            for action in actions {
+                scratch.add_action(action, ChainTopOrdering::Relaxed);
+            }
+            for entry in entries {
+                scratch.add_entry(entry, ChainTopOrdering::Relaxed);
+            }
+        })?;
+        Ok(Some(child_chain))
+    }
 }
 
 impl<AuthorDb, DhtDb> SourceChain<AuthorDb, DhtDb>
@@ -640,10 +731,25 @@ where
         check_agent: AgentPubKey,
         check_secret: Option<CapSecret>,
     ) -> SourceChainResult<Option<CapGrant>> {
+        Ok(self
+            .valid_cap_grant_info(check_function, check_agent, check_secret)
+            .await?
+            .map(|(cap_grant, _)| cap_grant))
+    }
+
+    /// Find the grant that authorizes a call, like [`Self::valid_cap_grant`],
+    /// along with the info of the action that committed it. The grant of
+    /// the chain author was never committed, so it has no info.
+    pub async fn valid_cap_grant_info(
+        &self,
+        check_function: GrantedFunction,
+        check_agent: AgentPubKey,
+        check_secret: Option<CapSecret>,
+    ) -> SourceChainResult<Option<(CapGrant, Option<CapGrantInfo>)>> {
         let author_grant = CapGrant::from(self.agent_pubkey().clone());
         if author_grant.is_valid(&check_function, &check_agent, check_secret.as_ref()) {
             // caller is source chain author
-            return Ok(Some(author_grant));
+            return Ok(Some((author_grant, None)));
         }
 
         // remote caller
@@ -654,13 +760,21 @@ where
                 move |txn| -> Result<_, DatabaseError> {
                     // closure to process resulting rows from query
                     let query_row_fn = |row: &Row| {
-                        from_blob::<Entry>(row.get("blob")?).map_err(|err| {
+                        fn to_sql_err(
+                            err: impl std::fmt::Display,
+                        ) -> holochain_sqlite::rusqlite::Error {
                             holochain_sqlite::rusqlite::Error::InvalidColumnType(
                                 0,
                                 err.to_string(),
                                 holochain_sqlite::rusqlite::types::Type::Blob,
                             )
-                        })
+                        }
+                        let entry = from_blob::<Entry>(row.get("blob")?).map_err(to_sql_err)?;
+                        let action_hash: ActionHash = row.get("action_hash")?;
+                        let SignedAction(action, _) =
+                            from_blob::<SignedAction>(row.get("action_blob")?)
+                                .map_err(to_sql_err)?;
+                        Ok((entry, action_hash, action.timestamp()))
                     };
                     // prepare sql statement depending on provided cap secret
                     // and query for one cap grant
@@ -668,7 +782,7 @@ where
                     // query row is called inside the two arms instead of once
                     // afterwards because of difficulty passing around params
                     // between scopes
-                    let maybe_entry = if let Some(cap_secret) = &check_secret {
+                    let maybe_row = if let Some(cap_secret) = &check_secret {
                         // cap grant for cap secret must exist
                         // that has not been updated or deleted
                         let cap_secret_blob = to_blob(cap_secret).map_err(|err| {
@@ -693,20 +807,132 @@ where
                         .optional()?
                     };
 
-                    let maybe_cap_grant = maybe_entry.and_then(|entry| entry.as_cap_grant());
+                    let maybe_cap_grant =
+                        maybe_row.and_then(|(entry, action_hash, created_at)| match entry {
+                            Entry::CapGrant(cap_grant) => Some(CapGrantInfo {
+                                cap_grant,
+                                action_hash,
+                                created_at,
+                                revoked_at: None,
+                            }),
+                            _ => None,
+                        });
                     Ok(maybe_cap_grant)
                 }
             })
             .await?;
 
         // check if assignees and functions are granted
-        Ok(maybe_cap_grant.and_then(|cap_grant| {
+        Ok(maybe_cap_grant.and_then(|info| {
+            let cap_grant = CapGrant::RemoteAgent(info.cap_grant.clone());
             cap_grant
                 .is_valid(&check_function, &check_agent, check_secret.as_ref())
-                .then_some(cap_grant)
+                .then_some((cap_grant, Some(info)))
         }))
     }
 
+    /// List the zome call capability grants committed to this chain,
+    /// including those still in the scratch.
+    ///
+    /// A grant counts as revoked from the first update or delete that
+    /// references its action.
+    pub async fn cap_grant_info(
+        &self,
+        cap_grant_query: CapGrantQuery,
+    ) -> SourceChainResult<Vec<CapGrantInfo>> {
+        let grants = self
+            .query(
+                ChainQueryFilter::new()
+                    .entry_type(EntryType::CapGrant)
+                    .include_entries(true),
+            )
+            .await?;
+        let revoked = self.revoked_at().await?;
+        Ok(grants
+            .into_iter()
+            .filter_map(|record| {
+                let cap_grant = match record.entry().as_option()? {
+                    Entry::CapGrant(cap_grant) => cap_grant.clone(),
+                    _ => return None,
+                };
+                let action_hash = record.action_address().clone();
+                Some(CapGrantInfo {
+                    cap_grant,
+                    created_at: record.action().timestamp(),
+                    revoked_at: revoked.get(&action_hash).copied(),
+                    action_hash,
+                })
+            })
+            .filter(|info| {
+                cap_grant_query
+                    .tag
+                    .as_ref()
+                    .map_or(true, |tag| &info.cap_grant.tag == tag)
+                    && (cap_grant_query.include_revoked || info.revoked_at.is_none())
+            })
+            .collect())
+    }
+
+    /// List the capability claims committed to this chain that have not
+    /// been deleted, including those still in the scratch.
+    pub async fn cap_claims(
+        &self,
+        cap_claim_query: CapClaimQuery,
+    ) -> SourceChainResult<Vec<CapClaim>> {
+        let claims = self
+            .query(
+                ChainQueryFilter::new()
+                    .entry_type(EntryType::CapClaim)
+                    .include_entries(true),
+            )
+            .await?;
+        let revoked = self.revoked_at().await?;
+        Ok(claims
+            .into_iter()
+            .filter(|record| !revoked.contains_key(record.action_address()))
+            .filter_map(|record| match record.entry().as_option()? {
+                Entry::CapClaim(cap_claim) => Some(cap_claim.clone()),
+                _ => None,
+            })
+            .filter(|cap_claim| {
+                cap_claim_query
+                    .tag
+                    .as_ref()
+                    .map_or(true, |tag| &cap_claim.tag == tag)
+                    && cap_claim_query
+                        .grantor
+                        .as_ref()
+                        .map_or(true, |grantor| &cap_claim.grantor == grantor)
+            })
+            .collect())
+    }
+
+    /// Earliest update or delete timestamp for every action on this chain
+    /// that has been updated or deleted.
+    async fn revoked_at(&self) -> SourceChainResult<HashMap<ActionHash, Timestamp>> {
+        let revocations = self
+            .query(
+                ChainQueryFilter::new()
+                    .action_type(ActionType::Update)
+                    .action_type(ActionType::Delete),
+            )
+            .await?;
+        let mut revoked_at: HashMap<ActionHash, Timestamp> = HashMap::new();
+        for record in revocations {
+            let revoked_action = match record.action() {
+                Action::Update(update) => update.original_action_address.clone(),
+                Action::Delete(delete) => delete.deletes_address.clone(),
+                _ => continue,
+            };
+            let timestamp = record.action().timestamp();
+            revoked_at
+                .entry(revoked_action)
+                .and_modify(|earliest| *earliest = (*earliest).min(timestamp))
+                .or_insert(timestamp);
+        }
+        Ok(revoked_at)
+    }
+
     /// Query Actions in the source chain.
     /// This returns a Vec rather than an iterator because it is intended to be
     /// used by the `query` host function, which crosses the wasm boundary
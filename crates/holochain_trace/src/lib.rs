@@ -60,6 +60,17 @@
 //!
 //! You can combine filter with Json as well.
 //!
+//! #### OpenTelemetry
+//! With the `otlp` feature, spans can be exported as OpenTelemetry traces
+//! with `--structured OpenTel`.
+//! Logs still go to the console as with `Log`.
+//! Traces are sent over OTLP/gRPC to the collector at `OTEL_EXPORTER_OTLP_ENDPOINT`
+//! (default `http://localhost:4317`), from a tokio runtime which must be running
+//! when this is initialized. See the `otlp` module for details.
+//! ```bash
+//! RUST_LOG=info OTEL_EXPORTER_OTLP_ENDPOINT=https://collector:4317 my_bin --structured OpenTel
+//! ```
+//!
 //! ##### Tools
 //! Some useful tools for formatting and using the json data.
 //! - [json2csv](https://www.npmjs.com/package/json2csv)
@@ -91,6 +102,8 @@ use fmt::*;
 mod flames;
 mod fmt;
 pub mod metrics;
+#[cfg(feature = "otlp")]
+pub mod otlp;
 mod writer;
 // mod open;
 
@@ -122,8 +135,9 @@ pub enum Output {
     FlameTimed,
     /// Creates a flamegraph from timed spans using idle time
     IceTimed,
-    // /// Opentelemetry tracing
-    // OpenTel,
+    /// Regular logging plus spans exported as OpenTelemetry traces
+    #[cfg(feature = "otlp")]
+    OpenTel,
     /// No logging to console
     None,
 }
@@ -144,7 +158,8 @@ impl FromStr for Output {
             "LogTimed" => Ok(Output::LogTimed),
             "FlameTimed" => Ok(Output::FlameTimed),
             "Compact" => Ok(Output::Compact),
-            // "OpenTel" => Ok(Output::OpenTel),
+            #[cfg(feature = "otlp")]
+            "OpenTel" => Ok(Output::OpenTel),
             "None" => Ok(Output::None),
             _ => Err("Could not parse log output type".into()),
         }
@@ -265,6 +280,7 @@ pub fn test_run_timed_ice_console(
 
 /// This checks RUST_LOG for a filter but doesn't complain if there is none or it doesn't parse.
 /// It then checks for CUSTOM_FILTER which if set will output an error if it doesn't parse.
+/// `Output::OpenTel` must be initialized from within a tokio runtime.
 pub fn init_fmt(output: Output) -> Result<(), errors::TracingError> {
     init_fmt_with_opts(output, std::io::stderr)
 }
@@ -336,29 +352,12 @@ where
             let subscriber = subscriber.compact();
             finish(subscriber.with_env_filter(filter).finish())
         }
-        // Output::OpenTel => {
-        //     #[cfg(feature = "opentelemetry-on")]
-        //     {
-        //         use open::OPEN_ON;
-        //         use opentelemetry::api::Provider;
-        //         OPEN_ON.store(true, std::sync::atomic::Ordering::SeqCst);
-        //         use tracing_subscriber::prelude::*;
-        //         open::init();
-        //         let tracer = opentelemetry::sdk::Provider::default().get_tracer("component_name");
-        //         let telemetry = tracing_opentelemetry::layer().with_tracer(tracer);
-        //         finish(
-        //             subscriber
-        //                 .with_env_filter(filter)
-        //                 .finish()
-        //                 .with(telemetry)
-        //                 .with(open::OpenLayer),
-        //         )
-        //     }
-        //     #[cfg(not(feature = "opentelemetry-on"))]
-        //     {
-        //         Ok(())
-        //     }
-        // }
+        #[cfg(feature = "otlp")]
+        Output::OpenTel => {
+            use tracing_subscriber::prelude::*;
+            let otlp = otlp::layer()?;
+            finish(subscriber.with_env_filter(filter).finish().with(otlp))
+        }
         Output::None => Ok(()),
     }
 }
@@ -389,5 +388,8 @@ pub mod errors {
         TracingFlame,
         #[error(transparent)]
         BadDirective(#[from] tracing_subscriber::filter::ParseError),
+        #[cfg(feature = "otlp")]
+        #[error(transparent)]
+        OpenTelemetry(#[from] opentelemetry::trace::TraceError),
     }
 }
@@ -0,0 +1,100 @@
+//! Export spans as OpenTelemetry traces over OTLP.
+//!
+//! Spans are turned into OpenTelemetry spans by [tracing_opentelemetry] and
+//! exported in batches by [opentelemetry_otlp] over gRPC to the collector at
+//! `OTEL_EXPORTER_OTLP_ENDPOINT`, which defaults to `http://localhost:4317`.
+//! TLS is used if the endpoint is `https://`.
+//!
+//! Spans wait to be exported in a bounded queue, and are dropped if it is full.
+//! The queue and batches can be sized with the standard `OTEL_BSP_*` variables.
+//! Export errors are written to stderr.
+//!
+//! Only spans enabled by the subscriber's filter are exported, so
+//! run with something like `RUST_LOG=info`.
+//!
+//! ## Across conductors
+//!
+//! A span's context can be sent to another process as a
+//! [W3C `traceparent`](https://www.w3.org/TR/trace-context/#traceparent-header)
+//! with [`traceparent`], and set as the remote parent of a span there
+//! with [`set_parent`], so that both ends appear in the same trace.
+
+use opentelemetry::propagation::TextMapPropagator;
+use opentelemetry::trace::TraceContextExt;
+use opentelemetry::trace::TraceError;
+use opentelemetry::KeyValue;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::Tracer;
+use opentelemetry_sdk::Resource;
+use std::collections::HashMap;
+use tracing::Subscriber;
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::registry::LookupSpan;
+
+/// The standard OpenTelemetry variable for the OTLP collector endpoint.
+pub const E_OTLP_ENDPOINT: &str = opentelemetry_otlp::OTEL_EXPORTER_OTLP_ENDPOINT;
+
+/// The standard OpenTelemetry variable for the name of this service.
+pub const E_SERVICE_NAME: &str = "OTEL_SERVICE_NAME";
+
+const DEFAULT_SERVICE_NAME: &str = "holochain";
+
+const TRACEPARENT: &str = "traceparent";
+
+/// A [`tracing_subscriber::Layer`] that exports spans over OTLP.
+/// This must be called from within a tokio runtime, which the exports run on.
+/// The service name is read from [`E_SERVICE_NAME`] and defaults to `holochain`.
+pub fn layer<S>() -> Result<OpenTelemetryLayer<S, Tracer>, TraceError>
+where
+    S: Subscriber + for<'span> LookupSpan<'span>,
+{
+    // Export errors are reported to the global error handler. They don't go
+    // through `tracing`, where they would be fed back into the exporter.
+    let _ = opentelemetry::global::set_error_handler(|error| {
+        eprintln!("OpenTelemetry error: {:?}", error);
+    });
+
+    let mut exporter = opentelemetry_otlp::new_exporter().tonic().with_env();
+    if std::env::var(E_OTLP_ENDPOINT).map_or(false, |endpoint| endpoint.starts_with("https://")) {
+        exporter = exporter.with_tls_config(tonic::transport::ClientTlsConfig::new());
+    }
+    let service_name =
+        std::env::var(E_SERVICE_NAME).unwrap_or_else(|_| DEFAULT_SERVICE_NAME.into());
+    let tracer = opentelemetry_otlp::new_pipeline()
+        .tracing()
+        .with_exporter(exporter)
+        .with_trace_config(opentelemetry_sdk::trace::config().with_resource(
+            Resource::default().merge(&Resource::new([KeyValue::new(
+                "service.name",
+                service_name,
+            )])),
+        ))
+        .install_batch(opentelemetry_sdk::runtime::Tokio)?;
+    Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+}
+
+/// The context of this span as a W3C `traceparent`, if it is being
+/// exported by an OpenTelemetry layer.
+pub fn traceparent(span: &tracing::Span) -> Option<String> {
+    let mut carrier = HashMap::new();
+    TraceContextPropagator::new().inject_context(&span.context(), &mut carrier);
+    carrier.remove(TRACEPARENT)
+}
+
+/// The context of the current span as a W3C `traceparent`.
+pub fn current_traceparent() -> Option<String> {
+    traceparent(&tracing::Span::current())
+}
+
+/// Make the span a child of the span in another process which the
+/// `traceparent` came from. This needs to happen before the span is entered.
+/// Does nothing if the `traceparent` can't be parsed.
+pub fn set_parent(span: &tracing::Span, traceparent: &str) {
+    let carrier = HashMap::from([(TRACEPARENT.to_string(), traceparent.to_string())]);
+    let context = TraceContextPropagator::new().extract(&carrier);
+    if context.span().span_context().is_valid() {
+        span.set_parent(context);
+    }
+}
@@ -0,0 +1,107 @@
+use holochain_trace::otlp::*;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::export::trace::ExportResult;
+use opentelemetry_sdk::export::trace::SpanData;
+use opentelemetry_sdk::export::trace::SpanExporter;
+use opentelemetry_sdk::trace::TracerProvider;
+use std::sync::Arc;
+use std::sync::Mutex;
+use tracing::*;
+use tracing_subscriber::prelude::*;
+
+/// Keeps every span it is given.
+#[derive(Debug, Clone, Default)]
+struct Exported(Arc<Mutex<Vec<SpanData>>>);
+
+impl SpanExporter for Exported {
+    fn export(
+        &mut self,
+        batch: Vec<SpanData>,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = ExportResult> + Send>> {
+        self.0.lock().unwrap().extend(batch);
+        Box::pin(std::future::ready(Ok(())))
+    }
+}
+
+fn span_named<'a>(spans: &'a [SpanData], name: &str) -> &'a SpanData {
+    spans.iter().find(|s| s.name == name).unwrap()
+}
+
+#[test]
+fn exports_spans_with_remote_parents() {
+    let exported = Exported::default();
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(exported.clone())
+        .build();
+    let subscriber = tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(provider.tracer("test")));
+
+    let remote_parent = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01";
+    let mut sent_traceparent = None;
+    tracing::subscriber::with_default(subscriber, || {
+        let outer = info_span!("outer", zome = "foo");
+        let _g = outer.enter();
+        let inner = info_span!("inner", n = 3);
+        let _g = inner.enter();
+        info!(hash = "abc", "looked something up");
+        sent_traceparent = current_traceparent();
+
+        let remote = info_span!("remote");
+        set_parent(&remote, remote_parent);
+        let _g = remote.enter();
+        info_span!("remote_child").in_scope(|| {});
+    });
+
+    // Spans are exported from another thread.
+    provider.force_flush();
+    let spans = exported.0.lock().unwrap().clone();
+    assert_eq!(spans.len(), 4);
+
+    let outer = span_named(&spans, "outer");
+    let inner = span_named(&spans, "inner");
+    assert_eq!(inner.span_context.trace_id(), outer.span_context.trace_id());
+    assert_eq!(inner.parent_span_id, outer.span_context.span_id());
+    assert_eq!(outer.parent_span_id, opentelemetry::trace::SpanId::INVALID);
+    assert!(outer
+        .attributes
+        .iter()
+        .any(|(key, value)| key.as_str() == "zome" && value.as_str() == "foo"));
+    assert!(inner
+        .attributes
+        .iter()
+        .any(|(key, value)| key.as_str() == "n" && *value == 3i64.into()));
+    assert_eq!(
+        inner.events.iter().next().unwrap().name,
+        "looked something up"
+    );
+    assert_eq!(
+        sent_traceparent.unwrap(),
+        format!(
+            "00-{}-{}-01",
+            inner.span_context.trace_id(),
+            inner.span_context.span_id()
+        )
+    );
+
+    let remote = span_named(&spans, "remote");
+    let remote_child = span_named(&spans, "remote_child");
+    assert_eq!(
+        remote.span_context.trace_id().to_string(),
+        "0af7651916cd43dd8448eb211c80319c"
+    );
+    assert_eq!(remote.parent_span_id.to_string(), "b7ad6b7169203331");
+    assert_eq!(
+        remote_child.span_context.trace_id(),
+        remote.span_context.trace_id()
+    );
+    assert_eq!(remote_child.parent_span_id, remote.span_context.span_id());
+}
+
+#[tokio::test]
+async fn builds_otlp_layer() {
+    // The exporter connects lazily, so no collector is needed.
+    let subscriber = tracing_subscriber::registry().with(layer().unwrap());
+    tracing::subscriber::with_default(subscriber, || {
+        info_span!("unexported").in_scope(|| {});
+    });
+}
@@ -16,13 +16,16 @@ pub use app_bundle::*;
 pub use app_manifest::app_manifest_validated::*;
 pub use app_manifest::*;
 use derive_more::{Display, Into};
-use holo_hash::{AgentPubKey, DnaHash};
+use holo_hash::{AgentPubKey, AppHash, DnaHash};
 use holochain_serialized_bytes::prelude::*;
 use holochain_util::ffs;
 use holochain_zome_types::cell::CloneId;
 use holochain_zome_types::prelude::*;
 use itertools::Itertools;
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
 
 use self::error::{AppError, AppResult};
 
@@ -101,6 +104,19 @@ pub struct CreateCloneCellPayload {
     pub name: Option<String>,
 }
 
+/// The arguments to migrate the agent of an app's cell to a new DNA.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MigrateAgentPayload {
+    /// The app id that the cell to migrate belongs to
+    pub app_id: InstalledAppId,
+    /// The role name of the cell to migrate
+    pub role_name: RoleName,
+    /// The hash of the DNA to migrate to, which must already be registered
+    pub new_dna_hash: DnaHash,
+    /// Optionally set a proof of membership for the new cell
+    pub membrane_proof: Option<MembraneProof>,
+}
+
 /// Ways of specifying a clone cell.
 #[derive(Clone, Debug, Display, serde::Serialize, serde::Deserialize)]
 #[serde(untagged)]
@@ -148,6 +164,12 @@ pub struct InstallAppPayload {
     /// The app can still use existing Cells, i.e. this does not require that
     /// all Cells have DNAs with the same overridden DNA.
     pub network_seed: Option<NetworkSeed>,
+
+    /// Coordinate the source chains of this app's cells with the Chain Head
+    /// Coordinator (CHC) configured for the conductor.
+    /// Clone cells created later follow the cell of their role.
+    #[serde(default)]
+    pub use_chc: bool,
 }
 
 /// The possible locations of an AppBundle
@@ -158,16 +180,23 @@ pub enum AppBundleSource {
     Bundle(AppBundle),
     /// A local file path
     Path(PathBuf),
+    /// The hash of a bundle which has previously been put in the
+    /// conductor's app store
+    Hash(AppHash),
     // /// A URL
     // Url(String),
 }
 
 impl AppBundleSource {
     /// Get the bundle from the source. Consumes the source.
+    ///
+    /// A [`AppBundleSource::Hash`] can't be resolved without an app store,
+    /// so it produces [`AppBundleError::AppStoreRequired`].
     pub async fn resolve(self) -> Result<AppBundle, AppBundleError> {
         Ok(match self {
             Self::Bundle(bundle) => bundle,
             Self::Path(path) => AppBundle::decode(&ffs::read(&path).await?)?,
+            Self::Hash(hash) => return Err(AppBundleError::AppStoreRequired(hash)),
             // Self::Url(url) => todo!("reqwest::get"),
         })
     }
@@ -410,6 +439,13 @@ pub struct InstalledAppCommon {
     role_assignments: HashMap<RoleName, AppRoleAssignment>,
     /// The manifest used to install the app.
     manifest: AppManifest,
+    /// The cells whose source chains are coordinated with the conductor's
+    /// Chain Head Coordinator.
+    ///
+    /// This is `None` for apps installed before the CHC could be chosen per
+    /// cell, until the conductor migrates them when it starts up.
+    #[serde(default)]
+    chc_cells: Option<HashSet<CellId>>,
 }
 
 impl InstalledAppCommon {
@@ -433,6 +469,7 @@ impl InstalledAppCommon {
             agent_key,
             role_assignments,
             manifest,
+            chc_cells: Some(HashSet::new()),
         })
     }
 
@@ -545,6 +582,30 @@ impl InstalledAppCommon {
         &self.role_assignments
     }
 
+    /// Point a role at a new DNA, after its agent has been migrated there.
+    ///
+    /// Returns the id of the cell the role was previously based on.
+    /// Clone cells of the role are left on the DNA they were cloned from.
+    pub fn migrate_base_cell(
+        &mut self,
+        role_name: &RoleName,
+        new_dna_hash: DnaHash,
+    ) -> AppResult<CellId> {
+        let app_role_assignment = self.role_mut(role_name)?;
+        if !app_role_assignment.is_provisioned {
+            return Err(AppError::RoleNotProvisioned(role_name.clone()));
+        }
+        let new_cell_id = CellId::new(new_dna_hash, app_role_assignment.agent_key().clone());
+        let old_cell_id =
+            std::mem::replace(&mut app_role_assignment.base_cell_id, new_cell_id.clone());
+        if let Some(chc_cells) = &mut self.chc_cells {
+            if chc_cells.remove(&old_cell_id) {
+                chc_cells.insert(new_cell_id);
+            }
+        }
+        Ok(old_cell_id)
+    }
+
     /// Add a clone cell.
     pub fn add_clone(&mut self, role_name: &RoleName, cell_id: &CellId) -> AppResult<CloneId> {
         let app_role_assignment = self.role_mut(role_name)?;
@@ -572,6 +633,11 @@ impl InstalledAppCommon {
             .insert(clone_id.clone(), cell_id.clone());
         // increment next clone index
         app_role_assignment.next_clone_index += 1;
+        // a clone uses a CHC if the cell it was cloned from does
+        let base_cell_id = app_role_assignment.base_cell_id.clone();
+        if self.uses_chc(&base_cell_id) {
+            self.chc_cells_mut().insert(cell_id.clone());
+        }
         Ok(clone_id)
     }
 
@@ -697,10 +763,9 @@ impl InstalledAppCommon {
     /// Delete a disabled clone cell.
     pub fn delete_clone_cell(&mut self, clone_id: &CloneId) -> AppResult<()> {
         let app_role_assignment = self.role_mut(&clone_id.as_base_role_name())?;
-        app_role_assignment
+        let cell_id = app_role_assignment
             .disabled_clones
             .remove(clone_id)
-            .map(|_| ())
             .ok_or_else(|| {
                 if app_role_assignment.clones.contains_key(clone_id) {
                     AppError::CloneCellMustBeDisabledBeforeDeleting(CloneCellId::CloneId(
@@ -709,7 +774,11 @@ impl InstalledAppCommon {
                 } else {
                     AppError::CloneCellNotFound(CloneCellId::CloneId(clone_id.to_owned()))
                 }
-            })
+            })?;
+        if let Some(chc_cells) = &mut self.chc_cells {
+            chc_cells.remove(&cell_id);
+        }
+        Ok(())
     }
 
     /// Accessor
@@ -717,6 +786,58 @@ impl InstalledAppCommon {
         &self.agent_key
     }
 
+    /// Whether the cell's source chain is coordinated with the conductor's
+    /// Chain Head Coordinator.
+    pub fn uses_chc(&self, cell_id: &CellId) -> bool {
+        self.chc_cells
+            .as_ref()
+            .map_or(true, |chc_cells| chc_cells.contains(cell_id))
+    }
+
+    /// Choose which cells of an app installed before the CHC could be chosen
+    /// per cell are coordinated with the conductor's Chain Head Coordinator.
+    /// Those apps used the CHC for all of their cells if the conductor had one,
+    /// so they keep doing so.
+    ///
+    /// Returns whether the app needed migrating.
+    pub fn migrate_chc_cells(&mut self, conductor_has_chc: bool) -> bool {
+        if self.chc_cells.is_some() {
+            return false;
+        }
+        self.chc_cells = Some(if conductor_has_chc {
+            self.all_cells().cloned().collect()
+        } else {
+            HashSet::new()
+        });
+        true
+    }
+
+    fn chc_cells_mut(&mut self) -> &mut HashSet<CellId> {
+        if self.chc_cells.is_none() {
+            self.migrate_chc_cells(true);
+        }
+        self.chc_cells.get_or_insert_with(HashSet::new)
+    }
+
+    /// Coordinate a cell of this app with the conductor's Chain Head Coordinator.
+    pub fn attach_chc(&mut self, cell_id: &CellId) -> AppResult<()> {
+        if !self.all_cells().any(|c| c == cell_id) {
+            return Err(AppError::CellNotInApp(cell_id.clone()));
+        }
+        self.chc_cells_mut().insert(cell_id.clone());
+        Ok(())
+    }
+
+    /// Stop coordinating a cell of this app with the conductor's Chain Head
+    /// Coordinator.
+    pub fn detach_chc(&mut self, cell_id: &CellId) -> AppResult<()> {
+        if !self.all_cells().any(|c| c == cell_id) {
+            return Err(AppError::CellNotInApp(cell_id.clone()));
+        }
+        self.chc_cells_mut().remove(cell_id);
+        Ok(())
+    }
+
     /// Constructor for apps not using a manifest.
     /// Allows for cloning up to 256 times and implies immediate provisioning.
     pub fn new_legacy<S: ToString, I: IntoIterator<Item = InstalledCell>>(
@@ -780,6 +901,7 @@ impl InstalledAppCommon {
             agent_key: _agent_key,
             role_assignments,
             manifest,
+            chc_cells: Some(HashSet::new()),
         })
     }
 
@@ -1191,4 +1313,76 @@ mod tests {
         // Assert the deleted cell cannot be enabled
         assert!(app.enable_clone_cell(&clone_id_0).is_err());
     }
+
+    #[test]
+    fn chc_cells() {
+        let base_cell_id = fixt!(CellId);
+        let agent = base_cell_id.agent_pubkey().clone();
+        let role_name: RoleName = "role_name".into();
+        let role1 = AppRoleAssignment::new(base_cell_id.clone(), true, 2);
+        let manifest = AppManifest::arbitrary(&mut unstructured_noise()).unwrap();
+        let mut app = InstalledAppCommon::new(
+            "app",
+            agent.clone(),
+            vec![(role_name.clone(), role1)],
+            manifest,
+        )
+        .unwrap();
+
+        // Only cells of the app can be attached
+        matches::assert_matches!(
+            app.attach_chc(&fixt!(CellId)),
+            Err(AppError::CellNotInApp(_))
+        );
+
+        // Clones follow the cell they were cloned from
+        let clone_0 = CellId::new(fixt!(DnaHash), agent.clone());
+        app.add_clone(&role_name, &clone_0).unwrap();
+        assert!(!app.uses_chc(&clone_0));
+        app.attach_chc(&base_cell_id).unwrap();
+        assert!(app.uses_chc(&base_cell_id));
+        let clone_1 = CellId::new(fixt!(DnaHash), agent.clone());
+        app.add_clone(&role_name, &clone_1).unwrap();
+        assert!(app.uses_chc(&clone_1));
+
+        // So does the cell an agent is migrated to
+        let new_dna_hash = fixt!(DnaHash);
+        app.migrate_base_cell(&role_name, new_dna_hash.clone())
+            .unwrap();
+        assert!(!app.uses_chc(&base_cell_id));
+        assert!(app.uses_chc(&CellId::new(new_dna_hash, agent)));
+
+        app.detach_chc(&clone_1).unwrap();
+        assert!(!app.uses_chc(&clone_1));
+    }
+
+    #[test]
+    fn chc_cells_of_apps_installed_before_they_could_be_chosen() {
+        let base_cell_id = fixt!(CellId);
+        let role1 = AppRoleAssignment::new(base_cell_id.clone(), true, 2);
+        let manifest = AppManifest::arbitrary(&mut unstructured_noise()).unwrap();
+        let app = InstalledAppCommon::new(
+            "app",
+            base_cell_id.agent_pubkey().clone(),
+            vec![("role_name".into(), role1)],
+            manifest,
+        )
+        .unwrap();
+        let mut legacy = serde_json::to_value(&app).unwrap();
+        legacy.as_object_mut().unwrap().remove("chc_cells");
+        let legacy: InstalledAppCommon = serde_json::from_value(legacy).unwrap();
+
+        // - Until migrated, every cell uses the CHC, as all cells used to
+        assert!(legacy.uses_chc(&base_cell_id));
+
+        let mut with_chc = legacy.clone();
+        assert!(with_chc.migrate_chc_cells(true));
+        assert!(with_chc.uses_chc(&base_cell_id));
+        assert!(!with_chc.migrate_chc_cells(false));
+        assert!(with_chc.uses_chc(&base_cell_id));
+
+        let mut without_chc = legacy;
+        assert!(without_chc.migrate_chc_cells(false));
+        assert!(!without_chc.uses_chc(&base_cell_id));
+    }
 }
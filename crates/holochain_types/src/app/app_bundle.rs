@@ -1,4 +1,8 @@
-use std::{collections::HashMap, path::PathBuf, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+};
 
 use self::error::AppBundleResult;
 
@@ -40,6 +44,15 @@ impl AppBundle {
             .map_err(Into::into)
     }
 
+    /// The content hash of this bundle.
+    ///
+    /// This is computed over the uncompressed serialization of the manifest
+    /// and resources, so it does not depend on how the bundle was compressed.
+    pub fn app_hash(&self) -> AppBundleResult<AppHash> {
+        let bytes = holochain_serialized_bytes::encode(&self.0)?;
+        Ok(AppHash::from_raw_32(holo_hash::blake2b_256(&bytes)))
+    }
+
     /// Convert to the inner Bundle
     pub fn into_inner(self) -> mr_bundle::Bundle<AppManifest> {
         self.0
@@ -57,12 +70,16 @@ impl AppBundle {
     }
 
     /// Given a partial list of already available DnaFiles, fetch the missing others via
-    /// mr_bundle::Location resolution
+    /// mr_bundle::Location resolution.
+    ///
+    /// `existing_cells` are the cells already installed in the conductor, which
+    /// roles with the `UseExisting` or `CreateIfNotExists` strategies may be bound to.
     pub async fn resolve_cells(
         self,
         dna_store: &impl DnaStore,
         agent: AgentPubKey,
         membrane_proofs: HashMap<RoleName, MembraneProof>,
+        existing_cells: &HashSet<CellId>,
     ) -> AppBundleResult<AppRoleResolution> {
         let AppManifestValidated { name: _, roles } = self.manifest().clone().validate()?;
         let bundle = Arc::new(self);
@@ -70,7 +87,9 @@ impl AppBundle {
             let bundle = bundle.clone();
             Ok((
                 role_name.clone(),
-                bundle.resolve_cell(dna_store, role_name, role).await?,
+                bundle
+                    .resolve_cell(dna_store, &agent, existing_cells, role_name, role)
+                    .await?,
             ))
         });
         let resolution = futures::future::join_all(tasks)
@@ -134,6 +153,8 @@ impl AppBundle {
     async fn resolve_cell(
         &self,
         dna_store: &impl DnaStore,
+        agent: &AgentPubKey,
+        existing_cells: &HashSet<CellId>,
         role_name: RoleName,
         role: AppRoleManifestValidated,
     ) -> AppBundleResult<CellProvisioningOp> {
@@ -149,7 +170,7 @@ impl AppBundle {
                     .resolve_dna(
                         role_name,
                         dna_store,
-                        &location,
+                        Some(&location),
                         installed_hash.as_ref(),
                         modifiers,
                     )
@@ -160,38 +181,59 @@ impl AppBundle {
             AppRoleManifestValidated::UseExisting {
                 installed_hash,
                 clone_limit,
+                location,
+                modifiers,
                 deferred: _,
-            } => self.resolve_cell_existing(&installed_hash, clone_limit),
+            } => {
+                // Without modifiers the cell has the installed hash, so the
+                // DNA doesn't have to be found.
+                let dna_hash = if modifiers == DnaModifiersOpt::none() {
+                    installed_hash.clone().into()
+                } else {
+                    self.resolve_dna(
+                        role_name.clone(),
+                        dna_store,
+                        location.as_ref(),
+                        Some(&installed_hash),
+                        modifiers,
+                    )
+                    .await?
+                    .dna_hash()
+                    .clone()
+                };
+                match Self::resolve_cell_existing(agent, existing_cells, dna_hash.clone()) {
+                    Some(cell_id) => CellProvisioningOp::Existing(cell_id, clone_limit),
+                    None => {
+                        return Err(AppBundleError::CellResolutionFailure(
+                            role_name,
+                            format!("No existing cell for DNA {} and agent {}", dna_hash, agent),
+                        ))
+                    }
+                }
+            }
             AppRoleManifestValidated::CreateIfNotExists {
                 location,
                 installed_hash,
                 clone_limit,
                 modifiers,
                 deferred: _,
-            } => match self.resolve_cell_existing(&installed_hash, clone_limit) {
-                op @ CellProvisioningOp::Existing(_, _) => op,
-                CellProvisioningOp::HashMismatch(_, _) => {
-                    let dna = self
-                        .resolve_dna(
-                            role_name,
-                            dna_store,
-                            &location,
-                            Some(&installed_hash),
-                            modifiers,
-                        )
-                        .await?;
-                    CellProvisioningOp::CreateFromDnaFile(dna, clone_limit)
-                }
-                CellProvisioningOp::Conflict(_) => {
-                    unimplemented!("conflicts are not handled, or even possible yet")
-                }
-                CellProvisioningOp::CreateFromDnaFile(_, _) => {
-                    unreachable!("resolve_cell_existing will never return a Create op")
-                }
-                CellProvisioningOp::ProvisionOnly(_, _) => {
-                    unreachable!("resolve_cell_existing will never return a ProvisionOnly")
+            } => {
+                // The existing cell has the DNA hash with the modifiers applied,
+                // like the cell which would be created.
+                let dna = self
+                    .resolve_dna(
+                        role_name,
+                        dna_store,
+                        Some(&location),
+                        Some(&installed_hash),
+                        modifiers,
+                    )
+                    .await?;
+                match Self::resolve_cell_existing(agent, existing_cells, dna.dna_hash().clone()) {
+                    Some(cell_id) => CellProvisioningOp::Existing(cell_id, clone_limit),
+                    None => CellProvisioningOp::CreateFromDnaFile(dna, clone_limit),
                 }
-            },
+            }
             AppRoleManifestValidated::CloneOnly {
                 clone_limit,
                 location,
@@ -202,7 +244,7 @@ impl AppBundle {
                     .resolve_dna(
                         role_name,
                         dna_store,
-                        &location,
+                        Some(&location),
                         installed_hash.as_ref(),
                         modifiers,
                     )
@@ -216,7 +258,7 @@ impl AppBundle {
         &self,
         role_name: RoleName,
         dna_store: &impl DnaStore,
-        location: &mr_bundle::Location,
+        location: Option<&mr_bundle::Location>,
         installed_hash: Option<&DnaHashB64>,
         modifiers: DnaModifiersOpt,
     ) -> AppBundleResult<DnaFile> {
@@ -227,7 +269,8 @@ impl AppBundle {
                     dna_file = dna_file.update_modifiers(modifiers);
                     (dna_file, original_hash)
                 } else {
-                    self.resolve_location(location, modifiers).await?
+                    self.resolve_location(&role_name, location, modifiers)
+                        .await?
                 };
             let expected_hash: DnaHash = hash.clone().into();
             if expected_hash != original_hash {
@@ -238,24 +281,35 @@ impl AppBundle {
             }
             dna_file
         } else {
-            self.resolve_location(location, modifiers).await?.0
+            self.resolve_location(&role_name, location, modifiers)
+                .await?
+                .0
         };
         Ok(dna_file)
     }
 
+    /// Find the existing cell with the given DNA hash which belongs to this agent.
     fn resolve_cell_existing(
-        &self,
-        _version: &DnaHashB64,
-        _clone_limit: u32,
-    ) -> CellProvisioningOp {
-        unimplemented!("Reusing existing cells is not yet implemented")
+        agent: &AgentPubKey,
+        existing_cells: &HashSet<CellId>,
+        dna_hash: DnaHash,
+    ) -> Option<CellId> {
+        let cell_id = CellId::new(dna_hash, agent.clone());
+        existing_cells.contains(&cell_id).then_some(cell_id)
     }
 
     async fn resolve_location(
         &self,
-        location: &mr_bundle::Location,
+        role_name: &RoleName,
+        location: Option<&mr_bundle::Location>,
         modifiers: DnaModifiersOpt,
     ) -> AppBundleResult<(DnaFile, DnaHash)> {
+        let location = location.ok_or_else(|| {
+            AppBundleError::CellResolutionFailure(
+                role_name.clone(),
+                "The DNA is not installed and has no location".into(),
+            )
+        })?;
         let bytes = self.resolve(location).await?;
         let dna_bundle: DnaBundle = mr_bundle::Bundle::decode(&bytes)?.into();
         let (dna_file, original_hash) = dna_bundle.into_dna_file(modifiers).await?;
@@ -263,12 +317,6 @@ impl AppBundle {
     }
 }
 
-/// This function is called in places where it will be necessary to rework that
-/// area after use_existing has been implemented
-pub fn we_must_remember_to_rework_cell_panic_handling_after_implementing_use_existing_cell_resolution(
-) {
-}
-
 /// The answer to the question:
 /// "how do we concretely assign DNAs to the open roles of this App?"
 /// Includes the DNAs selected to fill the roles and the details of the role assignments.
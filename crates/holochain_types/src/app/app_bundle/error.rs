@@ -1,7 +1,7 @@
 use holochain_util::ffs;
 use mr_bundle::error::MrBundleError;
 
-use crate::prelude::{AppManifestError, DnaError, RoleName};
+use crate::prelude::{AppHash, AppManifestError, DnaError, RoleName, SerializedBytesError};
 
 /// Errors occurring while installing an AppBundle
 #[derive(thiserror::Error, Debug)]
@@ -20,6 +20,12 @@ pub enum AppBundleError {
 
     #[error(transparent)]
     FfsIoError(#[from] ffs::IoError),
+
+    #[error(transparent)]
+    SerializedBytesError(#[from] SerializedBytesError),
+
+    #[error("The app bundle {0} can only be resolved through an app store")]
+    AppStoreRequired(AppHash),
 }
 
 pub type AppBundleResult<T> = Result<T, AppBundleError>;
@@ -33,12 +33,23 @@ impl AppManifestValidated {
         roles: HashMap<RoleName, AppRoleManifestValidated>,
     ) -> AppManifestResult<Self> {
         for (role_name, role) in roles.iter() {
-            if let AppRoleManifestValidated::CloneOnly { clone_limit, .. } = role {
-                if *clone_limit == 0 {
-                    return Err(AppManifestError::InvalidStrategyCloneOnly(
+            match role {
+                AppRoleManifestValidated::CloneOnly { clone_limit, .. } => {
+                    if *clone_limit == 0 {
+                        return Err(AppManifestError::InvalidStrategyCloneOnly(
+                            role_name.to_owned(),
+                        ));
+                    }
+                }
+                // Roles bound to an existing cell can't wait for it to be
+                // created later.
+                AppRoleManifestValidated::UseExisting { deferred: true, .. }
+                | AppRoleManifestValidated::CreateIfNotExists { deferred: true, .. } => {
+                    return Err(AppManifestError::InvalidStrategyDeferred(
                         role_name.to_owned(),
                     ));
                 }
+                _ => {}
             }
         }
         Ok(AppManifestValidated { name, roles })
@@ -58,11 +69,17 @@ pub enum AppRoleManifestValidated {
         installed_hash: Option<DnaHashB64>,
     },
     /// Require that a Cell is already installed with a specified DNA hash,
-    /// and which has an Agent that's associated with this App's agent
-    /// via DPKI. If no such Cell exists, *app installation fails*.
+    /// and which has the same Agent as this App.
+    /// If no such Cell exists, *app installation fails*.
+    ///
+    /// The Cell is looked up by the hash of the DNA with the modifiers
+    /// applied, so if there are any, the DNA must be installed already or
+    /// be found at the location.
     UseExisting {
         clone_limit: u32,
         deferred: bool,
+        location: Option<DnaLocation>,
+        modifiers: DnaModifiersOpt,
         installed_hash: DnaHashB64,
     },
     /// Try `UseExisting`, and if that fails, fallback to `Create`
@@ -26,6 +26,12 @@ pub enum AppError {
     #[error("Tried to access missing role name: '{0}'")]
     RoleNameMissing(RoleName),
 
+    #[error("Cell '{0}' is not part of the app")]
+    CellNotInApp(CellId),
+
+    #[error("Tried to use the cell of role '{0}', which was never provisioned")]
+    RoleNotProvisioned(RoleName),
+
     #[error("Tried to install app '{0}' which contains duplicate role names. The following role names have duplicates: {1:?}")]
     DuplicateRoleNames(InstalledAppId, Vec<RoleName>),
 }
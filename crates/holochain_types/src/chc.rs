@@ -58,13 +58,19 @@ pub trait ChainHeadCoordinatorExt:
         self: Arc<Self>,
         since_hash: Option<ActionHash>,
     ) -> MustBoxFuture<'static, ChcResult<Vec<Record>>> {
+        let (keystore, agent) = self.signing_info();
         let mut bytes = [0; 32];
         let _ = getrandom::getrandom(&mut bytes);
         let nonce = Nonce256Bits::from(bytes);
-        let payload = GetRecordsPayload { since_hash, nonce };
-        // TODO: real signature
-        let signature = Signature::from([0; 64]);
         async move {
+            let expires_at = (Timestamp::now() + GET_RECORDS_EXPIRES_AFTER)
+                .map_err(|e| ChcError::Other(e.to_string()))?;
+            let payload = GetRecordsPayload {
+                since_hash,
+                nonce,
+                expires_at,
+            };
+            let signature = keystore.sign(agent, payload.signable_bytes()?).await?;
             self.get_record_data_request(GetRecordsRequest { payload, signature })
                 .await?
                 .into_iter()
@@ -102,7 +108,7 @@ pub trait ChainHeadCoordinatorExt:
 /// is signed by the agent. This ensures that only the correct agent is adding
 /// records to its CHC. This EncryptedEntry signature is not used anywhere
 /// outside the context of the CHC.
-#[derive(Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct AddRecordPayload<A = SignedActionHashed> {
     /// The signed, hashed Action for the Record
     pub action: A,
@@ -172,12 +178,25 @@ pub type AddRecordsRequest = Vec<AddRecordPayload>;
 ///
 /// Since this payload is signed, including a unique nonce helps prevent replay
 /// attacks.
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct GetRecordsPayload {
     /// Only records beyond and including this hash are returned
     pub since_hash: Option<ActionHash>,
     /// Randomly selected nonce to prevent replay attacks
     pub nonce: Nonce256Bits,
+    /// The time after which the request is no longer valid, which bounds
+    /// how long the CHC must remember the nonce for.
+    pub expires_at: Timestamp,
+}
+
+/// How long a signed [`GetRecordsPayload`] is valid for.
+pub const GET_RECORDS_EXPIRES_AFTER: std::time::Duration = std::time::Duration::from_secs(60 * 5);
+
+impl GetRecordsPayload {
+    /// The bytes which the agent signs to make a [`GetRecordsRequest`].
+    pub fn signable_bytes(&self) -> ChcResult<Arc<[u8]>> {
+        Ok(holochain_serialized_bytes::encode(self)?.into())
+    }
 }
 
 /// The full request for get_record_data
@@ -190,7 +209,7 @@ pub struct GetRecordsRequest {
 }
 
 /// Encrypted bytes of an Entry
-#[derive(serde::Serialize, serde::Deserialize, derive_more::From)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, derive_more::From)]
 pub struct EncryptedEntry(#[serde(with = "serde_bytes")] pub Vec<u8>);
 
 /// Assemble records from a list of Actions and a map of Entries
@@ -111,6 +111,14 @@ pub enum DhtOp {
     #[display(fmt = "RegisterRemoveLink")]
     /// Op for removing a link
     RegisterRemoveLink(Signature, action::DeleteLink),
+
+    #[display(fmt = "RegisterWarrant")]
+    /// Used to notify the authority for an agent's public key that a
+    /// validator has found that agent to have authored something invalid.
+    ///
+    /// The signature is the warrant author's signature of the [`Warrant`],
+    /// not the offending action's signature, which is carried in the warrant.
+    RegisterWarrant(Signature, Warrant),
 }
 
 impl kitsune_p2p_dht::prelude::OpRegion for DhtOp {
@@ -154,6 +162,8 @@ pub enum DhtOpLight {
     RegisterAddLink(ActionHash, OpBasis),
     #[display(fmt = "RegisterRemoveLink")]
     RegisterRemoveLink(ActionHash, OpBasis),
+    #[display(fmt = "RegisterWarrant")]
+    RegisterWarrant(ActionHash, OpBasis),
 }
 
 impl PartialEq for DhtOpLight {
@@ -207,6 +217,8 @@ pub enum DhtOpType {
     RegisterAddLink,
     #[display(fmt = "RegisterRemoveLink")]
     RegisterRemoveLink,
+    #[display(fmt = "RegisterWarrant")]
+    RegisterWarrant,
 }
 
 impl ToSql for DhtOpType {
@@ -246,6 +258,7 @@ impl DhtOp {
             }
             Self::RegisterAddLink(_, action) => UniqueForm::RegisterAddLink(action),
             Self::RegisterRemoveLink(_, action) => UniqueForm::RegisterRemoveLink(action),
+            Self::RegisterWarrant(_, warrant) => UniqueForm::RegisterWarrant(warrant),
         }
     }
 
@@ -302,6 +315,10 @@ impl DhtOp {
                 let h = ActionHash::with_data_sync(&Action::from(a.clone()));
                 DhtOpLight::RegisterRemoveLink(h, basis)
             }
+            DhtOp::RegisterWarrant(_, w) => {
+                let h = ActionHash::with_data_sync(w.signed_action().action());
+                DhtOpLight::RegisterWarrant(h, basis)
+            }
         }
     }
 
@@ -316,13 +333,15 @@ impl DhtOp {
             | DhtOp::RegisterDeletedBy(s, _)
             | DhtOp::RegisterDeletedEntryAction(s, _)
             | DhtOp::RegisterAddLink(s, _)
-            | DhtOp::RegisterRemoveLink(s, _) => s,
+            | DhtOp::RegisterRemoveLink(s, _)
+            | DhtOp::RegisterWarrant(s, _) => s,
         }
     }
 
     /// Get the action from this op
     /// This requires cloning and converting the action
-    /// as some ops don't hold the Action type
+    /// as some ops don't hold the Action type.
+    /// For a [`DhtOp::RegisterWarrant`] this is the warranted action.
     pub fn action(&self) -> Action {
         match self {
             DhtOp::StoreRecord(_, a, _) => a.clone(),
@@ -334,6 +353,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, a) => a.clone().into(),
             DhtOp::RegisterAddLink(_, a) => a.clone().into(),
             DhtOp::RegisterRemoveLink(_, a) => a.clone().into(),
+            DhtOp::RegisterWarrant(_, w) => w.signed_action().action().clone(),
         }
     }
 
@@ -349,6 +369,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, _) => RecordEntry::NA,
             DhtOp::RegisterAddLink(_, _) => RecordEntry::NA,
             DhtOp::RegisterRemoveLink(_, _) => RecordEntry::NA,
+            DhtOp::RegisterWarrant(_, _) => RecordEntry::NA,
         }
     }
 
@@ -364,10 +385,12 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, _) => DhtOpType::RegisterDeletedEntryAction,
             DhtOp::RegisterAddLink(_, _) => DhtOpType::RegisterAddLink,
             DhtOp::RegisterRemoveLink(_, _) => DhtOpType::RegisterRemoveLink,
+            DhtOp::RegisterWarrant(_, _) => DhtOpType::RegisterWarrant,
         }
     }
 
-    /// From a type, action and an entry (if there is one)
+    /// From a type, action and an entry (if there is one).
+    /// Warrant ops can't be built this way, see [`DhtOp::from_warrant`].
     pub fn from_type(
         op_type: DhtOpType,
         action: SignedAction,
@@ -403,10 +426,27 @@ impl DhtOp {
             DhtOpType::RegisterRemoveLink => {
                 DhtOp::RegisterRemoveLink(signature, action.try_into()?)
             }
+            DhtOpType::RegisterWarrant => return Err(DhtOpError::WarrantWithoutWarrant),
         };
         Ok(r)
     }
 
+    /// Create the op which publishes this warrant.
+    pub fn from_warrant(warrant: SignedWarrant) -> Self {
+        let SignedWarrant(warrant, signature) = warrant;
+        DhtOp::RegisterWarrant(signature, warrant)
+    }
+
+    /// The signed warrant, if this is a [`DhtOp::RegisterWarrant`].
+    pub fn as_signed_warrant(&self) -> Option<SignedWarrant> {
+        match self {
+            DhtOp::RegisterWarrant(signature, warrant) => {
+                Some(SignedWarrant(warrant.clone(), signature.clone()))
+            }
+            _ => None,
+        }
+    }
+
     fn to_order(&self) -> OpOrder {
         OpOrder::new(self.get_type(), self.timestamp())
     }
@@ -456,7 +496,8 @@ impl DhtOpLight {
             | DhtOpLight::RegisterDeletedBy(_, b)
             | DhtOpLight::RegisterDeletedEntryAction(_, b)
             | DhtOpLight::RegisterAddLink(_, b)
-            | DhtOpLight::RegisterRemoveLink(_, b) => b,
+            | DhtOpLight::RegisterRemoveLink(_, b)
+            | DhtOpLight::RegisterWarrant(_, b) => b,
         }
     }
     /// Get the action hash from this op
@@ -470,7 +511,8 @@ impl DhtOpLight {
             | DhtOpLight::RegisterDeletedBy(h, _)
             | DhtOpLight::RegisterDeletedEntryAction(h, _)
             | DhtOpLight::RegisterAddLink(h, _)
-            | DhtOpLight::RegisterRemoveLink(h, _) => h,
+            | DhtOpLight::RegisterRemoveLink(h, _)
+            | DhtOpLight::RegisterWarrant(h, _) => h,
         }
     }
 
@@ -486,6 +528,7 @@ impl DhtOpLight {
             DhtOpLight::RegisterDeletedEntryAction(_, _) => DhtOpType::RegisterDeletedEntryAction,
             DhtOpLight::RegisterAddLink(_, _) => DhtOpType::RegisterAddLink,
             DhtOpLight::RegisterRemoveLink(_, _) => DhtOpType::RegisterRemoveLink,
+            DhtOpLight::RegisterWarrant(_, _) => DhtOpType::RegisterWarrant,
         }
     }
 
@@ -560,6 +603,9 @@ impl DhtOpLight {
                 };
                 Self::RegisterRemoveLink(action_hash, basis)
             }
+            DhtOpType::RegisterWarrant => {
+                Self::RegisterWarrant(action_hash, action.author().clone().into())
+            }
         };
         Ok(op)
     }
@@ -579,6 +625,7 @@ pub enum UniqueForm<'a> {
     RegisterDeletedEntryAction(&'a action::Delete),
     RegisterAddLink(&'a action::CreateLink),
     RegisterRemoveLink(&'a action::DeleteLink),
+    RegisterWarrant(&'a Warrant),
 }
 
 impl<'a> UniqueForm<'a> {
@@ -599,6 +646,7 @@ impl<'a> UniqueForm<'a> {
             }
             UniqueForm::RegisterAddLink(action) => action.base_address.clone(),
             UniqueForm::RegisterRemoveLink(action) => action.base_address.clone(),
+            UniqueForm::RegisterWarrant(warrant) => warrant.warrantee().clone().into(),
         }
     }
 
@@ -649,6 +697,7 @@ impl<'a> UniqueForm<'a> {
                 let hash = DhtOpHash::with_data_sync(&UniqueForm::RegisterRemoveLink(&action));
                 Ok((action.into(), hash))
             }
+            DhtOpType::RegisterWarrant => Err(DhtOpError::WarrantWithoutWarrant),
         }
     }
 }
@@ -711,6 +760,7 @@ pub fn produce_ops_from_record(record: &Record) -> DhtOpResult<Vec<DhtOp>> {
                 let link_remove = action.try_into()?;
                 DhtOp::RegisterRemoveLink(signature, link_remove)
             }
+            DhtOpLight::RegisterWarrant(_, _) => return Err(DhtOpError::WarrantWithoutWarrant),
         };
         ops.push(op);
     }
@@ -1005,6 +1055,7 @@ pub enum OpNumericalOrder {
     RegisterDeletedEntryAction,
     RegisterAddLink,
     RegisterRemoveLink,
+    RegisterWarrant,
 }
 
 /// This is used as an index for ordering ops in our database.
@@ -1041,6 +1092,7 @@ impl OpOrder {
             DhtOpType::RegisterDeletedEntryAction => OpNumericalOrder::RegisterDeletedEntryAction,
             DhtOpType::RegisterAddLink => OpNumericalOrder::RegisterAddLink,
             DhtOpType::RegisterRemoveLink => OpNumericalOrder::RegisterRemoveLink,
+            DhtOpType::RegisterWarrant => OpNumericalOrder::RegisterWarrant,
         };
         Self { order, timestamp }
     }
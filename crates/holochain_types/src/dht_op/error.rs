@@ -20,6 +20,8 @@ pub enum DhtOpError {
     OpActionMismatch(DhtOpType, ActionType),
     #[error("Link requests without tags require a tag in the response")]
     LinkKeyTagMissing,
+    #[error("Tried to create a RegisterWarrant DhtOp from an action instead of a warrant")]
+    WarrantWithoutWarrant,
 }
 
 pub type DhtOpResult<T> = Result<T, DhtOpError>;
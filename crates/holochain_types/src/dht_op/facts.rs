@@ -18,6 +18,10 @@ pub fn valid_dht_op(
         brute(
             "Action type matches Entry existence, and is public if exists",
             move |op: &DhtOp| {
+                // These facts are about ops produced from an action.
+                if let DhtOp::RegisterWarrant(_, _) = op {
+                    return false;
+                }
                 let action = op.action();
                 let h = action.entry_data();
                 let e = op.entry();
@@ -136,6 +140,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, h) => &mut h.author,
             DhtOp::RegisterAddLink(_, h) => &mut h.author,
             DhtOp::RegisterRemoveLink(_, h) => &mut h.author,
+            DhtOp::RegisterWarrant(_, w) => &mut w.author,
         }
     }
 
@@ -151,6 +156,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, h) => h.timestamp,
             DhtOp::RegisterAddLink(_, h) => h.timestamp,
             DhtOp::RegisterRemoveLink(_, h) => h.timestamp,
+            DhtOp::RegisterWarrant(_, w) => w.timestamp,
         }
     }
 
@@ -166,6 +172,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, h) => &mut h.timestamp,
             DhtOp::RegisterAddLink(_, h) => &mut h.timestamp,
             DhtOp::RegisterRemoveLink(_, h) => &mut h.timestamp,
+            DhtOp::RegisterWarrant(_, w) => &mut w.timestamp,
         }
     }
 
@@ -181,6 +188,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(s, _) => s,
             DhtOp::RegisterAddLink(s, _) => s,
             DhtOp::RegisterRemoveLink(s, _) => s,
+            DhtOp::RegisterWarrant(s, _) => s,
         }
     }
 
@@ -196,6 +204,7 @@ impl DhtOp {
             DhtOp::RegisterDeletedEntryAction(_, ref mut h) => Some(&mut h.action_seq),
             DhtOp::RegisterAddLink(_, ref mut h) => Some(&mut h.action_seq),
             DhtOp::RegisterRemoveLink(_, ref mut h) => Some(&mut h.action_seq),
+            DhtOp::RegisterWarrant(_, _) => None,
         }
     }
 
@@ -294,6 +294,7 @@ fn get_type_op() {
                 DhtOp::RegisterRemoveLink(_, _) => {
                     assert_eq!(op_type, DhtOpType::RegisterRemoveLink)
                 }
+                DhtOp::RegisterWarrant(_, _) => unreachable!("Records don't produce warrants"),
             }
         };
         for op in ops {
@@ -436,6 +437,7 @@ fn test_all_ops_basis() {
                     )
                 )
             }
+            (DhtOpType::RegisterWarrant, _) => unreachable!("Records don't produce warrants"),
         };
         for op in ops {
             assert_eq!(*op.to_light().dht_basis(), op.dht_basis());
@@ -43,6 +43,15 @@ impl DnaBundle {
         Ok((DnaFile::from_parts(dna_def, wasms), original_hash))
     }
 
+    /// The hash of the Dna without any modifier overrides, which is the hash
+    /// the bundle itself defines
+    pub async fn original_dna_hash(&self) -> DnaResult<DnaHash> {
+        let (integrity, coordinator, _) = self.inner_maps().await?;
+        let (_, original_hash) =
+            self.to_dna_def(integrity, coordinator, DnaModifiersOpt::none())?;
+        Ok(original_hash)
+    }
+
     /// Construct from raw bytes
     pub fn decode(bytes: &[u8]) -> DnaResult<Self> {
         mr_bundle::Bundle::decode(bytes)
@@ -124,6 +133,7 @@ impl DnaBundle {
                     },
                     integrity_zomes,
                     coordinator_zomes,
+                    rate_limits: manifest.integrity.rate_limits.clone(),
                 };
 
                 let original_hash = DnaHash::with_data_sync(&dna_def);
@@ -207,6 +217,7 @@ impl DnaBundle {
                 })?),
                 origin_time: dna_def.modifiers.origin_time.into(),
                 zomes: integrity,
+                rate_limits: dna_def.rate_limits,
             },
             coordinator: CoordinatorManifest { zomes: coordinator },
         }
@@ -267,6 +278,7 @@ mod tests {
                 network_seed: Some("original network seed".to_string()),
                 properties: Some(serde_yaml::Value::Null.into()),
                 origin_time: Timestamp::HOLOCHAIN_EPOCH.into(),
+                rate_limits: Default::default(),
                 zomes: vec![
                     ZomeManifest {
                         name: "zome1".into(),
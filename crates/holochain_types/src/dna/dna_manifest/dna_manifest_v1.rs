@@ -127,6 +127,13 @@ pub struct IntegrityManifest {
     /// The order is significant: it determines initialization order.
     /// The integrity zome manifests.
     pub zomes: Vec<ZomeManifest>,
+
+    /// The rate limits for each rate limiting bucket, keyed by bucket id.
+    /// Integrity zomes assign actions to buckets in their `weigh` callback.
+    /// Buckets without a limit are unlimited.
+    #[serde(default, skip_serializing_if = "RateLimits::is_empty")]
+    #[builder(default)]
+    pub rate_limits: RateLimits,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Default)]
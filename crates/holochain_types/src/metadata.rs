@@ -17,24 +17,37 @@ pub struct TimedActionHash {
 
 /// Metadata returned from a GetMeta request.
 /// The Ord derive on TimedActionHash means each set is ordered by time.
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, SerializedBytes)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, SerializedBytes)]
 pub struct MetadataSet {
     /// Actions that created or updated an entry.
     /// These are the actions that show the entry exists.
     pub actions: BTreeSet<TimedActionHash>,
-    // TODO: Implement after validation
-    /// Placeholder
+    /// Actions on the hash which were rejected by validation.
     pub invalid_actions: BTreeSet<TimedActionHash>,
     /// Deletes on an action
     pub deletes: BTreeSet<TimedActionHash>,
     /// Updates on an action or entry
     pub updates: BTreeSet<TimedActionHash>,
+    /// Link creates and deletes on a base
+    pub links: BTreeSet<TimedActionHash>,
     /// The status of an entry from an authority.
     /// This is simply a faster way of determining if
     /// there are any live actions on an entry.
     pub entry_dht_status: Option<EntryDhtStatus>,
 }
 
+impl MetadataSet {
+    /// Whether every action in this set is also in the other set,
+    /// in which case the other set's holder has nothing to learn from this one.
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.actions.is_subset(&other.actions)
+            && self.invalid_actions.is_subset(&other.invalid_actions)
+            && self.deletes.is_subset(&other.deletes)
+            && self.updates.is_subset(&other.updates)
+            && self.links.is_subset(&other.links)
+    }
+}
+
 impl From<ActionHashed> for TimedActionHash {
     fn from(h: ActionHashed) -> Self {
         let (action, hash) = h.into_inner();
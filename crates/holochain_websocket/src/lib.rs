@@ -83,10 +83,15 @@ use std::sync::Arc;
 use holochain_serialized_bytes::prelude::*;
 use stream_cancel::Valve;
 use tracing::instrument;
+use tungstenite::client::IntoClientRequest;
 use url2::Url2;
+use util::addr_to_url;
 use util::url_to_addr;
+use util::Socket;
 use websocket::Websocket;
 
+pub use tokio_native_tls::native_tls;
+
 mod websocket_config;
 pub use websocket_config::*;
 
@@ -109,30 +114,100 @@ mod util;
 
 #[instrument(skip(config))]
 /// Create a new external websocket connection.
+///
+/// Secure websocket (`wss://`) urls are connected to over TLS, trusting the
+/// system's root certificates. Use [`connect_tls`] to trust others.
 pub async fn connect(
     url: Url2,
     config: Arc<WebsocketConfig>,
 ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    if url.scheme() == "wss" {
+        let connector = native_tls::TlsConnector::new().map_err(tls_error)?;
+        return connect_tls(url, config, connector).await;
+    }
     let addr = url_to_addr(&url, config.scheme).await?;
     let socket = tokio::net::TcpStream::connect(addr).await?;
     // TODO: find equivalent of this in new tokio
     // socket.set_keepalive(Some(std::time::Duration::from_secs(
     //     config.tcp_keepalive_s as u64,
     // )))?;
-    let (socket, _) = tokio_tungstenite::client_async_with_config(
-        url.as_str(),
-        socket,
-        Some(config.to_tungstenite()),
-    )
-    .await
-    .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    let remote_addr = addr_to_url(socket.peer_addr()?, config.scheme);
+    client_handshake(url.as_str(), Box::new(socket), remote_addr, config).await
+}
+
+#[instrument(skip(config, connector))]
+/// Create a new external secure websocket (`wss://`) connection,
+/// using a TLS connector which has been set up to trust the server.
+pub async fn connect_tls(
+    url: Url2,
+    config: Arc<WebsocketConfig>,
+    connector: native_tls::TlsConnector,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let addr = url_to_addr(&url, "wss").await?;
+    let socket = tokio::net::TcpStream::connect(addr).await?;
+    let remote_addr = addr_to_url(socket.peer_addr()?, "wss");
+    let domain = url.host_str().unwrap_or_default().to_string();
+    let socket = tokio_native_tls::TlsConnector::from(connector)
+        .connect(&domain, socket)
+        .await
+        .map_err(tls_error)?;
+    client_handshake(url.as_str(), Box::new(socket), remote_addr, config).await
+}
+
+#[cfg(unix)]
+#[instrument(skip(config))]
+/// Create a new external websocket connection over a Unix domain socket.
+pub async fn connect_unix(
+    path: &std::path::Path,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    connect_unix_with_url(path, url2::url2!("ws://localhost"), config).await
+}
+
+#[cfg(unix)]
+#[instrument(skip(config))]
+/// Create a new external websocket connection over a Unix domain socket,
+/// sending `url` in the handshake request, e.g. to pass query parameters.
+pub async fn connect_unix_with_url(
+    path: &std::path::Path,
+    url: Url2,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let socket = tokio::net::UnixStream::connect(path).await?;
+    let remote_addr = util::unix_url(path);
+    client_handshake(url.as_str(), Box::new(socket), remote_addr, config).await
+}
+
+async fn client_handshake(
+    url: &str,
+    socket: Box<dyn Socket>,
+    remote_addr: Url2,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+    if let Some(origin) = &config.origin {
+        let origin = origin
+            .parse()
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?;
+        request.headers_mut().insert("Origin", origin);
+    }
+    let (socket, _) =
+        tokio_tungstenite::client_async_with_config(request, socket, Some(config.to_tungstenite()))
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
     tracing::debug!("Client connected");
 
     // Noop valve because we don't have a listener to shutdown the
     // ends when creating a client
     let (exit, valve) = Valve::new();
     exit.disable();
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, remote_addr, valve)
+}
+
+pub(crate) fn tls_error(e: native_tls::Error) -> WebsocketError {
+    Error::new(ErrorKind::Other, e).into()
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
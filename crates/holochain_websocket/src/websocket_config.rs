@@ -1,5 +1,38 @@
 //! defines a builder-style config struct for setting up websockets
 
+use std::sync::Arc;
+
+/// The http request which opens an incoming websocket connection.
+pub use tungstenite::handshake::server::Request as HandshakeRequest;
+
+/// A check run on the handshake request of every incoming connection.
+/// If it returns an error, the connection is refused with
+/// `403 Forbidden` and the error as the response body.
+#[derive(Clone)]
+pub struct HandshakeCheck(Arc<HandshakeCheckFn>);
+
+type HandshakeCheckFn = dyn Fn(&HandshakeRequest) -> Result<(), String> + Send + Sync;
+
+impl HandshakeCheck {
+    /// Construct a check from a function.
+    pub fn new(
+        check: impl Fn(&HandshakeRequest) -> Result<(), String> + Send + Sync + 'static,
+    ) -> Self {
+        Self(Arc::new(check))
+    }
+
+    /// Run the check on a handshake request.
+    pub fn check(&self, request: &HandshakeRequest) -> Result<(), String> {
+        (self.0)(request)
+    }
+}
+
+impl std::fmt::Debug for HandshakeCheck {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("HandshakeCheck(..)")
+    }
+}
+
 /// A builder-style config struct for setting up websockets.
 #[derive(Debug)]
 pub struct WebsocketConfig {
@@ -26,6 +59,14 @@ pub struct WebsocketConfig {
 
     /// Maximum number of pending new incoming connections. [default = 255]
     pub max_pending_connections: usize,
+
+    /// A check which incoming connections must pass before the websocket
+    /// handshake is completed. [default = None]
+    pub handshake_check: Option<HandshakeCheck>,
+
+    /// The `Origin` header which clients send in the handshake request.
+    /// [default = None]
+    pub origin: Option<String>,
 }
 
 impl Default for WebsocketConfig {
@@ -38,6 +79,8 @@ impl Default for WebsocketConfig {
             max_message_size: 64 << 20,
             max_frame_size: 16 << 20,
             max_pending_connections: 255,
+            handshake_check: None,
+            origin: None,
         }
     }
 }
@@ -78,6 +121,18 @@ impl WebsocketConfig {
         self.max_frame_size = max;
         self
     }
+
+    /// Builder-style setter.
+    pub fn handshake_check(mut self, check: HandshakeCheck) -> Self {
+        self.handshake_check = Some(check);
+        self
+    }
+
+    /// Builder-style setter.
+    pub fn origin(mut self, origin: impl Into<String>) -> Self {
+        self.origin = Some(origin.into());
+        self
+    }
 }
 
 /// internal helper to convert our configs into tungstenite configs
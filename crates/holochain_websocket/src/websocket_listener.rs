@@ -8,12 +8,21 @@ use std::sync::Arc;
 use stream_cancel::Trigger;
 use stream_cancel::Valve;
 use tracing::instrument;
+use tungstenite::handshake::server::ErrorResponse;
+use tungstenite::http::StatusCode;
 
 use url2::Url2;
 
+use crate::native_tls;
+use crate::tls_error;
 use crate::util::addr_to_url;
+#[cfg(unix)]
+use crate::util::unix_url;
 use crate::util::url_to_addr;
+use crate::util::Socket;
 use crate::websocket::Websocket;
+use crate::HandshakeRequest;
+use crate::TungsteniteConfigExt;
 use crate::WebsocketConfig;
 use crate::WebsocketError;
 use crate::WebsocketReceiver;
@@ -104,7 +113,40 @@ impl WebsocketListener {
         ListenerHandle,
         impl futures::stream::Stream<Item = ListenerItem>,
     )> {
-        websocket_bind(addr, config).await
+        websocket_bind(addr, config, None).await
+    }
+
+    #[instrument(skip(config, addr, identity))]
+    /// Same as [`WebsocketListener::bind_with_handle`] but serves secure
+    /// websockets (`wss://`), using the certificate and private key in `identity`.
+    pub async fn bind_tls_with_handle(
+        addr: Url2,
+        config: Arc<WebsocketConfig>,
+        identity: native_tls::Identity,
+    ) -> WebsocketResult<(
+        ListenerHandle,
+        impl futures::stream::Stream<Item = ListenerItem>,
+    )> {
+        websocket_bind(addr, config, Some(identity)).await
+    }
+
+    #[cfg(unix)]
+    #[instrument(skip(config))]
+    /// Same as [`WebsocketListener::bind_with_handle`] but listens on a Unix
+    /// domain socket at `path`, replacing any socket already there.
+    /// The socket is given the permissions in `mode`, if any, before anyone
+    /// can connect to it, and is deleted once the listener is closed and
+    /// its stream dropped.
+    /// The local address is given as a `unix://` url.
+    pub async fn bind_unix_with_handle(
+        path: &std::path::Path,
+        mode: Option<u32>,
+        config: Arc<WebsocketConfig>,
+    ) -> WebsocketResult<(
+        ListenerHandle,
+        impl futures::stream::Stream<Item = ListenerItem>,
+    )> {
+        unix_bind(path, mode, config).await
     }
     /// Shutdown the listener stream.
     pub fn close(self) {
@@ -187,11 +229,13 @@ impl futures::stream::Stream for WebsocketListener {
 async fn websocket_bind(
     addr: Url2,
     config: Arc<WebsocketConfig>,
+    tls: Option<native_tls::Identity>,
 ) -> WebsocketResult<(
     ListenerHandle,
     impl futures::stream::Stream<Item = ListenerItem>,
 )> {
-    let addr = url_to_addr(&addr, config.scheme).await?;
+    let scheme = if tls.is_some() { "wss" } else { config.scheme };
+    let addr = url_to_addr(&addr, scheme).await?;
     let socket = match &addr {
         SocketAddr::V4(_) => net2::TcpBuilder::new_v4()?,
         SocketAddr::V6(_) => net2::TcpBuilder::new_v6()?,
@@ -200,19 +244,143 @@ async fn websocket_bind(
     .bind(addr)?
     .listen(config.max_pending_connections as i32)?;
     socket.set_nonblocking(true)?;
-    let local_addr = addr_to_url(socket.local_addr()?, config.scheme);
+    let local_addr = addr_to_url(socket.local_addr()?, scheme);
     let listener = tokio::net::TcpListener::from_std(socket)?;
-    let listener_stream = tokio_stream::wrappers::TcpListenerStream::new(listener);
+    let acceptor = match tls {
+        Some(identity) => Some(Arc::new(tokio_native_tls::TlsAcceptor::from(
+            native_tls::TlsAcceptor::new(identity).map_err(tls_error)?,
+        ))),
+        None => None,
+    };
+    let incoming = tokio_stream::wrappers::TcpListenerStream::new(listener)
+        .map_err(WebsocketError::from)
+        .map_ok(move |socket| {
+            let acceptor = acceptor.clone();
+            async move {
+                let remote_addr = addr_to_url(socket.peer_addr()?, scheme);
+                let socket: Box<dyn Socket> = match acceptor {
+                    Some(acceptor) => Box::new(acceptor.accept(socket).await.map_err(tls_error)?),
+                    None => Box::new(socket),
+                };
+                Ok((socket, remote_addr))
+            }
+        })
+        .try_buffer_unordered(config.max_pending_connections);
+    Ok(listen(incoming, local_addr, config))
+}
+
+#[cfg(unix)]
+async fn unix_bind(
+    path: &std::path::Path,
+    mode: Option<u32>,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(
+    ListenerHandle,
+    impl futures::stream::Stream<Item = ListenerItem>,
+)> {
+    use std::os::unix::fs::DirBuilderExt;
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::fs::PermissionsExt;
 
+    // Only a socket left behind by a previous listener is replaced.
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if !metadata.file_type().is_socket() => {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("{} exists and is not a socket", path.display()),
+            )
+            .into())
+        }
+        Err(e) if e.kind() != ErrorKind::NotFound => return Err(e.into()),
+        _ => (),
+    }
+    // Bind in a directory which only we can enter, so that nobody can
+    // connect before the socket has its permissions, then move it into place.
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => std::path::Path::new("."),
+    };
+    let private_dir = parent.join(format!(".ws-{}", nanoid::nanoid!()));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)?;
+    let bound = (|| {
+        let private_path = private_dir.join("ws.sock");
+        let listener = tokio::net::UnixListener::bind(&private_path)?;
+        if let Some(mode) = mode {
+            std::fs::set_permissions(&private_path, std::fs::Permissions::from_mode(mode))?;
+        }
+        let socket_file = SocketFile::new(path, &std::fs::symlink_metadata(&private_path)?);
+        std::fs::rename(&private_path, path)?;
+        std::io::Result::Ok((listener, socket_file))
+    })();
+    let _ = std::fs::remove_dir_all(&private_dir);
+    let (listener, socket_file) = bound?;
+    let local_addr = unix_url(path);
+    let incoming = tokio_stream::wrappers::UnixListenerStream::new(listener)
+        .map_err(WebsocketError::from)
+        .map_ok({
+            let local_addr = local_addr.clone();
+            move |socket| {
+                // The socket file goes when the listener stream is dropped.
+                let _ = &socket_file;
+                (Box::new(socket) as Box<dyn Socket>, local_addr.clone())
+            }
+        });
+    Ok(listen(incoming, local_addr, config))
+}
+
+/// Deletes a Unix listener's socket file when dropped, unless the file has
+/// since been replaced, e.g. by a new listener on the same path.
+#[cfg(unix)]
+struct SocketFile {
+    path: std::path::PathBuf,
+    dev: u64,
+    ino: u64,
+}
+
+#[cfg(unix)]
+impl SocketFile {
+    fn new(path: &std::path::Path, metadata: &std::fs::Metadata) -> Self {
+        use std::os::unix::fs::MetadataExt;
+        Self {
+            path: path.to_owned(),
+            dev: metadata.dev(),
+            ino: metadata.ino(),
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for SocketFile {
+    fn drop(&mut self) {
+        use std::os::unix::fs::MetadataExt;
+        if let Ok(metadata) = std::fs::symlink_metadata(&self.path) {
+            if metadata.dev() == self.dev && metadata.ino() == self.ino {
+                let _ = std::fs::remove_file(&self.path);
+            }
+        }
+    }
+}
+
+/// Upgrade each incoming connection to a websocket, until the listener
+/// is shutdown.
+fn listen(
+    incoming: impl futures::stream::Stream<Item = WebsocketResult<(Box<dyn Socket>, Url2)>>,
+    local_addr: Url2,
+    config: Arc<WebsocketConfig>,
+) -> (
+    ListenerHandle,
+    impl futures::stream::Stream<Item = ListenerItem>,
+) {
     // Setup proper shutdown
     let (shutdown, valve) = Valve::new();
 
-    let buffered_listener = listener_stream
-        .map_err(WebsocketError::from)
+    let buffered_listener = incoming
         .map_ok({
             let config = config.clone();
             let valve = valve.clone();
-            move |socket_result| connect(config.clone(), socket_result, valve.clone())
+            move |(socket, remote_addr)| connect(config.clone(), socket, remote_addr, valve.clone())
         })
         .try_buffer_unordered(config.max_pending_connections);
     tracing::debug!(sever_listening_on = ?local_addr);
@@ -224,13 +392,14 @@ async fn websocket_bind(
         config,
         local_addr,
     };
-    Ok((listener_handle, stream))
+    (listener_handle, stream)
 }
 
 #[instrument(skip(config, socket, valve))]
 async fn connect(
     config: Arc<WebsocketConfig>,
-    socket: tokio::net::TcpStream,
+    socket: Box<dyn Socket>,
+    remote_addr: Url2,
     valve: Valve,
 ) -> WebsocketResult<Pair> {
     // TODO: find alternative to set the keepalive
@@ -239,19 +408,30 @@ async fn connect(
     // )))?;
     tracing::debug!(
         message = "accepted incoming raw socket",
-        remote_addr = %socket.peer_addr()?,
+        remote_addr = %remote_addr,
     );
-    let socket = tokio_tungstenite::accept_async_with_config(
-        socket,
-        Some(tungstenite::protocol::WebSocketConfig {
-            max_send_queue: Some(config.max_send_queue),
-            max_message_size: Some(config.max_message_size),
-            max_frame_size: Some(config.max_frame_size),
-            ..Default::default()
-        }),
-    )
-    .await
+    let ws_config = config.to_tungstenite();
+    let socket = match config.handshake_check.clone() {
+        Some(check) => {
+            let remote_addr = remote_addr.clone();
+            let callback = move |request: &HandshakeRequest, response| match check.check(request) {
+                Ok(()) => Ok(response),
+                Err(reason) => {
+                    tracing::debug!(
+                        message = "refused incoming connection",
+                        remote_addr = %remote_addr,
+                        %reason,
+                    );
+                    let mut response = ErrorResponse::new(Some(reason));
+                    *response.status_mut() = StatusCode::FORBIDDEN;
+                    Err(response)
+                }
+            };
+            tokio_tungstenite::accept_hdr_async_with_config(socket, callback, Some(ws_config)).await
+        }
+        None => tokio_tungstenite::accept_async_with_config(socket, Some(ws_config)).await,
+    }
     .map_err(|e| Error::new(ErrorKind::Other, e))?;
 
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, remote_addr, valve)
 }
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use futures::StreamExt;
 use holochain_serialized_bytes::prelude::*;
 use holochain_websocket::connect;
+use holochain_websocket::HandshakeCheck;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
 use holochain_websocket::WebsocketConfig;
@@ -537,3 +538,194 @@ async fn can_handle_many_connections_and_disconnects() {
 
     s_jh.abort();
 }
+
+fn server_echo(
+    mut listener: impl futures::stream::Stream<Item = ListenerItem> + Unpin + Send + 'static,
+) -> tokio::task::JoinHandle<()> {
+    tokio::task::spawn(async move {
+        let (_, mut receiver) = listener
+            .next()
+            .instrument(tracing::debug_span!("next_server_connection"))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let (msg, resp) = receiver
+            .next()
+            .instrument(tracing::debug_span!("next_server_recv"))
+            .await
+            .unwrap();
+        let msg: TestString = msg.try_into().unwrap();
+        resp.respond(TestString(format!("echo: {}", msg.0)).try_into().unwrap())
+            .instrument(tracing::debug_span!("server_respond"))
+            .await
+            .unwrap();
+    })
+}
+
+async fn client_echo(mut sender: holochain_websocket::WebsocketSender) {
+    let msg: TestString = sender
+        .request(TestString("Hey from client".into()))
+        .instrument(tracing::debug_span!("client_sending_request"))
+        .await
+        .unwrap();
+    assert_eq!(msg.0, "echo: Hey from client");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_connect_over_ipv6() {
+    holochain_trace::test_run().ok();
+    let (handle, listener) = WebsocketListener::bind_with_handle(
+        url2!("ws://[::1]:0"),
+        Arc::new(WebsocketConfig::default()),
+    )
+    .await
+    .unwrap();
+    let binding = handle.local_addr().clone();
+    assert_eq!(binding.host_str(), Some("[::1]"));
+    let jh = server_echo(listener);
+
+    let (sender, _receiver) = connect(binding, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    client_echo(sender).await;
+    jh.await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn can_connect_over_tls() {
+    use holochain_websocket::native_tls;
+
+    holochain_trace::test_run().ok();
+    let cert = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+    let cert_pem = cert.serialize_pem().unwrap();
+    let identity = native_tls::Identity::from_pkcs8(
+        cert_pem.as_bytes(),
+        cert.serialize_private_key_pem().as_bytes(),
+    )
+    .unwrap();
+    let (handle, mut listener) = WebsocketListener::bind_tls_with_handle(
+        url2!("wss://127.0.0.1:0"),
+        Arc::new(WebsocketConfig::default()),
+        identity,
+    )
+    .await
+    .unwrap();
+    let port = handle.local_addr().port().unwrap();
+    assert_eq!(handle.local_addr().scheme(), "wss");
+
+    // The certificate isn't trusted by default.
+    let (client, server) = tokio::join!(
+        connect(
+            url2!("wss://localhost:{}", port),
+            Arc::new(WebsocketConfig::default())
+        ),
+        listener.next()
+    );
+    assert!(client.is_err());
+    assert!(server.unwrap().is_err());
+    let jh = server_echo(listener);
+
+    let connector = native_tls::TlsConnector::builder()
+        .add_root_certificate(native_tls::Certificate::from_pem(cert_pem.as_bytes()).unwrap())
+        .build()
+        .unwrap();
+    let (sender, _receiver) = holochain_websocket::connect_tls(
+        url2!("wss://localhost:{}", port),
+        Arc::new(WebsocketConfig::default()),
+        connector,
+    )
+    .await
+    .unwrap();
+    client_echo(sender).await;
+    jh.await.unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn can_connect_over_unix_socket() {
+    holochain_trace::test_run().ok();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ws.sock");
+    // Any file other than a socket is left alone.
+    std::fs::write(&path, b"").unwrap();
+    assert!(WebsocketListener::bind_unix_with_handle(
+        &path,
+        None,
+        Arc::new(WebsocketConfig::default())
+    )
+    .await
+    .is_err());
+    assert_eq!(std::fs::read(&path).unwrap(), b"");
+    // A stale socket file is replaced.
+    std::fs::remove_file(&path).unwrap();
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+    let (handle, listener) = WebsocketListener::bind_unix_with_handle(
+        &path,
+        Some(0o600),
+        Arc::new(WebsocketConfig::default()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(handle.local_addr().scheme(), "unix");
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+    // The private directory the socket was bound in is gone.
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    let jh = server_echo(listener);
+
+    let (sender, _receiver) =
+        holochain_websocket::connect_unix(&path, Arc::new(WebsocketConfig::default()))
+            .await
+            .unwrap();
+    client_echo(sender).await;
+    jh.await.unwrap();
+    // The socket file goes with the listener.
+    handle.close();
+    assert!(!path.exists());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn handshake_check_can_refuse_connections() {
+    holochain_trace::test_run().ok();
+    let check = HandshakeCheck::new(|request| {
+        let origin = request.headers().get("Origin");
+        match (request.uri().query(), origin) {
+            (Some("token=secret"), Some(origin)) if origin == "http://localhost" => Ok(()),
+            _ => Err("not allowed".to_string()),
+        }
+    });
+    let (handle, mut listener) = WebsocketListener::bind_with_handle(
+        url2!("ws://127.0.0.1:0"),
+        Arc::new(WebsocketConfig::default().handshake_check(check)),
+    )
+    .await
+    .unwrap();
+    let port = handle.local_addr().port().unwrap();
+
+    for (query, origin) in [("", Some("http://localhost")), ("?token=secret", None)] {
+        let config = WebsocketConfig {
+            origin: origin.map(String::from),
+            ..Default::default()
+        };
+        let (client, server) = tokio::join!(
+            connect(url2!("ws://127.0.0.1:{}{}", port, query), Arc::new(config)),
+            listener.next()
+        );
+        assert!(client.is_err());
+        assert!(server.unwrap().is_err());
+    }
+    let jh = server_echo(listener);
+
+    let (sender, _receiver) = connect(
+        url2!("ws://127.0.0.1:{}?token=secret", port),
+        Arc::new(WebsocketConfig::default().origin("http://localhost")),
+    )
+    .await
+    .unwrap();
+    client_echo(sender).await;
+    jh.await.unwrap();
+}
@@ -42,7 +42,7 @@ impl From<kitsune_p2p_block::AgentSpaceBlockReason> for CellBlockReason {
 }
 
 /// Reason why we might want to block a node.
-#[derive(Clone, serde::Serialize, Debug)]
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
 pub enum NodeBlockReason {
     Kitsune(kitsune_p2p_block::NodeBlockReason),
 }
@@ -54,7 +54,7 @@ impl From<kitsune_p2p_block::NodeBlockReason> for NodeBlockReason {
 }
 
 /// Reason why we might want to block an IP.
-#[derive(Clone, serde::Serialize, Debug)]
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
 pub enum IpBlockReason {
     Kitsune(kitsune_p2p_block::IpBlockReason),
 }
@@ -65,12 +65,16 @@ impl From<kitsune_p2p_block::IpBlockReason> for IpBlockReason {
     }
 }
 
-/// The type to use for identifying blocking ipv4 addresses.
-type IpV4 = std::net::Ipv4Addr;
+/// The type to use for identifying blocking ip addresses.
+type IpAddr = std::net::IpAddr;
+
+/// The type to use for identifying blocking ip ranges, in CIDR notation.
+pub type IpNet = kitsune_p2p_block::IpNet;
 
 /// Target of a block.
 /// Each target type has an ID and associated reason.
-#[derive(Clone, Debug)]
+#[serde_with::serde_as]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub enum BlockTarget {
     /// Some cell did bad at the happ level.
     Cell(CellId, CellBlockReason),
@@ -78,7 +82,31 @@ pub enum BlockTarget {
     /// Some node is playing silly buggers.
     Node(kitsune_p2p_block::NodeId, NodeBlockReason),
     /// An entire college campus has it out for us.
-    Ip(IpV4, IpBlockReason),
+    Ip(IpAddr, IpBlockReason),
+    /// Every address in a range, for clients that hop between them.
+    IpRange(
+        #[serde_as(as = "serde_with::DisplayFromStr")] IpNet,
+        IpBlockReason,
+    ),
+}
+
+impl BlockTarget {
+    /// Put a target back together from its ID and reason, as they are
+    /// stored. Returns `None` if the reason is for a different kind of target.
+    pub fn from_parts(id: BlockTargetId, reason: BlockTargetReason) -> Option<Self> {
+        Some(match (id, reason) {
+            (BlockTargetId::Cell(id), BlockTargetReason::Cell(reason)) => Self::Cell(id, reason),
+            (BlockTargetId::NodeDna(id, dna), BlockTargetReason::NodeDna(reason)) => {
+                Self::NodeDna(id, dna, reason)
+            }
+            (BlockTargetId::Node(id), BlockTargetReason::Node(reason)) => Self::Node(id, reason),
+            (BlockTargetId::Ip(id), BlockTargetReason::Ip(reason)) => Self::Ip(id, reason),
+            (BlockTargetId::IpRange(id), BlockTargetReason::Ip(reason)) => {
+                Self::IpRange(id, reason)
+            }
+            _ => return None,
+        })
+    }
 }
 
 impl From<kitsune_p2p_block::BlockTarget> for BlockTarget {
@@ -91,16 +119,22 @@ impl From<kitsune_p2p_block::BlockTarget> for BlockTarget {
                 Self::Node(node_id, reason.into())
             }
             kitsune_p2p_block::BlockTarget::Ip(ip_addr, reason) => Self::Ip(ip_addr, reason.into()),
+            kitsune_p2p_block::BlockTarget::IpRange(ip_net, reason) => {
+                Self::IpRange(ip_net, reason.into())
+            }
         }
     }
 }
 
-#[derive(Debug, serde::Serialize, Clone)]
+#[serde_with::serde_as]
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
 pub enum BlockTargetId {
     Cell(CellId),
     NodeDna(kitsune_p2p_block::NodeId, DnaHash),
     Node(kitsune_p2p_block::NodeId),
-    Ip(IpV4),
+    Ip(IpAddr),
+    /// Serialized as its CIDR string, e.g. `"2001:db8::/64"`.
+    IpRange(#[serde_as(as = "serde_with::DisplayFromStr")] IpNet),
 }
 
 impl From<kitsune_p2p_block::BlockTargetId> for BlockTargetId {
@@ -111,6 +145,7 @@ impl From<kitsune_p2p_block::BlockTargetId> for BlockTargetId {
             }
             kitsune_p2p_block::BlockTargetId::Node(node_id) => Self::Node(node_id),
             kitsune_p2p_block::BlockTargetId::Ip(ip_addr) => Self::Ip(ip_addr),
+            kitsune_p2p_block::BlockTargetId::IpRange(ip_net) => Self::IpRange(ip_net),
         }
     }
 }
@@ -122,6 +157,26 @@ impl From<BlockTarget> for BlockTargetId {
             BlockTarget::NodeDna(node_id, dna, _) => Self::NodeDna(node_id, dna),
             BlockTarget::Node(id, _) => Self::Node(id),
             BlockTarget::Ip(id, _) => Self::Ip(id),
+            BlockTarget::IpRange(id, _) => Self::IpRange(id),
+        }
+    }
+}
+
+impl BlockTargetId {
+    /// The first and last addresses which an IP target covers, as IPv6
+    /// addresses so that IPv4 and IPv6 targets can be compared. IPv4
+    /// addresses are mapped into `::ffff:0:0/96`.
+    pub fn ip_bounds(&self) -> Option<(std::net::Ipv6Addr, std::net::Ipv6Addr)> {
+        fn to_v6(ip_addr: IpAddr) -> std::net::Ipv6Addr {
+            match ip_addr {
+                IpAddr::V4(ip_addr) => ip_addr.to_ipv6_mapped(),
+                IpAddr::V6(ip_addr) => ip_addr,
+            }
+        }
+        match self {
+            Self::Ip(ip_addr) => Some((to_v6(*ip_addr), to_v6(*ip_addr))),
+            Self::IpRange(ip_net) => Some((to_v6(ip_net.network()), to_v6(ip_net.broadcast()))),
+            _ => None,
         }
     }
 }
@@ -137,7 +192,7 @@ impl ToSql for BlockTargetId {
     }
 }
 
-#[derive(Debug, serde::Serialize, Clone)]
+#[derive(Debug, serde::Serialize, serde::Deserialize, Clone)]
 pub enum BlockTargetReason {
     Cell(CellBlockReason),
     NodeDna(NodeSpaceBlockReason),
@@ -162,7 +217,9 @@ impl From<BlockTarget> for BlockTargetReason {
             BlockTarget::Cell(_, reason) => BlockTargetReason::Cell(reason),
             BlockTarget::NodeDna(_, _, reason) => BlockTargetReason::NodeDna(reason),
             BlockTarget::Node(_, reason) => BlockTargetReason::Node(reason),
-            BlockTarget::Ip(_, reason) => BlockTargetReason::Ip(reason),
+            BlockTarget::Ip(_, reason) | BlockTarget::IpRange(_, reason) => {
+                BlockTargetReason::Ip(reason)
+            }
         }
     }
 }
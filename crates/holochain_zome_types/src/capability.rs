@@ -27,7 +27,10 @@
 mod grant;
 pub use grant::*;
 
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
 pub use holochain_integrity_types::capability::*;
+use holochain_integrity_types::Timestamp;
 use serde::{Deserialize, Serialize};
 
 use crate::CellId;
@@ -41,3 +44,37 @@ pub struct GrantZomeCallCapabilityPayload {
     /// signing for as well as access level, secret and assignees.
     pub cap_grant: ZomeCallCapGrant,
 }
+
+/// A zome call capability grant committed to the local source chain, along
+/// with the chain metadata needed to administer it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapGrantInfo {
+    /// The grant as it was committed.
+    pub cap_grant: ZomeCallCapGrant,
+    /// Hash of the action that created the grant.
+    pub action_hash: ActionHash,
+    /// Timestamp of the action that created the grant.
+    pub created_at: Timestamp,
+    /// Timestamp of the first update or delete of the grant, if any.
+    /// Revoked grants no longer authorize any zome calls.
+    pub revoked_at: Option<Timestamp>,
+}
+
+/// Filter for listing the capability grants of the local source chain.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapGrantQuery {
+    /// Only list grants with exactly this tag.
+    pub tag: Option<String>,
+    /// Also list grants that have been updated or deleted.
+    pub include_revoked: bool,
+}
+
+/// Filter for listing the capability claims of the local source chain.
+/// Deleted claims are never listed.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CapClaimQuery {
+    /// Only list claims with exactly this tag.
+    pub tag: Option<String>,
+    /// Only list claims on grants issued by this agent.
+    pub grantor: Option<AgentPubKey>,
+}
@@ -50,6 +50,12 @@ pub struct DnaDef {
     /// A vector of zomes that do not affect
     /// the [`DnaHash`].
     pub coordinator_zomes: CoordinatorZomes,
+
+    /// The rate limits placed on each rate limiting bucket, which the
+    /// integrity zomes assign actions to in their `weigh` callback.
+    #[serde(default, skip_serializing_if = "RateLimits::is_empty")]
+    #[cfg_attr(feature = "full-dna-def", builder(default))]
+    pub rate_limits: RateLimits,
 }
 
 #[derive(Serialize, Debug, PartialEq, Eq)]
@@ -57,6 +63,8 @@ pub struct DnaDef {
 struct DnaDefHash<'a> {
     modifiers: &'a DnaModifiers,
     integrity_zomes: &'a IntegrityZomes,
+    #[serde(skip_serializing_if = "RateLimits::is_empty")]
+    rate_limits: &'a RateLimits,
 }
 
 #[cfg(feature = "test_utils")]
@@ -241,6 +249,7 @@ impl HashableContent for DnaDef {
         let hash = DnaDefHash {
             modifiers: &self.modifiers,
             integrity_zomes: &self.integrity_zomes,
+            rate_limits: &self.rate_limits,
         };
         HashableContentBytes::Content(
             holochain_serialized_bytes::UnsafeBytes::from(
@@ -0,0 +1,52 @@
+//! Types for talking to a DPKI DNA
+//!
+//! The conductor's built-in DPKI service delegates key management to a DNA.
+//! These are the inputs and outputs of the zome functions that DNA must expose.
+
+use crate::timestamp::Timestamp;
+use holo_hash::AgentPubKey;
+pub use holochain_serialized_bytes::prelude::*;
+
+/// The name of the zome in the DPKI DNA which the conductor calls.
+pub const DPKI_ZOME_NAME: &str = "deepkey";
+
+/// The zome function which returns the [`KeyState`] of a key.
+/// Takes a [`KeyStateQuery`].
+pub const DPKI_KEY_STATE_FN_NAME: &str = "key_state";
+
+/// The zome function which registers, replaces or revokes keys.
+/// Takes a [`KeyMutation`].
+pub const DPKI_KEY_MUTATION_FN_NAME: &str = "key_mutation";
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// Ask for the state of a key as-at some point in time.
+pub struct KeyStateQuery {
+    /// The key to look up.
+    pub key: AgentPubKey,
+    /// The time at which the state of the key is wanted.
+    pub timestamp: Timestamp,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// The state of a key as-at the time it was queried for.
+pub enum KeyState {
+    /// The key was never registered with DPKI.
+    NotFound,
+    /// The key was registered at the given time, and not revoked.
+    Valid(Timestamp),
+    /// The key was revoked, or replaced by another key, at the given time.
+    Invalid(Timestamp),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// A change to the set of keys known to DPKI.
+///
+/// - If only `new_key` is set, it is registered.
+/// - If only `old_key` is set, it is revoked.
+/// - If both are set, `new_key` atomically replaces `old_key`.
+pub struct KeyMutation {
+    /// The key to revoke or replace.
+    pub old_key: Option<AgentPubKey>,
+    /// The key to register.
+    pub new_key: Option<AgentPubKey>,
+}
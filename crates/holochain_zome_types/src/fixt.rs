@@ -758,6 +758,7 @@ fixturator!(
         coordinator_zomes: CoordinatorZomesFixturator::new_indexed(Empty, get_fixt_index!())
             .next()
             .unwrap(),
+        rate_limits: Default::default(),
     };
 
     curve Unpredictable DnaDef {
@@ -780,6 +781,7 @@ fixturator!(
         coordinator_zomes: CoordinatorZomesFixturator::new_indexed(Empty, get_fixt_index!())
             .next()
             .unwrap(),
+        rate_limits: Default::default(),
     };
 
     curve Predictable DnaDef {
@@ -802,6 +804,7 @@ fixturator!(
         coordinator_zomes: CoordinatorZomesFixturator::new_indexed(Empty, get_fixt_index!())
             .next()
             .unwrap(),
+        rate_limits: Default::default(),
     };
 );
 
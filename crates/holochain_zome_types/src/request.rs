@@ -8,13 +8,14 @@ pub struct MetadataRequest {
     /// Get all the actions on an entry.
     /// Invalid request on an action.
     pub all_valid_actions: bool,
-    // TODO: Implement after validation
-    /// Placeholder
+    /// Get all the actions on an entry or action which failed validation.
     pub all_invalid_actions: bool,
     /// Get all the deletes on an action
     pub all_deletes: bool,
     /// Get all the updates on an entry or action
     pub all_updates: bool,
+    /// Get all the link creates and deletes on a base
+    pub all_links: bool,
     /// Placeholder
     pub follow_redirects: bool,
     /// Request the status of an entry.
@@ -30,6 +31,7 @@ impl Default for MetadataRequest {
             all_invalid_actions: false,
             all_deletes: true,
             all_updates: true,
+            all_links: true,
             follow_redirects: false,
             entry_dht_status: false,
         }
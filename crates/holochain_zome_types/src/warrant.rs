@@ -1,6 +1,86 @@
 //! Types for warrants
+//!
+//! A warrant is a proof of invalid behaviour by an author, issued and signed
+//! by the validator which found it. Warrants are published to the agent
+//! activity authorities of the offending author, so that anyone who looks up
+//! that author's activity can learn about it.
+
+use crate::record::SignedAction;
+use crate::signature::Signature;
+use crate::timestamp::Timestamp;
+use holo_hash::AgentPubKey;
+use holo_hash::DhtOpHash;
 pub use holochain_serialized_bytes::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-/// Placeholder for warrant type
-pub struct Warrant;
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// A signed claim by a validator that another author has behaved invalidly.
+pub struct Warrant {
+    /// The evidence of invalid behaviour.
+    pub proof: WarrantProof,
+    /// The validator which issued this warrant.
+    pub author: AgentPubKey,
+    /// When this warrant was issued.
+    pub timestamp: Timestamp,
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// The evidence a [`Warrant`] is based on.
+pub enum WarrantProof {
+    /// An op produced from this action was rejected by validation.
+    InvalidChainOp {
+        /// The offending action, signed by its author.
+        action: SignedAction,
+        /// The hash of the op which failed validation.
+        op_hash: DhtOpHash,
+    },
+}
+
+impl Warrant {
+    /// Create a new warrant.
+    pub fn new(proof: WarrantProof, author: AgentPubKey, timestamp: Timestamp) -> Self {
+        Self {
+            proof,
+            author,
+            timestamp,
+        }
+    }
+
+    /// The author whose behaviour this warrant is about.
+    pub fn warrantee(&self) -> &AgentPubKey {
+        match &self.proof {
+            WarrantProof::InvalidChainOp { action, .. } => action.action().author(),
+        }
+    }
+
+    /// The offending action, signed by its author.
+    pub fn signed_action(&self) -> &SignedAction {
+        match &self.proof {
+            WarrantProof::InvalidChainOp { action, .. } => action,
+        }
+    }
+}
+
+#[derive(
+    Clone, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+/// A [`Warrant`] with the signature of its author.
+pub struct SignedWarrant(pub Warrant, pub Signature);
+
+impl SignedWarrant {
+    /// Accessor for the Warrant
+    pub fn warrant(&self) -> &Warrant {
+        &self.0
+    }
+
+    /// Accessor for the Signature
+    pub fn signature(&self) -> &Signature {
+        &self.1
+    }
+}
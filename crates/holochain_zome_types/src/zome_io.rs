@@ -55,14 +55,15 @@ wasm_io_types! {
 
     fn call (Vec<zt::call::Call>) -> Vec<zt::ZomeCallResponse>;
 
-    // @todo List all the local capability claims.
-    fn capability_claims (()) -> ();
+    // List all the live local capability claims.
+    fn capability_claims (zt::capability::CapClaimQuery) -> Vec<zt::capability::CapClaim>;
 
-    // @todo List all the local capability grants.
-    fn capability_grants (()) -> ();
+    // List all the local capability grants.
+    fn capability_grants (zt::capability::CapGrantQuery) -> Vec<zt::capability::CapGrantInfo>;
 
-    // @todo Get the capability for the current zome call.
-    fn capability_info (()) -> ();
+    // Get the grant that authorized the current zome call.
+    // `None` if the call was authorized as the chain author.
+    fn capability_info (()) -> Option<zt::capability::CapGrantInfo>;
 
     // Returns ActionHash of the newly created record.
     fn create (zt::entry::CreateInput) -> holo_hash::ActionHash;
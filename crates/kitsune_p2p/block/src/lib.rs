@@ -1,13 +1,16 @@
 use kitsune_p2p_timestamp::InclusiveTimestampInterval;
 use kitsune_p2p_timestamp::Timestamp;
+use std::net::IpAddr;
 use std::sync::Arc;
 
+pub use ipnet::IpNet;
+
 #[derive(Clone)]
 pub enum AgentSpaceBlockReason {
     BadCrypto,
 }
 
-#[derive(Clone, serde::Serialize, Debug)]
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
 pub enum NodeBlockReason {
     /// The node did some bad cryptography.
     BadCrypto,
@@ -15,15 +18,17 @@ pub enum NodeBlockReason {
     DOS,
 }
 
-#[derive(Clone, serde::Serialize, Debug)]
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
 pub enum NodeSpaceBlockReason {
     BadWire,
 }
 
-#[derive(Clone, serde::Serialize, Debug)]
+#[derive(Clone, serde::Serialize, serde::Deserialize, Debug)]
 pub enum IpBlockReason {
     /// Classic DOS.
     DOS,
+    /// Abusive behaviour that isn't a DOS, e.g. spam.
+    Abuse,
 }
 
 pub type NodeId = Arc<[u8; 32]>;
@@ -36,13 +41,17 @@ pub enum BlockTarget {
         Arc<kitsune_p2p_bin_data::KitsuneSpace>,
         NodeSpaceBlockReason,
     ),
-    Ip(std::net::Ipv4Addr, IpBlockReason),
+    Ip(IpAddr, IpBlockReason),
+    /// Every address in a CIDR range, e.g. a /64 that an abusive client
+    /// rotates its IPv6 address within.
+    IpRange(IpNet, IpBlockReason),
 }
 
 pub enum BlockTargetId {
     Node(NodeId),
     NodeSpace(NodeId, Arc<kitsune_p2p_bin_data::KitsuneSpace>),
-    Ip(std::net::Ipv4Addr),
+    Ip(IpAddr),
+    IpRange(IpNet),
 }
 
 impl From<BlockTarget> for BlockTargetId {
@@ -51,6 +60,7 @@ impl From<BlockTarget> for BlockTargetId {
             BlockTarget::NodeSpace(node_id, space, _) => Self::NodeSpace(node_id, space),
             BlockTarget::Node(node_id, _) => Self::Node(node_id),
             BlockTarget::Ip(ip_addr, _) => Self::Ip(ip_addr),
+            BlockTarget::IpRange(ip_net, _) => Self::IpRange(ip_net),
         }
     }
 }
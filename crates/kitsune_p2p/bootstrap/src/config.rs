@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use kitsune_p2p_types::bin_types::KitsuneSpace;
+
+use crate::PutQuota;
+use crate::PRUNE_EXPIRED_FREQ;
+
+/// The most peers a single `random` query may ask for, unless configured otherwise.
+pub const DEFAULT_MAX_RANDOM_LIMIT: u32 = 64;
+
+/// Configuration for a bootstrap server.
+#[derive(Clone, Debug)]
+pub struct BootstrapConfig {
+    /// Proxy server addresses to return from `proxy_list` calls.
+    pub proxy_list: Vec<String>,
+
+    /// How often expired entries are pruned.
+    pub prune_frequency: Duration,
+
+    /// Cap on the `limit` of a `random` query. Larger limits are
+    /// silently reduced to this.
+    pub max_random_limit: u32,
+
+    /// If set, each source address may only make this many puts
+    /// within the quota's window.
+    pub put_quota: Option<PutQuota>,
+
+    /// If set, only these spaces can be put into or queried.
+    pub space_allow_list: Option<HashSet<Arc<KitsuneSpace>>>,
+
+    /// If set, peer data is persisted in an SQLite database at this path,
+    /// so that it survives restarts. Otherwise it is only kept in memory.
+    #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+    pub database_path: Option<std::path::PathBuf>,
+}
+
+impl Default for BootstrapConfig {
+    fn default() -> Self {
+        Self {
+            proxy_list: Vec::new(),
+            prune_frequency: PRUNE_EXPIRED_FREQ,
+            max_random_limit: DEFAULT_MAX_RANDOM_LIMIT,
+            put_quota: None,
+            space_allow_list: None,
+            #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+            database_path: None,
+        }
+    }
+}
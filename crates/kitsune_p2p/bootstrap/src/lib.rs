@@ -14,12 +14,19 @@ static RANDOM: AtomicUsize = AtomicUsize::new(0);
 static PUT: AtomicUsize = AtomicUsize::new(0);
 
 mod clear;
+mod config;
 mod now;
+#[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+mod persist;
 mod proxy_list;
 mod put;
+mod quota;
 mod random;
 mod store;
 
+pub use config::*;
+pub use quota::PutQuota;
+
 /// No reason to accept a peer data bigger then 1KB.
 // TODO: Maybe even that's too high?
 const SIZE_LIMIT: u64 = 1024;
@@ -45,14 +52,34 @@ pub async fn run_with_prune_freq(
     proxy_list: Vec<String>,
     prune_frequency: std::time::Duration,
 ) -> Result<(BootstrapDriver, SocketAddr, BootstrapShutdown), String> {
-    let store = Store::new(proxy_list);
+    run_with_config(
+        addr,
+        BootstrapConfig {
+            proxy_list,
+            prune_frequency,
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+/// Run a bootstrap server with the given configuration.
+pub async fn run_with_config(
+    addr: impl Into<SocketAddr> + 'static,
+    config: BootstrapConfig,
+) -> Result<(BootstrapDriver, SocketAddr, BootstrapShutdown), String> {
+    let store =
+        Store::from_config(&config).map_err(|e| format!("Failed to open store: {:?}", e))?;
 
     {
         let store = store.clone();
+        let prune_frequency = config.prune_frequency;
         tokio::task::spawn(async move {
             loop {
                 tokio::time::sleep(prune_frequency).await;
-                store.prune();
+                if let Err(e) = store.prune().await {
+                    tracing::error!(?e, "Failed to prune the bootstrap store");
+                }
             }
         });
     }
@@ -1,4 +1,10 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use clap::Parser;
+use kitsune_p2p_bootstrap::BootstrapConfig;
+use kitsune_p2p_bootstrap::PutQuota;
+use kitsune_p2p_types::bin_types::KitsuneSpace;
 
 #[derive(Parser, Debug)]
 #[clap(version, about, long_about = None)]
@@ -12,6 +18,37 @@ struct Args {
     /// multiple times
     #[clap(short, long, verbatim_doc_comment)]
     proxy: Vec<String>,
+
+    /// the most peers a single `random` query may return
+    #[clap(long, default_value_t = kitsune_p2p_bootstrap::DEFAULT_MAX_RANDOM_LIMIT)]
+    max_random_limit: u32,
+
+    /// the most puts a single source address may make
+    /// within `--put-quota-window-secs`, unlimited if not set
+    #[clap(long, verbatim_doc_comment)]
+    put_quota: Option<u32>,
+
+    /// the length of the window for `--put-quota`
+    #[clap(long, default_value_t = 60)]
+    put_quota_window_secs: u64,
+
+    /// only accept and serve peers in this space, given in
+    /// url-safe base64, can be specified multiple times.
+    /// If not specified, all spaces are allowed
+    #[clap(long, verbatim_doc_comment, parse(try_from_str = parse_space))]
+    allow_space: Vec<Arc<KitsuneSpace>>,
+
+    /// persist peer data in an sqlite database at this path,
+    /// so that it survives restarts
+    #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+    #[clap(long, verbatim_doc_comment)]
+    database: Option<std::path::PathBuf>,
+}
+
+fn parse_space(space: &str) -> Result<Arc<KitsuneSpace>, String> {
+    base64::decode_config(space, base64::URL_SAFE_NO_PAD)
+        .map(|bytes| Arc::new(KitsuneSpace(bytes)))
+        .map_err(|e| format!("must be url-safe base64: {}", e))
 }
 
 #[tokio::main(flavor = "multi_thread")]
@@ -27,7 +64,25 @@ async fn main() {
         .next()
         .unwrap();
 
-    match kitsune_p2p_bootstrap::run(addr, args.proxy).await {
+    let space_allow_list = if args.allow_space.is_empty() {
+        None
+    } else {
+        Some(args.allow_space.into_iter().collect())
+    };
+    let config = BootstrapConfig {
+        proxy_list: args.proxy,
+        max_random_limit: args.max_random_limit,
+        put_quota: args.put_quota.map(|max_puts| PutQuota {
+            max_puts,
+            window: Duration::from_secs(args.put_quota_window_secs),
+        }),
+        space_allow_list,
+        #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+        database_path: args.database,
+        ..Default::default()
+    };
+
+    match kitsune_p2p_bootstrap::run_with_config(addr, config).await {
         Ok((driver, addr, _shutdown)) => {
             println!("http://{}", addr);
             driver.await;
@@ -0,0 +1,91 @@
+use std::path::Path;
+
+use kitsune_p2p_types::agent_info::AgentInfoSigned;
+use kitsune_p2p_types::codec::rmp_decode;
+use kitsune_p2p_types::codec::rmp_encode;
+use rusqlite::params;
+use rusqlite::Connection;
+
+/// Keeps peer data in an SQLite database, so that the bootstrap
+/// server can pick up where it left off after a restart.
+#[derive(Debug)]
+pub(crate) struct Persist(Connection);
+
+fn io_err(e: rusqlite::Error) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::Other, e)
+}
+
+impl Persist {
+    pub fn open(path: &Path) -> std::io::Result<Self> {
+        let conn = Connection::open(path).map_err(io_err)?;
+        conn.execute_batch(
+            "
+            PRAGMA journal_mode = WAL;
+            CREATE TABLE IF NOT EXISTS AgentInfo (
+                space           BLOB    NOT NULL,
+                agent           BLOB    NOT NULL,
+                signed_at_ms    INTEGER NOT NULL,
+                expires_at_ms   INTEGER NOT NULL,
+                blob            BLOB    NOT NULL,
+                PRIMARY KEY (space, agent)
+            );
+            ",
+        )
+        .map_err(io_err)?;
+        Ok(Self(conn))
+    }
+
+    /// All the peer data which has not expired by `now`.
+    pub fn load(&self, now: u64) -> std::io::Result<Vec<AgentInfoSigned>> {
+        let mut stmt = self
+            .0
+            .prepare("SELECT blob FROM AgentInfo WHERE expires_at_ms > ?")
+            .map_err(io_err)?;
+        let blobs = stmt
+            .query_map([now as i64], |row| row.get::<_, Vec<u8>>(0))
+            .map_err(io_err)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(io_err)?;
+        blobs
+            .into_iter()
+            .map(|blob| rmp_decode(&mut blob.as_slice()))
+            .collect()
+    }
+
+    pub fn put(&self, info: &AgentInfoSigned) -> std::io::Result<()> {
+        let mut blob = Vec::new();
+        rmp_encode(&mut blob, info)?;
+        self.0
+            .execute(
+                "INSERT OR REPLACE INTO AgentInfo
+                (space, agent, signed_at_ms, expires_at_ms, blob)
+                VALUES (?, ?, ?, ?, ?)",
+                params![
+                    info.space.0,
+                    info.agent.0,
+                    info.signed_at_ms as i64,
+                    info.expires_at_ms as i64,
+                    blob
+                ],
+            )
+            .map_err(io_err)?;
+        Ok(())
+    }
+
+    pub fn prune(&self, now: u64) -> std::io::Result<()> {
+        self.0
+            .execute(
+                "DELETE FROM AgentInfo WHERE expires_at_ms < ?",
+                [now as i64],
+            )
+            .map_err(io_err)?;
+        Ok(())
+    }
+
+    pub fn clear(&self) -> std::io::Result<()> {
+        self.0
+            .execute("DELETE FROM AgentInfo", [])
+            .map_err(io_err)?;
+        Ok(())
+    }
+}
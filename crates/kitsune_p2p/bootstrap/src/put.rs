@@ -2,7 +2,9 @@ use crate::store::Store;
 
 use super::*;
 use kitsune_p2p_types::agent_info::AgentInfoSigned;
+use warp::http::StatusCode;
 use warp::Filter;
+use warp::Reply;
 
 pub(crate) fn put(
     store: Store,
@@ -10,22 +12,35 @@ pub(crate) fn put(
     warp::post()
         .and(warp::header::exact("X-Op", "put"))
         .and(warp::body::content_length_limit(SIZE_LIMIT))
+        .and(warp::addr::remote())
         .and(warp::body::bytes())
         .and(with_store(store))
         .and_then(put_info)
 }
 
-async fn put_info(peer: Bytes, store: Store) -> Result<impl warp::Reply, warp::Rejection> {
+async fn put_info(
+    remote: Option<SocketAddr>,
+    peer: Bytes,
+    store: Store,
+) -> Result<warp::reply::Response, warp::Rejection> {
+    if let Some(remote) = remote {
+        if !store.check_put_quota(remote.ip()) {
+            return Ok(StatusCode::TOO_MANY_REQUESTS.into_response());
+        }
+    }
     let peer: AgentInfoSigned =
         rmp_decode(&mut AsRef::<[u8]>::as_ref(&peer)).map_err(|_| warp::reject())?;
+    if !store.is_space_allowed(&peer.space) {
+        return Ok(StatusCode::FORBIDDEN.into_response());
+    }
     // TODO: Return rejection if agent info was invalid?
     if valid(&peer) {
-        store.put(peer);
+        store.put(peer).await.map_err(|_| warp::reject())?;
     }
     PUT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     let mut buf = Vec::with_capacity(1);
     rmp_encode(&mut buf, ()).map_err(|_| warp::reject())?;
-    Ok(buf)
+    Ok(buf.into_response())
 }
 
 fn valid(peer: &AgentInfoSigned) -> bool {
@@ -44,7 +59,7 @@ mod tests {
 
     use super::*;
     use fixt::prelude::*;
-    use kitsune_p2p::fixt::*;
+    use kitsune_p2p::{fixt::*, KitsuneSpace};
 
     #[tokio::test(flavor = "multi_thread")]
     async fn test_put() {
@@ -83,4 +98,88 @@ mod tests {
             info
         );
     }
+
+    async fn info(space: Arc<KitsuneSpace>) -> AgentInfoSigned {
+        AgentInfoSigned::sign(
+            space,
+            Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+            u32::MAX / 4,
+            fixt!(UrlList, Empty),
+            0,
+            std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64 + 60_000_000,
+            |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
+        )
+        .await
+        .unwrap()
+    }
+
+    async fn put_from(
+        filter: &(impl Filter<Extract = impl warp::Reply + Sized, Error = warp::Rejection>
+              + Clone
+              + 'static),
+        remote: SocketAddr,
+        info: AgentInfoSigned,
+    ) -> StatusCode {
+        let mut buf = Vec::new();
+        rmp_encode(&mut buf, info).unwrap();
+        warp::test::request()
+            .method("POST")
+            .header("Content-type", "application/octet")
+            .header("X-Op", "put")
+            .remote_addr(remote)
+            .body(buf)
+            .reply(filter)
+            .await
+            .status()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_quota() {
+        let store = Store::from_config(&BootstrapConfig {
+            put_quota: Some(PutQuota {
+                max_puts: 2,
+                window: std::time::Duration::from_secs(60),
+            }),
+            ..Default::default()
+        })
+        .unwrap();
+        let filter = put(store.clone());
+        let space = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        let a = SocketAddr::from(([127, 0, 0, 1], 1000));
+        let b = SocketAddr::from(([127, 0, 0, 2], 1000));
+
+        assert_eq!(put_from(&filter, a, info(space.clone()).await).await, 200);
+        assert_eq!(put_from(&filter, a, info(space.clone()).await).await, 200);
+        assert_eq!(
+            put_from(&filter, a, info(space.clone()).await).await,
+            StatusCode::TOO_MANY_REQUESTS
+        );
+        // - Other sources have their own quota
+        assert_eq!(put_from(&filter, b, info(space.clone()).await).await, 200);
+        assert_eq!(store.all().get(space.as_ref()).unwrap().len(), 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_allow_list() {
+        let allowed = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        let store = Store::from_config(&BootstrapConfig {
+            space_allow_list: Some([allowed.clone()].into_iter().collect()),
+            ..Default::default()
+        })
+        .unwrap();
+        let filter = put(store.clone());
+        let remote = SocketAddr::from(([127, 0, 0, 1], 1000));
+
+        assert_eq!(
+            put_from(&filter, remote, info(allowed.clone()).await).await,
+            200
+        );
+        let other = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        assert_eq!(
+            put_from(&filter, remote, info(other.clone()).await).await,
+            StatusCode::FORBIDDEN
+        );
+        assert!(store.all().get(allowed.as_ref()).is_some());
+        assert!(store.all().get(other.as_ref()).is_none());
+    }
 }
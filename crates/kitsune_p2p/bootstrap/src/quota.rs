@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use parking_lot::Mutex;
+
+/// How many puts a single source address may make within a window of time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PutQuota {
+    /// The number of puts allowed per window.
+    pub max_puts: u32,
+    /// The length of the window.
+    pub window: Duration,
+}
+
+/// Counts the puts made by each source address in its current window.
+#[derive(Clone, Debug)]
+pub(crate) struct PutQuotaTracker {
+    quota: PutQuota,
+    used: Arc<Mutex<HashMap<IpAddr, (Instant, u32)>>>,
+}
+
+impl PutQuotaTracker {
+    pub fn new(quota: PutQuota) -> Self {
+        Self {
+            quota,
+            used: Default::default(),
+        }
+    }
+
+    /// Count a put from this address, returning false if it is over quota.
+    pub fn check(&self, addr: IpAddr) -> bool {
+        let now = Instant::now();
+        let mut used = self.used.lock();
+        let (window_start, count) = used.entry(addr).or_insert((now, 0));
+        if now.duration_since(*window_start) >= self.quota.window {
+            *window_start = now;
+            *count = 0;
+        }
+        if *count >= self.quota.max_puts {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+
+    /// Forget the addresses whose window has ended.
+    pub fn prune(&self) {
+        let now = Instant::now();
+        self.used
+            .lock()
+            .retain(|_, (window_start, _)| now.duration_since(*window_start) < self.quota.window);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_quota() {
+        let tracker = PutQuotaTracker::new(PutQuota {
+            max_puts: 2,
+            window: Duration::from_millis(100),
+        });
+        let a: IpAddr = [127, 0, 0, 1].into();
+        let b: IpAddr = [127, 0, 0, 2].into();
+
+        assert!(tracker.check(a));
+        assert!(tracker.check(a));
+        assert!(!tracker.check(a));
+        // - Other addresses have their own quota
+        assert!(tracker.check(b));
+
+        // - The quota is restored once the window has passed
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(tracker.check(a));
+    }
+}
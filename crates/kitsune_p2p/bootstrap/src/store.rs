@@ -1,4 +1,4 @@
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, collections::HashSet, net::IpAddr, sync::Arc};
 
 use kitsune_p2p_types::{
     agent_info::AgentInfoSigned,
@@ -9,57 +9,179 @@ use kitsune_p2p_types::{
 use parking_lot::RwLock;
 use rand::seq::IteratorRandom;
 
+use crate::quota::PutQuotaTracker;
+use crate::BootstrapConfig;
+
 type AgentMap = HashMap<Arc<KitsuneAgent>, AgentInfoSigned>;
 type SpaceMap = HashMap<Arc<KitsuneSpace>, AgentMap>;
 
 #[derive(Clone, Debug)]
-pub(crate) struct Store(Arc<RwLock<SpaceMap>>, Arc<Vec<String>>);
+pub(crate) struct Store {
+    spaces: Arc<RwLock<SpaceMap>>,
+    proxy_list: Arc<Vec<String>>,
+    max_random_limit: u32,
+    put_quota: Option<PutQuotaTracker>,
+    space_allow_list: Option<Arc<HashSet<Arc<KitsuneSpace>>>>,
+    #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+    persist: Option<Arc<parking_lot::Mutex<crate::persist::Persist>>>,
+}
+
+fn now_ms() -> u64 {
+    std::time::UNIX_EPOCH
+        .elapsed()
+        .expect("Bootstrap server time set before epoch")
+        .as_millis() as u64
+}
 
 impl Store {
+    #[cfg(test)]
     pub fn new(proxy_list: Vec<String>) -> Self {
-        Self(Arc::new(RwLock::new(HashMap::new())), Arc::new(proxy_list))
+        Self::from_config(&BootstrapConfig {
+            proxy_list,
+            ..Default::default()
+        })
+        .expect("An in-memory store can't fail to open")
+    }
+
+    /// Create the store, loading any unexpired peer data from the
+    /// database if one is configured.
+    pub fn from_config(config: &BootstrapConfig) -> std::io::Result<Self> {
+        #[allow(unused_mut)]
+        let mut store = Self {
+            spaces: Default::default(),
+            proxy_list: Arc::new(config.proxy_list.clone()),
+            max_random_limit: config.max_random_limit,
+            put_quota: config.put_quota.map(PutQuotaTracker::new),
+            space_allow_list: config.space_allow_list.clone().map(Arc::new),
+            #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+            persist: None,
+        };
+        #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+        if let Some(path) = &config.database_path {
+            let persist = crate::persist::Persist::open(path)?;
+            for info in persist.load(now_ms())? {
+                store.put_in_memory(info);
+            }
+            store.persist = Some(Arc::new(parking_lot::Mutex::new(persist)));
+        }
+        Ok(store)
     }
 
     pub fn proxy_list(&self) -> Arc<Vec<String>> {
-        self.1.clone()
+        self.proxy_list.clone()
+    }
+
+    /// Whether peers may be put into or queried from this space.
+    pub fn is_space_allowed(&self, space: &KitsuneSpace) -> bool {
+        match &self.space_allow_list {
+            Some(allowed) => allowed.contains(space),
+            None => true,
+        }
     }
 
-    pub fn prune(&self) {
-        let now = std::time::UNIX_EPOCH
-            .elapsed()
-            .expect("Bootstrap server time set before epoch")
-            .as_millis() as u64;
+    /// Count a put from this address, returning false if it is over quota.
+    pub fn check_put_quota(&self, addr: IpAddr) -> bool {
+        match &self.put_quota {
+            Some(quota) => quota.check(addr),
+            None => true,
+        }
+    }
+
+    /// Run an operation on the store on the blocking thread pool, because
+    /// it may write to the database.
+    async fn blocking<R: Send + 'static>(
+        &self,
+        f: impl FnOnce(&Store) -> std::io::Result<R> + Send + 'static,
+    ) -> std::io::Result<R> {
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || f(&store))
+            .await
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::Other, e))?
+    }
+
+    pub async fn prune(&self) -> std::io::Result<()> {
+        self.blocking(Store::prune_blocking).await
+    }
 
-        self.0.write().retain(|_, map| {
+    fn prune_blocking(&self) -> std::io::Result<()> {
+        let now = now_ms();
+
+        // Prune the database first, so that it never holds less than memory.
+        #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+        if let Some(persist) = &self.persist {
+            persist.lock().prune(now)?;
+        }
+        self.spaces.write().retain(|_, map| {
             map.retain(|_, info| info.expires_at_ms >= now);
             !map.is_empty()
         });
+        if let Some(quota) = &self.put_quota {
+            quota.prune();
+        }
+        Ok(())
     }
 
-    pub fn put(&self, info: AgentInfoSigned) {
-        let mut lock = self.0.write();
+    pub async fn put(&self, info: AgentInfoSigned) -> std::io::Result<()> {
+        self.blocking(move |store| store.put_blocking(info)).await
+    }
+
+    fn put_blocking(&self, info: AgentInfoSigned) -> std::io::Result<()> {
+        #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+        if let Some(persist) = &self.persist {
+            // Hold the lock from checking memory until updating it, so that
+            // concurrent puts can't leave an older info in either place.
+            // The database is written first, so a failed write leaves
+            // memory as it was.
+            let persist = persist.lock();
+            if self.is_newer(&info) {
+                persist.put(&info)?;
+                self.put_in_memory(info);
+            }
+            return Ok(());
+        }
+        self.put_in_memory(info);
+        Ok(())
+    }
+
+    /// Whether the info is newer than what is already stored for its agent.
+    #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+    fn is_newer(&self, info: &AgentInfoSigned) -> bool {
+        self.spaces
+            .read()
+            .get(&info.space)
+            .and_then(|space_map| space_map.get(&info.agent))
+            .map_or(true, |stored| info.signed_at_ms > stored.signed_at_ms)
+    }
+
+    /// Put the info if it is newer than what is already stored,
+    /// returning it if it was.
+    fn put_in_memory(&self, info: AgentInfoSigned) -> Option<AgentInfoSigned> {
+        let mut lock = self.spaces.write();
         let space_map = lock.entry(info.space.clone()).or_insert_with(HashMap::new);
         match space_map.entry(info.agent.clone()) {
             std::collections::hash_map::Entry::Occupied(mut e) => {
                 if info.signed_at_ms > e.get().signed_at_ms {
-                    e.insert(info);
+                    e.insert(info.clone());
+                    Some(info)
+                } else {
+                    None
                 }
             }
             std::collections::hash_map::Entry::Vacant(e) => {
-                e.insert(info);
+                e.insert(info.clone());
+                Some(info)
             }
         }
     }
 
     pub fn random(&self, query: RandomQuery) -> Vec<Vec<u8>> {
-        // TODO: Max this limit
-        let limit = query.limit.0 as usize;
+        if !self.is_space_allowed(&query.space) {
+            return Vec::new();
+        }
+        let limit = query.limit.0.min(self.max_random_limit) as usize;
         let mut rng = rand::thread_rng();
-        let now = std::time::UNIX_EPOCH
-            .elapsed()
-            .expect("Bootstrap server time set before epoch")
-            .as_millis() as u64;
-        self.0
+        let now = now_ms();
+        self.spaces
             .read()
             .get(query.space.as_ref())
             .map(|space| {
@@ -83,12 +205,90 @@ impl Store {
             .unwrap_or_default()
     }
 
-    pub fn clear(&self) {
-        self.0.write().clear()
+    pub async fn clear(&self) -> std::io::Result<()> {
+        self.blocking(Store::clear_blocking).await
+    }
+
+    fn clear_blocking(&self) -> std::io::Result<()> {
+        #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+        if let Some(persist) = &self.persist {
+            persist.lock().clear()?;
+        }
+        self.spaces.write().clear();
+        Ok(())
     }
 
     #[cfg(test)]
     pub fn all(&self) -> HashMap<Arc<KitsuneSpace>, HashMap<Arc<KitsuneAgent>, AgentInfoSigned>> {
-        self.0.read().clone()
+        self.spaces.read().clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use fixt::prelude::*;
+    use kitsune_p2p::fixt::*;
+    use kitsune_p2p_types::bootstrap::RandomLimit;
+
+    async fn info(space: Arc<KitsuneSpace>) -> AgentInfoSigned {
+        AgentInfoSigned::sign(
+            space,
+            Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+            u32::MAX / 4,
+            vec!["fake:".into()],
+            0,
+            now_ms() + 60_000_000,
+            |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_random_limit_is_capped() {
+        let store = Store::from_config(&BootstrapConfig {
+            max_random_limit: 5,
+            ..Default::default()
+        })
+        .unwrap();
+        let space = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        for _ in 0..10 {
+            store.put(info(space.clone()).await).await.unwrap();
+        }
+        let result = store.random(RandomQuery {
+            space,
+            limit: RandomLimit(u32::MAX),
+        });
+        assert_eq!(result.len(), 5);
+    }
+
+    #[cfg(any(feature = "sqlite", feature = "sqlite-encrypted"))]
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_store_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = BootstrapConfig {
+            database_path: Some(dir.path().join("bootstrap.sqlite3")),
+            ..Default::default()
+        };
+        let space = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        let info = info(space.clone()).await;
+
+        let store = Store::from_config(&config).unwrap();
+        store.put(info.clone()).await.unwrap();
+        drop(store);
+
+        // - A store opened on the same database has the peer
+        let store = Store::from_config(&config).unwrap();
+        assert_eq!(
+            store.all().get(space.as_ref()).unwrap().get(&info.agent),
+            Some(&info)
+        );
+
+        // - But not once it has been cleared
+        store.clear().await.unwrap();
+        drop(store);
+        let store = Store::from_config(&config).unwrap();
+        assert!(store.all().is_empty());
     }
 }
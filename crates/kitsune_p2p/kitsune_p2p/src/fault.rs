@@ -0,0 +1,173 @@
+//! Fault injection on the links between nodes, for simulating unreliable
+//! networks and partitions in tests.
+//!
+//! A [`NetworkFaults`] handle is given to a node through its [`KitsuneHost`](crate::KitsuneHost).
+//! Every notify or request the node sends to a remote node first consults the
+//! [`LinkFault`] configured for that remote, which can delay the message or
+//! drop it altogether. Faults only act on the sending side, so a partition
+//! between two nodes is made by configuring each of them to drop messages to
+//! the other.
+
+use kitsune_p2p_block::NodeId;
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The faults to inject on messages sent to one remote node.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct LinkFault {
+    /// How long to hold each message before sending it.
+    pub latency: Duration,
+
+    /// The probability, from 0.0 to 1.0, that a message is dropped
+    /// instead of sent.
+    pub drop_rate: f64,
+}
+
+impl LinkFault {
+    /// A link over which no message gets through.
+    pub fn partitioned() -> Self {
+        Self {
+            latency: Duration::ZERO,
+            drop_rate: 1.0,
+        }
+    }
+
+    /// A link which delays every message.
+    pub fn latency(latency: Duration) -> Self {
+        Self {
+            latency,
+            drop_rate: 0.0,
+        }
+    }
+
+    /// A link which drops some proportion of the messages.
+    pub fn drop_rate(drop_rate: f64) -> Self {
+        Self {
+            latency: Duration::ZERO,
+            drop_rate,
+        }
+    }
+}
+
+/// A shared, mutable set of [`LinkFault`]s keyed by the remote node id.
+///
+/// Dropped messages are chosen with a seeded rng, so a test which sends the
+/// same messages in the same order sees the same messages dropped.
+#[derive(Clone, Debug)]
+pub struct NetworkFaults(Arc<Mutex<NetworkFaultsInner>>);
+
+#[derive(Debug)]
+struct NetworkFaultsInner {
+    links: HashMap<NodeId, LinkFault>,
+    rng: StdRng,
+    dropped: u64,
+}
+
+impl Default for NetworkFaults {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl NetworkFaults {
+    /// Construct an empty set of faults, seeding the rng used to drop messages.
+    pub fn new(seed: u64) -> Self {
+        Self(Arc::new(Mutex::new(NetworkFaultsInner {
+            links: HashMap::new(),
+            rng: StdRng::seed_from_u64(seed),
+            dropped: 0,
+        })))
+    }
+
+    /// Set the faults on the link to a remote node, replacing any already set.
+    pub fn set_link(&self, remote: NodeId, fault: LinkFault) {
+        self.0.lock().links.insert(remote, fault);
+    }
+
+    /// Get the faults on the link to a remote node, if any are set.
+    pub fn link(&self, remote: &NodeId) -> Option<LinkFault> {
+        self.0.lock().links.get(remote).copied()
+    }
+
+    /// Remove the faults on the link to a remote node.
+    pub fn heal_link(&self, remote: &NodeId) {
+        self.0.lock().links.remove(remote);
+    }
+
+    /// Remove the faults on every link.
+    pub fn heal(&self) {
+        self.0.lock().links.clear();
+    }
+
+    /// The number of messages dropped so far, across all links.
+    pub fn dropped(&self) -> u64 {
+        self.0.lock().dropped
+    }
+
+    /// Apply the faults on the link to a remote node to a message about to be
+    /// sent to it: wait out the latency, then decide whether the message
+    /// should be sent at all.
+    pub async fn transmit(&self, remote: &NodeId) -> bool {
+        let (latency, dropped) = {
+            let mut inner = self.0.lock();
+            let fault = match inner.links.get(remote) {
+                Some(fault) => *fault,
+                None => return true,
+            };
+            let dropped = fault.drop_rate > 0.0 && inner.rng.gen_bool(fault.drop_rate.min(1.0));
+            if dropped {
+                inner.dropped += 1;
+            }
+            (fault.latency, dropped)
+        };
+        if !latency.is_zero() {
+            tokio::time::sleep(latency).await;
+        }
+        !dropped
+    }
+}
+
+/// The node id that messages to a peer at this url are sent to,
+/// for use as a key in [`NetworkFaults`].
+pub fn node_id_from_url(url: &str) -> Option<NodeId> {
+    kitsune_p2p_proxy::ProxyUrl::from_full(url)
+        .ok()
+        .map(|url| url.digest().0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn faults_apply_per_link() {
+        let a: NodeId = Arc::new([1; 32]);
+        let b: NodeId = Arc::new([2; 32]);
+        let faults = NetworkFaults::new(42);
+
+        faults.set_link(a.clone(), LinkFault::partitioned());
+        faults.set_link(b.clone(), LinkFault::latency(Duration::from_millis(100)));
+        assert!(!faults.transmit(&a).await);
+        let start = std::time::Instant::now();
+        assert!(faults.transmit(&b).await);
+        assert!(start.elapsed() >= Duration::from_millis(100));
+
+        faults.set_link(b.clone(), LinkFault::drop_rate(0.5));
+        let sent = (0..1000)
+            .filter(|_| futures::executor::block_on(faults.transmit(&b)))
+            .count();
+        assert!((400..600).contains(&sent));
+        assert_eq!(faults.dropped(), 1 + 1000 - sent as u64);
+
+        faults.heal_link(&a);
+        assert!(faults.transmit(&a).await);
+        assert_eq!(faults.link(&b), Some(LinkFault::drop_rate(0.5)));
+        faults.heal();
+        assert_eq!(faults.link(&b), None);
+    }
+}
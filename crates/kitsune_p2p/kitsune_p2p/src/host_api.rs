@@ -115,6 +115,13 @@ pub trait KitsuneHost: 'static + Send + Sync + std::fmt::Debug {
     fn lair_client(&self) -> Option<lair_keystore_api::LairClient> {
         None
     }
+
+    /// Get the faults to inject on messages this node sends, for testing
+    /// behaviour on an unreliable network.
+    #[cfg(any(test, feature = "test_utils"))]
+    fn network_faults(&self) -> Option<crate::fault::NetworkFaults> {
+        None
+    }
 }
 
 /// Trait object for the host interface
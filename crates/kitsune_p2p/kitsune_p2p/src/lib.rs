@@ -59,6 +59,9 @@ pub use spawn::*;
 mod host_api;
 pub use host_api::*;
 
+#[cfg(any(test, feature = "test_utils"))]
+pub mod fault;
+
 #[allow(missing_docs)]
 #[cfg(any(test, feature = "test_utils"))]
 pub mod test_util;
@@ -30,6 +30,7 @@ pub(crate) mod meta_net;
 use meta_net::*;
 mod space;
 use ghost_actor::dependencies::tracing;
+use ghost_actor::dependencies::tracing::Instrument;
 use space::*;
 
 type EvtRcv = futures::channel::mpsc::Receiver<KitsuneP2pEvent>;
@@ -298,14 +299,7 @@ impl KitsuneP2pActor {
                                     data,
                                     respond,
                                 } => {
-                                    match nodespace_is_authorized(
-                                        &host,
-                                        con.peer_id(),
-                                        data.maybe_space(),
-                                        Timestamp::now(),
-                                    )
-                                    .await
-                                    {
+                                    match con.wire_is_authorized(&data, Timestamp::now()).await {
                                         MetaNetAuth::UnauthorizedIgnore => {}
                                         MetaNetAuth::UnauthorizedDisconnect => {
                                             con.close(
@@ -389,14 +383,7 @@ impl KitsuneP2pActor {
                                     con,
                                     data,
                                 } => {
-                                    match nodespace_is_authorized(
-                                        &host,
-                                        con.peer_id(),
-                                        data.maybe_space(),
-                                        Timestamp::now(),
-                                    )
-                                    .await
-                                    {
+                                    match con.wire_is_authorized(&data, Timestamp::now()).await {
                                         MetaNetAuth::UnauthorizedIgnore => {}
                                         MetaNetAuth::UnauthorizedDisconnect => {
                                             con.close(
@@ -1154,16 +1141,22 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
             Some(space) => space.get(),
         };
         Ok(async move {
-            let (space_sender, _) = space_sender.await;
-            space_sender
-                .rpc_single(space, to_agent, payload, timeout_ms)
-                .await
+            // Created here rather than in the handler so that it is a
+            // child of the caller's span.
+            let span = tracing::info_span!("rpc_single", ?space, ?to_agent);
+            async move {
+                let (space_sender, _) = space_sender.await;
+                space_sender
+                    .rpc_single(space, to_agent, payload, timeout_ms)
+                    .await
+            }
+            .instrument(span)
+            .await
         }
         .boxed()
         .into())
     }
 
-    #[tracing::instrument(skip(self, input))]
     fn handle_rpc_multi(
         &mut self,
         input: actor::RpcMulti,
@@ -1173,8 +1166,15 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
             Some(space) => space.get(),
         };
         Ok(async move {
-            let (space_sender, _) = space_sender.await;
-            space_sender.rpc_multi(input).await
+            // Created here rather than in the handler so that it is a
+            // child of the caller's span.
+            let span = tracing::info_span!("rpc_multi", space = ?input.space, basis = ?input.basis);
+            async move {
+                let (space_sender, _) = space_sender.await;
+                space_sender.rpc_multi(input).await
+            }
+            .instrument(span)
+            .await
         }
         .boxed()
         .into())
@@ -186,6 +186,42 @@ pub async fn nodespace_is_authorized(
     }
 }
 
+async fn ip_is_authorized(
+    host: &HostApi,
+    maybe_ip: Option<std::net::IpAddr>,
+    now: Timestamp,
+) -> MetaNetAuth {
+    if let Some(ip) = maybe_ip {
+        match host.is_blocked(BlockTargetId::Ip(ip), now).await {
+            Ok(true) => MetaNetAuth::UnauthorizedDisconnect,
+            Ok(false) => MetaNetAuth::Authorized,
+            Err(_) => MetaNetAuth::UnauthorizedIgnore,
+        }
+    } else {
+        MetaNetAuth::Authorized
+    }
+}
+
+/// The IP address of a peer, if its transport address names one.
+/// A proxy address names the proxy which the peer is reachable through,
+/// which is the peer itself unless it relays through another node.
+fn peer_ip(peer_addr: &url2::Url2) -> Option<std::net::IpAddr> {
+    let base;
+    let url = if peer_addr.scheme() == "kitsune-proxy" {
+        base = kitsune_p2p_proxy::ProxyUrl::from_full(peer_addr.as_str())
+            .ok()?
+            .into_base();
+        &base
+    } else {
+        peer_addr
+    };
+    url.host_str()?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
 pub type MetaNetEvtRecv = futures::channel::mpsc::Receiver<MetaNetEvt>;
 
 type ResStore = Arc<Mutex<HashMap<u64, tokio::sync::oneshot::Sender<wire::Wire>>>>;
@@ -303,14 +339,51 @@ impl MetaNetCon {
         true
     }
 
-    async fn wire_is_authorized(&self, payload: &wire::Wire, now: Timestamp) -> MetaNetAuth {
+    /// The IP address of the remote peer, if the transport knows it.
+    /// Tx5 doesn't expose the addresses of its WebRTC connections, so only
+    /// tx2 connections can be checked against IP blocks.
+    pub fn peer_ip(&self) -> Option<std::net::IpAddr> {
+        #[cfg(feature = "tx2")]
+        {
+            if let MetaNetCon::Tx2(con, _) = self {
+                return con.peer_addr().ok().and_then(|url| peer_ip(&url));
+            }
+        }
+
+        None
+    }
+
+    /// Check the remote peer against the host's IP blocks, then its node
+    /// and node-space blocks.
+    pub async fn wire_is_authorized(&self, payload: &wire::Wire, now: Timestamp) -> MetaNetAuth {
         match self {
             MetaNetCon::Tx5 { host, .. } | MetaNetCon::Tx2(_, host) => {
-                nodespace_is_authorized(host, self.peer_id(), payload.maybe_space(), now).await
+                match ip_is_authorized(host, self.peer_ip(), now).await {
+                    MetaNetAuth::Authorized => {
+                        nodespace_is_authorized(host, self.peer_id(), payload.maybe_space(), now)
+                            .await
+                    }
+                    unauthorized => unauthorized,
+                }
             }
         }
     }
 
+    /// Apply any faults injected on the link to this peer to a message
+    /// about to be sent, returning whether it should be sent at all.
+    async fn transmit_through_faults(&self) -> bool {
+        #[cfg(any(test, feature = "test_utils"))]
+        {
+            let host = match self {
+                MetaNetCon::Tx5 { host, .. } | MetaNetCon::Tx2(_, host) => host,
+            };
+            if let Some(faults) = host.network_faults() {
+                return faults.transmit(&self.peer_id()).await;
+            }
+        }
+        true
+    }
+
     pub async fn notify(&self, payload: &wire::Wire, timeout: KitsuneTimeout) -> KitsuneResult<()> {
         let start = std::time::Instant::now();
         let msg_id = next_msg_id();
@@ -318,6 +391,10 @@ impl MetaNetCon {
         let result = (move || async move {
             match self.wire_is_authorized(payload, Timestamp::now()).await {
                 MetaNetAuth::Authorized => {
+                    if !self.transmit_through_faults().await {
+                        return Ok(());
+                    }
+
                     #[cfg(feature = "tx2")]
                     {
                         if let MetaNetCon::Tx2(con, _) = self {
@@ -380,6 +457,14 @@ impl MetaNetCon {
         let result = (move || async move {
             match self.wire_is_authorized(payload, Timestamp::now()).await {
                 MetaNetAuth::Authorized => {
+                    if !self.transmit_through_faults().await {
+                        tokio::time::sleep(timeout.time_remaining()).await;
+                        return Err(KitsuneErrorKind::TimedOut(
+                            "request dropped by network fault".into(),
+                        )
+                        .into());
+                    }
+
                     #[cfg(feature = "tx2")]
                     {
                         if let MetaNetCon::Tx2(con, _) = self {
@@ -617,10 +702,23 @@ impl MetaNet {
                         }
                     }
                     Tx2EpEvent::IncomingConnection(Tx2EpConnection { con, url }) => {
+                        let con = MetaNetCon::Tx2(con, host.clone());
+                        match ip_is_authorized(&host, con.peer_ip(), Timestamp::now()).await {
+                            MetaNetAuth::Authorized => (),
+                            MetaNetAuth::UnauthorizedIgnore => continue,
+                            MetaNetAuth::UnauthorizedDisconnect => {
+                                con.close(
+                                    UNAUTHORIZED_DISCONNECT_CODE,
+                                    UNAUTHORIZED_DISCONNECT_REASON,
+                                )
+                                .await;
+                                continue;
+                            }
+                        }
                         if evt_send
                             .send(MetaNetEvt::Connected {
                                 remote_url: url.to_string(),
-                                con: MetaNetCon::Tx2(con, host.clone()),
+                                con,
                             })
                             .await
                             .is_err()
@@ -1083,3 +1181,64 @@ impl MetaNet {
         async move { Err("invalid features".into()) }.boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kitsune_p2p_fetch::FetchPoolConfig;
+
+    #[derive(Debug)]
+    struct IpBlockHost(Vec<std::net::IpAddr>);
+
+    impl FetchPoolConfig for IpBlockHost {
+        fn merge_fetch_contexts(&self, _a: u32, _b: u32) -> u32 {
+            unimplemented!()
+        }
+    }
+
+    impl KitsuneHostDefaultError for IpBlockHost {
+        const NAME: &'static str = "IpBlockHost";
+
+        fn is_blocked(
+            &self,
+            input: BlockTargetId,
+            _timestamp: Timestamp,
+        ) -> KitsuneHostResult<bool> {
+            box_fut(Ok(
+                matches!(input, BlockTargetId::Ip(ip) if self.0.contains(&ip)),
+            ))
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn blocked_ip_is_refused() {
+        let host: HostApi = Arc::new(IpBlockHost(vec![
+            "10.0.0.1".parse().unwrap(),
+            "2001:db8::1".parse().unwrap(),
+        ]));
+
+        for (peer_addr, expect_blocked) in [
+            (
+                "kitsune-proxy://cert/kitsune-quic/h/10.0.0.1/p/5778/--",
+                true,
+            ),
+            (
+                "kitsune-proxy://cert/kitsune-quic/h/10.0.0.2/p/5778/--",
+                false,
+            ),
+            ("kitsune-quic://[2001:db8::1]:5778", true),
+            ("kitsune-quic://[2001:db8::2]:5778", false),
+            // Mem connections have no IP address to block
+            ("kitsune-mem://h-1", false),
+        ] {
+            let maybe_ip = peer_ip(&url2::url2!("{}", peer_addr));
+            let auth = ip_is_authorized(&host, maybe_ip, Timestamp::now()).await;
+            assert_eq!(
+                matches!(auth, MetaNetAuth::UnauthorizedDisconnect),
+                expect_blocked,
+                "{}",
+                peer_addr,
+            );
+        }
+    }
+}
@@ -12,6 +12,11 @@ use kitsune_p2p_types::tx2::*;
 use kitsune_p2p_types::*;
 use std::sync::Arc;
 
+/// Decides whether connections from a remote address should be refused,
+/// e.g. because the address is blocked.
+pub type IpBlockedCb =
+    Arc<dyn Fn(std::net::IpAddr) -> BoxFuture<'static, bool> + 'static + Send + Sync>;
+
 /// Configuration for QuicBackendAdapt
 #[non_exhaustive]
 #[derive(Default)]
@@ -23,12 +28,19 @@ pub struct QuicConfig {
     /// Tuning Params
     /// Default: None = default.
     pub tuning_params: Option<KitsuneP2pTuningParams>,
+
+    /// Incoming connections are refused before the handshake if this
+    /// returns true for their remote address.
+    /// Default: None = accept from any address.
+    pub ip_blocked: Option<IpBlockedCb>,
 }
 
 impl QuicConfig {
     /// into inner contents with default application
     pub async fn split(self) -> KitsuneResult<(TlsConfig, KitsuneP2pTuningParams)> {
-        let QuicConfig { tls, tuning_params } = self;
+        let QuicConfig {
+            tls, tuning_params, ..
+        } = self;
 
         let tls = match tls {
             None => TlsConfig::new_ephemeral().await?,
@@ -212,10 +224,34 @@ fn connecting(con_fut: quinn::Connecting, local_cert: Tx2Cert, dir: Tx2ConDir) -
     .boxed()
 }
 
+fn accepting(
+    con_fut: quinn::Connecting,
+    local_cert: Tx2Cert,
+    ip_blocked: Option<IpBlockedCb>,
+) -> ConFut {
+    async move {
+        let addr = con_fut.remote_address();
+        if let Some(ip_blocked) = ip_blocked {
+            if ip_blocked(addr.ip()).await {
+                tracing::info!(?local_cert, %addr, "refused incoming connection from blocked address (quic)");
+                // dropping the connecting future closes the connection
+                return Err(format!("{} is blocked", addr.ip()).into());
+            }
+        }
+        connecting(con_fut, local_cert, Tx2ConDir::Incoming).await
+    }
+    .boxed()
+}
+
 struct QuicConRecvAdapt(BoxStream<'static, ConFut>);
 
 impl QuicConRecvAdapt {
-    pub fn new(recv: quinn::Incoming, local_cert: Tx2Cert, ep: Arc<dyn EndpointAdapt>) -> Self {
+    pub fn new(
+        recv: quinn::Incoming,
+        local_cert: Tx2Cert,
+        ep: Arc<dyn EndpointAdapt>,
+        ip_blocked: Option<IpBlockedCb>,
+    ) -> Self {
         struct OnDrop(Arc<dyn EndpointAdapt>);
 
         impl Drop for OnDrop {
@@ -231,12 +267,12 @@ impl QuicConRecvAdapt {
 
         Self(
             futures::stream::unfold(
-                (recv, local_cert, on_drop),
-                move |(mut recv, local_cert, on_drop)| async move {
+                (recv, local_cert, ip_blocked, on_drop),
+                move |(mut recv, local_cert, ip_blocked, on_drop)| async move {
                     recv.next().await.map(|con| {
                         (
-                            connecting(con, local_cert.clone(), Tx2ConDir::Incoming),
-                            (recv, local_cert, on_drop),
+                            accepting(con, local_cert.clone(), ip_blocked.clone()),
+                            (recv, local_cert, ip_blocked, on_drop),
                         )
                     })
                 },
@@ -378,11 +414,13 @@ pub struct QuicBackendAdapt {
     local_cert: Tx2Cert,
     quic_srv: quinn::ServerConfig,
     quic_cli: quinn::ClientConfig,
+    ip_blocked: Option<IpBlockedCb>,
 }
 
 impl QuicBackendAdapt {
     /// Construct a new quic tx2 backend bind adapter
     pub async fn new(config: QuicConfig) -> KitsuneResult<AdapterFactory> {
+        let ip_blocked = config.ip_blocked.clone();
         let (tls, tuning_params) = config.split().await?;
 
         let local_cert = tls.cert_digest.clone().into();
@@ -424,6 +462,7 @@ impl QuicBackendAdapt {
             local_cert,
             quic_srv,
             quic_cli,
+            ip_blocked,
         });
 
         Ok(out)
@@ -435,6 +474,7 @@ impl BindAdapt for QuicBackendAdapt {
         let local_cert = self.local_cert.clone();
         let quic_srv = self.quic_srv.clone();
         let quic_cli = self.quic_cli.clone();
+        let ip_blocked = self.ip_blocked.clone();
         timeout
             .mix("QuicBackendAdapt::bind", async move {
                 let addr = crate::url_to_addr(url.as_url2(), crate::SCHEME)
@@ -448,8 +488,12 @@ impl BindAdapt for QuicBackendAdapt {
 
                 let ep: Arc<dyn EndpointAdapt> =
                     Arc::new(QuicEndpointAdapt::new(ep, local_cert.clone()));
-                let con_recv: Box<dyn ConRecvAdapt> =
-                    Box::new(QuicConRecvAdapt::new(inc, local_cert.clone(), ep.clone()));
+                let con_recv: Box<dyn ConRecvAdapt> = Box::new(QuicConRecvAdapt::new(
+                    inc,
+                    local_cert.clone(),
+                    ep.clone(),
+                    ip_blocked,
+                ));
 
                 let url = ep.local_addr()?;
 
@@ -526,4 +570,35 @@ mod tests {
 
         rt.await.unwrap().unwrap();
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_quic_tx2_refuses_blocked_ip() {
+        let t = KitsuneTimeout::from_millis(5000);
+
+        let factory = QuicBackendAdapt::new(QuicConfig::default()).await.unwrap();
+        let (ep1, _con_recv1) = factory
+            .bind("kitsune-quic://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+
+        let config = QuicConfig {
+            ip_blocked: Some(Arc::new(|ip| async move { ip.is_loopback() }.boxed())),
+            ..Default::default()
+        };
+        let factory = QuicBackendAdapt::new(config).await.unwrap();
+        let (ep2, mut con_recv2) = factory
+            .bind("kitsune-quic://127.0.0.1:0".into(), t)
+            .await
+            .unwrap();
+        let addr2 = ep2.local_addr().unwrap();
+
+        let (_, accepted) = futures::join!(
+            ep1.connect(addr2, KitsuneTimeout::from_millis(1000)),
+            async { con_recv2.next().await.unwrap().await },
+        );
+        assert!(accepted.is_err());
+
+        ep1.close(0, "").await;
+        ep2.close(0, "").await;
+    }
 }
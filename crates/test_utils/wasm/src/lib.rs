@@ -28,6 +28,7 @@ pub enum TestWasm {
     Crd,
     Crud,
     Debug,
+    Deepkey,
     EntryDefs,
     EmitSignal,
     HashEntry,
@@ -49,6 +50,7 @@ pub enum TestWasm {
     PostCommitVolley,
     Query,
     RandomBytes,
+    RateLimits,
     Schedule,
     XSalsa20Poly1305,
     SerRegression,
@@ -126,6 +128,7 @@ impl From<TestWasm> for ZomeName {
             TestWasm::Crd => "crd",
             TestWasm::Crud => "crud",
             TestWasm::Debug => "debug",
+            TestWasm::Deepkey => "deepkey",
             TestWasm::EntryDefs => "entry_defs",
             TestWasm::EmitSignal => "emit_signal",
             TestWasm::HashEntry => "hash_entry",
@@ -147,6 +150,7 @@ impl From<TestWasm> for ZomeName {
             TestWasm::PostCommitVolley => "post_commit_volley",
             TestWasm::Query => "query",
             TestWasm::RandomBytes => "random_bytes",
+            TestWasm::RateLimits => "rate_limits",
             TestWasm::Schedule => "schedule",
             TestWasm::XSalsa20Poly1305 => "x_salsa20_poly1305",
             TestWasm::SerRegression => "ser_regression",
@@ -191,6 +195,7 @@ impl From<TestWasm> for PathBuf {
             TestWasm::Crd => "wasm32-unknown-unknown/release/test_wasm_crd.wasm",
             TestWasm::Crud => "wasm32-unknown-unknown/release/test_wasm_crud.wasm",
             TestWasm::Debug => "wasm32-unknown-unknown/release/test_wasm_debug.wasm",
+            TestWasm::Deepkey => "wasm32-unknown-unknown/release/test_wasm_deepkey.wasm",
             TestWasm::EntryDefs => "wasm32-unknown-unknown/release/test_wasm_entry_defs.wasm",
             TestWasm::EmitSignal => "wasm32-unknown-unknown/release/test_wasm_emit_signal.wasm",
             TestWasm::HashEntry => "wasm32-unknown-unknown/release/test_wasm_hash_entry.wasm",
@@ -230,6 +235,7 @@ impl From<TestWasm> for PathBuf {
             }
             TestWasm::Query => "wasm32-unknown-unknown/release/test_wasm_query.wasm",
             TestWasm::RandomBytes => "wasm32-unknown-unknown/release/test_wasm_random_bytes.wasm",
+            TestWasm::RateLimits => "wasm32-unknown-unknown/release/test_wasm_rate_limits.wasm",
             TestWasm::Schedule => "wasm32-unknown-unknown/release/test_wasm_schedule.wasm",
             TestWasm::XSalsa20Poly1305 => {
                 "wasm32-unknown-unknown/release/test_wasm_x_salsa20_poly1305.wasm"
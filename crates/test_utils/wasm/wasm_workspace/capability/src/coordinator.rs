@@ -50,7 +50,8 @@ pub fn unblock_agent(target: AgentPubKey) -> ExternResult<()> {
 fn cap_grant_entry(secret: CapSecret) -> ExternResult<CapGrantEntry> {
     let mut fns = BTreeSet::new();
     let this_zome = zome_info()?.name;
-    fns.insert((this_zome, "needs_cap_claim".into()));
+    fns.insert((this_zome.clone(), "needs_cap_claim".into()));
+    fns.insert((this_zome, "cap_info".into()));
     let functions = GrantedFunctions::Listed(fns);
     Ok(CapGrantEntry {
         tag: "".into(),
@@ -97,6 +98,32 @@ fn needs_cap_claim(_: ()) -> ExternResult<()> {
     Ok(())
 }
 
+#[hdk_extern]
+fn list_cap_grants(query: CapGrantQuery) -> ExternResult<Vec<CapGrantInfo>> {
+    capability_grants(query)
+}
+
+#[hdk_extern]
+fn list_cap_claims(query: CapClaimQuery) -> ExternResult<Vec<CapClaim>> {
+    capability_claims(query)
+}
+
+#[hdk_extern]
+fn cap_info(_: ()) -> ExternResult<Option<CapGrantInfo>> {
+    capability_info()
+}
+
+#[hdk_extern]
+fn try_cap_info(cap_for: crate::CapFor) -> ExternResult<ZomeCallResponse> {
+    call_remote(
+        cap_for.1,
+        zome_info()?.name,
+        "cap_info".to_string().into(),
+        Some(cap_for.0),
+        &(),
+    )
+}
+
 #[hdk_extern]
 fn try_cap_claim(cap_for: crate::CapFor) -> ExternResult<ZomeCallResponse> {
     call_remote(
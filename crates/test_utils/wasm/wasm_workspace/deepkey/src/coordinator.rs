@@ -0,0 +1,34 @@
+use crate::integrity::*;
+use hdk::prelude::*;
+
+#[hdk_extern]
+fn key_mutation(mutation: KeyMutation) -> ExternResult<()> {
+    if let Some(old_key) = mutation.old_key {
+        create_link(old_key.clone(), old_key, LinkTypes::Revocation, ())?;
+    }
+    if let Some(new_key) = mutation.new_key {
+        create_link(new_key.clone(), new_key, LinkTypes::Registration, ())?;
+    }
+    Ok(())
+}
+
+#[hdk_extern]
+fn key_state(query: KeyStateQuery) -> ExternResult<KeyState> {
+    // The earliest link of a type made at or before the queried time.
+    let earliest = |link_type: LinkTypes| -> ExternResult<Option<Timestamp>> {
+        Ok(
+            get_links(GetLinksInputBuilder::try_new(query.key.clone(), link_type)?.build())?
+                .into_iter()
+                .map(|link| link.timestamp)
+                .filter(|timestamp| *timestamp <= query.timestamp)
+                .min(),
+        )
+    };
+    Ok(match earliest(LinkTypes::Revocation)? {
+        Some(revoked_at) => KeyState::Invalid(revoked_at),
+        None => match earliest(LinkTypes::Registration)? {
+            Some(registered_at) => KeyState::Valid(registered_at),
+            None => KeyState::NotFound,
+        },
+    })
+}
@@ -0,0 +1,9 @@
+use hdi::prelude::*;
+
+/// Keys are registered and revoked by linking the key to itself.
+/// The timestamp of the link is the time of the registration or revocation.
+#[hdk_link_types]
+pub enum LinkTypes {
+    Registration,
+    Revocation,
+}
@@ -0,0 +1,22 @@
+use crate::integrity::*;
+use hdk::prelude::*;
+
+#[hdk_extern]
+fn create_post(content: String) -> ExternResult<ActionHash> {
+    create_entry(EntryTypes::Post(Post(content)))
+}
+
+#[hdk_extern]
+fn delete_post(action_hash: ActionHash) -> ExternResult<ActionHash> {
+    delete_entry(action_hash)
+}
+
+#[hdk_extern]
+fn create_comment_link(action_hash: ActionHash) -> ExternResult<ActionHash> {
+    create_link(action_hash.clone(), action_hash, LinkTypes::Comments, ())
+}
+
+#[hdk_extern]
+fn get_post(action_hash: ActionHash) -> ExternResult<Option<Record>> {
+    get(action_hash, GetOptions::default())
+}
@@ -0,0 +1,30 @@
+use hdi::prelude::*;
+
+#[hdk_entry_helper]
+pub struct Post(pub String);
+
+#[hdk_entry_defs]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+    Post(Post),
+}
+
+#[hdk_link_types]
+pub enum LinkTypes {
+    Comments,
+}
+
+/// Entries and links are all weighed into bucket 0, deletes into bucket 1.
+#[hdk_extern]
+fn weigh(input: WeighInput) -> ExternResult<RateWeight> {
+    Ok(match input {
+        WeighInput::Create(_, _) | WeighInput::Update(_, _) | WeighInput::Link(_) => RateWeight {
+            bucket_id: 0,
+            units: 10,
+        },
+        WeighInput::Delete(_) => RateWeight {
+            bucket_id: 1,
+            units: 1,
+        },
+    })
+}